@@ -1,7 +1,7 @@
 use rust_libretro::{
     contexts::*, core::Core, env_version, input_descriptors, proc::*, retro_core, sys::*, types::*,
 };
-use std::ffi::CString;
+use std::{ffi::CString, time::Duration};
 
 const INPUT_DESCRIPTORS: &[retro_input_descriptor] = &input_descriptors!(
     { 0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_UP, "Up" },
@@ -80,12 +80,12 @@ impl Core for ExampleCore {
             return;
         }
 
-        ctx.set_support_no_game(true);
+        let _ = ctx.set_support_no_game(true);
     }
 
     fn on_init(&mut self, ctx: &mut InitContext) {
         let gctx: GenericContext = ctx.into();
-        gctx.set_input_descriptors(INPUT_DESCRIPTORS);
+        let _ = gctx.set_input_descriptors(INPUT_DESCRIPTORS);
     }
 
     fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
@@ -109,40 +109,43 @@ impl Core for ExampleCore {
         _info: Option<retro_game_info>,
         ctx: &mut LoadGameContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        ctx.set_pixel_format(PixelFormat::XRGB8888);
-        ctx.set_performance_level(0);
-        ctx.enable_frame_time_callback((1000000.0f64 / 60.0).round() as retro_usec_t);
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        // These are optional, so the core keeps working if the frontend rejects them
+        let _ = ctx.set_performance_level(0);
+        let _ = ctx.enable_frame_time_callback((1000000.0f64 / 60.0).round() as retro_usec_t);
 
         let gctx: GenericContext = ctx.into();
-        gctx.enable_audio_callback();
+        let _ = gctx.enable_audio_callback();
 
         Ok(())
     }
 
     fn on_options_changed(&mut self, ctx: &mut OptionsChangedContext) {
         match ctx.get_variable("foo_option_1") {
-            Some("true") => self.option_1 = true,
-            Some("false") => self.option_1 = false,
+            Ok(Some("true")) => self.option_1 = true,
+            Ok(Some("false")) => self.option_1 = false,
             _ => (),
         }
 
         match ctx.get_variable("foo_option_2") {
-            Some("true") => self.option_2 = true,
-            Some("false") => self.option_2 = false,
+            Ok(Some("true")) => self.option_2 = true,
+            Ok(Some("false")) => self.option_2 = false,
             _ => (),
         }
     }
 
     #[inline]
-    fn on_run(&mut self, ctx: &mut RunContext, delta_us: Option<i64>) {
+    fn on_run(&mut self, ctx: &mut RunContext, delta: Option<Duration>) {
         let gctx: GenericContext = ctx.into();
 
-        self.timer += delta_us.unwrap_or(16_666);
+        self.timer += delta.map_or(16_666, |delta| delta.as_micros() as i64);
 
         let input = unsafe { ctx.get_joypad_bitmask(0, 0) };
 
         if input.contains(JoypadState::START) && input.contains(JoypadState::SELECT) {
-            return gctx.shutdown();
+            let _ = gctx.shutdown();
+            return;
         }
 
         if !ctx.can_dupe() || self.timer >= 1_000_000 || input.contains(JoypadState::A) {
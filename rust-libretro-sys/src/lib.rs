@@ -46,6 +46,57 @@ pub const RETRO_DEVICE_ID_LIGHTGUN_PAUSE: u32 = 5;
 pub const RETRO_HW_FRAME_BUFFER_VALID: *mut std::os::raw::c_void =
     -1_i32 as *mut std::os::raw::c_void;
 
+/// Not part of the vendored `libretro.h` yet, taken from upstream libretro-common.
+///
+/// `bool *` --
+/// Result is set to true if the frontend has already verified JIT can be
+/// used, mainly for use iOS/tvOS. On other platforms, true is always returned.
+pub const RETRO_ENVIRONMENT_GET_JIT_CAPABLE: u32 = 74;
+
+/// Not part of the vendored `libretro.h` yet, taken from upstream libretro-common.
+///
+/// `struct retro_device_power *` --
+/// Returns the device's current power state as reported by the frontend.
+/// This is useful for emulating the battery level in handheld consoles,
+/// or for reducing power consumption when on battery power.
+pub const RETRO_ENVIRONMENT_GET_DEVICE_POWER: u32 = 77 | RETRO_ENVIRONMENT_EXPERIMENTAL;
+
+/// Describes how the device running the frontend is powered,
+/// see [`RETRO_ENVIRONMENT_GET_DEVICE_POWER`].
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct retro_power_state(pub std::os::raw::c_uint);
+
+impl retro_power_state {
+    /// The frontend cannot report its power state at this time.
+    pub const RETRO_POWERSTATE_UNKNOWN: retro_power_state = retro_power_state(0);
+    /// The device is running on its battery.
+    pub const RETRO_POWERSTATE_DISCHARGING: retro_power_state = retro_power_state(1);
+    /// The device is plugged in and charging its battery.
+    pub const RETRO_POWERSTATE_CHARGING: retro_power_state = retro_power_state(2);
+    /// The device is connected to a power source and its battery is full.
+    pub const RETRO_POWERSTATE_CHARGED: retro_power_state = retro_power_state(3);
+    /// The device is connected to a power source and does not have a battery.
+    pub const RETRO_POWERSTATE_PLUGGED_IN: retro_power_state = retro_power_state(4);
+}
+
+/// Used for the battery level or time remaining if there is no estimate,
+/// even if the power state is known.
+pub const RETRO_POWERSTATE_NO_ESTIMATE: i32 = -1;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct retro_device_power {
+    /// The current state of the power supply.
+    pub state: retro_power_state,
+    /// The estimated battery life left in seconds,
+    /// or [`RETRO_POWERSTATE_NO_ESTIMATE`].
+    pub seconds: std::os::raw::c_int,
+    /// The remaining battery charge in percent,
+    /// or [`RETRO_POWERSTATE_NO_ESTIMATE`].
+    pub percent: i8,
+}
+
 #[derive(Debug, Default)]
 pub struct InvalidEnumValue<T: Display>(T);
 
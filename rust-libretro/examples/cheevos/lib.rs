@@ -0,0 +1,149 @@
+use rust_libretro::{
+    contexts::*,
+    core::{Core, CoreOptions},
+    env_version, retro_core,
+    sys::*,
+    types::*,
+};
+use std::{ffi::CString, time::Duration};
+
+const WIDTH: u32 = 256;
+const HEIGHT: u32 = 128;
+
+/// The size of the emulated system RAM.
+const RAM_SIZE: usize = 0x800;
+
+/// Where the RAM starts in the emulated address space.
+const RAM_START: usize = 0x0000;
+
+// Addresses of the “game” variables, useful for writing achievements
+const ADDR_FRAME: usize = 0x00;
+const ADDR_SCORE: usize = 0x04;
+const ADDR_LEVEL: usize = 0x08;
+
+/// Exposes its RAM to the frontend, so achievements and memory viewers can inspect it.
+///
+/// The RAM gets allocated once and never moves, as the frontend keeps using the pointers it was given.
+struct CheevosCore {
+    pixels: Vec<u8>,
+    ram: Box<[u8]>,
+}
+
+retro_core!(CheevosCore {
+    pixels: vec![0; (WIDTH * HEIGHT * 4) as usize],
+    ram: vec![0; RAM_SIZE].into_boxed_slice(),
+});
+
+impl CheevosCore {
+    fn read_u32(&self, address: usize) -> u32 {
+        let bytes = &self.ram[address..address + 4];
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+
+    fn write_u32(&mut self, address: usize, value: u32) {
+        self.ram[address..address + 4].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+impl CoreOptions for CheevosCore {}
+impl Core for CheevosCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo {
+            library_name: CString::new("CheevosCore").unwrap(),
+            library_version: CString::new(env_version!("CARGO_PKG_VERSION").to_string()).unwrap(),
+            valid_extensions: CString::new("").unwrap(),
+
+            need_fullpath: false,
+            block_extract: false,
+        }
+    }
+
+    fn on_set_environment(&mut self, initial: bool, ctx: &mut SetEnvironmentContext) {
+        if !initial {
+            return;
+        }
+
+        let _ = ctx.set_support_no_game(true);
+    }
+
+    fn on_init(&mut self, ctx: &mut InitContext) {
+        if unsafe { ctx.set_support_achievements(true) }.is_err() {
+            log::warn!("The frontend does not support achievements");
+        }
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        retro_system_av_info {
+            geometry: retro_game_geometry {
+                base_width: WIDTH,
+                base_height: HEIGHT,
+                max_width: WIDTH,
+                max_height: HEIGHT,
+                aspect_ratio: 0.0,
+            },
+            timing: retro_system_timing {
+                fps: 60.0,
+                sample_rate: 0.0,
+            },
+        }
+    }
+
+    fn on_load_game(
+        &mut self,
+        _info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        self.ram.fill(0);
+
+        // The memory map and `get_memory_data` describe the same memory
+        let descriptor = MemoryDescriptor::system_ram(&mut self.ram, RAM_START);
+        let ctx: GenericContext = ctx.into();
+
+        if let Err(err) = unsafe { ctx.set_memory_maps(&[descriptor]) } {
+            log::warn!("Failed to set the memory maps: {err}");
+        }
+
+        Ok(())
+    }
+
+    fn on_cheevos_ready(&mut self, _ctx: &mut GenericContext) {
+        log::info!(
+            "Exposing {RAM_SIZE} bytes of RAM at {:p}",
+            self.ram.as_ptr()
+        );
+    }
+
+    fn memory(&mut self, id: MemoryId) -> Option<&mut [u8]> {
+        match id {
+            MemoryId::SystemRam => Some(&mut self.ram[..]),
+            _ => None,
+        }
+    }
+
+    fn on_reset(&mut self, _ctx: &mut ResetContext) {
+        self.ram.fill(0);
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
+        // A tiny “game”: the score rises every frame and the level every 600 points
+        let frame = self.read_u32(ADDR_FRAME).wrapping_add(1);
+        let score = self.read_u32(ADDR_SCORE).wrapping_add(1);
+
+        self.write_u32(ADDR_FRAME, frame);
+        self.write_u32(ADDR_SCORE, score);
+        self.write_u32(ADDR_LEVEL, score / 600);
+
+        // Show the RAM as a grid of 8×8 pixel cells, one per byte
+        for (index, pixel) in self.pixels.chunks_exact_mut(4).enumerate() {
+            let x = index % WIDTH as usize / 8;
+            let y = index / WIDTH as usize / 8;
+
+            let value = self.ram[(y * (WIDTH as usize / 8) + x) % RAM_SIZE];
+            pixel.copy_from_slice(&[value, value, value, 0]);
+        }
+
+        ctx.draw_frame(self.pixels.as_ref(), WIDTH, HEIGHT, WIDTH as usize * 4);
+    }
+}
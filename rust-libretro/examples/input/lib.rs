@@ -6,7 +6,7 @@ use rust_libretro::{
     sys::*,
     types::*,
 };
-use std::ffi::CString;
+use std::{ffi::CString, time::Duration};
 
 const WIDTH: u32 = 640;
 const HEIGHT: u32 = 400;
@@ -54,7 +54,7 @@ impl Core for InputTestCore {
             return;
         }
 
-        ctx.set_support_no_game(true);
+        let _ = ctx.set_support_no_game(true);
     }
 
     fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
@@ -81,8 +81,8 @@ impl Core for InputTestCore {
         use image::imageops::{flip_horizontal, flip_vertical, rotate90};
         use DynamicImage::ImageRgba8;
 
-        ctx.set_pixel_format(PixelFormat::XRGB8888);
-        ctx.set_performance_level(0);
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+        let _ = ctx.set_performance_level(0);
 
         fn load(buf: &[u8]) -> ImageResult<DynamicImage> {
             image::load_from_memory_with_format(buf, ImageFormat::Png)
@@ -123,7 +123,7 @@ impl Core for InputTestCore {
     }
 
     #[inline]
-    fn on_run(&mut self, ctx: &mut RunContext, _delta_us: Option<i64>) {
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
         // try to get a software framebuffer from the frontend
         let fb = unsafe {
             ctx.get_current_framebuffer_or_fallback(
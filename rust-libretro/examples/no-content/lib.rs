@@ -0,0 +1,119 @@
+use rust_libretro::{
+    contexts::*,
+    core::{Core, CoreOptions},
+    env_version, retro_core,
+    sys::*,
+    types::*,
+};
+use std::{ffi::CString, time::Duration};
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+const FPS: f64 = 60.0;
+const SAMPLE_RATE: f64 = 48000.0;
+const SAMPLES_PER_FRAME: usize = (SAMPLE_RATE / FPS) as usize;
+
+/// A demo that starts without any content and plays an animation with a tone.
+struct NoContentCore {
+    pixels: Vec<u8>,
+    samples: Vec<i16>,
+    frame: u32,
+    phase: f64,
+}
+
+retro_core!(NoContentCore {
+    pixels: vec![0; (WIDTH * HEIGHT * 4) as usize],
+    samples: vec![0; SAMPLES_PER_FRAME],
+    frame: 0,
+    phase: 0.0,
+});
+
+impl CoreOptions for NoContentCore {}
+impl Core for NoContentCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo {
+            library_name: CString::new("NoContentCore").unwrap(),
+            library_version: CString::new(env_version!("CARGO_PKG_VERSION").to_string()).unwrap(),
+            valid_extensions: CString::new("").unwrap(),
+
+            need_fullpath: false,
+            block_extract: false,
+        }
+    }
+
+    fn on_set_environment(&mut self, initial: bool, ctx: &mut SetEnvironmentContext) {
+        if !initial {
+            return;
+        }
+
+        // Without this the frontend would never start the core without content
+        if ctx.set_support_no_game(true).is_err() {
+            log::warn!("The frontend does not support starting without content");
+        }
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        retro_system_av_info {
+            geometry: retro_game_geometry {
+                base_width: WIDTH,
+                base_height: HEIGHT,
+                max_width: WIDTH,
+                max_height: HEIGHT,
+                aspect_ratio: 0.0,
+            },
+            timing: retro_system_timing {
+                fps: FPS,
+                sample_rate: SAMPLE_RATE,
+            },
+        }
+    }
+
+    fn on_load_game(
+        &mut self,
+        info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if info.is_some() {
+            return Err("This core does not load any content".into());
+        }
+
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        Ok(())
+    }
+
+    fn on_reset(&mut self, _ctx: &mut ResetContext) {
+        self.frame = 0;
+        self.phase = 0.0;
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
+        let t = self.frame as f64 / FPS;
+
+        // Plasma-like color bands that scroll over time
+        for (index, pixel) in self.pixels.chunks_exact_mut(4).enumerate() {
+            let x = (index % WIDTH as usize) as f64 / WIDTH as f64;
+            let y = (index / WIDTH as usize) as f64 / HEIGHT as f64;
+
+            let value =
+                (x * 10.0 + t).sin() + (y * 8.0 - t * 1.3).sin() + ((x + y) * 6.0 + t).sin();
+            let channel = |offset: f64| ((value + offset).sin() * 127.0 + 128.0) as u8;
+
+            pixel.copy_from_slice(&[channel(0.0), channel(2.0), channel(4.0), 0]);
+        }
+
+        // A quiet tone that slowly changes its pitch
+        let frequency = 220.0 + 110.0 * (t * 0.5).sin();
+        for sample in self.samples.iter_mut() {
+            *sample = (self.phase.sin() * 0.1 * i16::MAX as f64) as i16;
+            self.phase = (self.phase + std::f64::consts::TAU * frequency / SAMPLE_RATE)
+                % std::f64::consts::TAU;
+        }
+
+        ctx.audio().batch_audio_samples_mono(&self.samples);
+        ctx.draw_frame(self.pixels.as_ref(), WIDTH, HEIGHT, WIDTH as usize * 4);
+
+        self.frame = self.frame.wrapping_add(1);
+    }
+}
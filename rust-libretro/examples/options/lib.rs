@@ -0,0 +1,152 @@
+use rust_libretro::{
+    contexts::*, core::Core, env_version, proc::CoreOptions, retro_core, sys::*, types::*,
+};
+use std::{ffi::CString, time::Duration};
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+/// Draws a pattern that is configured through core options.
+///
+/// Only the options that affect the selected pattern are shown in the frontend’s menu.
+#[derive(CoreOptions)]
+#[typed_options(PatternOptions)]
+#[options({
+    "options_pattern",
+    "Pattern",
+    "Selects what to draw.",
+    {
+        { "solid", "Solid color" },
+        { "checkerboard", "Checkerboard" },
+    },
+    "solid"
+}, {
+    "options_color",
+    "Color",
+    "The color used by the solid pattern.",
+    {
+        { "red", "Red" },
+        { "green", "Green" },
+        { "blue", "Blue" },
+    },
+    "blue"
+}, {
+    "options_tile_size",
+    "Tile size",
+    "The size of the checkerboard tiles in pixels.",
+    range(8, 64, 8),
+    "16"
+})]
+struct OptionsCore {
+    pixels: Vec<u8>,
+    options: PatternOptions,
+    solid_shown: Option<bool>,
+}
+
+retro_core!(OptionsCore {
+    pixels: vec![0; (WIDTH * HEIGHT * 4) as usize],
+    options: PatternOptions::default(),
+    solid_shown: None,
+});
+
+impl OptionsCore {
+    /// Hides the settings of the pattern that is not selected.
+    ///
+    /// Returns `true` if the visibility of any option changed.
+    fn update_visibility(&mut self, ctx: &OptionsChangedContext) -> bool {
+        self.options.refresh(ctx);
+
+        let solid = self.options.options_pattern() == "solid";
+        if self.solid_shown == Some(solid) {
+            return false;
+        }
+
+        self.solid_shown = Some(solid);
+        ctx.set_variable_visible("options_color", solid);
+        ctx.set_variable_visible("options_tile_size", !solid);
+
+        true
+    }
+}
+
+impl Core for OptionsCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo {
+            library_name: CString::new("OptionsCore").unwrap(),
+            library_version: CString::new(env_version!("CARGO_PKG_VERSION").to_string()).unwrap(),
+            valid_extensions: CString::new("").unwrap(),
+
+            need_fullpath: false,
+            block_extract: false,
+        }
+    }
+
+    fn on_set_environment(&mut self, initial: bool, ctx: &mut SetEnvironmentContext) {
+        if !initial {
+            return;
+        }
+
+        let _ = ctx.set_support_no_game(true);
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        retro_system_av_info {
+            geometry: retro_game_geometry {
+                base_width: WIDTH,
+                base_height: HEIGHT,
+                max_width: WIDTH,
+                max_height: HEIGHT,
+                aspect_ratio: 0.0,
+            },
+            timing: retro_system_timing {
+                fps: 60.0,
+                sample_rate: 0.0,
+            },
+        }
+    }
+
+    fn on_options_changed(&mut self, ctx: &mut OptionsChangedContext) {
+        self.update_visibility(ctx);
+    }
+
+    // Called while the menu is open, so the visibility updates immediately
+    fn on_core_options_update_display(&mut self, ctx: &mut OptionsChangedContext) -> bool {
+        self.update_visibility(ctx)
+    }
+
+    fn on_load_game(
+        &mut self,
+        _info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        Ok(())
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
+        let color = match self.options.options_color() {
+            "red" => [0x00, 0x00, 0xFF, 0x00],
+            "green" => [0x00, 0xFF, 0x00, 0x00],
+            _ => [0xFF, 0x00, 0x00, 0x00],
+        };
+
+        let solid = self.options.options_pattern() == "solid";
+        let tile_size = self.options.options_tile_size().max(1) as usize;
+
+        for (index, pixel) in self.pixels.chunks_exact_mut(4).enumerate() {
+            let x = index % WIDTH as usize / tile_size;
+            let y = index / WIDTH as usize / tile_size;
+
+            if solid {
+                pixel.copy_from_slice(&color);
+            } else if (x ^ y) & 1 == 0 {
+                pixel.copy_from_slice(&[0xFF, 0xFF, 0xFF, 0x00]);
+            } else {
+                pixel.copy_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+            }
+        }
+
+        ctx.draw_frame(self.pixels.as_ref(), WIDTH, HEIGHT, WIDTH as usize * 4);
+    }
+}
@@ -0,0 +1,94 @@
+use rust_libretro::{
+    contexts::*,
+    core::{Core, CoreOptions},
+    retro_core,
+    sys::*,
+    types::*,
+};
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+const FPS: f64 = 60.0;
+
+/// The name the frontend uses to look up [`proc_address_set_color`].
+const SET_COLOR: &str = "proc_address_set_color";
+
+/// The color set by the frontend, as `0x00RRGGBB`.
+static COLOR: AtomicU32 = AtomicU32::new(0x00336699);
+
+/// A custom extension function, the frontend can change the fill color with it.
+unsafe extern "C" fn proc_address_set_color(color: u32) {
+    COLOR.store(color & 0x00FF_FFFF, Ordering::Relaxed);
+}
+
+/// Fills the screen with a color that a frontend can change by looking up
+/// the `proc_address_set_color` function, e.g. from a frontend plugin.
+struct ProcAddressCore {
+    pixels: Vec<u8>,
+}
+
+retro_core!(ProcAddressCore {
+    pixels: vec![0; (WIDTH * HEIGHT * 4) as usize],
+});
+
+impl CoreOptions for ProcAddressCore {}
+impl Core for ProcAddressCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo::builder("ProcAddressCore")
+            .version(env!("CARGO_PKG_VERSION"))
+            .build()
+    }
+
+    fn on_set_environment(&mut self, initial: bool, ctx: &mut SetEnvironmentContext) {
+        if !initial {
+            return;
+        }
+
+        let _ = ctx.set_support_no_game(true);
+    }
+
+    fn exports_proc_addresses(&self) -> bool {
+        true
+    }
+
+    fn get_proc_address(&self, sym: &str) -> retro_proc_address_t {
+        match sym {
+            // The frontend has to cast the function back to its actual signature
+            SET_COLOR => Some(unsafe {
+                std::mem::transmute::<unsafe extern "C" fn(u32), unsafe extern "C" fn()>(
+                    proc_address_set_color,
+                )
+            }),
+            _ => None,
+        }
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        SystemAvInfoBuilder::new(WIDTH, HEIGHT).fps(FPS).build()
+    }
+
+    fn on_load_game(
+        &mut self,
+        _info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        Ok(())
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
+        let color = COLOR.load(Ordering::Relaxed).to_ne_bytes();
+
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+
+        ctx.draw_frame(self.pixels.as_ref(), WIDTH, HEIGHT, WIDTH as usize * 4);
+    }
+}
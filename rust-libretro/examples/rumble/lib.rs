@@ -0,0 +1,115 @@
+use rust_libretro::{
+    contexts::*,
+    core::{Core, CoreOptions},
+    env_version, input_descriptors, retro_core,
+    sys::*,
+    types::*,
+};
+use std::{ffi::CString, time::Duration};
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+const INPUT_DESCRIPTORS: &[retro_input_descriptor] = &input_descriptors!(
+    { 0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_A, "Strong rumble" },
+    { 0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_B, "Weak rumble" },
+);
+
+/// Rumbles the first controller while A (strong motor) or B (weak motor) is held.
+struct RumbleCore {
+    pixels: Vec<u8>,
+    strong: bool,
+    weak: bool,
+}
+
+retro_core!(RumbleCore {
+    pixels: vec![0; (WIDTH * HEIGHT * 4) as usize],
+    strong: false,
+    weak: false,
+});
+
+impl CoreOptions for RumbleCore {}
+impl Core for RumbleCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo {
+            library_name: CString::new("RumbleCore").unwrap(),
+            library_version: CString::new(env_version!("CARGO_PKG_VERSION").to_string()).unwrap(),
+            valid_extensions: CString::new("").unwrap(),
+
+            need_fullpath: false,
+            block_extract: false,
+        }
+    }
+
+    fn on_set_environment(&mut self, initial: bool, ctx: &mut SetEnvironmentContext) {
+        if !initial {
+            return;
+        }
+
+        let _ = ctx.set_support_no_game(true);
+    }
+
+    fn on_init(&mut self, ctx: &mut InitContext) {
+        let gctx: GenericContext = ctx.into();
+        let _ = gctx.set_input_descriptors(INPUT_DESCRIPTORS);
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        retro_system_av_info {
+            geometry: retro_game_geometry {
+                base_width: WIDTH,
+                base_height: HEIGHT,
+                max_width: WIDTH,
+                max_height: HEIGHT,
+                aspect_ratio: 0.0,
+            },
+            timing: retro_system_timing {
+                fps: 60.0,
+                sample_rate: 0.0,
+            },
+        }
+    }
+
+    fn on_load_game(
+        &mut self,
+        _info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        if ctx.enable_rumble_interface().is_err() {
+            log::warn!("The frontend does not support rumble");
+        }
+
+        Ok(())
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
+        let input = ctx.get_joypad_state(0, 0);
+        let strong = input.contains(JoypadState::A);
+        let weak = input.contains(JoypadState::B);
+
+        // Only talk to the frontend when the state changes
+        if strong != self.strong {
+            let strength = if strong { 0xFFFF } else { 0 };
+            ctx.set_rumble_state(0, retro_rumble_effect::RETRO_RUMBLE_STRONG, strength);
+            self.strong = strong;
+        }
+
+        if weak != self.weak {
+            let strength = if weak { 0xFFFF } else { 0 };
+            ctx.set_rumble_state(0, retro_rumble_effect::RETRO_RUMBLE_WEAK, strength);
+            self.weak = weak;
+        }
+
+        // Show the motor state: red for strong, blue for weak
+        let red = if strong { 0xFF } else { 0 };
+        let blue = if weak { 0xFF } else { 0 };
+
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[blue, 0, red, 0]);
+        }
+
+        ctx.draw_frame(self.pixels.as_ref(), WIDTH, HEIGHT, WIDTH as usize * 4);
+    }
+}
@@ -0,0 +1,92 @@
+use rust_libretro::{
+    contexts::*,
+    core::{Core, CoreOptions},
+    retro_core,
+    sys::*,
+    types::*,
+};
+use std::time::Duration;
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+const FPS: f64 = 60.0;
+
+/// The number of frames to run before asking the frontend to close the core.
+const FRAMES: u64 = 5 * FPS as u64;
+
+/// Counts down for five seconds and then asks the frontend to close it,
+/// like a game that turns off the emulated system when it ends.
+struct ShutdownCore {
+    pixels: Vec<u8>,
+    shutdown_requested: bool,
+}
+
+retro_core!(ShutdownCore {
+    pixels: vec![0; (WIDTH * HEIGHT * 4) as usize],
+    shutdown_requested: false,
+});
+
+impl CoreOptions for ShutdownCore {}
+impl Core for ShutdownCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo::builder("ShutdownCore")
+            .version(env!("CARGO_PKG_VERSION"))
+            .build()
+    }
+
+    fn on_set_environment(&mut self, initial: bool, ctx: &mut SetEnvironmentContext) {
+        if !initial {
+            return;
+        }
+
+        let _ = ctx.set_support_no_game(true);
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        SystemAvInfoBuilder::new(WIDTH, HEIGHT).fps(FPS).build()
+    }
+
+    fn on_load_game(
+        &mut self,
+        _info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+        self.shutdown_requested = false;
+
+        Ok(())
+    }
+
+    fn on_reset(&mut self, _ctx: &mut ResetContext) {
+        self.shutdown_requested = false;
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
+        let frame = ctx.frame_count();
+
+        // A bar that shrinks until the countdown is over
+        let remaining = FRAMES.saturating_sub(frame) as f64 / FRAMES as f64;
+        let bar_width = (remaining * WIDTH as f64) as usize;
+
+        for (index, pixel) in self.pixels.chunks_exact_mut(4).enumerate() {
+            let value = if index % (WIDTH as usize) < bar_width {
+                0xFF
+            } else {
+                0x20
+            };
+
+            pixel.copy_from_slice(&[value, value, value, 0]);
+        }
+
+        ctx.draw_frame(self.pixels.as_ref(), WIDTH, HEIGHT, WIDTH as usize * 4);
+
+        if frame >= FRAMES && !self.shutdown_requested {
+            self.shutdown_requested = true;
+
+            let gctx: GenericContext = ctx.into();
+            if !gctx.shutdown() {
+                log::warn!("The frontend does not support shutting down the core");
+            }
+        }
+    }
+}
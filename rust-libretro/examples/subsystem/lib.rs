@@ -0,0 +1,148 @@
+use rust_libretro::{
+    contexts::*,
+    core::{Core, CoreOptions},
+    env_version, retro_core,
+    sys::*,
+    types::*,
+};
+use std::{ffi::CString, time::Duration};
+
+const WIDTH: u32 = 256;
+const HEIGHT: u32 = 128;
+
+const SUBSYSTEM_DUAL: u32 = 1;
+
+/// Shows the contents of two ROMs side by side.
+///
+/// Both ROMs are loaded together through the “Dual ROM” subsystem.
+struct SubsystemCore {
+    pixels: Vec<u8>,
+    roms: [Vec<u8>; 2],
+}
+
+retro_core!(SubsystemCore {
+    pixels: vec![0; (WIDTH * HEIGHT * 4) as usize],
+    roms: [Vec::new(), Vec::new()],
+});
+
+fn game_data(game: &GameInfo) -> Vec<u8> {
+    game.data().map(<[u8]>::to_vec).unwrap_or_default()
+}
+
+impl CoreOptions for SubsystemCore {}
+impl Core for SubsystemCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo {
+            library_name: CString::new("SubsystemCore").unwrap(),
+            library_version: CString::new(env_version!("CARGO_PKG_VERSION").to_string()).unwrap(),
+            valid_extensions: CString::new("bin").unwrap(),
+
+            need_fullpath: false,
+            block_extract: false,
+        }
+    }
+
+    fn get_subsystem_info(&self) -> Vec<SubsystemInfo> {
+        let rom = |desc: &str| SubsystemRomInfo {
+            desc: CString::new(desc).unwrap(),
+            valid_extensions: CString::new("bin").unwrap(),
+            need_fullpath: false,
+            block_extract: false,
+            required: true,
+            memory: Vec::new(),
+        };
+
+        vec![SubsystemInfo {
+            desc: CString::new("Dual ROM").unwrap(),
+            ident: CString::new("dual").unwrap(),
+            id: SUBSYSTEM_DUAL,
+            roms: vec![rom("Left ROM"), rom("Right ROM")],
+        }]
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        retro_system_av_info {
+            geometry: retro_game_geometry {
+                base_width: WIDTH,
+                base_height: HEIGHT,
+                max_width: WIDTH,
+                max_height: HEIGHT,
+                aspect_ratio: 0.0,
+            },
+            timing: retro_system_timing {
+                fps: 60.0,
+                sample_rate: 0.0,
+            },
+        }
+    }
+
+    fn on_load_game(
+        &mut self,
+        info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        // Without the subsystem only the left half gets used
+        self.roms = [
+            info.as_ref()
+                .map(GameInfo::new)
+                .map(game_data)
+                .unwrap_or_default(),
+            Vec::new(),
+        ];
+
+        Ok(())
+    }
+
+    fn on_load_game_special(
+        &mut self,
+        game_type: std::os::raw::c_uint,
+        _info: *const retro_game_info,
+        _num_info: usize,
+        ctx: &mut LoadGameSpecialContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let [left, right] = match ctx.games() {
+            [left, right] if game_type == SUBSYSTEM_DUAL => [left, right],
+            games => {
+                return Err(format!(
+                    "Unsupported subsystem {game_type} with {} ROMs",
+                    games.len()
+                )
+                .into())
+            }
+        };
+
+        self.roms = [game_data(left), game_data(right)];
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+
+        log::info!(
+            "Loaded {} and {} bytes",
+            self.roms[0].len(),
+            self.roms[1].len()
+        );
+
+        Ok(())
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
+        let half = (WIDTH / 2) as usize;
+
+        // Every byte becomes a grey pixel, the left half shows the first ROM
+        for (index, pixel) in self.pixels.chunks_exact_mut(4).enumerate() {
+            let x = index % WIDTH as usize;
+            let y = index / WIDTH as usize;
+
+            let (rom, x) = if x < half {
+                (&self.roms[0], x)
+            } else {
+                (&self.roms[1], x - half)
+            };
+
+            let value = rom.get(y * half + x).copied().unwrap_or(0);
+            pixel.copy_from_slice(&[value, value, value, 0]);
+        }
+
+        ctx.draw_frame(self.pixels.as_ref(), WIDTH, HEIGHT, WIDTH as usize * 4);
+    }
+}
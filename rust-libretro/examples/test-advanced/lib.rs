@@ -27,7 +27,7 @@ use rust_libretro::{
     contexts::*, core::Core, env_version, proc::CoreOptions, retro_core, sys::*, types::*,
 };
 use serde::{Deserialize, Serialize, Serializer};
-use std::{char, ffi::CString, fmt::Display};
+use std::{char, ffi::CString, fmt::Display, time::Duration};
 
 const FRAMERATE: f64 = 60.0;
 const SAMPLE_RATE: f64 = 30720.0;
@@ -813,7 +813,7 @@ impl Core for AdvancedTestCore {
             return;
         }
 
-        ctx.set_support_no_game(true);
+        let _ = ctx.set_support_no_game(true);
     }
 
     fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
@@ -834,9 +834,9 @@ impl Core for AdvancedTestCore {
 
     fn on_options_changed(&mut self, ctx: &mut OptionsChangedContext) {
         match ctx.get_variable("test_advanced_pixel_format") {
-            Some("0RGB1555") => self.pixel_format = PixelFormat::XRGB1555,
-            Some("XRGB8888") => self.pixel_format = PixelFormat::XRGB8888,
-            Some("RGB565") => self.pixel_format = PixelFormat::RGB565,
+            Ok(Some("0RGB1555")) => self.pixel_format = PixelFormat::XRGB1555,
+            Ok(Some("XRGB8888")) => self.pixel_format = PixelFormat::XRGB8888,
+            Ok(Some("RGB565")) => self.pixel_format = PixelFormat::RGB565,
             _ => (),
         }
     }
@@ -847,13 +847,13 @@ impl Core for AdvancedTestCore {
         ctx: &mut LoadGameContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
         self.active_pixel_format = self.pixel_format;
-        ctx.set_pixel_format(self.active_pixel_format);
-        ctx.set_performance_level(0);
-        ctx.enable_frame_time_callback((1000000.0f64 / FRAMERATE).round() as retro_usec_t);
+        ctx.set_pixel_format(self.active_pixel_format)?;
+        let _ = ctx.set_performance_level(0);
+        let _ = ctx.enable_frame_time_callback((1000000.0f64 / FRAMERATE).round() as retro_usec_t);
         self.has_perf = ctx.enable_perf_interface().is_ok();
 
         let gctx: GenericContext = ctx.into();
-        gctx.enable_audio_callback();
+        let _ = gctx.enable_audio_callback();
 
         Ok(())
     }
@@ -863,7 +863,7 @@ impl Core for AdvancedTestCore {
     }
 
     #[inline]
-    fn on_run(&mut self, ctx: &mut RunContext, _delta_us: Option<i64>) {
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
         // try to get a software framebuffer from the frontend
         let fb = unsafe {
             ctx.get_current_framebuffer_or_fallback(
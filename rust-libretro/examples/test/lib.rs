@@ -23,7 +23,7 @@ use rust_libretro::{
     contexts::*, core::Core, env_version, input_descriptor, input_descriptors, proc::CoreOptions,
     retro_core, sys::*, types::*,
 };
-use std::ffi::CString;
+use std::{ffi::CString, time::Duration};
 
 const WIDTH: u32 = 320;
 const HEIGHT: u32 = 240;
@@ -515,7 +515,7 @@ impl TestCore {
             },
         ];
 
-        ctx.set_subsystem_info(&[
+        let _ = ctx.set_subsystem_info(&[
             retro_subsystem_info {
                 desc: b"Foo\0".as_ptr() as *const c_char,
                 ident: b"foo\0".as_ptr() as *const c_char,
@@ -574,7 +574,7 @@ impl TestCore {
         ];
 
         let gctx: GenericContext = ctx.into();
-        gctx.set_controller_info(&PORTS);
+        let _ = gctx.set_controller_info(&PORTS);
     }
 }
 
@@ -599,7 +599,7 @@ impl Core for TestCore {
         );
 
         let gctx: GenericContext = ctx.into();
-        gctx.set_input_descriptors(INPUT_DESCRIPTORS);
+        let _ = gctx.set_input_descriptors(INPUT_DESCRIPTORS);
     }
 
     fn on_set_environment(&mut self, initial: bool, ctx: &mut SetEnvironmentContext) {
@@ -607,7 +607,7 @@ impl Core for TestCore {
             return;
         }
 
-        ctx.set_support_no_game(true);
+        let _ = ctx.set_support_no_game(true);
 
         self.set_sub_system_info(ctx);
         self.set_controller_info(ctx);
@@ -622,12 +622,12 @@ impl Core for TestCore {
         _info: Option<retro_game_info>,
         ctx: &mut LoadGameContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if !ctx.set_pixel_format(PixelFormat::XRGB8888) {
+        if ctx.set_pixel_format(PixelFormat::XRGB8888).is_err() {
             return Err("XRGB8888 is not supported".into());
         }
 
-        ctx.set_performance_level(0);
-        ctx.enable_frame_time_callback((1000000.0f64 / 60.0).round() as retro_usec_t);
+        let _ = ctx.set_performance_level(0);
+        let _ = ctx.enable_frame_time_callback((1000000.0f64 / 60.0).round() as retro_usec_t);
 
         match ctx.enable_rumble_interface() {
             Ok(_) => log::info!("Rumble is supported"),
@@ -635,8 +635,8 @@ impl Core for TestCore {
         }
 
         let gctx: GenericContext = ctx.into();
-        gctx.enable_audio_callback();
-        gctx.enable_keyboard_callback();
+        let _ = gctx.enable_audio_callback();
+        let _ = gctx.enable_keyboard_callback();
 
         Ok(())
     }
@@ -673,41 +673,46 @@ impl Core for TestCore {
 
     fn on_options_changed(&mut self, ctx: &mut OptionsChangedContext) {
         match ctx.get_variable("test_aspect") {
-            Some("4:3") => self.aspect = 4.0 / 3.0,
-            Some("16:9") => self.aspect = 16.0 / 9.0,
+            Ok(Some("4:3")) => self.aspect = 4.0 / 3.0,
+            Ok(Some("16:9")) => self.aspect = 16.0 / 9.0,
             _ => (),
         }
 
-        if let Some(value) = ctx.get_variable("test_samplerate") {
+        if let Ok(Some(value)) = ctx.get_variable("test_samplerate") {
             self.sample_rate = value.parse().unwrap()
         }
 
         match ctx.get_variable("test_analog_mouse") {
-            Some("true") => self.analog_mouse = true,
-            Some("false") => self.analog_mouse = false,
+            Ok(Some("true")) => self.analog_mouse = true,
+            Ok(Some("false")) => self.analog_mouse = false,
             _ => (),
         }
 
         match ctx.get_variable("test_analog_mouse_relative") {
-            Some("true") => self.analog_mouse_relative = true,
-            Some("false") => self.analog_mouse_relative = false,
+            Ok(Some("true")) => self.analog_mouse_relative = true,
+            Ok(Some("false")) => self.analog_mouse_relative = false,
             _ => (),
         }
 
         match ctx.get_variable("test_audio_enable") {
-            Some("true") => self.audio_enable = true,
-            Some("false") => self.audio_enable = false,
+            Ok(Some("true")) => self.audio_enable = true,
+            Ok(Some("false")) => self.audio_enable = false,
             _ => (),
         }
     }
 
-    fn on_set_controller_port_device(&mut self, port: u32, device: u32, ctx: &mut GenericContext) {
+    fn on_set_controller_port_device(
+        &mut self,
+        port: u32,
+        device: DeviceType,
+        ctx: &mut SetControllerPortDeviceContext,
+    ) {
         let mut descriptors: [retro_input_descriptor; 6 + 1] =
             unsafe { std::mem::MaybeUninit::zeroed().assume_init() };
 
         match device {
-            RETRO_DEVICE_NONE => (),
-            RETRO_DEVICE_LIGHTGUN => {
+            DeviceType::None => (),
+            DeviceType::Lightgun { subclass: None } => {
                 descriptors[0] = input_descriptor!(
                     port,
                     RETRO_DEVICE_JOYPAD,
@@ -737,7 +742,7 @@ impl Core for TestCore {
                     "Gun Select"
                 );
             }
-            RETRO_DEVICE_JOYPAD => {
+            DeviceType::Joypad { subclass: None } => {
                 descriptors[0] = input_descriptor!(
                     port,
                     RETRO_DEVICE_JOYPAD,
@@ -813,17 +818,17 @@ impl Core for TestCore {
             }
         }
 
-        ctx.set_input_descriptors(&descriptors);
+        let _ = ctx.set_input_descriptors(&descriptors);
     }
 
     #[inline]
-    fn on_run(&mut self, ctx: &mut RunContext, _delta_us: Option<i64>) {
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
         if self.last_samplerate != self.sample_rate {
             log::info!("Changing sample rate to {}", self.sample_rate);
-            ctx.set_system_av_info(self.get_av_info());
+            let _ = ctx.set_system_av_info(self.get_av_info());
         } else if self.last_aspect != self.aspect {
             log::info!("Changing aspect ratio to {}", self.aspect);
-            ctx.set_game_geometry(self.get_av_info().geometry);
+            let _ = ctx.set_game_geometry(self.get_av_info().geometry);
         }
 
         self.update_input(ctx);
@@ -854,14 +859,8 @@ impl Core for TestCore {
         ctx.batch_audio_samples(&samples);
     }
 
-    fn on_keyboard_event(
-        &mut self,
-        down: bool,
-        keycode: retro_key,
-        character: u32,
-        key_modifiers: retro_mod,
-    ) {
-        log::info!("Keyboard:\n\tDown: {down}\n\tCode: {keycode:?}\n\tChar: {character}\n\tMod: {key_modifiers:?}");
+    fn on_keyboard_event(&mut self, down: bool, key: Key, character: u32, key_modifiers: KeyMods) {
+        log::info!("Keyboard:\n\tDown: {down}\n\tKey: {key:?}\n\tChar: {character}\n\tMod: {key_modifiers:?}");
     }
 
     fn get_serialize_size(&mut self, _ctx: &mut GetSerializeSizeContext) -> usize {
@@ -36,6 +36,7 @@ use rust_libretro::{
 use std::{
     ffi::CString,
     ptr::{null, null_mut},
+    time::Duration,
 };
 use vk_shader_macros::include_glsl;
 
@@ -911,12 +912,12 @@ impl Core for TestCore {
             return;
         }
 
-        ctx.set_support_no_game(true);
+        let _ = ctx.set_support_no_game(true);
     }
 
     fn on_options_changed(&mut self, ctx: &mut OptionsChangedContext) {
         match ctx.get_variable("testvulkan_resolution") {
-            Some(value) => {
+            Ok(Some(value)) => {
                 let dimensions = value
                     .split('x')
                     .map(|x| x.parse::<u16>().unwrap())
@@ -941,58 +942,48 @@ impl Core for TestCore {
         ctx: &mut LoadGameContext,
     ) -> Result<(), Box<dyn std::error::Error>> {
         unsafe {
-            let enabled = ctx.enable_hw_render(
-                retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN,
-                false,
-                VK_API_VERSION,
-                0,
-                false,
-            );
-
-            if !enabled {
+            if ctx
+                .enable_hw_render(
+                    retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN,
+                    false,
+                    VK_API_VERSION,
+                    0,
+                    false,
+                )
+                .is_err()
+            {
                 return Err("Failed to enable Vulkan context".into());
             }
+        }
 
-            let success = ctx.enable_hw_render_negotiation_interface_vulkan(
-                Some(Self::get_application_info),
-                None,
-                None,
-            );
+        let result = unsafe {
+            ctx.set_vulkan_negotiation_interface(VulkanNegotiationInterface {
+                get_application_info: Some(Self::get_application_info),
+                ..Default::default()
+            })
+        };
 
-            if !success {
-                log::warn!("Failed to set hardware context negotiation interface");
-            }
+        if result.is_err() {
+            log::warn!("Failed to set hardware context negotiation interface");
         }
 
         Ok(())
     }
 
-    fn on_hw_context_reset(&mut self, ctx: &mut GenericContext) {
+    fn on_hw_context_reset(&mut self, ctx: &mut HwContext) {
         log::info!("on_hw_context_reset");
 
         self.vulkan.take();
-        let iface = unsafe { ctx.get_hw_render_interface_vulkan() };
-
-        if let Some(iface) = iface {
-            if iface.interface_type
-                != retro_hw_render_interface_type::RETRO_HW_RENDER_INTERFACE_VULKAN
-            {
-                log::error!(
-                    "Unexpected hardware interface type: {:?}",
-                    iface.interface_type as u32
-                );
-                return;
-            }
+        let iface = unsafe { ctx.vulkan_interface() };
 
-            if iface.interface_version != RETRO_HW_RENDER_INTERFACE_VULKAN_VERSION {
-                log::error!(
-                    "Unexpected hardware interface version {}, got version {}",
-                    RETRO_HW_RENDER_INTERFACE_VULKAN_VERSION,
-                    iface.interface_version
-                );
-                return;
-            }
+        if iface.is_none() {
+            log::error!(
+                "Expected a Vulkan rendering interface of version {}",
+                RETRO_HW_RENDER_INTERFACE_VULKAN_VERSION
+            );
+        }
 
+        if let Some(iface) = iface {
             if iface.get_instance_proc_addr.is_none() {
                 log::error!("Invalid function pointer to \"get_instance_proc_addr\"");
                 return;
@@ -1015,7 +1006,7 @@ impl Core for TestCore {
         }
     }
 
-    fn on_hw_context_destroyed(&mut self, _ctx: &mut GenericContext) {
+    fn on_hw_context_destroyed(&mut self, _ctx: &mut HwContext) {
         log::info!("on_hw_context_destroyed");
 
         self.deinit();
@@ -1023,7 +1014,7 @@ impl Core for TestCore {
         self.vk = Default::default();
     }
 
-    fn on_run(&mut self, ctx: &mut RunContext, _delta_us: Option<i64>) {
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<Duration>) {
         if let Some(vulkan) = self.vulkan.take() {
             let handle = vulkan.handle;
 
@@ -4,14 +4,14 @@
 )]
 
 use proc_macro::{self, TokenStream};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
 use rust_libretro_sys::RETRO_NUM_CORE_OPTION_VALUES_MAX;
 use syn::{
     braced, parenthesized,
     parse::{discouraged::Speculative, Parse, ParseStream, Result},
     parse2, parse_macro_input, parse_quote,
     punctuated::Punctuated,
-    Attribute, DeriveInput, LitByteStr, LitStr, NestedMeta, Token,
+    Attribute, DeriveInput, Ident, LitByteStr, LitStr, NestedMeta, Token,
 };
 
 mod util;
@@ -52,12 +52,179 @@ impl Parse for CoreOptionValue {
     }
 }
 
+/// A number used in `range(min, max, step)`.
+#[derive(Debug)]
+struct RangeNumber {
+    value: f64,
+    decimals: usize,
+    is_float: bool,
+    span: proc_macro2::Span,
+}
+
+impl Parse for RangeNumber {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let negative = input.parse::<Option<Token![-]>>()?.is_some();
+        let lit: syn::Lit = input.parse()?;
+
+        let (digits, is_float) = match lit {
+            syn::Lit::Int(ref lit) => (lit.base10_digits(), false),
+            syn::Lit::Float(ref lit) => (lit.base10_digits(), true),
+            _ => return Err(syn::Error::new(lit.span(), "Expected a number")),
+        };
+
+        let value = digits
+            .parse::<f64>()
+            .map_err(|err| syn::Error::new(lit.span(), err))?;
+        let decimals = digits
+            .split_once('.')
+            .map_or(0, |(_, decimals)| decimals.len());
+
+        Ok(Self {
+            value: if negative { -value } else { value },
+            decimals,
+            is_float,
+            span: lit.span(),
+        })
+    }
+}
+
+/// A numeric option declared as `range(min, max, step)` instead of a list of values.
+#[derive(Debug)]
+struct CoreOptionRange {
+    span: proc_macro2::Span,
+    min: RangeNumber,
+    max: RangeNumber,
+    step: RangeNumber,
+}
+
+impl Parse for CoreOptionRange {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        if ident != "range" {
+            return Err(syn::Error::new(
+                ident.span(),
+                "Expected a list of values or `range(min, max, step)`",
+            ));
+        }
+
+        let content;
+        parenthesized!(content in input);
+
+        let min = content.parse()?;
+        content.parse::<Token![,]>()?;
+
+        let max = content.parse()?;
+        content.parse::<Token![,]>()?;
+
+        let step = content.parse()?;
+
+        // allow trailing comma
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+
+        Ok(Self {
+            span: ident.span(),
+            min,
+            max,
+            step,
+        })
+    }
+}
+
+impl CoreOptionRange {
+    /// Lists every value of the range, formatted with as many decimals as its bounds and step.
+    fn values(&self) -> Result<Vec<CoreOptionValue>> {
+        let Self {
+            span,
+            min,
+            max,
+            step,
+        } = self;
+
+        if min.value >= max.value {
+            return Err(syn::Error::new(
+                max.span,
+                "The maximum of a range must be greater than its minimum",
+            ));
+        }
+
+        if step.value <= 0.0 {
+            return Err(syn::Error::new(
+                step.span,
+                "The step of a range must be greater than zero",
+            ));
+        }
+
+        let steps = (max.value - min.value) / step.value;
+        if (steps - steps.round()).abs() > 1e-6 {
+            return Err(syn::Error::new(
+                step.span,
+                "The step of a range must divide the distance between its minimum and maximum",
+            ));
+        }
+
+        let count = steps.round() as usize + 1;
+        let max_count = RETRO_NUM_CORE_OPTION_VALUES_MAX as usize - 1;
+        if count > max_count {
+            return Err(syn::Error::new(
+                *span,
+                format!(
+                    "A range must not have more than {max_count} values, but this one has {count}"
+                ),
+            ));
+        }
+
+        let is_float = min.is_float || max.is_float || step.is_float;
+        let decimals = min.decimals.max(max.decimals).max(step.decimals);
+
+        Ok((0..count)
+            .map(|index| {
+                let value = min.value + step.value * index as f64;
+                let value = if is_float {
+                    format!("{value:.decimals$}")
+                } else {
+                    format!("{}", value.round() as i64)
+                };
+
+                CoreOptionValue {
+                    value: LitStr::new(&value, *span),
+                    label: None,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Parses either a braced list of values or a `range(min, max, step)`.
+fn parse_values(input: ParseStream) -> Result<(Vec<CoreOptionValue>, Option<CoreOptionRange>)> {
+    if input.peek(Ident) {
+        return Ok((Vec::new(), Some(input.parse()?)));
+    }
+
+    let content;
+    braced!(content in input);
+
+    let mut values = Vec::new();
+    while !content.is_empty() {
+        let value = content.parse::<CoreOptionValue>()?;
+        values.push(value);
+
+        if !content.is_empty() {
+            content.parse::<Token![,]>()?;
+        }
+    }
+
+    Ok((values, None))
+}
+
 #[derive(Debug)]
 struct CoreOption {
     key: LitStr,
     desc: LitStr,
     info: LitStr,
     values: Vec<CoreOptionValue>,
+    range: Option<CoreOptionRange>,
     default_value: Option<LitStr>,
 }
 
@@ -72,8 +239,7 @@ impl Parse for CoreOption {
         let info: LitStr = input.parse()?;
         input.parse::<Token![,]>()?;
 
-        let options_content;
-        braced!(options_content in input);
+        let (values, range) = parse_values(input)?;
 
         let default_value: Option<LitStr> = if !input.is_empty() {
             input.parse::<Token![,]>()?;
@@ -82,21 +248,12 @@ impl Parse for CoreOption {
             None
         };
 
-        let mut values = Vec::new();
-        while !options_content.is_empty() {
-            let value = options_content.parse::<CoreOptionValue>()?;
-            values.push(value);
-
-            if !options_content.is_empty() {
-                options_content.parse::<Token![,]>()?;
-            }
-        }
-
         Ok(Self {
             key,
             desc,
             info,
             values,
+            range,
             default_value,
         })
     }
@@ -111,6 +268,7 @@ struct CoreOptionV2 {
     info_categorized: Option<LitStr>,
     category_key: Option<LitStr>,
     values: Vec<CoreOptionValue>,
+    range: Option<CoreOptionRange>,
     default_value: Option<LitStr>,
 }
 
@@ -134,8 +292,7 @@ impl Parse for CoreOptionV2 {
         let category_key: LitStr = input.parse()?;
         input.parse::<Token![,]>()?;
 
-        let options_content;
-        braced!(options_content in input);
+        let (values, range) = parse_values(input)?;
 
         if !input.is_empty() {
             input.parse::<Token![,]>()?;
@@ -147,16 +304,6 @@ impl Parse for CoreOptionV2 {
             None
         };
 
-        let mut values = Vec::new();
-        while !options_content.is_empty() {
-            let value = options_content.parse::<CoreOptionValue>()?;
-            values.push(value);
-
-            if !options_content.is_empty() {
-                options_content.parse::<Token![,]>()?;
-            }
-        }
-
         let ret = Ok(Self {
             key,
             desc,
@@ -165,6 +312,7 @@ impl Parse for CoreOptionV2 {
             info_categorized: Some(info_categorized),
             category_key: Some(category_key),
             values,
+            range,
             default_value,
         });
 
@@ -178,6 +326,153 @@ impl Parse for CoreOptionV2 {
     }
 }
 
+/// Values that map to `true` and `false` in the accessor generated by `#[typed_options(...)]`.
+const TRUE_VALUES: &[&str] = &["true", "enabled", "on"];
+const FALSE_VALUES: &[&str] = &["false", "disabled", "off"];
+
+/// The Rust type of a core option in the accessor generated by `#[typed_options(...)]`.
+enum CoreOptionKind {
+    /// Every value is a toggle, the vector holds the state of each value.
+    Bool(Vec<bool>),
+
+    /// Every value is an integer.
+    Int(Vec<i32>),
+
+    /// Every value is a number, but not all of them are integers.
+    Float(Vec<f32>),
+
+    /// Any other list of values, exposed as `&'static str`.
+    Choice,
+}
+
+impl CoreOptionV2 {
+    fn kind(&self) -> CoreOptionKind {
+        let values = self
+            .values
+            .iter()
+            .map(|value| value.value.value())
+            .collect::<Vec<_>>();
+
+        let bools = values
+            .iter()
+            .map(|value| {
+                if TRUE_VALUES.contains(&value.as_str()) {
+                    Some(true)
+                } else if FALSE_VALUES.contains(&value.as_str()) {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+            .collect::<Option<Vec<_>>>();
+
+        if let Some(bools) = bools {
+            if bools.contains(&true) && bools.contains(&false) {
+                return CoreOptionKind::Bool(bools);
+            }
+        }
+
+        if let Ok(ints) = values
+            .iter()
+            .map(|value| value.parse::<i32>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+        {
+            if !ints.is_empty() {
+                return CoreOptionKind::Int(ints);
+            }
+        }
+
+        if let Ok(floats) = values
+            .iter()
+            .map(|value| value.parse::<f32>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+        {
+            if !floats.is_empty() {
+                return CoreOptionKind::Float(floats);
+            }
+        }
+
+        CoreOptionKind::Choice
+    }
+
+    /// Replaces a `range(min, max, step)` with the list of values it describes.
+    ///
+    /// Numeric default values are matched by value, so `"1.0"` selects the value `"1.00"`.
+    fn expand_range(&mut self) -> Result<()> {
+        let range = match self.range.take() {
+            Some(range) => range,
+            None => return Ok(()),
+        };
+
+        self.values = range.values()?;
+
+        if let Some(ref default_value) = self.default_value {
+            if let Ok(default) = default_value.value().parse::<f64>() {
+                let value = self.values.iter().find(|value| {
+                    value
+                        .value
+                        .value()
+                        .parse::<f64>()
+                        .is_ok_and(|value| (value - default).abs() < 1e-9)
+                });
+
+                if let Some(value) = value {
+                    self.default_value =
+                        Some(LitStr::new(&value.value.value(), default_value.span()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of the default value, which is the first value if none was given.
+    fn default_index(&self) -> Result<usize> {
+        match self.default_value {
+            Some(ref default_value) => self
+                .values
+                .iter()
+                .position(|value| value.value.value() == default_value.value())
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        default_value.span(),
+                        format!(
+                            "Default value “{}” is not a value of core option “{}”",
+                            default_value.value(),
+                            self.key.value()
+                        ),
+                    )
+                }),
+            None => Ok(0),
+        }
+    }
+
+    /// Turns the option key into a valid identifier, e.g. `foo-bar` into `foo_bar`.
+    fn ident(&self) -> Ident {
+        let mut name = self
+            .key
+            .value()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            name.insert(0, '_');
+        }
+
+        match syn::parse_str::<Ident>(&name) {
+            Ok(_) => Ident::new(&name, self.key.span()),
+            Err(_) => Ident::new_raw(&name, self.key.span()),
+        }
+    }
+}
+
 impl From<CoreOption> for CoreOptionV2 {
     fn from(option: CoreOption) -> Self {
         Self {
@@ -188,6 +483,7 @@ impl From<CoreOption> for CoreOptionV2 {
             info_categorized: None,
             category_key: None,
             values: option.values,
+            range: option.range,
             default_value: option.default_value,
         }
     }
@@ -201,6 +497,12 @@ impl Parse for CoreOptions {
         let input;
         parenthesized!(input in outer);
 
+        Self::parse_list(&input)
+    }
+}
+
+impl CoreOptions {
+    fn parse_list(input: ParseStream) -> Result<Self> {
         let mut options = Self::default();
 
         while !input.is_empty() {
@@ -230,6 +532,12 @@ impl Parse for CoreOptions {
     }
 }
 
+impl CoreOptions {
+    fn expand_ranges(&mut self) -> Result<()> {
+        self.0.iter_mut().try_for_each(CoreOptionV2::expand_range)
+    }
+}
+
 impl Concat<CoreOptions> for Vec<CoreOptions> {
     fn concat(self) -> CoreOptions {
         CoreOptions(self.into_iter().flat_map(|x| x.0).collect::<Vec<_>>())
@@ -273,6 +581,12 @@ impl Parse for CoreOptionCategories {
         let input;
         parenthesized!(input in outer);
 
+        Self::parse_list(&input)
+    }
+}
+
+impl CoreOptionCategories {
+    fn parse_list(input: ParseStream) -> Result<Self> {
         let mut categories = Self::default();
 
         while !input.is_empty() {
@@ -300,6 +614,193 @@ impl Concat<CoreOptionCategories> for Vec<CoreOptionCategories> {
     }
 }
 
+/// Translated core options or categories, prefixed by a `retro_language` variant.
+#[derive(Debug)]
+struct Localized<T> {
+    language: Ident,
+    items: T,
+}
+
+impl Parse for Localized<CoreOptions> {
+    fn parse(outer: ParseStream) -> Result<Self> {
+        let input;
+        parenthesized!(input in outer);
+
+        let language = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        Ok(Self {
+            language,
+            items: CoreOptions::parse_list(&input)?,
+        })
+    }
+}
+
+impl Parse for Localized<CoreOptionCategories> {
+    fn parse(outer: ParseStream) -> Result<Self> {
+        let input;
+        parenthesized!(input in outer);
+
+        let language = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        Ok(Self {
+            language,
+            items: CoreOptionCategories::parse_list(&input)?,
+        })
+    }
+}
+
+/// All translations for a single `retro_language`.
+#[derive(Debug)]
+struct Translation {
+    language: Ident,
+    options: CoreOptions,
+    categories: CoreOptionCategories,
+}
+
+/// Generates the accessor struct requested with `#[typed_options(...)]`.
+fn get_typed_options(
+    vis: &syn::Visibility,
+    name: &Ident,
+    accessor: &Ident,
+    options: &CoreOptions,
+) -> Result<proc_macro2::TokenStream> {
+    let mut fields = Vec::new();
+    let mut types = Vec::new();
+    let mut defaults = Vec::new();
+    let mut keys = Vec::new();
+    let mut parsers = Vec::new();
+    let mut docs = Vec::new();
+
+    for option in &options.0 {
+        if option.values.is_empty() {
+            return Err(syn::Error::new(
+                option.key.span(),
+                format!("Core option “{}” has no values", option.key.value()),
+            ));
+        }
+
+        let default_index = option.default_index()?;
+        let values = option.values.iter().map(|value| &value.value);
+
+        let (ty, default, parser) = match option.kind() {
+            CoreOptionKind::Bool(bools) => {
+                let default = bools[default_index];
+
+                (
+                    quote! { bool },
+                    quote! { #default },
+                    quote! {
+                        match value {
+                            #(#values => Some(#bools),)*
+                            _ => None,
+                        }
+                    },
+                )
+            }
+            CoreOptionKind::Int(ints) => {
+                let default = ints[default_index];
+
+                (
+                    quote! { i32 },
+                    quote! { #default },
+                    quote! { value.parse::<i32>().ok() },
+                )
+            }
+            CoreOptionKind::Float(floats) => {
+                let default = floats[default_index];
+
+                (
+                    quote! { f32 },
+                    quote! { #default },
+                    quote! { value.parse::<f32>().ok() },
+                )
+            }
+            CoreOptionKind::Choice => {
+                let default = &option.values[default_index].value;
+                let results = option.values.iter().map(|value| &value.value);
+
+                (
+                    quote! { &'static str },
+                    quote! { #default },
+                    quote! {
+                        match value {
+                            #(#values => Some(#results),)*
+                            _ => None,
+                        }
+                    },
+                )
+            }
+        };
+
+        fields.push(option.ident());
+        types.push(ty);
+        defaults.push(default);
+        keys.push(&option.key);
+        parsers.push(parser);
+        docs.push(format!(
+            "{} (`{}`)",
+            option.desc.value(),
+            option.key.value()
+        ));
+    }
+
+    let struct_doc = format!(
+        "The current values of the core options of [`{name}`], see [`{accessor}::refresh`]."
+    );
+
+    Ok(quote! {
+        #[doc = #struct_doc]
+        #[derive(Debug, Clone, PartialEq)]
+        #vis struct #accessor {
+            #(#fields: #types,)*
+        }
+
+        impl ::core::default::Default for #accessor {
+            fn default() -> Self {
+                Self {
+                    #(#fields: #defaults,)*
+                }
+            }
+        }
+
+        #[allow(dead_code)]
+        impl #accessor {
+            #(
+                #[doc = #docs]
+                #vis fn #fields(&self) -> #types {
+                    self.#fields
+                }
+            )*
+
+            /// Reads the current option values from the frontend.
+            ///
+            /// Options that the frontend does not report or that have an unknown value keep
+            /// their previous value. Returns `true` if any value changed.
+            #[allow(unused_mut, unused_variables)]
+            #vis fn refresh<'a, C: ::core::convert::Into<::rust_libretro::contexts::GenericContext<'a>>>(
+                &mut self,
+                ctx: C,
+            ) -> bool {
+                let mut ctx: ::rust_libretro::contexts::GenericContext = ctx.into();
+                let mut changed = false;
+
+                #(
+                    if let Ok(Some(value)) = ctx.get_variable(#keys) {
+                        if let Some(value) = #parsers {
+                            changed |= self.#fields != value;
+                            self.#fields = value;
+                        }
+                    }
+                )*
+
+                changed
+            }
+        }
+    })
+}
+
 /// Implements the CoreOptions trait by generating a `set_core_options()` implementation
 /// that checks whether the frontend supports “options v2” or “options v1”
 /// and uses `retro_variable`s as fallback.
@@ -338,10 +839,124 @@ impl Concat<CoreOptionCategories> for Vec<CoreOptionCategories> {
 /// struct TestCore;
 /// ```
 ///
-/// **TODO**:
-/// - Add V2 (category support) documentation
-/// - Support `*_intl` variants
-#[proc_macro_derive(CoreOptions, attributes(options, categories))]
+/// Options can be grouped into categories, which frontends supporting “options v2”
+/// display as submenus. Categories are declared with `#[categories(...)]` as
+/// `{ key, description, info }` and options reference them by key.
+/// A categorized option additionally takes the description and info text
+/// shown inside its category, followed by the category key:
+///
+/// ```ignore
+/// #[derive(CoreOptions)]
+/// #[categories({
+///     "video_settings",
+///     "Video",
+///     "Options related to video output."
+/// })]
+/// #[options({
+///     "foo_aspect",
+///     "Video > Aspect Ratio",
+///     "Aspect Ratio",
+///     "Setting 'Video > Aspect Ratio' forces the aspect ratio to either 4:3 or 16:9.",
+///     "Setting 'Aspect Ratio' forces the aspect ratio to either 4:3 or 16:9.",
+///     "video_settings",
+///     {
+///         { "4:3" },
+///         { "16:9" },
+///     },
+///     "4:3"
+/// })]
+/// struct TestCore;
+/// ```
+///
+/// Frontends that only support “options v1” or the legacy variables API
+/// receive a flat list that uses the uncategorized descriptions.
+///
+/// Translations are declared with `#[options_intl(...)]` and `#[categories_intl(...)]`,
+/// which take a `retro_language` variant followed by the same entries as
+/// `#[options(...)]` and `#[categories(...)]`. Keys must match the US English definitions,
+/// entries that have not been translated may be omitted.
+/// If the frontend reports one of these languages, the options are registered with
+/// `RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL` or `RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL`,
+/// otherwise only the US English definitions are used.
+///
+/// ```ignore
+/// #[derive(CoreOptions)]
+/// #[options({
+///     "foo_option_1",
+///     "Speed hack coprocessor X",
+///     "Provides increased performance at the expense of reduced accuracy",
+///     {
+///         { "false" },
+///         { "true" },
+///     },
+///     "false"
+/// })]
+/// #[options_intl(RETRO_LANGUAGE_GERMAN, {
+///     "foo_option_1",
+///     "Geschwindigkeits-Hack für Koprozessor X",
+///     "Erhöht die Geschwindigkeit auf Kosten der Genauigkeit",
+///     {
+///         { "false", "Aus" },
+///         { "true", "An" },
+///     },
+/// })]
+/// struct TestCore;
+/// ```
+///
+/// Numeric options can declare `range(min, max, step)` instead of a list of values,
+/// which expands to every value from `min` to `max`, e.g. `"100"`, `"110"`, …, `"400"`:
+///
+/// ```ignore
+/// #[derive(CoreOptions)]
+/// #[options({
+///     "foo_overclock",
+///     "CPU Overclock (%)",
+///     "Runs the emulated CPU faster than the original hardware.",
+///     range(100, 400, 10),
+///     "100"
+/// })]
+/// struct TestCore;
+/// ```
+///
+/// Ranges are validated at compile time: `min` must be less than `max`, `step` must divide
+/// the distance between them, and the range must not exceed the maximum number of values.
+///
+/// `#[typed_options(Name)]` additionally generates a struct `Name` with a getter per option,
+/// named after the option key, and a `refresh(ctx)` method that reads the current values.
+/// Options whose values are toggles like `"false"`/`"true"` or `"disabled"`/`"enabled"`
+/// are returned as `bool`, options with only integer values as `i32`, other numeric options
+/// as `f32` and all other options as the matching `&'static str` from the list of values.
+/// Default values that are not in the list of values are rejected at compile time.
+///
+/// ```ignore
+/// #[derive(CoreOptions)]
+/// #[typed_options(TestCoreOptions)]
+/// #[options({
+///     "foo_turbo",
+///     "Turbo",
+///     "Runs the emulated CPU faster.",
+///     {
+///         { "disabled" },
+///         { "enabled" },
+///     },
+///     "disabled"
+/// })]
+/// struct TestCore {
+///     options: TestCoreOptions,
+/// }
+///
+/// impl Core for TestCore {
+///     fn on_options_changed(&mut self, ctx: &mut OptionsChangedContext) {
+///         self.options.refresh(ctx);
+///
+///         let turbo: bool = self.options.foo_turbo();
+///     }
+/// }
+/// ```
+#[proc_macro_derive(
+    CoreOptions,
+    attributes(options, categories, options_intl, categories_intl, typed_options)
+)]
 pub fn derive_core_options(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -359,11 +974,35 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
         .map(|attr| -> Result<CoreOptions> { parse2(attr.tokens.clone()) })
         .collect::<Result<Vec<_>>>();
 
-    let options = match options {
+    let mut options = match options {
         Ok(options) => options.concat(),
         Err(err) => return TokenStream::from(err.to_compile_error()),
     };
 
+    if let Err(err) = options.expand_ranges() {
+        return TokenStream::from(err.to_compile_error());
+    }
+
+    for option in &options.0 {
+        if let Err(err) = option.default_index() {
+            return TokenStream::from(err.to_compile_error());
+        }
+    }
+
+    let typed_options = match attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("typed_options"))
+        .map(|attr| attr.parse_args::<Ident>())
+        .transpose()
+        .and_then(|accessor| {
+            accessor
+                .map(|accessor| get_typed_options(&input.vis, name, &accessor, &options))
+                .transpose()
+        }) {
+        Ok(typed_options) => typed_options,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
     let categories = attrs
         .iter()
         .filter(|attr| attr.path.is_ident("categories"))
@@ -375,6 +1014,126 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
         Err(err) => return TokenStream::from(err.to_compile_error()),
     };
 
+    let localized_options = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("options_intl"))
+        .map(|attr| -> Result<Localized<CoreOptions>> { parse2(attr.tokens.clone()) })
+        .collect::<Result<Vec<_>>>();
+
+    let mut localized_options = match localized_options {
+        Ok(localized_options) => localized_options,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
+    for localized in &mut localized_options {
+        if let Err(err) = localized.items.expand_ranges() {
+            return TokenStream::from(err.to_compile_error());
+        }
+    }
+
+    let localized_categories = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("categories_intl"))
+        .map(|attr| -> Result<Localized<CoreOptionCategories>> { parse2(attr.tokens.clone()) })
+        .collect::<Result<Vec<_>>>();
+
+    let localized_categories = match localized_categories {
+        Ok(localized_categories) => localized_categories,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
+    // Group the translations by language, multiple attributes for the same language get merged
+    let mut translations: Vec<Translation> = Vec::new();
+
+    fn get_translation<'a>(
+        translations: &'a mut Vec<Translation>,
+        language: &Ident,
+    ) -> &'a mut Translation {
+        let index = match translations
+            .iter()
+            .position(|translation| &translation.language == language)
+        {
+            Some(index) => index,
+            None => {
+                translations.push(Translation {
+                    language: language.clone(),
+                    options: CoreOptions::default(),
+                    categories: CoreOptionCategories::default(),
+                });
+
+                translations.len() - 1
+            }
+        };
+
+        &mut translations[index]
+    }
+
+    for localized in localized_options {
+        let translation = get_translation(&mut translations, &localized.language);
+        translation.options.0.extend(localized.items.0);
+    }
+
+    for localized in localized_categories {
+        let translation = get_translation(&mut translations, &localized.language);
+        translation.categories.0.extend(localized.items.0);
+    }
+
+    for translation in &translations {
+        for option in &translation.options.0 {
+            let key = option.key.value();
+
+            if !options.0.iter().any(|option| option.key.value() == key) {
+                return TokenStream::from(
+                    syn::Error::new(option.key.span(), format!("Unknown core option “{key}”"))
+                        .to_compile_error(),
+                );
+            }
+        }
+
+        for category in &translation.categories.0 {
+            let key = category.key.value();
+
+            if !categories
+                .0
+                .iter()
+                .any(|category| category.key.value() == key)
+            {
+                return TokenStream::from(
+                    syn::Error::new(
+                        category.key.span(),
+                        format!("Unknown option category “{key}”"),
+                    )
+                    .to_compile_error(),
+                );
+            }
+        }
+    }
+
+    for option in options.0.iter().chain(
+        translations
+            .iter()
+            .flat_map(|translation| &translation.options.0),
+    ) {
+        if let Some(ref category_key) = option.category_key {
+            let key = category_key.value();
+
+            if !key.is_empty()
+                && !categories
+                    .0
+                    .iter()
+                    .any(|category| category.key.value() == key)
+            {
+                return TokenStream::from(
+                    syn::Error::new(
+                        category_key.span(),
+                        format!("Unknown option category “{key}”"),
+                    )
+                    .to_compile_error(),
+                );
+            }
+        }
+    }
+
     let option_count = options.0.len();
     let category_count = categories.0.len();
 
@@ -432,6 +1191,21 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
         }
     }
 
+    fn optional_lit_str(lit: Option<&LitStr>) -> proc_macro2::TokenStream {
+        match lit {
+            Some(lit) if !lit.value().is_empty() => {
+                let lit = lit_byte_str(lit);
+
+                quote! {
+                    #lit as *const u8 as *const libc::c_char
+                }
+            }
+            _ => quote! {
+                0 as *const libc::c_char
+            },
+        }
+    }
+
     fn get_option_default_value(option: &CoreOptionV2) -> proc_macro2::TokenStream {
         if let Some(ref default_value) = option.default_value {
             let default_value = lit_byte_str(default_value);
@@ -446,27 +1220,29 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
         }
     }
 
-    let core_options = options
-        .0
-        .iter()
-        .map(|option| {
-            let key = lit_byte_str(&option.key);
-            let desc = lit_byte_str(&option.desc);
-            let info = lit_byte_str(&option.info);
-            let values = get_option_values(option);
-            let default_value = get_option_default_value(option);
+    fn get_option_definitions(options: &CoreOptions) -> Vec<proc_macro2::TokenStream> {
+        options
+            .0
+            .iter()
+            .map(|option| {
+                let key = lit_byte_str(&option.key);
+                let desc = lit_byte_str(&option.desc);
+                let info = lit_byte_str(&option.info);
+                let values = get_option_values(option);
+                let default_value = get_option_default_value(option);
 
-            quote! {
-                retro_core_option_definition {
-                    key:    #key  as *const u8 as *const libc::c_char,
-                    desc:   #desc as *const u8 as *const libc::c_char,
-                    info:   #info as *const u8 as *const libc::c_char,
-                    values: #values,
-                    default_value: #default_value,
+                quote! {
+                        retro_core_option_definition {
+                        key:    #key  as *const u8 as *const libc::c_char,
+                        desc:   #desc as *const u8 as *const libc::c_char,
+                        info:   #info as *const u8 as *const libc::c_char,
+                        values: #values,
+                        default_value: #default_value,
+                    }
                 }
-            }
-        })
-        .collect::<Vec<_>>();
+            })
+            .collect::<Vec<_>>()
+    }
 
     let core_variables = options
         .0
@@ -496,83 +1272,221 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
         })
         .collect::<Vec<_>>();
 
-    let core_options_v2 = options
-        .0
-        .iter()
-        .map(|option| {
-            let key = lit_byte_str(&option.key);
-            let desc = lit_byte_str(&option.desc);
-            let info = lit_byte_str(&option.info);
-            let values = get_option_values(option);
-            let default_value = get_option_default_value(option);
+    fn get_option_v2_definitions(options: &CoreOptions) -> Vec<proc_macro2::TokenStream> {
+        options
+            .0
+            .iter()
+            .map(|option| {
+                let key = lit_byte_str(&option.key);
+                let desc = lit_byte_str(&option.desc);
+                let info = lit_byte_str(&option.info);
+                let values = get_option_values(option);
+                let default_value = get_option_default_value(option);
+
+                // Uncategorized options must pass NULL pointers, so frontends fall back to `desc` and `info`
+                let desc_categorized = optional_lit_str(option.desc_categorized.as_ref());
+                let info_categorized = optional_lit_str(option.info_categorized.as_ref());
+                let category_key = optional_lit_str(option.category_key.as_ref());
 
-            let desc_categorized = lit_byte_str(
-                option
-                    .desc_categorized
-                    .as_ref()
-                    .unwrap_or(&LitStr::new("", proc_macro2::Span::call_site())),
-            );
-            let info_categorized = lit_byte_str(
-                option
-                    .info_categorized
-                    .as_ref()
-                    .unwrap_or(&LitStr::new("", proc_macro2::Span::call_site())),
-            );
-            let category_key = lit_byte_str(
-                option
-                    .category_key
-                    .as_ref()
-                    .unwrap_or(&LitStr::new("", proc_macro2::Span::call_site())),
-            );
+                quote! {
+                    retro_core_option_v2_definition {
+                        key:  #key  as *const u8 as *const libc::c_char,
+                        desc: #desc as *const u8 as *const libc::c_char,
+                        info: #info as *const u8 as *const libc::c_char,
 
-            quote! {
-                retro_core_option_v2_definition {
-                    key:  #key  as *const u8 as *const libc::c_char,
-                    desc: #desc as *const u8 as *const libc::c_char,
-                    info: #info as *const u8 as *const libc::c_char,
+                        desc_categorized: #desc_categorized,
+                        info_categorized: #info_categorized,
+                        category_key:     #category_key,
 
-                    desc_categorized: #desc_categorized as *const u8 as *const libc::c_char,
-                    info_categorized: #info_categorized as *const u8 as *const libc::c_char,
-                    category_key:     #category_key     as *const u8 as *const libc::c_char,
+                        values: #values,
+                        default_value: #default_value,
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    }
 
-                    values: #values,
-                    default_value: #default_value,
+    fn get_option_categories(categories: &CoreOptionCategories) -> Vec<proc_macro2::TokenStream> {
+        categories
+            .0
+            .iter()
+            .map(|category| {
+                let key = lit_byte_str(&category.key);
+                let desc = lit_byte_str(&category.desc);
+                let info = lit_byte_str(&category.info);
+
+                quote! {
+                    retro_core_option_v2_category {
+                        key:    #key  as *const u8 as *const libc::c_char,
+                        desc:   #desc as *const u8 as *const libc::c_char,
+                        info:   #info as *const u8 as *const libc::c_char,
+                    }
                 }
-            }
-        })
+            })
+            .collect::<Vec<_>>()
+    }
+
+    let core_options = get_option_definitions(&options);
+    let core_options_v2 = get_option_v2_definitions(&options);
+    let core_option_categories = get_option_categories(&categories);
+
+    let option_terminator = quote! {
+        retro_core_option_definition {
+            key:    0 as *const libc::c_char,
+            desc:   0 as *const libc::c_char,
+            info:   0 as *const libc::c_char,
+            values: [retro_core_option_value {
+                value: 0 as *const libc::c_char,
+                label: 0 as *const libc::c_char,
+            }; #RETRO_NUM_CORE_OPTION_VALUES_MAX as usize],
+            default_value: 0 as *const libc::c_char,
+        }
+    };
+
+    let option_v2_terminator = quote! {
+        retro_core_option_v2_definition {
+            key: 0 as *const libc::c_char,
+            desc: 0 as *const libc::c_char,
+            desc_categorized: 0 as *const libc::c_char,
+            info: 0 as *const libc::c_char,
+            info_categorized: 0 as *const libc::c_char,
+            category_key: 0 as *const libc::c_char,
+            values: [retro_core_option_value {
+                value: 0 as *const libc::c_char,
+                label: 0 as *const libc::c_char,
+            }; #RETRO_NUM_CORE_OPTION_VALUES_MAX as usize],
+            default_value: 0 as *const libc::c_char,
+        }
+    };
+
+    let category_terminator = quote! {
+        retro_core_option_v2_category {
+            key: 0 as *const libc::c_char,
+            desc: 0 as *const libc::c_char,
+            info: 0 as *const libc::c_char,
+        }
+    };
+
+    let languages = translations
+        .iter()
+        .map(|translation| &translation.language)
         .collect::<Vec<_>>();
 
-    let core_option_categories = categories
-        .0
+    let mut localized_v1_names = Vec::new();
+    let mut localized_v2_names = Vec::new();
+
+    let localized_consts = translations
         .iter()
-        .map(|category| {
-            let key = lit_byte_str(&category.key);
-            let desc = lit_byte_str(&category.desc);
-            let info = lit_byte_str(&category.info);
+        .map(|translation| {
+            let suffix = translation
+                .language
+                .to_string()
+                .trim_start_matches("RETRO_")
+                .to_owned();
+
+            let v1_name = format_ident!("__RETRO_CORE_OPTIONS_{}", suffix);
+            let v2_name = format_ident!("__RETRO_CORE_OPTIONS_V2_{}", suffix);
+            let v2_categories_name = format_ident!("__RETRO_CORE_OPTION_V2_CATEGORIES_{}", suffix);
+            let v2_definitions_name =
+                format_ident!("__RETRO_CORE_OPTION_V2_DEFINITIONS_{}", suffix);
+
+            let option_count = translation.options.0.len();
+            let category_count = translation.categories.0.len();
+
+            let options = get_option_definitions(&translation.options);
+            let options_v2 = get_option_v2_definitions(&translation.options);
+            let categories = get_option_categories(&translation.categories);
+
+            let consts = quote! {
+                #[doc(hidden)]
+                const #v1_name: [retro_core_option_definition; #option_count + 1] = [
+                    #(#options,)*
+                    #option_terminator
+                ];
+
+                #[doc(hidden)]
+                const #v2_categories_name: [retro_core_option_v2_category; #category_count + 1] = [
+                    #(#categories,)*
+                    #category_terminator
+                ];
+
+                #[doc(hidden)]
+                const #v2_definitions_name: [retro_core_option_v2_definition; #option_count + 1] = [
+                    #(#options_v2,)*
+                    #option_v2_terminator
+                ];
+
+                #[doc(hidden)]
+                const #v2_name: retro_core_options_v2 = retro_core_options_v2 {
+                    categories: &Self::#v2_categories_name as *const _ as *mut _,
+                    definitions: &Self::#v2_definitions_name as *const _ as *mut _,
+                };
+            };
 
-            quote! {
-                retro_core_option_v2_category {
-                    key:    #key  as *const u8 as *const libc::c_char,
-                    desc:   #desc as *const u8 as *const libc::c_char,
-                    info:   #info as *const u8 as *const libc::c_char,
-                }
-            }
+            localized_v1_names.push(v1_name);
+            localized_v2_names.push(v2_name);
+
+            consts
         })
         .collect::<Vec<_>>();
 
+    // Without translations there is no need to ask the frontend for its language
+    let (get_language, set_core_options_v2, set_core_options_v1) = if translations.is_empty() {
+        (
+            quote! {},
+            quote! { ctx.set_core_options_v2(&Self::__RETRO_CORE_OPTIONS_V2) },
+            quote! { ctx.set_core_options(&Self::__RETRO_CORE_OPTIONS) },
+        )
+    } else {
+        (
+            quote! {
+                let language = gctx
+                    .get_language()
+                    .unwrap_or(retro_language::RETRO_LANGUAGE_ENGLISH);
+            },
+            quote! {
+                match language {
+                    #(
+                        retro_language::#languages => ctx
+                            .set_core_options_v2_intl(retro_core_options_v2_intl {
+                                us: &Self::__RETRO_CORE_OPTIONS_V2 as *const _ as *mut _,
+                                local: &Self::#localized_v2_names as *const _ as *mut _,
+                            })
+                            .or_else(|_| ctx.set_core_options_v2(&Self::__RETRO_CORE_OPTIONS_V2)),
+                    )*
+                    _ => ctx.set_core_options_v2(&Self::__RETRO_CORE_OPTIONS_V2),
+                }
+            },
+            quote! {
+                match language {
+                    #(
+                        retro_language::#languages => ctx
+                            .set_core_options_intl(retro_core_options_intl {
+                                us: &Self::__RETRO_CORE_OPTIONS as *const _ as *mut _,
+                                local: &Self::#localized_v1_names as *const _ as *mut _,
+                            })
+                            .or_else(|_| ctx.set_core_options(&Self::__RETRO_CORE_OPTIONS)),
+                    )*
+                    _ => ctx.set_core_options(&Self::__RETRO_CORE_OPTIONS),
+                }
+            },
+        )
+    };
+
     let expanded = quote! {
         impl #impl_generics ::rust_libretro::core::CoreOptions for #name #ty_generics #where_clause {
             fn set_core_options(&self, ctx: &SetEnvironmentContext) -> bool {
                 let gctx: GenericContext = ctx.into();
+                #get_language
 
                 // For some reason the call to `supports_set_core_options` only works on the initial call of `on_set_environment`.
                 // On subsequent calls of `on_set_environment` querying `RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION` returns NULL pointers.
                 // But our `retro_set_environment` wrapper makes sure to call us on the initial call of `on_set_environment` only.
-                match gctx.get_core_options_version() {
-                    n if n >= 2 => ctx.set_core_options_v2(&Self::__RETRO_CORE_OPTIONS_V2),
-                    n if n >= 1 => ctx.set_core_options(&Self::__RETRO_CORE_OPTIONS),
+                match gctx.get_core_options_version().unwrap_or(0) {
+                    n if n >= 2 => #set_core_options_v2,
+                    n if n >= 1 => #set_core_options_v1,
                     _ => ctx.set_variables(&Self::__RETRO_CORE_VARIABLES)
-                }
+                }.is_ok()
             }
         }
 
@@ -582,16 +1496,7 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
                 #(#core_options,)*
 
                 // List terminator
-                retro_core_option_definition {
-                    key:    0 as *const libc::c_char,
-                    desc:   0 as *const libc::c_char,
-                    info:   0 as *const libc::c_char,
-                    values: [retro_core_option_value {
-                        value: 0 as *const libc::c_char,
-                        label: 0 as *const libc::c_char,
-                    }; #RETRO_NUM_CORE_OPTION_VALUES_MAX as usize],
-                    default_value: 0 as *const libc::c_char,
-                }
+                #option_terminator
             ];
 
             #[doc(hidden)]
@@ -609,11 +1514,7 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
             const __RETRO_CORE_OPTION_V2_CATEGORIES: [retro_core_option_v2_category; 1 + #category_count] = [
                 #(#core_option_categories,)*
 
-                retro_core_option_v2_category {
-                    key: 0 as *const libc::c_char,
-                    desc: 0 as *const libc::c_char,
-                    info: 0 as *const libc::c_char,
-                }
+                #category_terminator
             ];
 
             #[doc(hidden)]
@@ -621,19 +1522,7 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
                 #(#core_options_v2,)*
 
                 // List terminator
-                retro_core_option_v2_definition {
-                    key: 0 as *const libc::c_char,
-                    desc: 0 as *const libc::c_char,
-                    desc_categorized: 0 as *const libc::c_char,
-                    info: 0 as *const libc::c_char,
-                    info_categorized: 0 as *const libc::c_char,
-                    category_key: 0 as *const libc::c_char,
-                    values: [retro_core_option_value {
-                        value: 0 as *const libc::c_char,
-                        label: 0 as *const libc::c_char,
-                    }; 128],
-                    default_value: 0 as *const libc::c_char,
-                }
+                #option_v2_terminator
             ];
 
             #[doc(hidden)]
@@ -643,7 +1532,11 @@ fn impl_derive_core_options(input: DeriveInput) -> TokenStream {
                 /// HERE BE DRAGONS, but mutable references are not allowed
                 definitions: &Self::__RETRO_CORE_OPTION_V2_DEFINITIONS as *const _ as *mut _,
             };
+
+            #(#localized_consts)*
         }
+
+        #typed_options
     };
 
     TokenStream::from(expanded)
@@ -1,12 +1,11 @@
 //! This module contains abstractions of the libretro environment callbacks.
-use crate::core_wrapper::Interfaces;
-use once_cell::unsync::Lazy;
+use crate::core_wrapper::{Interfaces, SingleThreadCell};
 use std::collections::HashMap;
 
 use super::*;
 
 /// This would only be used in [`Core::on_run`] from a single thread.
-static mut FALLBACK_FRAMEBUFFER: Lazy<Vec<u8>> = Lazy::new(Vec::new);
+static FALLBACK_FRAMEBUFFER: SingleThreadCell<Vec<u8>> = SingleThreadCell::new(Vec::new());
 
 #[doc(hidden)]
 macro_rules! into_generic {
@@ -75,22 +74,51 @@ impl<'a> GenericContext<'a> {
         Arc::clone(&self.interfaces)
     }
 
+    /// Returns the current value of the core option `key`,
+    /// or `Ok(None)` if the frontend does not know the option.
+    ///
+    /// The value is owned by the frontend and only valid until the next environment call,
+    /// so it keeps this context mutably borrowed to rule out any other calls while it is alive.
+    /// Use `.ok().flatten()` if the reason for a missing value does not matter.
+    pub fn get_variable(
+        &mut self,
+        key: &str,
+    ) -> Result<Option<&str>, environment::EnvironmentError> {
+        unsafe { environment::get_variable(*self.environment_callback, key) }
+    }
+
     /// Enables the [`Core::on_keyboard_event`] callback.
-    pub fn enable_keyboard_callback(&self) -> bool {
+    ///
+    /// The frontend does not call it unless this has been called,
+    /// e.g. in [`Core::on_set_environment`] or [`Core::on_load_game`].
+    pub fn enable_keyboard_callback(&self) -> Result<(), environment::EnvironmentError> {
         self.set_keyboard_callback(retro_keyboard_callback {
             callback: Some(retro_keyboard_callback_fn),
         })
     }
 
-    /// Enables the [`Core::on_write_audio`] and [`Core::on_audio_set_state`] callbacks.
-    pub fn enable_audio_callback(&self) -> bool {
+    /// Enables the [`Core::on_write_audio`] and [`Core::on_audio_set_state`] callbacks,
+    /// which let the frontend request audio asynchronously, even while the core is paused.
+    ///
+    /// Most cores should keep submitting audio once per frame from [`Core::on_run`] instead.
+    /// Should be called in [`Core::on_load_game`], fails if the frontend does not support it.
+    pub fn enable_audio_callback(&self) -> Result<(), environment::EnvironmentError> {
         self.set_audio_callback(retro_audio_callback {
             callback: Some(retro_audio_callback_fn),
             set_state: Some(retro_audio_set_state_callback_fn),
         })
     }
 
-    pub fn enable_disk_control_interface(&self) -> bool {
+    /// Returns the version of the disk control interface supported by the frontend,
+    /// or 0 if it does not support the query.
+    ///
+    /// Version 0 only supports [`GenericContext::enable_disk_control_interface`],
+    /// version 1 and later also [`GenericContext::enable_extended_disk_control_interface`].
+    pub fn disk_control_interface_version(&self) -> u32 {
+        self.get_disk_control_interface_version().unwrap_or(0)
+    }
+
+    pub fn enable_disk_control_interface(&self) -> Result<(), environment::EnvironmentError> {
         self.set_disk_control_interface(retro_disk_control_callback {
             set_eject_state: Some(retro_set_eject_state_callback),
             get_eject_state: Some(retro_get_eject_state_callback),
@@ -103,8 +131,8 @@ impl<'a> GenericContext<'a> {
     }
 
     pub fn enable_extended_disk_control_interface(&self) -> Result<(), Box<dyn std::error::Error>> {
-        if self.get_disk_control_interface_version() >= 1 {
-            let success = self.set_disk_control_ext_interface(retro_disk_control_ext_callback {
+        if self.disk_control_interface_version() >= 1 {
+            self.set_disk_control_ext_interface(retro_disk_control_ext_callback {
                 set_eject_state: Some(retro_set_eject_state_callback),
                 get_eject_state: Some(retro_get_eject_state_callback),
                 get_image_index: Some(retro_get_image_index_callback),
@@ -116,11 +144,7 @@ impl<'a> GenericContext<'a> {
                 set_initial_image: Some(retro_set_initial_image_callback),
                 get_image_path: Some(retro_get_image_path_callback),
                 get_image_label: Some(retro_get_image_label_callback),
-            });
-
-            if !success {
-                return Err("Failed to enable the extended disk control interface.".into());
-            }
+            })?;
         } else {
             return Err("The extended disk control interface is unsupported.".into());
         }
@@ -128,324 +152,474 @@ impl<'a> GenericContext<'a> {
         Ok(())
     }
 
-    pub fn enable_audio_buffer_status_callback(&self) -> bool {
+    /// Asks the frontend to report the occupancy of its audio buffer
+    /// to [`Core::on_audio_buffer_status`] once per frame.
+    ///
+    /// Combine with [`GenericContext::set_minimum_audio_latency`] to implement
+    /// audio-buffer-based frame skipping.
+    pub fn set_audio_buffer_status_callback(&self) -> Result<(), environment::EnvironmentError> {
         let data = retro_audio_buffer_status_callback {
             callback: Some(retro_audio_buffer_status_callback_fn),
         };
 
-        self.set_audio_buffer_status_callback(data)
+        unsafe {
+            environment::set_audio_buffer_status_callback(*self.environment_callback, Some(data))
+        }
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn set_led_state(&self, led: i32, state: i32) {
-        let interfaces = self.interfaces.read().unwrap();
+    /// Disables audio buffer status reporting.
+    pub fn clear_audio_buffer_status_callback(&self) -> Result<(), environment::EnvironmentError> {
+        unsafe { environment::set_audio_buffer_status_callback(*self.environment_callback, None) }
+    }
 
-        if let Some(interface) = interfaces.led_interface {
-            if let Some(set_led_state) = interface.set_led_state {
-                unsafe { set_led_state(led, state) };
-            }
+    /// Displays a message for `duration_ms` milliseconds using the legacy
+    /// [`RETRO_ENVIRONMENT_SET_MESSAGE`] command.
+    ///
+    /// The legacy interface counts in frames, so the duration is converted assuming 60 FPS.
+    pub fn set_message(
+        &self,
+        msg: &str,
+        duration_ms: u32,
+    ) -> Result<(), environment::EnvironmentError> {
+        let frames = (duration_ms as u64 * 60 + 999) / 1000;
+
+        unsafe {
+            environment::set_message(
+                *self.environment_callback,
+                msg,
+                frames.min(u32::MAX as u64) as u32,
+            )
         }
     }
 
-    pub fn set_rumble_state(&self, port: u32, effect: retro_rumble_effect, strength: u16) -> bool {
-        let interfaces = self.interfaces.read().unwrap();
+    /// Displays a message using [`RETRO_ENVIRONMENT_SET_MESSAGE_EXT`].
+    ///
+    /// Falls back to [`GenericContext::set_message`] if the frontend
+    /// does not support version 1 of the message interface.
+    pub fn set_message_ext(
+        &self,
+        message: MessageExt,
+    ) -> Result<(), environment::EnvironmentError> {
+        let version =
+            unsafe { environment::get_message_interface_version(*self.environment_callback) };
+
+        if version.unwrap_or(0) >= 1 {
+            let result = unsafe {
+                environment::set_message_ext(
+                    *self.environment_callback,
+                    message.msg,
+                    message.duration,
+                    message.priority,
+                    message.level,
+                    message.target,
+                    message.type_,
+                    message.progress,
+                )
+            };
 
-        if let Some(interface) = interfaces.rumble_interface {
-            if let Some(set_rumble_state) = interface.set_rumble_state {
-                return unsafe { set_rumble_state(port, effect, strength) };
+            if result.is_ok() {
+                return result;
             }
         }
 
-        false
+        self.set_message(message.msg, message.duration)
     }
 
-    pub fn start_perf_counter(
-        &mut self,
-        name: &'static str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        use std::collections::hash_map::Entry;
-
-        let mut interfaces = self.interfaces.write().unwrap();
-
-        let interface = interfaces
-            .perf_interface
-            .interface
-            .ok_or("Performance interface not found, did you call `enable_perf_interface()`?")?;
-
-        let counter = match interfaces.perf_interface.counters.entry(name) {
-            Entry::Occupied(counter) => counter.into_mut(),
-            Entry::Vacant(entry) => {
-                let ident = CString::new(name)?;
-                let ptr = ident.as_ptr();
-
-                entry.insert(PerfCounter {
-                    ident,
-                    counter: retro_perf_counter {
-                        ident: ptr,
-                        start: 0,
-                        total: 0,
-                        call_cnt: 0,
-                        registered: false,
-                    },
-                })
-            }
-        };
+    /// Keeps a copy of every frame drawn with [`RunContext::draw_frame`] or
+    /// [`RunContext::draw_framebuffer`] around, which [`RunContext::last_frame`] returns.
+    ///
+    /// Disabled by default, as copying every frame costs time. Frames only get copied anyway
+    /// if the frontend cannot dupe frames, see [`RunContext::can_dupe`].
+    pub fn set_keep_last_frame(&self, keep: bool) {
+        self.interfaces.write().unwrap().keep_last_frame = keep;
+    }
 
-        if !counter.counter.registered {
-            let register = interface
-                .perf_register
-                .ok_or("`perf_register()` is missing on the performance interface")?;
+    /// Returns the enabled cheats ordered by their index.
+    ///
+    /// Always reflects the calls of [`Core::on_cheat_set`] and [`Core::on_cheat_reset`],
+    /// including the one currently being handled.
+    pub fn cheats(&self) -> Vec<Cheat> {
+        self.interfaces
+            .read()
+            .unwrap()
+            .cheats
+            .enabled()
+            .cloned()
+            .collect()
+    }
 
-            unsafe {
-                register(&mut counter.counter as *mut _);
-            }
-        }
+    /// Tells the user that the cheat at `index` could not be applied, both on screen and in the log.
+    ///
+    /// Gets called automatically if [`Core::on_cheat_set`] returns an error,
+    /// as `retro_cheat_set` has no way to report failures to the frontend.
+    pub fn log_cheat_error(
+        &self,
+        index: u32,
+        reason: &str,
+    ) -> Result<(), environment::EnvironmentError> {
+        let msg = format!("Cheat #{index} could not be applied: {reason}");
 
-        let start = interface
-            .perf_start
-            .ok_or("`perf_start()` is missing on the performance interface")?;
+        #[cfg(feature = "log")]
+        log::warn!("{msg}");
 
-        unsafe {
-            start(&mut counter.counter as *mut _);
-        }
+        self.set_message_ext(MessageExt::new(&msg).level(retro_log_level::RETRO_LOG_WARN))
+    }
 
-        Ok(())
+    /// Returns the directory for save data such as SRAM and memory cards.
+    ///
+    /// [`None`] means the frontend has no save directory,
+    /// in which case the core should save next to the loaded content.
+    pub fn get_save_directory(&self) -> Option<PathBuf> {
+        unsafe { environment::get_save_directory(*self.environment_callback) }
+            .ok()
+            .flatten()
+            .map(Path::to_path_buf)
     }
 
-    pub fn stop_perf_counter(
-        &mut self,
-        name: &'static str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        use std::collections::hash_map::Entry;
+    /// Returns the directory for system files such as BIOS images.
+    ///
+    /// [`None`] means the frontend has no system directory,
+    /// in which case it's up to the core to find a suitable location,
+    /// e.g. the current working directory.
+    pub fn get_system_directory(&self) -> Option<PathBuf> {
+        unsafe { environment::get_system_directory(*self.environment_callback) }
+            .ok()
+            .flatten()
+            .map(Path::to_path_buf)
+    }
 
-        let mut interfaces = self.interfaces.write().unwrap();
+    /// Returns the directory for assets bundled with the core,
+    /// such as shaders, fonts or overlays.
+    ///
+    /// [`None`] means the frontend has no core assets directory.
+    pub fn get_core_assets_directory(&self) -> Option<PathBuf> {
+        unsafe { environment::get_core_assets_directory(*self.environment_callback) }
+            .ok()
+            .flatten()
+            .map(Path::to_path_buf)
+    }
 
-        let interface = interfaces
-            .perf_interface
-            .interface
-            .ok_or("Performance interface not found, did you call `enable_perf_interface()`?")?;
+    /// Returns the absolute path of the loaded core library.
+    ///
+    /// [`None`] means the core was linked statically or the path is unknown.
+    pub fn get_libretro_path(&self) -> Option<PathBuf> {
+        unsafe { environment::get_libretro_path(*self.environment_callback) }
+            .ok()
+            .flatten()
+            .map(Path::to_path_buf)
+    }
 
-        match interfaces.perf_interface.counters.entry(name) {
-            Entry::Occupied(counter) => {
-                let counter = counter.into_mut();
+    /// Asks the frontend to close the core, e.g. when the game reached its end
+    /// or the user turned off the emulated system.
+    ///
+    /// Returns `false` if the frontend does not support this.
+    pub fn shutdown(&self) -> bool {
+        unsafe { environment::shutdown(*self.environment_callback) }.is_ok()
+    }
 
-                if counter.counter.registered {
-                    let stop = interface
-                        .perf_stop
-                        .ok_or("`perf_stop()` is missing on the performance interface")?;
+    /// Returns the language configured in the frontend,
+    /// or [`None`] if the frontend does not report one.
+    ///
+    /// Useful for localizing in-game text, e.g. in [`Core::on_load_game`].
+    pub fn get_language(&self) -> Option<retro_language> {
+        unsafe { environment::get_language(*self.environment_callback).ok() }
+    }
 
-                    unsafe {
-                        stop(&mut counter.counter as *mut _);
-                    }
+    /// Returns the refresh rate of the display the frontend is targeting, e.g. `59.94`,
+    /// or [`None`] if the frontend does not report one.
+    ///
+    /// Cores that can run at slightly different speeds may use it for the timing
+    /// returned from [`Core::on_get_av_info`]. Changing the timing after that requires
+    /// [`RunContext::set_system_av_info`].
+    pub fn get_target_refresh_rate(&self) -> Option<f32> {
+        unsafe { environment::get_target_refresh_rate(*self.environment_callback) }
+            .ok()
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+    }
 
-                    return Ok(());
-                }
+    /// Collects what the frontend reports about itself,
+    /// so cores can work around the quirks of specific frontends.
+    pub fn frontend_info(&self) -> FrontendInfo {
+        let callback = *self.environment_callback;
 
-                return Err(format!("Performance counter “{name}” has not been registered").into());
+        unsafe {
+            FrontendInfo {
+                core_options_version: environment::get_core_options_version(callback).unwrap_or(0),
+                message_interface_version: environment::get_message_interface_version(callback)
+                    .unwrap_or(0),
+                disk_control_interface_version: environment::get_disk_control_interface_version(
+                    callback,
+                )
+                .unwrap_or(0),
+                input_max_users: environment::get_input_max_users(callback).ok(),
+                language: environment::get_language(callback).ok(),
+                libretro_path: environment::get_libretro_path(callback)
+                    .ok()
+                    .flatten()
+                    .map(Path::to_path_buf),
             }
-            _ => Err(format!("Unknown performance counter “{name}”").into()),
         }
     }
 
-    pub fn perf_log(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let interfaces = self.interfaces.read().unwrap();
-
-        let interface = interfaces
-            .perf_interface
-            .interface
-            .ok_or("Performance interface not found, did you call `enable_perf_interface()`?")?;
+    /// Describes the memory layout of the emulated system to the frontend,
+    /// which enables core-agnostic cheats and achievements.
+    ///
+    /// Should be called from [`Core::on_init`] or [`Core::on_load_game`].
+    /// The memory referenced by the descriptors must stay valid until the game gets unloaded.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn set_memory_maps(
+        &self,
+        descriptors: &[MemoryDescriptor],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let addrspaces = descriptors
+            .iter()
+            .map(|descriptor| descriptor.addrspace.map(CString::new).transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let descriptors = descriptors
+            .iter()
+            .zip(&addrspaces)
+            .map(|(descriptor, addrspace)| retro_memory_descriptor {
+                flags: descriptor.flags.bits(),
+                ptr: descriptor.ptr,
+                offset: descriptor.offset,
+                start: descriptor.start,
+                select: descriptor.select,
+                disconnect: descriptor.disconnect,
+                len: descriptor.len,
+                addrspace: addrspace
+                    .as_ref()
+                    .map_or(std::ptr::null(), |addrspace| addrspace.as_ptr()),
+            })
+            .collect::<Vec<_>>();
+
+        let map = retro_memory_map {
+            descriptors: descriptors.as_ptr(),
+            num_descriptors: descriptors.len() as u32,
+        };
 
-        let log = interface
-            .perf_log
-            .ok_or("`perf_log()` is missing on the performance interface")?;
+        unsafe { environment::set_memory_maps(*self.environment_callback, map)? };
 
-        unsafe {
-            log();
-        }
+        // The frontend copies the descriptors but keeps pointing to the address space names
+        self.interfaces.write().unwrap().memory_map_addrspaces =
+            addrspaces.into_iter().flatten().collect();
 
         Ok(())
     }
 
-    pub fn perf_get_time_usec(&self) -> i64 {
+    #[proc::unstable(feature = "env-commands")]
+    pub fn set_led_state(&self, led: i32, state: i32) {
         let interfaces = self.interfaces.read().unwrap();
 
-        if let Some(interface) = interfaces.perf_interface.interface {
-            if let Some(get_time_usec) = interface.get_time_usec {
-                return unsafe { get_time_usec() };
+        if let Some(interface) = interfaces.led_interface {
+            if let Some(set_led_state) = interface.set_led_state {
+                unsafe { set_led_state(led, state) };
             }
-
-            #[cfg(feature = "log")]
-            log::error!("`get_time_usec()` is missing on the performance interface");
         }
-
-        #[cfg(feature = "log")]
-        log::error!("Performance interface not found, did you call `enable_perf_interface()`?");
-
-        0
     }
 
-    pub fn perf_get_counter(&self) -> u64 {
+    pub fn set_rumble_state(&self, port: u32, effect: retro_rumble_effect, strength: u16) -> bool {
         let interfaces = self.interfaces.read().unwrap();
 
-        if let Some(interface) = interfaces.perf_interface.interface {
-            if let Some(get_perf_counter) = interface.get_perf_counter {
-                return unsafe { get_perf_counter() };
+        if let Some(interface) = interfaces.rumble_interface {
+            if let Some(set_rumble_state) = interface.set_rumble_state {
+                return unsafe { set_rumble_state(port, effect, strength) };
             }
-
-            #[cfg(feature = "log")]
-            log::error!("`get_perf_counter()` is missing on the performance interface");
         }
 
-        #[cfg(feature = "log")]
-        log::error!("Performance interface not found, did you call `enable_perf_interface()`?");
+        false
+    }
 
-        0
+    /// Registers the performance counter `name` if necessary and starts it.
+    ///
+    /// See also [`PerfContext::perf_guard`].
+    pub fn start_perf_counter(
+        &mut self,
+        name: &'static str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let perf = self.perf();
+        let counter = perf.perf_register(name)?;
+
+        perf.perf_start(&counter)
     }
 
-    pub fn get_cpu_features(&self) -> CpuFeatures {
-        let interfaces = self.interfaces.read().unwrap();
+    /// Stops the performance counter `name`.
+    pub fn stop_perf_counter(
+        &mut self,
+        name: &'static str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.perf().perf_stop(&PerfCounter { name })
+    }
 
-        if let Some(interface) = interfaces.perf_interface.interface {
-            if let Some(get_cpu_features) = interface.get_cpu_features {
-                return unsafe { CpuFeatures::from_bits_unchecked(get_cpu_features()) };
-            }
+    pub fn perf_log(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.perf().perf_log()
+    }
 
-            #[cfg(feature = "log")]
-            log::error!("`get_cpu_features()` is missing on the performance interface");
-        }
+    pub fn perf_get_time_usec(&self) -> i64 {
+        self.perf().get_time_usec()
+    }
 
-        #[cfg(feature = "log")]
-        log::error!("Performance interface not found, did you call `enable_perf_interface()`?");
+    pub fn perf_get_counter(&self) -> u64 {
+        self.perf().get_counter()
+    }
 
-        CpuFeatures::empty()
+    pub fn get_cpu_features(&self) -> CpuFeatures {
+        self.perf().get_cpu_features()
+    }
+
+    /// Gives access to the performance interface.
+    #[inline]
+    pub fn perf(&self) -> PerfContext {
+        self.into()
     }
 
-    pub fn location_service_start(&self) {
+    /// Starts the camera driver.
+    ///
+    /// Requires the camera interface to be enabled using
+    /// [`LoadGameContext::enable_camera_interface`].
+    /// Frames get delivered to [`Core::on_camera_frame_raw`] or
+    /// [`Core::on_camera_frame_gl_texture`] afterwards.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn camera_start(&self) -> bool {
         let interfaces = self.interfaces.read().unwrap();
 
-        if let Some(interface) = interfaces.location_interface {
+        if let Some(interface) = interfaces.camera_interface {
             if let Some(start) = interface.start {
-                unsafe { start() };
+                return unsafe { start() };
             }
         }
+
+        false
     }
 
-    pub fn location_service_stop(&self) {
+    /// Stops the camera driver.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn camera_stop(&self) {
         let interfaces = self.interfaces.read().unwrap();
 
-        if let Some(interface) = interfaces.location_interface {
+        if let Some(interface) = interfaces.camera_interface {
             if let Some(stop) = interface.stop {
                 unsafe { stop() };
             }
         }
     }
 
-    pub fn location_service_get_position(&self) -> Option<Position> {
-        let interfaces = self.interfaces.read().unwrap();
-
-        if let Some(interface) = interfaces.location_interface {
-            if let Some(get_position) = interface.get_position {
-                let mut lat = 0f64;
-                let mut lon = 0f64;
-                let mut horiz_accuracy = 0f64;
-                let mut vert_accuracy = 0f64;
-
-                unsafe {
-                    if !get_position(
-                        &mut lat as *mut f64,
-                        &mut lon as *mut f64,
-                        &mut horiz_accuracy as *mut f64,
-                        &mut vert_accuracy as *mut f64,
-                    ) {
-                        return None;
-                    }
-                };
+    /// See [`LocationContext::start`].
+    pub fn location_service_start(&self) -> bool {
+        let ctx: LocationContext = self.into();
+        ctx.start()
+    }
 
-                return Some(Position {
-                    lat,
-                    lon,
-                    horiz_accuracy,
-                    vert_accuracy,
-                });
-            }
-        }
+    /// See [`LocationContext::stop`].
+    pub fn location_service_stop(&self) {
+        let ctx: LocationContext = self.into();
+        ctx.stop()
+    }
 
-        None
+    /// See [`LocationContext::get_position`].
+    pub fn location_service_get_position(&self) -> Option<Position> {
+        let ctx: LocationContext = self.into();
+        ctx.get_position()
     }
 
+    /// See [`LocationContext::set_interval`].
     pub fn location_service_set_interval(&self, interval_ms: u32, interval_distance: u32) {
-        let interfaces = self.interfaces.read().unwrap();
+        let ctx: LocationContext = self.into();
+        ctx.set_interval(interval_ms, interval_distance)
+    }
 
-        if let Some(interface) = interfaces.location_interface {
-            if let Some(set_interval) = interface.set_interval {
-                unsafe { set_interval(interval_ms, interval_distance) };
-            }
-        }
+    /// Returns the pixel format of the frames passed to [`RunContext::draw_frame`].
+    ///
+    /// This is [`PixelFormat::XRGB1555`] unless [`LoadGameContext::set_pixel_format`]
+    /// or [`GetAvInfoContext::set_pixel_format`] succeeded.
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.interfaces
+            .read()
+            .unwrap()
+            .pixel_format
+            .unwrap_or(PixelFormat::XRGB1555)
     }
 
-    pub fn midi_input_enabled(&self) -> bool {
-        let interfaces = self.interfaces.read().unwrap();
+    pub(crate) fn set_pixel_format<F: Into<retro_pixel_format>>(
+        &self,
+        format: F,
+    ) -> Result<(), environment::EnvironmentError> {
+        let format = format.into();
 
-        if let Some(interface) = interfaces.midi_interface {
-            if let Some(input_enabled) = interface.input_enabled {
-                return unsafe { input_enabled() };
-            }
-        }
+        unsafe { environment::set_pixel_format(*self.environment_callback, format)? };
+        self.interfaces.write().unwrap().pixel_format = Some(format.into());
 
-        false
+        Ok(())
     }
 
-    pub fn midi_output_enabled(&self) -> bool {
-        let interfaces = self.interfaces.read().unwrap();
+    /// Returns the reference frame time in microseconds if
+    /// [`LoadGameContext::enable_frame_time_callback`] succeeded.
+    pub fn frame_time_reference(&self) -> Option<i64> {
+        self.interfaces.read().unwrap().frame_time_reference
+    }
 
-        if let Some(interface) = interfaces.midi_interface {
-            if let Some(output_enabled) = interface.output_enabled {
-                return unsafe { output_enabled() };
-            }
-        }
+    /// Returns which optional interfaces were successfully acquired,
+    /// so cores can disable features instead of calling into missing interfaces.
+    pub fn available_interfaces(&self) -> AvailableInterfaces {
+        self.interfaces.read().unwrap().available()
+    }
 
-        false
+    /// Makes the next `retro_get_system_info` call [`Core::get_info`] again
+    /// instead of returning the cached [`SystemInfo`].
+    ///
+    /// The frontend decides when it asks for the info, RetroArch only does when loading the core.
+    /// The previous info stays valid until [`Core::on_deinit`], as the frontend may still use it.
+    pub fn refresh_system_info(&self) {
+        self.interfaces.write().unwrap().system_info_outdated = true;
     }
 
-    pub fn midi_read_next(&self) -> Option<u8> {
-        let interfaces = self.interfaces.read().unwrap();
+    /// Returns the frontend features queried when the core got initialized.
+    ///
+    /// Unlike the individual queries this does not call into the frontend.
+    pub fn capabilities(&self) -> Capabilities {
+        self.interfaces.read().unwrap().capabilities
+    }
 
-        if let Some(interface) = interfaces.midi_interface {
-            if let Some(read) = interface.read {
-                let mut value = 0;
-                unsafe {
-                    if read(&mut value as *mut u8) {
-                        return Some(value);
-                    }
-                }
-            }
-        }
+    /// Returns the MIDI interface if
+    /// [`LoadGameContext::enable_midi_interface`] succeeded.
+    pub fn midi(&self) -> Option<MidiInterface> {
+        self.interfaces
+            .read()
+            .unwrap()
+            .midi_interface
+            .map(|interface| MidiInterface { interface })
+    }
 
-        None
+    pub fn midi_input_enabled(&self) -> bool {
+        self.midi().is_some_and(|midi| midi.input_enabled())
     }
 
-    pub fn midi_write_byte(&self, value: u8, delta_time: u32) -> bool {
-        let interfaces = self.interfaces.read().unwrap();
+    pub fn midi_output_enabled(&self) -> bool {
+        self.midi().is_some_and(|midi| midi.output_enabled())
+    }
 
-        if let Some(interface) = interfaces.midi_interface {
-            if let Some(write) = interface.write {
-                return unsafe { write(value, delta_time) };
-            }
-        }
+    pub fn midi_read_next(&self) -> Option<u8> {
+        self.midi()?.read()
+    }
 
-        false
+    pub fn midi_write_byte(&self, value: u8, delta_time: u32) -> bool {
+        self.midi()
+            .is_some_and(|mut midi| midi.write(value, delta_time))
     }
 
     pub fn midi_flush(&self) -> bool {
-        let interfaces = self.interfaces.read().unwrap();
+        self.midi().is_some_and(|mut midi| midi.flush())
+    }
 
-        if let Some(interface) = interfaces.midi_interface {
-            if let Some(flush) = interface.flush {
-                return unsafe { flush() };
-            }
-        }
+    /// Returns the frontend’s virtual filesystem if
+    /// [`SetEnvironmentContext::enable_vfs_interface`] succeeded.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn vfs(&self) -> Option<crate::vfs::Vfs> {
+        let interfaces = self.interfaces.read().unwrap();
+        let info = &interfaces.vfs_interface_info;
 
-        false
+        info.interface
+            .map(|interface| crate::vfs::Vfs::new(interface, info.supported_version))
     }
 
     #[proc::unstable(feature = "env-commands")]
@@ -893,6 +1067,9 @@ impl<'a> GenericContext<'a> {
 /// Functions that are safe to be called in [`Core::on_reset`].
 pub type ResetContext<'a> = GenericContext<'a>;
 
+/// Functions that are safe to be called in [`Core::on_set_controller_port_device`].
+pub type SetControllerPortDeviceContext<'a> = GenericContext<'a>;
+
 /// Functions that are safe to be called in [`Core::on_deinit`].
 pub type DeinitContext<'a> = GenericContext<'a>;
 
@@ -900,10 +1077,42 @@ pub type DeinitContext<'a> = GenericContext<'a>;
 pub type GetSerializeSizeContext<'a> = GenericContext<'a>;
 
 /// Functions that are safe to be called in [`Core::on_serialize`].
-pub type SerializeContext<'a> = GenericContext<'a>;
-
-/// Functions that are safe to be called in [`Core::on_unserialize`].
-pub type UnserializeContext<'a> = GenericContext<'a>;
+///
+/// Use [`GenericContext::from`] for the functions that are safe to be called in every context.
+pub struct SerializeContext<'a> {
+    pub(crate) environment_callback: &'a retro_environment_t,
+    pub(crate) interfaces: Interfaces,
+    pub(crate) save_state_context: SaveStateContext,
+}
+
+impl<'a> SerializeContext<'a> {
+    pub(crate) fn new(
+        environment_callback: &'a retro_environment_t,
+        interfaces: Interfaces,
+    ) -> Self {
+        Self {
+            environment_callback,
+            interfaces,
+            save_state_context: SaveStateContext::default(),
+        }
+    }
+
+    pub(crate) fn with_save_state_context(mut self, save_state_context: SaveStateContext) -> Self {
+        self.save_state_context = save_state_context;
+        self
+    }
+
+    /// Why the frontend saves or loads the state, e.g. to skip expensive validation
+    /// or compression for the frequent runahead states.
+    pub fn save_state_context(&self) -> SaveStateContext {
+        self.save_state_context
+    }
+}
+
+into_generic!(SerializeContext<'a>, 'a);
+
+/// Functions that are safe to be called in [`Core::on_unserialize`].
+pub type UnserializeContext<'a> = SerializeContext<'a>;
 
 /// Functions that are safe to be called in [`Core::on_unload_game`].
 pub type UnloadGameContext<'a> = GenericContext<'a>;
@@ -927,62 +1136,116 @@ make_context!(GetAvInfoContext, #[doc = "Functions that are safe to be called in
 make_context!(InitContext, #[doc = "Functions that are safe to be called in [`Core::on_init`]"]);
 make_context!(OptionsChangedContext, #[doc = "Functions that are safe to be called in [`Core::on_options_changed`]"]);
 
-make_context!(LoadGameSpecialContext, #[doc = "Functions that are safe to be called in [`Core::on_load_game_special`]"]);
-into_generic!(LoadGameSpecialContext<'a>, LoadGameContext, 'a);
+/// Registers [`retro_frame_time_callback_fn`] and remembers `reference`,
+/// see [`LoadGameContext::enable_frame_time_callback`].
+fn enable_frame_time_callback(
+    environment_callback: &retro_environment_t,
+    interfaces: &Interfaces,
+    reference: i64,
+) -> Result<(), environment::EnvironmentError> {
+    unsafe {
+        environment::set_frame_time_callback(
+            *environment_callback,
+            retro_frame_time_callback {
+                callback: Some(retro_frame_time_callback_fn),
+                reference,
+            },
+        )
+    }?;
 
-make_context!(SetEnvironmentContext, #[doc = "Functions that are safe to be called in [`Core::on_set_environment`]"]);
+    interfaces.write().unwrap().frame_time_reference = Some(reference);
 
-impl<'a> SetEnvironmentContext<'a> {
-    pub fn enable_proc_address_interface(&mut self) -> bool {
-        self.set_proc_address_callback(retro_get_proc_address_interface {
-            get_proc_address: Some(retro_get_proc_address_callback),
-        })
+    Ok(())
+}
+
+impl InitContext<'_> {
+    /// Makes the frontend pass the time since the last frame to [`Core::on_run`]
+    /// for every game, instead of enabling it while loading one.
+    ///
+    /// `reference` is the duration of one frame in microseconds,
+    /// see [`LoadGameContext::enable_frame_time_callback`].
+    pub fn enable_frame_time_callback(
+        &self,
+        reference: i64,
+    ) -> Result<(), environment::EnvironmentError> {
+        enable_frame_time_callback(self.environment_callback, &self.interfaces, reference)
     }
 
-    pub fn enable_options_update_display_callback(&mut self) -> bool {
-        self.set_core_options_update_display_callback(retro_core_options_update_display_callback {
-            callback: Some(retro_core_options_update_display_callback_fn),
-        })
+    /// Returns the hardware rendering API the frontend prefers,
+    /// or [`None`] if it has no preference or does not support the query.
+    ///
+    /// Cores that support several APIs should request this one in [`Core::on_load_game`].
+    pub fn get_preferred_hw_render(&self) -> Option<HwContextType> {
+        unsafe { environment::get_preferred_hw_render(*self.environment_callback) }
+            .ok()
+            .flatten()
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn enable_vfs_interface(
+    /// See [`GenericContext::capabilities`].
+    pub fn capabilities(&self) -> Capabilities {
+        self.interfaces.read().unwrap().capabilities
+    }
+
+    /// Whether JIT compilation is available, so cores can choose between
+    /// a dynamic recompiler and an interpreter.
+    ///
+    /// Returns `false` if the frontend does not support the query.
+    pub fn jit_capable(&self) -> bool {
+        unsafe { environment::get_jit_capable(*self.environment_callback) }.unwrap_or(false)
+    }
+}
+
+impl OptionsChangedContext<'_> {
+    /// See [`GenericContext::get_variable`].
+    pub fn get_variable(
         &mut self,
-        min_version: u32,
-    ) -> Result<u32, Box<dyn std::error::Error>> {
-        let mut interfaces = self.interfaces.write().unwrap();
+        key: &str,
+    ) -> Result<Option<&str>, environment::EnvironmentError> {
+        unsafe { environment::get_variable(*self.environment_callback, key) }
+    }
 
-        let info = self.get_vfs_interface(retro_vfs_interface_info {
-            required_interface_version: min_version,
-            iface: std::ptr::null_mut(),
-        });
+    /// Suggests the frontend to show or hide the core option `key`,
+    /// e.g. to hide settings that have no effect given the values of other options.
+    ///
+    /// All options are visible by default.
+    /// Returns `false` if the frontend does not support this.
+    pub fn set_variable_visible(&self, key: &str, visible: bool) -> bool {
+        let key = match CString::new(key) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
 
-        if let Some(info) = info {
-            if !info.iface.is_null() && info.required_interface_version >= min_version {
-                interfaces.vfs_interface_info = VfsInterfaceInfo {
-                    supported_version: info.required_interface_version,
-                    interface: Some(*info.iface),
-                }
-            }
-        }
+        let display = retro_core_option_display {
+            key: key.as_ptr(),
+            visible,
+        };
 
-        if interfaces.vfs_interface_info.interface.is_some() {
-            Ok(interfaces.vfs_interface_info.supported_version)
-        } else {
-            Err("Failed to enable VFS interface".into())
-        }
+        unsafe { environment::set_core_options_display(*self.environment_callback, display) }
+            .is_ok()
     }
 }
 
-/// Functions that are safe to be called in [`Core::on_load_game`].
+/// Functions that are safe to be called in [`Core::on_load_game_special`].
 ///
-/// For a description of the callbacks see [`CoreWrapper`].
-pub struct LoadGameContext<'a> {
+/// The games passed by the frontend are available through [`LoadGameSpecialContext::games`].
+/// Like the data in [`LoadGameContext`], they are only valid until
+/// [`Core::on_load_game_special`] returns.
+///
+/// Converting this context into a [`LoadGameContext`] gives access to the same
+/// interfaces that can be enabled while loading a single game, like the rumble or
+/// performance interfaces:
+///
+/// ```ignore
+/// let mut ctx: LoadGameContext = ctx.into();
+/// ctx.enable_rumble_interface()?;
+/// ```
+pub struct LoadGameSpecialContext<'a> {
     pub(crate) environment_callback: &'a retro_environment_t,
     pub(crate) interfaces: Interfaces,
+    pub(crate) games: &'a [GameInfo],
 }
 
-impl<'a> LoadGameContext<'a> {
+impl<'a> LoadGameSpecialContext<'a> {
     pub(crate) fn new(
         environment_callback: &'a retro_environment_t,
         interfaces: Interfaces,
@@ -990,627 +1253,4399 @@ impl<'a> LoadGameContext<'a> {
         Self {
             environment_callback,
             interfaces,
+            games: &[],
         }
     }
 
-    /// The reference represents the time of one frame.
-    /// It is computed as `1000000 / fps`, but the implementation will resolve the
-    /// rounding to ensure that framestepping, etc is exact.
-    pub fn enable_frame_time_callback(&self, reference: i64) {
-        self.set_frame_time_callback(retro_frame_time_callback {
-            callback: Some(retro_frame_time_callback_fn),
-            reference,
-        });
+    /// Makes the games passed by the frontend available to [`LoadGameSpecialContext::games`].
+    pub(crate) fn with_games(mut self, games: &'a [GameInfo]) -> Self {
+        self.games = games;
+        self
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn enable_camera_interface(
-        &mut self,
-        caps: u64,
-        width: u32,
-        height: u32,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        use retro_camera_buffer::*;
+    /// Returns the games to load, one for each of the [`SubsystemInfo::roms`]
+    /// of the requested subsystem and in the same order.
+    pub fn games(&self) -> &[GameInfo] {
+        self.games
+    }
 
-        let enable_raw = caps & (1 << RETRO_CAMERA_BUFFER_RAW_FRAMEBUFFER as u64) > 0;
-        let enable_opengl = caps & (1 << RETRO_CAMERA_BUFFER_OPENGL_TEXTURE as u64) > 0;
+    /// See [`LoadGameContext::set_pixel_format`].
+    pub fn set_pixel_format<F: Into<retro_pixel_format>>(
+        &self,
+        format: F,
+    ) -> Result<(), environment::EnvironmentError> {
+        GenericContext::from(self).set_pixel_format(format)
+    }
+}
 
-        let mut interfaces = self.interfaces.write().unwrap();
+impl GetAvInfoContext<'_> {
+    /// See [`LoadGameContext::set_pixel_format`].
+    pub fn set_pixel_format<F: Into<retro_pixel_format>>(
+        &self,
+        format: F,
+    ) -> Result<(), environment::EnvironmentError> {
+        GenericContext::from(self).set_pixel_format(format)
+    }
+}
+into_generic!(LoadGameSpecialContext<'a>, 'a);
+into_generic!(LoadGameSpecialContext<'a>, LoadGameContext, 'a);
 
-        interfaces.camera_interface = self.get_camera_interface(retro_camera_callback {
-            caps,
-            width,
-            height,
+impl LoadGameSpecialContext<'_> {
+    /// Returns extended information about the loaded content files, one entry for each of
+    /// the [`LoadGameSpecialContext::games`], see [`LoadGameContext::get_game_info_ext`].
+    pub fn get_game_info_ext(&self) -> Option<&[GameInfoExt]> {
+        let info = unsafe { environment::get_game_info_ext(*self.environment_callback) }.ok()?;
 
-            start: None,
-            stop: None,
+        if info.is_null() {
+            return None;
+        }
 
-            frame_raw_framebuffer: if enable_raw {
-                Some(retro_camera_frame_raw_framebuffer_callback)
-            } else {
-                None
-            },
-            frame_opengl_texture: if enable_opengl {
-                Some(retro_camera_frame_opengl_texture_callback)
-            } else {
-                None
-            },
-            initialized: Some(retro_camera_initialized_callback),
-            deinitialized: Some(retro_camera_deinitialized_callback),
-        });
+        // SAFETY: The frontend provides one entry for each of the `num_info` games
+        // passed to `retro_load_game_special`, which `games` has been created from.
+        // `GameInfoExt` is a transparent wrapper around `retro_game_info_ext`
+        Some(unsafe { std::slice::from_raw_parts(info as *const GameInfoExt, self.games.len()) })
+    }
+}
 
-        if interfaces.camera_interface.is_some() {
-            Ok(())
-        } else {
-            Err("Failed to enable camera interface".into())
-        }
+make_context!(LocationContext, #[doc = "Wraps the location interface enabled by [`LoadGameContext::enable_location_interface`]"]);
+into_generic!(GenericContext<'a>, LocationContext, 'a);
+
+impl LocationContext<'_> {
+    fn interface(&self) -> Option<retro_location_callback> {
+        self.interfaces.read().unwrap().location_interface
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn enable_sensor_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let ctx: GenericContext = self.into();
-        let mut interfaces = self.interfaces.write().unwrap();
-        interfaces.sensor_interface = ctx.get_sensor_interface();
+    /// Starts the location service.
+    /// The device might start listening for changes to the current location at regular intervals,
+    /// which are defined with [`LocationContext::set_interval`].
+    pub fn start(&self) -> bool {
+        if let Some(start) = self.interface().and_then(|interface| interface.start) {
+            return unsafe { start() };
+        }
 
-        if interfaces.sensor_interface.is_some() {
-            Ok(())
-        } else {
-            Err("Failed to enable sensor interface".into())
+        false
+    }
+
+    /// Stops the location service.
+    pub fn stop(&self) {
+        if let Some(stop) = self.interface().and_then(|interface| interface.stop) {
+            unsafe { stop() };
         }
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn enable_led_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let ctx: GenericContext = self.into();
-        let mut interfaces = self.interfaces.write().unwrap();
-        interfaces.led_interface = ctx.get_led_interface();
+    /// Gets the current position of the device.
+    ///
+    /// Returns [`None`] if the location interface is unavailable or no position fix has been found yet.
+    pub fn get_position(&self) -> Option<Position> {
+        let get_position = self.interface()?.get_position?;
+
+        let mut position = Position::default();
+
+        let success = unsafe {
+            get_position(
+                &mut position.lat,
+                &mut position.lon,
+                &mut position.horiz_accuracy,
+                &mut position.vert_accuracy,
+            )
+        };
 
-        if interfaces.led_interface.is_some() {
-            Ok(())
+        if success {
+            Some(position)
         } else {
-            Err("Failed to enable led interface".into())
+            None
         }
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn enable_midi_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let ctx: GenericContext = self.into();
-        let mut interfaces = self.interfaces.write().unwrap();
-        interfaces.midi_interface = ctx.get_midi_interface();
-
-        if interfaces.midi_interface.is_some() {
-            Ok(())
-        } else {
-            Err("Failed to enable MIDI interface".into())
+    /// Sets the interval of time (`interval_ms`) and/or distance in meters (`interval_distance`)
+    /// at which to update the location.
+    pub fn set_interval(&self, interval_ms: u32, interval_distance: u32) {
+        if let Some(set_interval) = self
+            .interface()
+            .and_then(|interface| interface.set_interval)
+        {
+            unsafe { set_interval(interval_ms, interval_distance) };
         }
     }
+}
 
-    pub fn enable_location_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let ctx: GenericContext = self.into();
-        let mut interfaces = self.interfaces.write().unwrap();
-        interfaces.location_interface = ctx.get_location_callback();
+make_context!(PerfContext, #[doc = "Wraps the performance interface enabled by [`LoadGameContext::enable_perf_interface`]"]);
+into_generic!(GenericContext<'a>, PerfContext, 'a);
 
-        if let Some(mut interface) = interfaces.location_interface {
-            interface.initialized = Some(retro_location_lifetime_status_initialized_callback);
-            interface.deinitialized = Some(retro_location_lifetime_status_deinitialized_callback);
-            Ok(())
-        } else {
-            Err("Failed to enable location interface".into())
+impl PerfContext<'_> {
+    fn interface(&self) -> Result<retro_perf_callback, Box<dyn std::error::Error>> {
+        Ok(self
+            .interfaces
+            .read()
+            .unwrap()
+            .perf_interface
+            .interface
+            .ok_or("Performance interface not found, did you call `enable_perf_interface()`?")?)
+    }
+
+    /// Returns the current time in microseconds.
+    pub fn get_time_usec(&self) -> i64 {
+        match self.interface() {
+            Ok(interface) => {
+                if let Some(get_time_usec) = interface.get_time_usec {
+                    return unsafe { get_time_usec() };
+                }
+
+                #[cfg(feature = "log")]
+                log::error!("`get_time_usec()` is missing on the performance interface");
+            }
+            #[cfg(feature = "log")]
+            Err(err) => log::error!("{err}"),
+            #[cfg(not(feature = "log"))]
+            Err(_) => (),
         }
+
+        0
     }
 
-    pub fn enable_rumble_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut interfaces = self.interfaces.write().unwrap();
-        interfaces.rumble_interface = self.get_rumble_interface();
+    /// Returns a high resolution, platform specific counter, e.g. the CPU’s time stamp counter.
+    pub fn get_counter(&self) -> u64 {
+        match self.interface() {
+            Ok(interface) => {
+                if let Some(get_perf_counter) = interface.get_perf_counter {
+                    return unsafe { get_perf_counter() };
+                }
 
-        if interfaces.rumble_interface.is_some() {
-            Ok(())
-        } else {
-            Err("Failed to enable rumble interface".into())
+                #[cfg(feature = "log")]
+                log::error!("`get_perf_counter()` is missing on the performance interface");
+            }
+            #[cfg(feature = "log")]
+            Err(err) => log::error!("{err}"),
+            #[cfg(not(feature = "log"))]
+            Err(_) => (),
         }
+
+        0
     }
 
-    pub fn enable_perf_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let ctx: GenericContext = self.into();
-        let mut interfaces = self.interfaces.write().unwrap();
-        interfaces.perf_interface = PerfCounters {
-            interface: ctx.get_perf_interface(),
-            counters: HashMap::new(),
-        };
+    /// Returns the SIMD instruction sets supported by the CPU.
+    pub fn get_cpu_features(&self) -> CpuFeatures {
+        match self.interface() {
+            Ok(interface) => {
+                if let Some(get_cpu_features) = interface.get_cpu_features {
+                    return unsafe { CpuFeatures::from_bits_unchecked(get_cpu_features()) };
+                }
 
-        if interfaces.perf_interface.interface.is_some() {
-            Ok(())
-        } else {
-            Err("Failed to enable perf interface".into())
+                #[cfg(feature = "log")]
+                log::error!("`get_cpu_features()` is missing on the performance interface");
+            }
+            #[cfg(feature = "log")]
+            Err(err) => log::error!("{err}"),
+            #[cfg(not(feature = "log"))]
+            Err(_) => (),
         }
+
+        CpuFeatures::empty()
     }
 
-    pub unsafe fn enable_hw_render(
-        &mut self,
-        context_type: retro_hw_context_type,
-        bottom_left_origin: bool,
-        version_major: u32,
-        version_minor: u32,
-        debug_context: bool,
-    ) -> bool {
-        let data = retro_hw_render_callback {
-            context_type,
-            bottom_left_origin,
-            version_major,
-            version_minor,
-            cache_context: true,
-            debug_context,
+    /// Registers a performance counter with the frontend.
+    ///
+    /// Registering the same `name` again returns the existing counter.
+    pub fn perf_register(
+        &self,
+        name: &'static str,
+    ) -> Result<PerfCounter, Box<dyn std::error::Error>> {
+        use std::collections::hash_map::Entry;
 
-            depth: false,   // obsolete
-            stencil: false, // obsolete
+        let interface = self.interface()?;
+        let mut interfaces = self.interfaces.write().unwrap();
 
-            context_reset: Some(retro_hw_context_reset_callback),
-            context_destroy: Some(retro_hw_context_destroyed_callback),
+        let counter = match interfaces.perf_interface.counters.entry(name) {
+            Entry::Occupied(counter) => counter.into_mut(),
+            Entry::Vacant(entry) => {
+                let ident = CString::new(name)?;
+                let ptr = ident.as_ptr();
 
-            // Set by the frontend
-            get_current_framebuffer: None,
-            get_proc_address: None,
+                entry.insert(RegisteredPerfCounter {
+                    ident,
+                    counter: Box::new(retro_perf_counter {
+                        ident: ptr,
+                        start: 0,
+                        total: 0,
+                        call_cnt: 0,
+                        registered: false,
+                    }),
+                })
+            }
         };
 
-        self.set_hw_render(data)
-    }
+        if !counter.counter.registered {
+            let register = interface
+                .perf_register
+                .ok_or("`perf_register()` is missing on the performance interface")?;
 
-    #[proc::unstable(feature = "env-commands")]
-    pub unsafe fn set_hw_render_context_negotiation_interface_data<
-        T: HwRenderContextNegotiationInterface + 'static,
-    >(
-        &mut self,
-        interface: T,
-    ) -> bool {
-        assert!(
-            std::mem::size_of::<T>()
-                >= std::mem::size_of::<retro_hw_render_context_negotiation_interface>()
-        );
+            unsafe {
+                register(counter.counter.as_mut() as *mut _);
+            }
+        }
+
+        Ok(PerfCounter { name })
+    }
 
+    fn with_registered_counter(
+        &self,
+        counter: &PerfCounter,
+        f: impl FnOnce(&mut retro_perf_counter),
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let mut interfaces = self.interfaces.write().unwrap();
+        let name = counter.name;
 
-        let data = Box::new(interface);
+        match interfaces.perf_interface.counters.get_mut(name) {
+            Some(counter) if counter.counter.registered => {
+                f(counter.counter.as_mut());
+                Ok(())
+            }
+            Some(_) => Err(format!("Performance counter “{name}” has not been registered").into()),
+            None => Err(format!("Unknown performance counter “{name}”").into()),
+        }
+    }
 
-        interfaces
-            .hw_render_context_negotiation_interface
-            .replace(data);
+    /// Starts measuring with the given `counter`.
+    pub fn perf_start(&self, counter: &PerfCounter) -> Result<(), Box<dyn std::error::Error>> {
+        let start = self
+            .interface()?
+            .perf_start
+            .ok_or("`perf_start()` is missing on the performance interface")?;
 
-        let interface = interfaces
-            .hw_render_context_negotiation_interface
-            .as_ref()
-            .unwrap()
-            .as_any()
-            .downcast_ref::<T>()
-            .unwrap();
+        self.with_registered_counter(counter, |counter| unsafe { start(counter) })
+    }
 
-        let interface =
-            interface as *const _ as *const retro_hw_render_context_negotiation_interface;
+    /// Stops measuring with the given `counter`.
+    pub fn perf_stop(&self, counter: &PerfCounter) -> Result<(), Box<dyn std::error::Error>> {
+        let stop = self
+            .interface()?
+            .perf_stop
+            .ok_or("`perf_stop()` is missing on the performance interface")?;
 
-        self.set_hw_render_context_negotiation_interface(&*interface)
+        self.with_registered_counter(counter, |counter| unsafe { stop(counter) })
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub unsafe fn enable_hw_render_negotiation_interface(
-        &mut self,
-        interface_type: retro_hw_render_context_negotiation_interface_type,
-        interface_version: u32,
-    ) -> bool {
-        self.set_hw_render_context_negotiation_interface_data(
-            retro_hw_render_context_negotiation_interface {
-                interface_type,
-                interface_version,
-            },
-        )
-    }
+    /// Starts the given `counter` and returns a guard that stops it once dropped.
+    ///
+    /// ```ignore
+    /// let perf = ctx.perf();
+    /// let counter = perf.perf_register("render")?;
+    ///
+    /// {
+    ///     let _guard = perf.perf_guard(&counter)?;
+    ///     render();
+    /// }
+    /// ```
+    pub fn perf_guard(
+        &self,
+        counter: &PerfCounter,
+    ) -> Result<PerfCounterGuard<'_>, Box<dyn std::error::Error>> {
+        self.perf_start(counter)?;
 
-    #[cfg(feature = "vulkan")]
-    #[proc::unstable(feature = "env-commands")]
-    pub unsafe fn enable_hw_render_negotiation_interface_vulkan(
-        &mut self,
-        get_application_info: retro_vulkan_get_application_info_t,
-        create_device: retro_vulkan_create_device_t,
-        destroy_device: retro_vulkan_destroy_device_t,
-    ) -> bool {
-        self.set_hw_render_context_negotiation_interface_data(retro_hw_render_context_negotiation_interface_vulkan {
-            interface_type: retro_hw_render_context_negotiation_interface_type::RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN,
-            interface_version: RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN_VERSION,
-            get_application_info,
-            create_device,
-            destroy_device,
+        Ok(PerfCounterGuard {
+            ctx: self,
+            counter: *counter,
         })
     }
+
+    /// Asks the frontend to log the current state of all registered counters.
+    pub fn perf_log(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let log = self
+            .interface()?
+            .perf_log
+            .ok_or("`perf_log()` is missing on the performance interface")?;
+
+        unsafe {
+            log();
+        }
+
+        Ok(())
+    }
 }
-into_generic!(LoadGameContext<'a>, 'a);
 
-/// Functions that are safe to be called in [`Core::on_write_audio`].
-///
-/// For a description of the callbacks see [`CoreWrapper`].
-pub struct AudioContext<'a> {
-    pub(crate) environment_callback: &'a retro_environment_t,
-    pub(crate) interfaces: Interfaces,
+/// Stops a performance counter when dropped, see [`PerfContext::perf_guard`].
+pub struct PerfCounterGuard<'a> {
+    ctx: &'a PerfContext<'a>,
+    counter: PerfCounter,
+}
 
-    pub(crate) audio_sample_batch_callback: &'a retro_audio_sample_batch_t,
-    pub(crate) audio_sample_callback: &'a retro_audio_sample_t,
+impl Drop for PerfCounterGuard<'_> {
+    fn drop(&mut self) {
+        if let Err(err) = self.ctx.perf_stop(&self.counter) {
+            #[cfg(feature = "log")]
+            log::error!("{err}");
+            #[cfg(not(feature = "log"))]
+            let _ = err;
+        }
+    }
 }
 
-impl AudioContext<'_> {
-    /// Renders multiple audio frames in one go if [`AudioContext::audio_sample_batch_callback`] has been set.
-    ///
-    /// One frame is defined as a sample of left and right channels, interleaved.
-    /// I.e. `let buf: [u16; 4] = [ l, r, l, r ];` would be 2 frames.
-    ///
-    /// Only one of the audio callbacks must ever be used.
-    pub fn batch_audio_samples(&self, samples: &[i16]) {
-        if let Some(callback) = self.audio_sample_batch_callback {
-            let len = samples.len();
+/// Wraps the MIDI interface enabled by [`LoadGameContext::enable_midi_interface`].
+///
+/// Obtained through [`GenericContext::midi`].
+#[derive(Debug, Copy, Clone)]
+pub struct MidiInterface {
+    interface: retro_midi_interface,
+}
 
-            unsafe {
-                (callback)(samples.as_ptr(), len / 2);
-            }
+impl MidiInterface {
+    /// Returns `true` if the frontend has MIDI input enabled.
+    pub fn input_enabled(&self) -> bool {
+        if let Some(input_enabled) = self.interface.input_enabled {
+            return unsafe { input_enabled() };
         }
+
+        false
     }
 
-    /// Renders a single audio frame if [`AudioContext::audio_sample_callback`] has been set.
-    /// Should only be used if implementation generates a single sample at a time.
-    /// Format is signed 16-bit native endian.
-    ///
-    /// Only one of the audio callbacks must ever be used.
-    pub fn queue_audio_sample(&self, left: i16, right: i16) {
-        if let Some(callback) = self.audio_sample_callback {
-            unsafe {
-                (callback)(left, right);
-            }
+    /// Returns `true` if the frontend has MIDI output enabled.
+    pub fn output_enabled(&self) -> bool {
+        if let Some(output_enabled) = self.interface.output_enabled {
+            return unsafe { output_enabled() };
         }
+
+        false
     }
-}
 
-into_generic!(AudioContext<'a>, 'a);
+    /// Reads the next byte from the MIDI input stream, if one is available.
+    pub fn read(&mut self) -> Option<u8> {
+        let read = self.interface.read?;
+        let mut value = 0;
 
-/// Functions that are safe to be called in [`Core::on_run`].
-///
-/// For a description of the callbacks see [`CoreWrapper`].
-pub struct RunContext<'a> {
-    pub(crate) environment_callback: &'a retro_environment_t,
-    pub(crate) interfaces: Interfaces,
+        if unsafe { read(&mut value) } {
+            Some(value)
+        } else {
+            None
+        }
+    }
 
-    pub(crate) audio_sample_batch_callback: &'a retro_audio_sample_batch_t,
-    pub(crate) audio_sample_callback: &'a retro_audio_sample_t,
-    pub(crate) input_poll_callback: &'a retro_input_poll_t,
-    pub(crate) input_state_callback: &'a retro_input_state_t,
-    pub(crate) video_refresh_callback: &'a retro_video_refresh_t,
+    /// Writes a byte to the MIDI output stream.
+    /// `delta_time` is the time in microseconds since the previous write.
+    ///
+    /// Written bytes are buffered by the frontend until [`MidiInterface::flush`] is called,
+    /// which should happen at least once per frame.
+    pub fn write(&mut self, byte: u8, delta_time: u32) -> bool {
+        if let Some(write) = self.interface.write {
+            return unsafe { write(byte, delta_time) };
+        }
 
-    pub(crate) can_dupe: bool,
-    pub(crate) had_frame: &'a mut bool,
-    pub(crate) last_width: &'a mut u32,
-    pub(crate) last_height: &'a mut u32,
-    pub(crate) last_pitch: &'a mut usize,
+        false
+    }
 
-    pub(crate) supports_bitmasks: bool,
+    /// Sends all bytes buffered by [`MidiInterface::write`] to the output device.
+    pub fn flush(&mut self) -> bool {
+        if let Some(flush) = self.interface.flush {
+            return unsafe { flush() };
+        }
+
+        false
+    }
 }
 
-into_generic!(RunContext<'a>, 'a);
+make_context!(NetplayContext, #[doc = "Provides information about netplay sessions, see [`Core::on_netplay_started`]"]);
+into_generic!(GenericContext<'a>, NetplayContext, 'a);
 
-impl<'a> From<&mut RunContext<'a>> for AudioContext<'a> {
-    fn from(other: &mut RunContext<'a>) -> AudioContext<'a> {
-        AudioContext {
-            environment_callback: other.environment_callback,
-            interfaces: Arc::clone(&other.interfaces),
+impl NetplayContext<'_> {
+    /// Returns `true` once the frontend started using save states for rollback netplay
+    /// with the currently loaded game.
+    ///
+    /// libretro has no command to query the netplay state directly, so this only turns `true`
+    /// with the first save state the frontend asks for, and stays so until another game gets loaded.
+    /// While it is `true`, all connected instances must stay deterministic.
+    pub fn is_netplay_active(&self) -> bool {
+        self.interfaces.read().unwrap().netplay_active
+    }
+}
 
-            audio_sample_batch_callback: other.audio_sample_batch_callback,
-            audio_sample_callback: other.audio_sample_callback,
+make_context!(SensorContext, #[doc = "Wraps the sensor interface enabled by [`LoadGameContext::enable_sensor_interface`]"]);
+into_generic!(GenericContext<'a>, SensorContext, 'a);
+
+impl SensorContext<'_> {
+    fn interface(&self) -> Option<retro_sensor_interface> {
+        self.interfaces.read().unwrap().sensor_interface
+    }
+
+    /// Enables or disables a sensor on the given `port`.
+    /// `rate` is the requested update rate in Hz.
+    ///
+    /// Returns `false` if the sensor interface is unavailable or the frontend rejected the request.
+    pub fn set_sensor_state(&self, port: u32, action: retro_sensor_action, rate: u32) -> bool {
+        if let Some(set_sensor_state) = self
+            .interface()
+            .and_then(|interface| interface.set_sensor_state)
+        {
+            return unsafe { set_sensor_state(port, action, rate) };
+        }
+
+        false
+    }
+
+    /// Reads the current value of a sensor on the given `port`.
+    ///
+    /// Returns `0.0` if the sensor interface is unavailable.
+    pub fn get_sensor_input(&self, port: u32, id: SensorInput) -> f32 {
+        if let Some(get_sensor_input) = self
+            .interface()
+            .and_then(|interface| interface.get_sensor_input)
+        {
+            return unsafe { get_sensor_input(port, id as u32) };
         }
+
+        0.0
     }
 }
 
-impl RunContext<'_> {
-    #[inline(always)]
-    pub fn can_dupe(&self) -> bool {
-        self.can_dupe
+make_context!(HwContext, #[doc = "Functions that are safe to be called in [`Core::on_hw_context_reset`] and [`Core::on_hw_context_destroyed`]"]);
+
+impl HwContext<'_> {
+    /// Returns the [`retro_hw_render_callback`] that has been negotiated
+    /// with the frontend in [`LoadGameContext::enable_hw_render`], if any.
+    pub fn hw_render_callback(&self) -> Option<retro_hw_render_callback> {
+        self.interfaces.read().unwrap().hw_render_callback
     }
 
-    /// Polls for input if [`RunContext::input_poll_callback`] has been set
-    pub fn poll_input(&self) {
-        if let Some(callback) = self.input_poll_callback {
-            unsafe {
-                (callback)();
+    /// Returns the framebuffer object the [`Core`] should render into.
+    ///
+    /// For OpenGL this is the FBO that has to be bound before drawing a frame.
+    /// Returns `0` if hardware rendering has not been negotiated.
+    pub fn get_current_framebuffer(&self) -> usize {
+        if let Some(callback) = self.hw_render_callback() {
+            if let Some(get_current_framebuffer) = callback.get_current_framebuffer {
+                return unsafe { get_current_framebuffer() };
             }
         }
+
+        0
     }
 
-    /// Gets the input state for the given player and device if [`RunContext::input_state_callback`] has been set
-    pub fn get_input_state(&self, port: u32, device: u32, index: u32, id: u32) -> i16 {
-        if let Some(callback) = self.input_state_callback {
-            unsafe { (callback)(port, device, index, id) }
-        } else {
-            0
+    /// Looks up a function of the negotiated rendering API, e.g. an OpenGL symbol.
+    pub fn get_proc_address(&self, symbol: &str) -> retro_proc_address_t {
+        let symbol = CString::new(symbol).ok()?;
+
+        if let Some(callback) = self.hw_render_callback() {
+            if let Some(get_proc_address) = callback.get_proc_address {
+                return unsafe { get_proc_address(symbol.as_ptr()) };
+            }
         }
+
+        None
     }
 
-    /// Queries the libretro frontend for the state of each joypad button
-    /// by making an environment call for every button separately.
+    /// Returns the Vulkan instance, device, queue and function pointers of the frontend.
     ///
-    /// See also [`Self::get_joypad_bitmask`].
-    pub fn get_joypad_state(&self, port: u32, index: u32) -> JoypadState {
-        if let Some(callback) = self.input_state_callback {
-            let mut mask = JoypadState::empty();
+    /// Returns [`None`] if no Vulkan context has been negotiated with
+    /// [`LoadGameContext::set_hw_render`] or [`LoadGameContext::enable_hw_render`],
+    /// or if the frontend provides an interface of an unexpected type or version.
+    ///
+    /// The frontend creates the interface right before [`Core::on_hw_context_reset`]
+    /// and invalidates it after [`Core::on_hw_context_destroyed`], so it can't be queried
+    /// from [`Core::on_load_game`] and has to be queried again after every reset.
+    #[cfg(feature = "vulkan")]
+    #[proc::unstable(feature = "env-commands")]
+    pub fn vulkan_interface(&self) -> Option<retro_hw_render_interface_vulkan> {
+        let context_type = self.hw_render_callback()?.context_type;
 
-            unsafe {
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_B) != 0 {
-                    mask |= JoypadState::B
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_Y) != 0 {
-                    mask |= JoypadState::Y
-                }
-                if (callback)(
-                    port,
-                    RETRO_DEVICE_JOYPAD,
-                    index,
-                    RETRO_DEVICE_ID_JOYPAD_SELECT,
-                ) != 0
-                {
-                    mask |= JoypadState::SELECT
-                }
-                if (callback)(
-                    port,
-                    RETRO_DEVICE_JOYPAD,
-                    index,
-                    RETRO_DEVICE_ID_JOYPAD_START,
-                ) != 0
-                {
-                    mask |= JoypadState::START
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_UP) != 0 {
-                    mask |= JoypadState::UP
-                }
-                if (callback)(
-                    port,
-                    RETRO_DEVICE_JOYPAD,
-                    index,
-                    RETRO_DEVICE_ID_JOYPAD_DOWN,
-                ) != 0
-                {
-                    mask |= JoypadState::DOWN
-                }
-                if (callback)(
-                    port,
-                    RETRO_DEVICE_JOYPAD,
-                    index,
-                    RETRO_DEVICE_ID_JOYPAD_LEFT,
-                ) != 0
-                {
-                    mask |= JoypadState::LEFT
-                }
-                if (callback)(
-                    port,
-                    RETRO_DEVICE_JOYPAD,
-                    index,
-                    RETRO_DEVICE_ID_JOYPAD_RIGHT,
-                ) != 0
-                {
-                    mask |= JoypadState::RIGHT
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_A) != 0 {
-                    mask |= JoypadState::A
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_X) != 0 {
-                    mask |= JoypadState::X
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_L) != 0 {
-                    mask |= JoypadState::L
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_R) != 0 {
-                    mask |= JoypadState::R
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_L2) != 0 {
-                    mask |= JoypadState::L2
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_R2) != 0 {
-                    mask |= JoypadState::R2
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_L3) != 0 {
-                    mask |= JoypadState::L3
-                }
-                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_R3) != 0 {
-                    mask |= JoypadState::R3
+        if context_type != retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN {
+            return None;
+        }
+
+        let interface =
+            unsafe { environment::get_hw_render_interface_vulkan(*self.environment_callback) }
+                .ok()?;
+
+        if interface.interface_type
+            != retro_hw_render_interface_type::RETRO_HW_RENDER_INTERFACE_VULKAN
+            || interface.interface_version != RETRO_HW_RENDER_INTERFACE_VULKAN_VERSION
+        {
+            return None;
+        }
+
+        Some(interface)
+    }
+}
+
+make_context!(SetEnvironmentContext, #[doc = "Functions that are safe to be called in [`Core::on_set_environment`]"]);
+
+impl<'a> SetEnvironmentContext<'a> {
+    /// See [`InitContext::enable_frame_time_callback`].
+    pub fn enable_frame_time_callback(
+        &self,
+        reference: i64,
+    ) -> Result<(), environment::EnvironmentError> {
+        enable_frame_time_callback(self.environment_callback, &self.interfaces, reference)
+    }
+
+    /// Lets the frontend look up functions using [`Core::get_proc_address`].
+    ///
+    /// This happens automatically during the first call to `retro_set_environment`
+    /// if [`Core::exports_proc_addresses`] returns `true`.
+    pub fn enable_proc_address(&self) -> Result<(), environment::EnvironmentError> {
+        self.set_proc_address_callback(retro_get_proc_address_interface {
+            get_proc_address: Some(retro_get_proc_address_callback),
+        })
+    }
+
+    /// See [`SetEnvironmentContext::enable_proc_address`].
+    pub fn enable_proc_address_interface(&mut self) -> Result<(), environment::EnvironmentError> {
+        self.enable_proc_address()
+    }
+
+    /// Makes the frontend call [`Core::on_core_options_update_display`].
+    ///
+    /// This happens automatically during the first call to `retro_set_environment`.
+    pub fn enable_options_update_display_callback(
+        &mut self,
+    ) -> Result<(), environment::EnvironmentError> {
+        self.set_core_options_update_display_callback(retro_core_options_update_display_callback {
+            callback: Some(retro_core_options_update_display_callback_fn),
+        })
+    }
+
+    /// Tells the frontend what each input does, so it can show meaningful
+    /// labels like “Jump” instead of “B” in its remapping menu.
+    ///
+    /// The descriptors get lowered to a C array that is terminated by a zeroed-out entry.
+    ///
+    /// See also [`GenericContext::set_input_descriptors`] for passing `retro_input_descriptor`s directly.
+    pub fn set_input_descriptors(
+        &self,
+        descriptors: &[InputDescriptor],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let descriptions = descriptors
+            .iter()
+            .map(|descriptor| CString::new(descriptor.description))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut raw_descriptors = descriptors
+            .iter()
+            .zip(&descriptions)
+            .map(|(descriptor, description)| retro_input_descriptor {
+                port: descriptor.port,
+                device: descriptor.device,
+                index: descriptor.index,
+                id: descriptor.id,
+                description: description.as_ptr(),
+            })
+            .collect::<Vec<_>>();
+
+        // End of list
+        raw_descriptors.push(retro_input_descriptor {
+            port: 0,
+            device: 0,
+            index: 0,
+            id: 0,
+            description: std::ptr::null(),
+        });
+
+        let gctx: GenericContext = self.into();
+        gctx.set_input_descriptors(&raw_descriptors)?;
+
+        self.interfaces.write().unwrap().input_descriptions = descriptions;
+
+        Ok(())
+    }
+
+    /// Tells the frontend which devices can be selected for each input port,
+    /// e.g. “Standard Gamepad” and “Mouse” for port 0.
+    ///
+    /// The selected device gets passed to [`Core::on_set_controller_port_device`].
+    ///
+    /// See also [`GenericContext::set_controller_info`] for passing `retro_controller_info`s directly.
+    pub fn set_controller_info(
+        &self,
+        ports: &[ControllerDescription],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let info = RawControllerInfo::new(ports)?;
+
+        let gctx: GenericContext = self.into();
+        gctx.set_controller_info(info.as_slice())?;
+
+        self.interfaces.write().unwrap().controller_info = info;
+
+        Ok(())
+    }
+
+    /// Overrides how the frontend loads content with specific extensions,
+    /// e.g. to require a path for one format while loading all others from memory.
+    ///
+    /// The overrides take precedence over [`SystemInfo::need_fullpath`] for the listed extensions,
+    /// all other extensions from [`SystemInfo::valid_extensions`] keep using the static values.
+    /// If an extension is listed multiple times, only the first override applies.
+    pub fn set_content_info_override(
+        &self,
+        overrides: &[ContentInfoOverride],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let extensions = overrides
+            .iter()
+            .map(|info| CString::new(info.extensions))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut raw_overrides = overrides
+            .iter()
+            .zip(&extensions)
+            .map(|(info, extensions)| retro_system_content_info_override {
+                extensions: extensions.as_ptr(),
+                need_fullpath: info.need_fullpath,
+                persistent_data: info.persistent_data,
+            })
+            .collect::<Vec<_>>();
+
+        // End of list
+        raw_overrides.push(retro_system_content_info_override {
+            extensions: std::ptr::null(),
+            need_fullpath: false,
+            persistent_data: false,
+        });
+
+        unsafe {
+            environment::set_content_info_override(*self.environment_callback, &raw_overrides)?
+        };
+
+        self.interfaces
+            .write()
+            .unwrap()
+            .content_info_override_extensions = extensions;
+
+        Ok(())
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub fn enable_vfs_interface(
+        &mut self,
+        min_version: u32,
+    ) -> Result<u32, Box<dyn std::error::Error>> {
+        let info = self.get_vfs_interface(retro_vfs_interface_info {
+            required_interface_version: min_version,
+            iface: std::ptr::null_mut(),
+        });
+
+        let mut interfaces = self.interfaces.write().unwrap();
+
+        if let Ok(info) = info {
+            if !info.iface.is_null() && info.required_interface_version >= min_version {
+                interfaces.vfs_interface_info = VfsInterfaceInfo {
+                    supported_version: info.required_interface_version,
+                    interface: Some(*info.iface),
                 }
             }
+        }
+
+        if interfaces.vfs_interface_info.interface.is_some() {
+            Ok(interfaces.vfs_interface_info.supported_version)
+        } else {
+            Err("Failed to enable VFS interface".into())
+        }
+    }
+}
+
+/// Functions that are safe to be called in [`Core::on_load_game`].
+///
+/// For a description of the callbacks see [`CoreWrapper`].
+///
+/// # Lifetime of the game data
+///
+/// The `data` pointer of the [`retro_game_info`] passed to [`Core::on_load_game`]
+/// is only valid until that function returns, so cores must not keep it around.
+/// Use [`LoadGameContext::take_game_data`] to get an owned copy instead.
+///
+/// If the core declared [`ContentInfoOverride::persistent_data`] for the extension of
+/// the loaded content, the frontend keeps the data alive until [`Core::on_deinit`] returns,
+/// see [`GameInfoExt::persistent_data`]. [`LoadGameContext::persistent_game_data`]
+/// returns it without copying, leaving it to the core not to use it after [`Core::on_deinit`].
+pub struct LoadGameContext<'a> {
+    pub(crate) environment_callback: &'a retro_environment_t,
+    pub(crate) interfaces: Interfaces,
+    pub(crate) game_data: Option<&'a [u8]>,
+}
+
+impl<'a> LoadGameContext<'a> {
+    pub(crate) fn new(
+        environment_callback: &'a retro_environment_t,
+        interfaces: Interfaces,
+    ) -> Self {
+        Self {
+            environment_callback,
+            interfaces,
+            game_data: None,
+        }
+    }
+
+    /// Makes the data of the content being loaded available to [`LoadGameContext::take_game_data`].
+    pub(crate) fn with_game_data(mut self, game_data: Option<&'a [u8]>) -> Self {
+        self.game_data = game_data;
+        self
+    }
+
+    /// Copies the data of the loaded content into a buffer owned by the core.
+    ///
+    /// Returns [`None`] if no content was loaded, if the frontend only passed its path
+    /// (see [`SystemInfo::need_fullpath`]), or if the data has already been taken.
+    pub fn take_game_data(&mut self) -> Option<Vec<u8>> {
+        self.game_data.take().map(<[u8]>::to_vec)
+    }
+
+    /// Lets the frontend load and persist `save_ram` as the battery backed save data of the game.
+    ///
+    /// Requests for [`MemoryId::SaveRam`] get answered from it instead of calling [`Core::memory`]
+    /// or [`Core::get_memory_data`]. The registration gets cleared when the next game gets loaded.
+    pub fn set_save_ram(&self, save_ram: &SaveRam) {
+        self.interfaces.write().unwrap().save_ram = Some(save_ram.clone());
+    }
+
+    /// Sets the pixel format of the frames passed to [`RunContext::draw_frame`].
+    ///
+    /// Returns an error if the frontend does not support `format`, in which case the
+    /// previous format stays active. Only [`PixelFormat::XRGB1555`], the default,
+    /// is guaranteed to be supported.
+    pub fn set_pixel_format<F: Into<retro_pixel_format>>(
+        &self,
+        format: F,
+    ) -> Result<(), environment::EnvironmentError> {
+        GenericContext::from(self).set_pixel_format(format)
+    }
+
+    /// Returns extended information about the loaded content file,
+    /// like its directory, canonical name and whether its data is persistent.
+    ///
+    /// Returns [`None`] if the frontend does not support this, which is never the case
+    /// if [`SetEnvironmentContext::set_content_info_override`] succeeded.
+    /// The slice always contains exactly one entry.
+    pub fn get_game_info_ext(&self) -> Option<&[GameInfoExt]> {
+        let info = unsafe { environment::get_game_info_ext(*self.environment_callback) }.ok()?;
+
+        if info.is_null() {
+            return None;
+        }
+
+        // SAFETY: `GameInfoExt` is a transparent wrapper around `retro_game_info_ext`
+        Some(unsafe { std::slice::from_raw_parts(info as *const GameInfoExt, 1) })
+    }
+
+    /// Returns the data of the loaded content if the frontend keeps it alive until
+    /// [`Core::on_deinit`] returns, i.e. if the core declared [`ContentInfoOverride::persistent_data`]
+    /// for its extension.
+    ///
+    /// Unlike [`LoadGameContext::take_game_data`] the data does not get copied.
+    /// Returns [`None`] if the data is not persistent, or if the frontend only passed the path.
+    ///
+    /// # Safety
+    ///
+    /// The returned slice is not actually `'static`: the frontend frees the data once
+    /// [`Core::on_deinit`] returned, so it must not be used after that.
+    pub unsafe fn persistent_game_data(&self) -> Option<&'static [u8]> {
+        let info = unsafe { environment::get_game_info_ext(*self.environment_callback) }.ok()?;
+        let info = unsafe { info.as_ref() }?;
+
+        if !info.persistent_data || info.data.is_null() {
+            return None;
+        }
+
+        // SAFETY: The frontend keeps persistent data alive until `retro_deinit`.
+        Some(unsafe { std::slice::from_raw_parts(info.data as *const u8, info.size) })
+    }
+
+    /// Makes the frontend pass the time since the last frame to [`Core::on_run`].
+    ///
+    /// The reference represents the time of one frame.
+    /// It is computed as `1000000 / fps`, but the implementation will resolve the
+    /// rounding to ensure that framestepping, etc is exact.
+    /// While fast-forwarding or in slow-motion the frontend passes the reference instead
+    /// of the real time, so deltas can be scaled against [`GenericContext::frame_time_reference`].
+    ///
+    /// Cores that need the frame time independently of the loaded game can enable it
+    /// earlier with [`InitContext::enable_frame_time_callback`].
+    pub fn enable_frame_time_callback(
+        &self,
+        reference: i64,
+    ) -> Result<(), environment::EnvironmentError> {
+        enable_frame_time_callback(self.environment_callback, &self.interfaces, reference)
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub fn enable_camera_interface(
+        &mut self,
+        caps: u64,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        use retro_camera_buffer::*;
+
+        let enable_raw = caps & (1 << RETRO_CAMERA_BUFFER_RAW_FRAMEBUFFER as u64) > 0;
+        let enable_opengl = caps & (1 << RETRO_CAMERA_BUFFER_OPENGL_TEXTURE as u64) > 0;
+
+        let camera_interface = self
+            .get_camera_interface(retro_camera_callback {
+                caps,
+                width,
+                height,
+
+                start: None,
+                stop: None,
+
+                frame_raw_framebuffer: if enable_raw {
+                    Some(retro_camera_frame_raw_framebuffer_callback)
+                } else {
+                    None
+                },
+                frame_opengl_texture: if enable_opengl {
+                    Some(retro_camera_frame_opengl_texture_callback)
+                } else {
+                    None
+                },
+                initialized: Some(retro_camera_initialized_callback),
+                deinitialized: Some(retro_camera_deinitialized_callback),
+            })
+            .ok();
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.camera_interface = camera_interface;
+
+        if interfaces.camera_interface.is_some() {
+            Ok(())
+        } else {
+            Err("Failed to enable camera interface".into())
+        }
+    }
+
+    /// See [`GenericContext::camera_start`].
+    #[proc::unstable(feature = "env-commands")]
+    pub fn camera_start(&self) -> bool {
+        let ctx: GenericContext = self.into();
+        ctx.camera_start()
+    }
+
+    /// See [`GenericContext::camera_stop`].
+    #[proc::unstable(feature = "env-commands")]
+    pub fn camera_stop(&self) {
+        let ctx: GenericContext = self.into();
+        ctx.camera_stop()
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub fn enable_sensor_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let ctx: GenericContext = self.into();
+        let sensor_interface = ctx.get_sensor_interface().ok();
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.sensor_interface = sensor_interface;
+
+        if interfaces.sensor_interface.is_some() {
+            Ok(())
+        } else {
+            Err("Failed to enable sensor interface".into())
+        }
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub fn enable_led_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let ctx: GenericContext = self.into();
+        let led_interface = ctx.get_led_interface().ok();
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.led_interface = led_interface;
+
+        if interfaces.led_interface.is_some() {
+            Ok(())
+        } else {
+            Err("Failed to enable led interface".into())
+        }
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub fn enable_midi_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let ctx: GenericContext = self.into();
+        let midi_interface = ctx.get_midi_interface().ok();
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.midi_interface = midi_interface;
+
+        if interfaces.midi_interface.is_some() {
+            Ok(())
+        } else {
+            Err("Failed to enable MIDI interface".into())
+        }
+    }
+
+    /// Enables the location interface.
+    ///
+    /// Once the frontend calls [`Core::on_location_initialized`] the location service
+    /// can be used through [`RunContext::location`].
+    pub fn enable_location_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let ctx: GenericContext = self.into();
+        let location_interface = ctx
+            .get_location_callback(retro_location_callback {
+                start: None,
+                stop: None,
+                get_position: None,
+                set_interval: None,
+                initialized: Some(retro_location_lifetime_status_initialized_callback),
+                deinitialized: Some(retro_location_lifetime_status_deinitialized_callback),
+            })
+            .ok();
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.location_interface = location_interface;
+
+        if interfaces.location_interface.is_some() {
+            Ok(())
+        } else {
+            Err("Failed to enable location interface".into())
+        }
+    }
+
+    /// Returns `true` if [`LoadGameContext::enable_rumble_interface`] succeeded.
+    pub fn has_rumble(&self) -> bool {
+        self.interfaces.read().unwrap().rumble_interface.is_some()
+    }
+
+    pub fn enable_rumble_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let rumble_interface = self.get_rumble_interface().ok();
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.rumble_interface = rumble_interface;
+
+        if interfaces.rumble_interface.is_some() {
+            Ok(())
+        } else {
+            Err("Failed to enable rumble interface".into())
+        }
+    }
+
+    pub fn enable_perf_interface(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let ctx: GenericContext = self.into();
+        let perf_interface = ctx.get_perf_interface().ok();
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.perf_interface = PerfCounters {
+            interface: perf_interface,
+            counters: HashMap::new(),
+        };
+
+        if interfaces.perf_interface.interface.is_some() {
+            Ok(())
+        } else {
+            Err("Failed to enable perf interface".into())
+        }
+    }
+
+    pub unsafe fn enable_hw_render(
+        &mut self,
+        context_type: retro_hw_context_type,
+        bottom_left_origin: bool,
+        version_major: u32,
+        version_minor: u32,
+        debug_context: bool,
+    ) -> Result<(), environment::EnvironmentError> {
+        let data = retro_hw_render_callback {
+            context_type,
+            bottom_left_origin,
+            version_major,
+            version_minor,
+            cache_context: true,
+            debug_context,
+
+            depth: false,   // obsolete
+            stencil: false, // obsolete
+
+            context_reset: Some(retro_hw_context_reset_callback),
+            context_destroy: Some(retro_hw_context_destroyed_callback),
+
+            // Set by the frontend
+            get_current_framebuffer: None,
+            get_proc_address: None,
+        };
+
+        self.set_hw_render(data)
+    }
+
+    /// Requests a hardware rendering context from the frontend.
+    ///
+    /// `context_type`, `version_major`, `version_minor`, `depth`, `stencil`,
+    /// `bottom_left_origin`, `cache_context` and `debug_context` are passed on as given,
+    /// the context callbacks get replaced with ones forwarding to
+    /// [`Core::on_hw_context_reset`] and [`Core::on_hw_context_destroyed`].
+    ///
+    /// The `get_current_framebuffer` and `get_proc_address` functions provided by the frontend
+    /// are kept around and are available in [`HwContext`] and [`RunContext::get_current_hw_render_framebuffer`].
+    pub fn set_hw_render(
+        &mut self,
+        render_callback: retro_hw_render_callback,
+    ) -> Result<(), environment::EnvironmentError> {
+        let data = retro_hw_render_callback {
+            context_reset: Some(retro_hw_context_reset_callback),
+            context_destroy: Some(retro_hw_context_destroyed_callback),
+
+            // Set by the frontend
+            get_current_framebuffer: None,
+            get_proc_address: None,
+
+            ..render_callback
+        };
+
+        let data = unsafe { environment::set_hw_render(*self.environment_callback, data)? };
+
+        let mut interfaces = self.interfaces.write().unwrap();
+        interfaces.hw_render_callback.replace(data);
+
+        Ok(())
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub unsafe fn set_hw_render_context_negotiation_interface_data<
+        T: HwRenderContextNegotiationInterface + 'static,
+    >(
+        &mut self,
+        interface: T,
+    ) -> Result<(), environment::EnvironmentError> {
+        assert!(
+            std::mem::size_of::<T>()
+                >= std::mem::size_of::<retro_hw_render_context_negotiation_interface>()
+        );
+
+        let mut interfaces = self.interfaces.write().unwrap();
+
+        let data = Box::new(interface);
+
+        interfaces
+            .hw_render_context_negotiation_interface
+            .replace(data);
+
+        let interface = interfaces
+            .hw_render_context_negotiation_interface
+            .as_ref()
+            .unwrap()
+            .as_any()
+            .downcast_ref::<T>()
+            .unwrap();
+
+        let interface =
+            interface as *const _ as *const retro_hw_render_context_negotiation_interface;
+
+        // The boxed interface stays in place, but the frontend must not be called with the lock held
+        drop(interfaces);
+
+        self.set_hw_render_context_negotiation_interface(&*interface)
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub unsafe fn enable_hw_render_negotiation_interface(
+        &mut self,
+        interface_type: retro_hw_render_context_negotiation_interface_type,
+        interface_version: u32,
+    ) -> Result<(), environment::EnvironmentError> {
+        self.set_hw_render_context_negotiation_interface_data(
+            retro_hw_render_context_negotiation_interface {
+                interface_type,
+                interface_version,
+            },
+        )
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[proc::unstable(feature = "env-commands")]
+    pub unsafe fn enable_hw_render_negotiation_interface_vulkan(
+        &mut self,
+        get_application_info: retro_vulkan_get_application_info_t,
+        create_device: retro_vulkan_create_device_t,
+        destroy_device: retro_vulkan_destroy_device_t,
+    ) -> Result<(), environment::EnvironmentError> {
+        self.set_hw_render_context_negotiation_interface_data(retro_hw_render_context_negotiation_interface_vulkan {
+            interface_type: retro_hw_render_context_negotiation_interface_type::RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN,
+            interface_version: RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN_VERSION,
+            get_application_info,
+            create_device,
+            destroy_device,
+        })
+    }
+
+    /// Lets the frontend call back into the [`Core`] while it creates the Vulkan instance and device.
+    ///
+    /// Has to be called in [`Core::on_load_game`], after a Vulkan context has been requested
+    /// with [`LoadGameContext::enable_hw_render`] or [`LoadGameContext::set_hw_render`],
+    /// returns [`environment::EnvironmentError::InvalidData`] otherwise.
+    /// The interface is kept alive until the next call.
+    #[cfg(feature = "vulkan")]
+    #[proc::unstable(feature = "env-commands")]
+    pub fn set_vulkan_negotiation_interface(
+        &mut self,
+        interface: VulkanNegotiationInterface,
+    ) -> Result<(), environment::EnvironmentError> {
+        let context_type = self
+            .interfaces
+            .read()
+            .unwrap()
+            .hw_render_callback
+            .map(|callback| callback.context_type);
+
+        if context_type != Some(retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN) {
+            return Err(environment::EnvironmentError::InvalidData);
+        }
+
+        let interface: retro_hw_render_context_negotiation_interface_vulkan = interface.into();
+
+        // The type matches the interface type, which makes the frontend read the whole struct
+        unsafe { self.set_hw_render_context_negotiation_interface_data(interface) }
+    }
+}
+into_generic!(LoadGameContext<'a>, 'a);
+
+/// Functions that are safe to be called in [`Core::on_write_audio`].
+///
+/// For a description of the callbacks see [`CoreWrapper`].
+pub struct AudioContext<'a> {
+    pub(crate) environment_callback: &'a retro_environment_t,
+    pub(crate) interfaces: Interfaces,
+
+    pub(crate) audio_sample_batch_callback: &'a retro_audio_sample_batch_t,
+    pub(crate) audio_sample_callback: &'a retro_audio_sample_t,
+}
+
+impl AudioContext<'_> {
+    /// Renders multiple audio frames in one go if [`AudioContext::audio_sample_batch_callback`] has been set.
+    ///
+    /// One frame is defined as a sample of left and right channels, interleaved.
+    /// I.e. `let buf: [u16; 4] = [ l, r, l, r ];` would be 2 frames.
+    /// A trailing sample of an odd-length slice is not a complete frame and gets ignored.
+    ///
+    /// Only one of the audio callbacks must ever be used.
+    pub fn batch_audio_samples(&self, samples: &[i16]) {
+        if let Some(callback) = self.audio_sample_batch_callback {
+            let mut frames = &samples[..samples.len() / 2 * 2];
+
+            #[cfg(feature = "log")]
+            if frames.len() != samples.len() {
+                log::warn!(
+                    "Ignoring the incomplete last frame of {} audio samples",
+                    samples.len()
+                );
+            }
+
+            // The frontend may consume fewer frames than offered
+            while !frames.is_empty() {
+                let written = unsafe { (callback)(frames.as_ptr(), frames.len() / 2) };
+
+                if written == 0 {
+                    break;
+                }
+
+                frames = &frames[(written * 2).min(frames.len())..];
+            }
+        }
+    }
+
+    /// Like [`AudioContext::batch_audio_samples`], but takes a single channel
+    /// that gets played on both the left and right channel.
+    pub fn batch_audio_samples_mono(&self, samples: &[i16]) {
+        let mut buffer = [0; 2 * 512];
+
+        for chunk in samples.chunks(buffer.len() / 2) {
+            for (frame, &sample) in buffer.chunks_exact_mut(2).zip(chunk) {
+                frame.copy_from_slice(&[sample, sample]);
+            }
+
+            self.batch_audio_samples(&buffer[..chunk.len() * 2]);
+        }
+    }
+
+    /// Renders a single audio frame if [`AudioContext::audio_sample_callback`] has been set.
+    /// Should only be used if implementation generates a single sample at a time.
+    /// Format is signed 16-bit native endian.
+    ///
+    /// Only one of the audio callbacks must ever be used.
+    pub fn queue_audio_sample(&self, left: i16, right: i16) {
+        if let Some(callback) = self.audio_sample_callback {
+            unsafe {
+                (callback)(left, right);
+            }
+        }
+    }
+
+    /// Like [`AudioContext::queue_audio_sample`], but plays `sample` on both channels.
+    pub fn queue_audio_sample_mono(&self, sample: i16) {
+        self.queue_audio_sample(sample, sample)
+    }
+}
+
+into_generic!(AudioContext<'a>, 'a);
+
+/// Functions that are safe to be called in [`Core::on_run`].
+///
+/// For a description of the callbacks see [`CoreWrapper`].
+pub struct RunContext<'a> {
+    pub(crate) environment_callback: &'a retro_environment_t,
+    pub(crate) interfaces: Interfaces,
+
+    pub(crate) audio_sample_batch_callback: &'a retro_audio_sample_batch_t,
+    pub(crate) audio_sample_callback: &'a retro_audio_sample_t,
+    pub(crate) input_poll_callback: &'a retro_input_poll_t,
+    pub(crate) input_state_callback: &'a retro_input_state_t,
+    pub(crate) video_refresh_callback: &'a retro_video_refresh_t,
+
+    pub(crate) can_dupe: bool,
+    pub(crate) had_frame: &'a mut bool,
+    pub(crate) last_width: &'a mut u32,
+    pub(crate) last_height: &'a mut u32,
+    pub(crate) last_pitch: &'a mut usize,
+    pub(crate) last_frame: &'a mut Vec<u8>,
+
+    pub(crate) supports_bitmasks: bool,
+}
+
+into_generic!(RunContext<'a>, 'a);
+into_generic!(RunContext<'a>, HwContext, 'a);
+into_generic!(RunContext<'a>, LocationContext, 'a);
+into_generic!(RunContext<'a>, SensorContext, 'a);
+into_generic!(RunContext<'a>, NetplayContext, 'a);
+into_generic!(RunContext<'a>, PerfContext, 'a);
+
+impl<'a> From<&mut RunContext<'a>> for AudioContext<'a> {
+    fn from(other: &mut RunContext<'a>) -> AudioContext<'a> {
+        AudioContext {
+            environment_callback: other.environment_callback,
+            interfaces: Arc::clone(&other.interfaces),
+
+            audio_sample_batch_callback: other.audio_sample_batch_callback,
+            audio_sample_callback: other.audio_sample_callback,
+        }
+    }
+}
+
+impl RunContext<'_> {
+    /// Gives access to the location service.
+    #[inline]
+    pub fn location(&self) -> LocationContext {
+        self.into()
+    }
+
+    /// Gives access to the netplay state.
+    #[inline]
+    pub fn netplay(&self) -> NetplayContext {
+        self.into()
+    }
+
+    /// Makes the next call to `retro_serialize` fail,
+    /// so the frontend does not record the current frame for rewind.
+    ///
+    /// Useful for frames in which the state cannot be captured consistently, e.g. during loading screens.
+    pub fn skip_serialization(&self) {
+        self.interfaces.write().unwrap().skip_serialization = true;
+    }
+
+    /// Changes the nominal video size and aspect ratio on-the-fly, e.g. after a video mode switch.
+    ///
+    /// This is cheap and guaranteed not to reinitialize the frontend’s drivers,
+    /// so it should be preferred over [`RunContext::set_system_av_info`].
+    /// `max_width` and `max_height` cannot be changed this way and get ignored;
+    /// growing beyond them or changing the timing requires [`RunContext::set_system_av_info`],
+    /// which may reinitialize the audio and video drivers and should be used sparingly.
+    ///
+    /// Returns `false` if the frontend did not accept the new geometry.
+    pub fn set_geometry(&self, geometry: retro_game_geometry) -> bool {
+        self.set_game_geometry(geometry).is_ok()
+    }
+
+    /// Asks the frontend to buffer at least `ms` milliseconds of audio,
+    /// see [`GenericContext::set_minimum_audio_latency`] for the caveats.
+    ///
+    /// Dynamic rate control using a [`FramePacer`](crate::pacing::FramePacer) works best with
+    /// a latency of several frames.
+    /// Returns `false` if the frontend does not support this.
+    pub fn notify_audio_latency_target(&self, ms: u32) -> bool {
+        self.set_minimum_audio_latency(ms).is_ok()
+    }
+
+    /// Updates `pacer` with the current throttle state and returns its recommended resampler ratio,
+    /// see [`FramePacer::resampler_ratio`](crate::pacing::FramePacer::resampler_ratio).
+    #[proc::unstable(feature = "env-commands")]
+    pub fn resampler_ratio(&self, pacer: &mut crate::pacing::FramePacer) -> f32 {
+        pacer.throttle_state = unsafe { self.get_throttle_state() };
+
+        pacer.resampler_ratio()
+    }
+
+    /// Forces or inhibits the frontend’s fast-forwarding, e.g. to skip through loading screens
+    /// or to keep cutscenes at normal speed.
+    ///
+    /// - `ratio` is the speed multiplier while fast-forwarding, `0.0` fast-forwards as fast as
+    ///   possible and a negative value uses the ratio configured in the frontend.
+    /// - `notification` lets the frontend display that fast-forwarding is active.
+    /// - `inhibit_toggle` prevents the user from toggling fast-forwarding while set.
+    /// - `fastforward` enables or disables fast-forwarding.
+    ///
+    /// Returns `false` if the frontend does not support this.
+    pub fn set_fastforwarding_override(
+        &self,
+        ratio: f32,
+        notification: bool,
+        inhibit_toggle: bool,
+        fastforward: bool,
+    ) -> bool {
+        let value = retro_fastforwarding_override {
+            ratio,
+            fastforward,
+            notification,
+            inhibit_toggle,
+        };
+
+        unsafe { environment::set_fastforwarding_override(*self.environment_callback, value) }
+            .is_ok()
+    }
+
+    /// Returns `true` if the frontend is currently fast-forwarding,
+    /// which allows skipping work that would not be noticed anyway.
+    ///
+    /// Returns `false` if the frontend does not support this.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn get_fastforwarding(&self) -> bool {
+        unsafe { environment::get_fastforwarding(*self.environment_callback) }.unwrap_or(false)
+    }
+
+    /// Returns whether the frontend wants the core to produce video and audio this frame,
+    /// e.g. to skip rendering while rewinding or when running ahead.
+    ///
+    /// The emulation itself has to continue as usual, only the output may be skipped.
+    /// Returns [`AvEnable::default`] (both enabled) if the frontend does not support this.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn get_av_enable(&self) -> AvEnable {
+        unsafe { environment::get_audio_video_enable(*self.environment_callback) }
+            .map(AvEnable::from)
+            .unwrap_or_default()
+    }
+
+    /// Returns why and at which rate the frontend is calling [`Core::on_run`],
+    /// e.g. to skip generating audio while rewinding.
+    ///
+    /// Returns [`None`] if the frontend does not support this.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn get_throttle_state(&self) -> Option<ThrottleState> {
+        let state = unsafe { environment::get_throttle_state(*self.environment_callback) }.ok()?;
+
+        state.try_into().ok()
+    }
+
+    /// Returns whether the device runs on battery and how much of it is left,
+    /// e.g. to lower the emulation accuracy on mobile devices.
+    ///
+    /// Returns [`None`] if the frontend does not support this.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn get_device_power(&self) -> Option<DevicePower> {
+        unsafe { environment::get_device_power(*self.environment_callback) }
+            .ok()
+            .map(DevicePower::from)
+    }
+
+    /// Returns the number of frames that have been run since the game has been loaded
+    /// or reset, i.e. `0` during the first call to [`Core::on_run`].
+    ///
+    /// This counter is not part of save states, so loading one does not rewind it.
+    /// Cores that need a deterministic frame counter, e.g. for netplay, rewind or run-ahead,
+    /// should count frames themselves and store the count in their save states.
+    pub fn frame_count(&self) -> u64 {
+        self.interfaces.read().unwrap().frame_count
+    }
+
+    /// Returns the current time of the host, for cores that emulate a real-time clock.
+    ///
+    /// Reading the host time makes emulation depend on when a frame was run, so the
+    /// time is withheld while that would desync the emulation, i.e. once rollback netplay
+    /// (see [`NetplayContext::is_netplay_active`]) or runahead (see [`Core::on_runahead_started`])
+    /// started. In that case [`None`] is returned and the clock should keep advancing from
+    /// its last value by a frame count the core keeps in its own save states instead,
+    /// which also keeps rewinding consistent. [`RunContext::frame_count`] can't be used
+    /// for this, as it does not get restored with save states.
+    ///
+    /// libretro has no interface for frontends to provide the time, so this uses [`SystemTime::now`].
+    /// Use this instead of calling [`SystemTime::now`] directly, so all reads of the host clock
+    /// go through a single place that respects the deterministic contexts.
+    ///
+    /// [`SystemTime::now`]: std::time::SystemTime::now
+    pub fn wall_clock_time(&self) -> Option<std::time::SystemTime> {
+        let interfaces = self.interfaces.read().unwrap();
+
+        if interfaces.netplay_active || interfaces.runahead_active {
+            return None;
+        }
+
+        Some(std::time::SystemTime::now())
+    }
+
+    /// Returns the time since the last frame in microseconds, as reported by the frontend,
+    /// see [`LoadGameContext::enable_frame_time_callback`].
+    pub fn frame_delta(&self) -> Option<i64> {
+        self.interfaces.read().unwrap().frame_delta
+    }
+
+    /// Like [`RunContext::frame_delta`], but as a [`Duration`](std::time::Duration),
+    /// i.e. the `delta` passed to the current call of [`Core::on_run`].
+    ///
+    /// Returns [`None`] if the frame time callback is not enabled.
+    pub fn frame_time(&self) -> Option<std::time::Duration> {
+        self.frame_delta()
+            .map(|delta| std::time::Duration::from_micros(delta.max(0) as u64))
+    }
+
+    /// Returns the reference frame time in microseconds that the `delta`
+    /// passed to [`Core::on_run`] is based on, see [`GenericContext::frame_time_reference`].
+    pub fn frame_time_reference(&self) -> Option<i64> {
+        self.interfaces.read().unwrap().frame_time_reference
+    }
+
+    /// See [`GenericContext::available_interfaces`].
+    pub fn available_interfaces(&self) -> AvailableInterfaces {
+        self.interfaces.read().unwrap().available()
+    }
+
+    /// See [`GenericContext::capabilities`].
+    pub fn capabilities(&self) -> Capabilities {
+        self.interfaces.read().unwrap().capabilities
+    }
+
+    /// Gives access to the performance interface.
+    #[inline]
+    pub fn perf(&self) -> PerfContext {
+        self.into()
+    }
+
+    /// Gives access to the audio callbacks, for submitting audio from [`Core::on_run`].
+    #[inline]
+    pub fn audio(&self) -> AudioContext {
+        AudioContext {
+            environment_callback: self.environment_callback,
+            interfaces: Arc::clone(&self.interfaces),
+
+            audio_sample_batch_callback: self.audio_sample_batch_callback,
+            audio_sample_callback: self.audio_sample_callback,
+        }
+    }
+
+    /// Gives access to the accelerometer, gyroscope and illuminance sensors.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn sensor(&self) -> SensorContext {
+        self.into()
+    }
+
+    /// Sets the rumble strength of the given `effect` on `port`.
+    ///
+    /// Returns `false` if the frontend did not provide a rumble interface,
+    /// see [`LoadGameContext::enable_rumble_interface`].
+    pub fn set_rumble_state(&self, port: u32, effect: retro_rumble_effect, strength: u16) -> bool {
+        let ctx: GenericContext = self.into();
+        ctx.set_rumble_state(port, effect, strength)
+    }
+
+    /// Returns `true` if the frontend can show the previous frame again by itself,
+    /// which makes [`RunContext::dupe_frame`] free.
+    #[inline(always)]
+    pub fn can_dupe(&self) -> bool {
+        self.can_dupe
+    }
+
+    /// Polls for input if [`RunContext::input_poll_callback`] has been set
+    pub fn poll_input(&self) {
+        if let Some(callback) = self.input_poll_callback {
+            unsafe {
+                (callback)();
+            }
+        }
+    }
+
+    /// Gets the input state for the given player and device if [`RunContext::input_state_callback`] has been set
+    pub fn get_input_state(&self, port: u32, device: u32, index: u32, id: u32) -> i16 {
+        if let Some(callback) = self.input_state_callback {
+            unsafe { (callback)(port, device, index, id) }
+        } else {
+            0
+        }
+    }
+
+    /// Returns the position of the given `axis` of an analog `stick`
+    /// in the range `[-0x7FFF, 0x7FFF]`.
+    pub fn get_analog(&self, port: u32, stick: AnalogStick, axis: AnalogAxis) -> i16 {
+        self.get_input_state(port, RETRO_DEVICE_ANALOG, stick as u32, axis as u32)
+    }
+
+    /// Returns how far the joypad button `id` (e.g. [`RETRO_DEVICE_ID_JOYPAD_R2`])
+    /// is pressed in the range `[0, 0x7FFF]`, for pressure-sensitive buttons and triggers.
+    ///
+    /// Frontends without analog button support return `0`,
+    /// so you might want to fall back to [`Self::get_joypad_state`].
+    pub fn get_analog_button(&self, port: u32, id: u32) -> i16 {
+        self.get_input_state(
+            port,
+            RETRO_DEVICE_ANALOG,
+            RETRO_DEVICE_INDEX_ANALOG_BUTTON,
+            id,
+        )
+    }
+
+    /// Returns the relative movement and the button state of the mouse for player `port`.
+    pub fn get_mouse(&self, port: u32) -> MouseState {
+        let get = |id| self.get_input_state(port, RETRO_DEVICE_MOUSE, 0, id);
+
+        MouseState {
+            x: get(RETRO_DEVICE_ID_MOUSE_X),
+            y: get(RETRO_DEVICE_ID_MOUSE_Y),
+            left: get(RETRO_DEVICE_ID_MOUSE_LEFT) != 0,
+            right: get(RETRO_DEVICE_ID_MOUSE_RIGHT) != 0,
+            middle: get(RETRO_DEVICE_ID_MOUSE_MIDDLE) != 0,
+            wheel_up: get(RETRO_DEVICE_ID_MOUSE_WHEELUP) != 0,
+            wheel_down: get(RETRO_DEVICE_ID_MOUSE_WHEELDOWN) != 0,
+        }
+    }
+
+    /// Returns the state of the pointer `index` for player `port`.
+    ///
+    /// With multi-touch input, pointers `0..count` are pressed.
+    pub fn get_pointer(&self, port: u32, index: u32) -> PointerState {
+        let get = |id| self.get_input_state(port, RETRO_DEVICE_POINTER, index, id);
+
+        PointerState {
+            x: get(RETRO_DEVICE_ID_POINTER_X),
+            y: get(RETRO_DEVICE_ID_POINTER_Y),
+            pressed: get(RETRO_DEVICE_ID_POINTER_PRESSED) != 0,
+            count: get(RETRO_DEVICE_ID_POINTER_COUNT),
+        }
+    }
+
+    /// Returns `true` if `key` is currently pressed on the keyboard of player `port`.
+    ///
+    /// Polling like this fits cores that check their inputs once per frame.
+    /// Cores that need every key press, or the typed characters, should use
+    /// [`Core::on_keyboard_event`] instead, see [`GenericContext::enable_keyboard_callback`].
+    pub fn get_key(&self, port: u32, key: retro_key) -> bool {
+        self.get_input_state(port, RETRO_DEVICE_KEYBOARD, 0, key.0 as u32) != 0
+    }
+
+    /// Queries the state of all given `keys` at once, see [`Self::get_key`].
+    pub fn get_keyboard_state(&self, port: u32, keys: &[retro_key]) -> KeyboardState {
+        KeyboardState {
+            pressed: keys
+                .iter()
+                .copied()
+                .filter(|&key| self.get_key(port, key))
+                .collect(),
+        }
+    }
+
+    /// Queries the libretro frontend for the state of each joypad button
+    /// by making an environment call for every button separately.
+    ///
+    /// See also [`Self::get_joypad_bitmask`].
+    pub fn get_joypad_state(&self, port: u32, index: u32) -> JoypadState {
+        if let Some(callback) = self.input_state_callback {
+            let mut mask = JoypadState::empty();
+
+            unsafe {
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_B) != 0 {
+                    mask |= JoypadState::B
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_Y) != 0 {
+                    mask |= JoypadState::Y
+                }
+                if (callback)(
+                    port,
+                    RETRO_DEVICE_JOYPAD,
+                    index,
+                    RETRO_DEVICE_ID_JOYPAD_SELECT,
+                ) != 0
+                {
+                    mask |= JoypadState::SELECT
+                }
+                if (callback)(
+                    port,
+                    RETRO_DEVICE_JOYPAD,
+                    index,
+                    RETRO_DEVICE_ID_JOYPAD_START,
+                ) != 0
+                {
+                    mask |= JoypadState::START
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_UP) != 0 {
+                    mask |= JoypadState::UP
+                }
+                if (callback)(
+                    port,
+                    RETRO_DEVICE_JOYPAD,
+                    index,
+                    RETRO_DEVICE_ID_JOYPAD_DOWN,
+                ) != 0
+                {
+                    mask |= JoypadState::DOWN
+                }
+                if (callback)(
+                    port,
+                    RETRO_DEVICE_JOYPAD,
+                    index,
+                    RETRO_DEVICE_ID_JOYPAD_LEFT,
+                ) != 0
+                {
+                    mask |= JoypadState::LEFT
+                }
+                if (callback)(
+                    port,
+                    RETRO_DEVICE_JOYPAD,
+                    index,
+                    RETRO_DEVICE_ID_JOYPAD_RIGHT,
+                ) != 0
+                {
+                    mask |= JoypadState::RIGHT
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_A) != 0 {
+                    mask |= JoypadState::A
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_X) != 0 {
+                    mask |= JoypadState::X
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_L) != 0 {
+                    mask |= JoypadState::L
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_R) != 0 {
+                    mask |= JoypadState::R
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_L2) != 0 {
+                    mask |= JoypadState::L2
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_R2) != 0 {
+                    mask |= JoypadState::R2
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_L3) != 0 {
+                    mask |= JoypadState::L3
+                }
+                if (callback)(port, RETRO_DEVICE_JOYPAD, index, RETRO_DEVICE_ID_JOYPAD_R3) != 0 {
+                    mask |= JoypadState::R3
+                }
+            }
+
+            return mask;
+        }
+
+        JoypadState::empty()
+    }
+
+    /// Queries the frontend for the joypad state with the more efficient, but currently experimental,
+    /// joypad bitmask feature. Only a single call into the frontend gets made.
+    ///
+    /// Falls back to [`Self::get_joypad_state`] if the frontend does not support bitmasks,
+    /// so the result is the same either way.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn get_joypad_bitmask(&self, port: u32, index: u32) -> JoypadState {
+        if let Some(callback) = self.input_state_callback {
+            if self.supports_bitmasks {
+                return JoypadState::from_bits_truncate((callback)(
+                    port,
+                    RETRO_DEVICE_JOYPAD,
+                    index,
+                    RETRO_DEVICE_ID_JOYPAD_MASK,
+                ) as u16);
+            }
+
+            // Fallback
+            return self.get_joypad_state(port, index);
+        }
+
+        JoypadState::empty()
+    }
+
+    /// Returns the joypad states of the ports `0..ports`, e.g. for multiplayer cores.
+    ///
+    /// Uses a single call per port if the frontend supports bitmasks and the
+    /// `unstable-env-commands` feature is enabled, see [`RunContext::get_joypad_bitmask`].
+    /// Use [`RunContext::joypad_states`] to avoid the allocation.
+    pub fn poll_all_joypads(&self, ports: u32) -> Vec<JoypadState> {
+        self.joypad_states(ports).collect()
+    }
+
+    /// Like [`RunContext::poll_all_joypads`], but queries each port lazily.
+    pub fn joypad_states(&self, ports: u32) -> impl Iterator<Item = JoypadState> + '_ {
+        (0..ports).map(move |port| {
+            #[cfg(feature = "unstable-env-commands")]
+            {
+                unsafe { self.get_joypad_bitmask(port, 0) }
+            }
+
+            #[cfg(not(feature = "unstable-env-commands"))]
+            {
+                self.get_joypad_state(port, 0)
+            }
+        })
+    }
+
+    /// Asks the frontend for a framebuffer of the given size to draw into directly,
+    /// which avoids a copy when passing it to [`RunContext::draw_frame`] afterwards.
+    ///
+    /// The returned [`Framebuffer`] may differ from the requested one, e.g. in its `pitch`,
+    /// and is only valid until the end of the current [`Core::on_run`] call.
+    /// Returns an error if the frontend declines, in which case the core has to provide
+    /// its own buffer; see [`RunContext::get_current_framebuffer_or_fallback`].
+    #[proc::unstable(feature = "env-commands")]
+    pub fn get_current_framebuffer(
+        &self,
+        width: u32,
+        height: u32,
+        access_flags: MemoryAccess,
+        format: PixelFormat,
+    ) -> Result<Framebuffer, Box<dyn std::error::Error>> {
+        let ctx: GenericContext = self.into();
+
+        let fb = ctx.get_current_software_framebuffer(retro_framebuffer {
+            data: std::ptr::null_mut(),
+            width,
+            height,
+            pitch: 0,
+            format: format.into(),
+            access_flags: access_flags.bits(),
+            memory_flags: 0,
+        });
+
+        if let Ok(fb) = fb {
+            if !fb.data.is_null() {
+                // TODO: Can we get rid of the raw pointer and PhantomData in an ergonomic way?
+                // When defining `data` as `&'a mut [u8]` it has the same lifetime as `self`,
+                // which means we borrow `self` for as long as this `FrameBuffer` exists.
+                // Thus we cannot pass the `FrameBuffer` to `Self::draw_frame` for example.
+                return Ok(Framebuffer {
+                    data: fb.data as *mut u8,
+                    data_len: fb.height as usize * fb.pitch,
+                    phantom: ::core::marker::PhantomData,
+
+                    width: fb.width,
+                    height: fb.height,
+                    pitch: fb.pitch,
+                    format: fb.format.into(),
+                    access_flags: MemoryAccess::from_bits_unchecked(fb.access_flags),
+                    memory_flags: MemoryType::from_bits_unchecked(fb.memory_flags),
+                });
+            }
+        }
+
+        Err("Failed to get current software framebuffer".into())
+    }
+
+    /// Like [`RunContext::get_current_framebuffer`], but falls back to a buffer owned by this crate
+    /// if the frontend declines or does not grant all of the requested `access_flags`.
+    #[proc::unstable(feature = "env-commands")]
+    pub fn get_current_framebuffer_or_fallback(
+        &self,
+        width: u32,
+        height: u32,
+        access_flags: MemoryAccess,
+        format: PixelFormat,
+    ) -> Framebuffer {
+        match self.get_current_framebuffer(width, height, access_flags, format) {
+            Ok(fb) if fb.access_flags.contains(access_flags) => fb,
+            _ => {
+                let data = unsafe { FALLBACK_FRAMEBUFFER.get_mut() };
+
+                let pitch = width as usize * format.bit_per_pixel();
+                let data_len = height as usize * pitch;
+
+                if data.len() < data_len {
+                    data.resize(data_len, 0);
+                }
+
+                Framebuffer {
+                    data: data.as_mut_ptr(),
+                    data_len,
+                    phantom: ::core::marker::PhantomData,
+
+                    width,
+                    height,
+                    pitch,
+                    format,
+                    access_flags: MemoryAccess::READ | MemoryAccess::WRITE,
+                    memory_flags: MemoryType::UNCACHED,
+                }
+            }
+        }
+    }
+
+    /// Returns the pixel format of the frames passed to [`RunContext::draw_frame`],
+    /// see [`GenericContext::pixel_format`].
+    pub fn pixel_format(&self) -> PixelFormat {
+        GenericContext::from(self).pixel_format()
+    }
+
+    /// Draws a new frame if [`RunContext::video_refresh_callback`] has been set
+    ///
+    /// `data` must hold `height` rows of `width` pixels in the active [`RunContext::pixel_format`],
+    /// each starting `pitch` bytes after the previous one. Frames that do not fit are dropped.
+    pub fn draw_frame(&mut self, data: &[u8], width: u32, height: u32, pitch: usize) {
+        let row_len = width as usize * self.pixel_format().bit_per_pixel();
+        let data_len = match height as usize {
+            0 => 0,
+            height => (height - 1) * pitch + row_len,
+        };
+
+        if pitch < row_len || data.len() < data_len {
+            #[cfg(feature = "log")]
+            log::error!(
+                "Cannot draw a {width}x{height} frame with a pitch of {pitch} from {} bytes!",
+                data.len()
+            );
+            return;
+        }
+
+        if let Some(callback) = self.video_refresh_callback {
+            *self.had_frame = true;
+            *self.last_width = width;
+            *self.last_height = height;
+            *self.last_pitch = pitch;
+
+            // Keep a copy for `dupe_frame` if the frontend cannot do that for us
+            self.last_frame.clear();
+
+            if self.keeps_last_frame() {
+                self.last_frame.extend_from_slice(&data[..data_len]);
+            }
+
+            unsafe { (callback)(data.as_ptr() as *const c_void, width, height, pitch) }
+        }
+    }
+
+    /// Shows the previous frame again, e.g. if the emulated system did not produce a new one
+    /// because it is paused internally, or to skip rendering an unchanged frame.
+    ///
+    /// If [`RunContext::can_dupe`] is `true` the frontend gets told to reuse the previous frame,
+    /// otherwise the copy kept from the last call to [`RunContext::draw_frame`]
+    /// or [`RunContext::draw_framebuffer`] gets submitted again.
+    pub fn dupe_frame(&self) {
+        if !*self.had_frame {
+            #[cfg(feature = "log")]
+            log::error!("Cannot dupe frame, no previous frame has been drawn!");
+            return;
+        }
+
+        let data = if self.can_dupe {
+            std::ptr::null()
+        } else if !self.last_frame.is_empty() {
+            self.last_frame.as_ptr() as *const c_void
+        } else {
+            #[cfg(feature = "log")]
+            log::error!("This frontend does not support frame duping and the previous frame is unavailable!");
+            return;
+        };
+
+        if let Some(callback) = self.video_refresh_callback {
+            unsafe { (callback)(data, *self.last_width, *self.last_height, *self.last_pitch) }
+        }
+    }
+
+    /// Repeats the previous frame instead of rendering an unchanged one,
+    /// e.g. while an emulator running ahead or paused internally has nothing new to show.
+    ///
+    /// Passes `NULL` with the previous dimensions to the frontend if [`RunContext::can_dupe`]
+    /// is `true`, and re-submits the last buffer otherwise, see [`RunContext::dupe_frame`].
+    pub fn repeat_frame(&mut self) {
+        self.dupe_frame()
+    }
+
+    /// Returns the frame most recently drawn with [`RunContext::draw_frame`]
+    /// or [`RunContext::draw_framebuffer`], e.g. to create thumbnails.
+    ///
+    /// Returns [`None`] if no copy has been kept, see [`GenericContext::set_keep_last_frame`].
+    pub fn last_frame(&self) -> Option<FrameView> {
+        if !*self.had_frame || self.last_frame.is_empty() {
+            return None;
+        }
+
+        Some(FrameView {
+            data: self.last_frame,
+            width: *self.last_width,
+            height: *self.last_height,
+            pitch: *self.last_pitch,
+            format: self.pixel_format(),
+        })
+    }
+
+    fn keeps_last_frame(&self) -> bool {
+        !self.can_dupe || self.interfaces.read().unwrap().keep_last_frame
+    }
+
+    pub fn draw_framebuffer(&mut self, framebuffer: retro_framebuffer) {
+        if let Some(callback) = self.video_refresh_callback {
+            *self.had_frame = true;
+            *self.last_width = framebuffer.width;
+            *self.last_height = framebuffer.height;
+            *self.last_pitch = framebuffer.pitch;
+
+            self.last_frame.clear();
+
+            if self.keeps_last_frame() && !framebuffer.data.is_null() {
+                self.last_frame.extend_from_slice(unsafe {
+                    std::slice::from_raw_parts(
+                        framebuffer.data as *const u8,
+                        framebuffer.height as usize * framebuffer.pitch,
+                    )
+                });
+            }
+
+            unsafe {
+                (callback)(
+                    framebuffer.data,
+                    framebuffer.width,
+                    framebuffer.height,
+                    framebuffer.pitch,
+                )
+            }
+        }
+    }
+
+    /// Returns the framebuffer object to render into when hardware rendering is used,
+    /// as reported by the `get_current_framebuffer` function of the negotiated
+    /// [`retro_hw_render_callback`].
+    ///
+    /// Not to be confused with [`RunContext::get_current_framebuffer`], which asks for a
+    /// software framebuffer. See [`HwContext::get_current_framebuffer`].
+    pub fn get_current_hw_render_framebuffer(&self) -> usize {
+        let ctx: HwContext = self.into();
+
+        ctx.get_current_framebuffer()
+    }
+
+    pub fn draw_hardware_frame(&mut self, width: u32, height: u32, pitch: usize) {
+        if let Some(callback) = self.video_refresh_callback {
+            *self.had_frame = true;
+            *self.last_width = width;
+            *self.last_height = height;
+            *self.last_pitch = pitch;
+            self.last_frame.clear();
+
+            unsafe {
+                (callback)(
+                    RETRO_HW_FRAME_BUFFER_VALID as *const c_void,
+                    width,
+                    height,
+                    pitch,
+                )
+            }
+        }
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub fn camera_start(&self) -> bool {
+        let ctx: GenericContext = self.into();
+        ctx.camera_start()
+    }
+
+    #[proc::unstable(feature = "env-commands")]
+    pub fn camera_stop(&self) {
+        let ctx: GenericContext = self.into();
+        ctx.camera_stop()
+    }
+}
+
+#[test]
+fn set_hw_render_keeps_frontend_callbacks() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static RECEIVED: Mutex<Option<retro_hw_render_callback>> = Mutex::new(None);
+
+    unsafe extern "C" fn get_current_framebuffer() -> usize {
+        42
+    }
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_HW_RENDER {
+            return false;
+        }
+
+        let data = &mut *(data as *mut retro_hw_render_callback);
+        RECEIVED.lock().unwrap().replace(*data);
+
+        data.get_current_framebuffer = Some(get_current_framebuffer);
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let mut ctx = LoadGameContext::new(&callback, Arc::clone(&interfaces));
+
+    let result = ctx.set_hw_render(retro_hw_render_callback {
+        context_type: retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE,
+        context_reset: None,
+        get_current_framebuffer: None,
+        get_proc_address: None,
+        depth: true,
+        stencil: false,
+        bottom_left_origin: true,
+        version_major: 3,
+        version_minor: 3,
+        cache_context: true,
+        context_destroy: None,
+        debug_context: false,
+    });
+    assert_eq!(result, Ok(()));
+
+    let received = RECEIVED.lock().unwrap().unwrap();
+    assert_eq!(
+        received.context_type,
+        retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE
+    );
+    assert_eq!((received.version_major, received.version_minor), (3, 3));
+    assert!(received.depth && !received.stencil && received.bottom_left_origin);
+    assert!(received.context_reset.is_some() && received.context_destroy.is_some());
+
+    let hw_ctx = HwContext::new(&callback, interfaces);
+    assert_eq!(hw_ctx.get_current_framebuffer(), 42);
+}
+
+#[test]
+fn location_context_forwards_to_interface() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use std::sync::RwLock;
+
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    static HAS_FIX: AtomicBool = AtomicBool::new(false);
+    static INTERVAL: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn start() -> bool {
+        STARTED.store(true, Ordering::SeqCst);
+        true
+    }
+
+    unsafe extern "C" fn stop() {
+        STARTED.store(false, Ordering::SeqCst);
+    }
+
+    unsafe extern "C" fn get_position(
+        lat: *mut f64,
+        lon: *mut f64,
+        horiz_accuracy: *mut f64,
+        vert_accuracy: *mut f64,
+    ) -> bool {
+        if !HAS_FIX.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        *lat = 52.52;
+        *lon = 13.405;
+        *horiz_accuracy = 5.0;
+        *vert_accuracy = 10.0;
+        true
+    }
+
+    unsafe extern "C" fn set_interval(interval_ms: u32, _interval_distance: u32) {
+        INTERVAL.store(interval_ms, Ordering::SeqCst);
+    }
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE {
+            return false;
+        }
+
+        let data = &mut *(data as *mut retro_location_callback);
+        assert!(data.initialized.is_some() && data.deinitialized.is_some());
+
+        data.start = Some(start);
+        data.stop = Some(stop);
+        data.get_position = Some(get_position);
+        data.set_interval = Some(set_interval);
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let mut ctx = LoadGameContext::new(&callback, Arc::clone(&interfaces));
+    assert!(ctx.enable_location_interface().is_ok());
+
+    let location = LocationContext::new(&callback, interfaces);
+
+    assert!(location.start());
+    assert!(STARTED.load(Ordering::SeqCst));
+
+    // No fix has been found yet
+    assert!(location.get_position().is_none());
+
+    HAS_FIX.store(true, Ordering::SeqCst);
+    let position = location.get_position().expect("a position fix");
+    assert_eq!((position.lat, position.lon), (52.52, 13.405));
+    assert_eq!(
+        (position.horiz_accuracy, position.vert_accuracy),
+        (5.0, 10.0)
+    );
+
+    location.set_interval(1000, 0);
+    assert_eq!(INTERVAL.load(Ordering::SeqCst), 1000);
+
+    location.stop();
+    assert!(!STARTED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn get_language_maps_frontend_values() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::RwLock;
+
+    static LANGUAGE: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_LANGUAGE {
+            return false;
+        }
+
+        *(data as *mut u32) = LANGUAGE.load(Ordering::SeqCst);
+        true
+    }
+
+    unsafe extern "C" fn unsupported(_cmd: std::os::raw::c_uint, _data: *mut c_void) -> bool {
+        false
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, Arc::clone(&interfaces));
+
+    for language in [
+        retro_language::RETRO_LANGUAGE_ENGLISH,
+        retro_language::RETRO_LANGUAGE_JAPANESE,
+        retro_language::RETRO_LANGUAGE_GERMAN,
+        retro_language::RETRO_LANGUAGE_PORTUGUESE_BRAZIL,
+    ] {
+        LANGUAGE.store(language as u32, Ordering::SeqCst);
+        assert_eq!(ctx.get_language(), Some(language));
+    }
+
+    LANGUAGE.store(retro_language::RETRO_LANGUAGE_LAST as u32, Ordering::SeqCst);
+    assert_eq!(ctx.get_language(), None);
+
+    let callback: retro_environment_t = Some(unsupported);
+    let ctx = GenericContext::new(&callback, interfaces);
+    assert_eq!(ctx.get_language(), None);
+}
+
+#[test]
+fn run_context_forwards_rumble_state() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static LAST_STATE: Mutex<Option<(u32, retro_rumble_effect, u16)>> = Mutex::new(None);
+
+    unsafe extern "C" fn set_rumble_state(
+        port: u32,
+        effect: retro_rumble_effect,
+        strength: u16,
+    ) -> bool {
+        LAST_STATE.lock().unwrap().replace((port, effect, strength));
+        true
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces: Arc::clone(&interfaces),
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert!(!ctx.set_rumble_state(0, retro_rumble_effect::RETRO_RUMBLE_STRONG, 0xFFFF));
+    assert_eq!(*LAST_STATE.lock().unwrap(), None);
+
+    interfaces.write().unwrap().rumble_interface = Some(retro_rumble_interface {
+        set_rumble_state: Some(set_rumble_state),
+    });
+
+    assert!(ctx.set_rumble_state(1, retro_rumble_effect::RETRO_RUMBLE_WEAK, 0x8000));
+    assert_eq!(
+        *LAST_STATE.lock().unwrap(),
+        Some((1, retro_rumble_effect::RETRO_RUMBLE_WEAK, 0x8000))
+    );
+}
+
+#[test]
+fn sensor_context_forwards_to_interface() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::RwLock;
+
+    static RATE: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn set_sensor_state(
+        _port: u32,
+        action: retro_sensor_action,
+        rate: u32,
+    ) -> bool {
+        RATE.store(rate, Ordering::SeqCst);
+        action == retro_sensor_action::RETRO_SENSOR_ACCELEROMETER_ENABLE
+    }
+
+    unsafe extern "C" fn get_sensor_input(port: u32, id: u32) -> f32 {
+        port as f32 * 10.0 + id as f32
+    }
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let sensor = SensorContext::new(&callback, Arc::clone(&interfaces));
+
+    assert!(!sensor.set_sensor_state(
+        0,
+        retro_sensor_action::RETRO_SENSOR_ACCELEROMETER_ENABLE,
+        60
+    ));
+    assert_eq!(sensor.get_sensor_input(0, SensorInput::AccelerometerX), 0.0);
+
+    interfaces.write().unwrap().sensor_interface = Some(retro_sensor_interface {
+        set_sensor_state: Some(set_sensor_state),
+        get_sensor_input: Some(get_sensor_input),
+    });
+
+    assert!(sensor.set_sensor_state(
+        0,
+        retro_sensor_action::RETRO_SENSOR_ACCELEROMETER_ENABLE,
+        60
+    ));
+    assert_eq!(RATE.load(Ordering::SeqCst), 60);
+    assert!(!sensor.set_sensor_state(0, retro_sensor_action::RETRO_SENSOR_GYROSCOPE_ENABLE, 30));
+
+    assert_eq!(
+        sensor.get_sensor_input(1, SensorInput::AccelerometerZ),
+        12.0
+    );
+    assert_eq!(sensor.get_sensor_input(0, SensorInput::Illuminance), 6.0);
+}
+
+#[test]
+fn perf_counter_guard_stops_counter() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn perf_register(counter: *mut retro_perf_counter) {
+        (*counter).registered = true;
+    }
+
+    unsafe extern "C" fn perf_start(counter: *mut retro_perf_counter) {
+        (*counter).call_cnt += 1;
+        (*counter).start = 1;
+    }
+
+    unsafe extern "C" fn perf_stop(counter: *mut retro_perf_counter) {
+        (*counter).total += 1;
+        (*counter).start = 0;
+    }
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    interfaces.write().unwrap().perf_interface.interface = Some(retro_perf_callback {
+        get_time_usec: None,
+        get_cpu_features: None,
+        get_perf_counter: None,
+        perf_register: Some(perf_register),
+        perf_start: Some(perf_start),
+        perf_stop: Some(perf_stop),
+        perf_log: None,
+    });
+
+    let perf = PerfContext::new(&callback, Arc::clone(&interfaces));
+    let counter = perf.perf_register("test").unwrap();
+
+    // Registering more counters must not move the ones the frontend already knows about
+    let counter_ptr = || {
+        let interfaces = interfaces.read().unwrap();
+        &*interfaces.perf_interface.counters["test"].counter as *const retro_perf_counter
+    };
+
+    let ptr = counter_ptr();
+    for name in ["a", "b", "c", "d", "e", "f", "g", "h"] {
+        perf.perf_register(name).unwrap();
+    }
+    assert_eq!(ptr, counter_ptr());
+
+    {
+        let _guard = perf.perf_guard(&counter).unwrap();
+
+        let interfaces = interfaces.read().unwrap();
+        assert_eq!(interfaces.perf_interface.counters["test"].counter.start, 1);
+    }
+
+    {
+        let interfaces = interfaces.read().unwrap();
+        let registered = &interfaces.perf_interface.counters["test"].counter;
+        assert_eq!(registered.start, 0);
+        assert_eq!(registered.call_cnt, 1);
+        assert_eq!(registered.total, 1);
+    }
+
+    assert!(perf.perf_log().is_err());
+    assert!(perf.perf_stop(&PerfCounter { name: "unknown" }).is_err());
+}
+
+#[test]
+fn midi_interface_buffers_until_flush() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static PENDING: Mutex<Vec<(u8, u32)>> = Mutex::new(Vec::new());
+    static SENT: Mutex<Vec<(u8, u32)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn enabled() -> bool {
+        true
+    }
+
+    unsafe extern "C" fn read(byte: *mut u8) -> bool {
+        *byte = 0x90;
+        true
+    }
+
+    unsafe extern "C" fn write(byte: u8, delta_time: u32) -> bool {
+        PENDING.lock().unwrap().push((byte, delta_time));
+        true
+    }
+
+    unsafe extern "C" fn flush() -> bool {
+        let mut pending = PENDING.lock().unwrap();
+        SENT.lock().unwrap().append(&mut pending);
+        true
+    }
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, Arc::clone(&interfaces));
+
+    assert!(ctx.midi().is_none());
+    assert!(!ctx.midi_write_byte(0x90, 0));
+
+    interfaces.write().unwrap().midi_interface = Some(retro_midi_interface {
+        input_enabled: Some(enabled),
+        output_enabled: Some(enabled),
+        read: Some(read),
+        write: Some(write),
+        flush: Some(flush),
+    });
+
+    let mut midi = ctx.midi().unwrap();
+    assert!(midi.input_enabled());
+    assert!(midi.output_enabled());
+    assert_eq!(midi.read(), Some(0x90));
+
+    assert!(midi.write(0x90, 0));
+    assert!(midi.write(0x3C, 100));
+    assert!(midi.write(0x7F, 0));
+    assert!(SENT.lock().unwrap().is_empty());
+
+    assert!(midi.flush());
+    assert_eq!(
+        *SENT.lock().unwrap(),
+        vec![(0x90, 0), (0x3C, 100), (0x7F, 0)]
+    );
+}
+
+#[test]
+fn set_memory_maps_lowers_descriptors() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static DESCRIPTORS: Mutex<Vec<(u64, usize, usize, usize, usize, Option<String>)>> =
+        Mutex::new(Vec::new());
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_MEMORY_MAPS {
+            return false;
+        }
+
+        let map = &*(data as *const retro_memory_map);
+        let descriptors = std::slice::from_raw_parts(map.descriptors, map.num_descriptors as usize);
+
+        *DESCRIPTORS.lock().unwrap() = descriptors
+            .iter()
+            .map(|descriptor| {
+                (
+                    descriptor.flags,
+                    descriptor.ptr as usize,
+                    descriptor.start,
+                    descriptor.select,
+                    descriptor.len,
+                    get_string_from_pointer(descriptor.addrspace),
+                )
+            })
+            .collect();
+
+        true
+    }
+
+    let mut wram = [0u8; 0x2000];
+    let mut sram = [0u8; 0x800];
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, Arc::clone(&interfaces));
+
+    unsafe {
+        ctx.set_memory_maps(&[
+            MemoryDescriptor {
+                flags: MemoryDescriptorFlags::SYSTEM_RAM,
+                ptr: wram.as_mut_ptr() as *mut c_void,
+                start: 0xC000,
+                select: 0xE000,
+                len: wram.len(),
+                ..Default::default()
+            },
+            MemoryDescriptor {
+                flags: MemoryDescriptorFlags::SAVE_RAM | MemoryDescriptorFlags::BIGENDIAN,
+                ptr: sram.as_mut_ptr() as *mut c_void,
+                start: 0xA000,
+                len: sram.len(),
+                addrspace: Some("S"),
+                ..Default::default()
+            },
+        ])
+    }
+    .unwrap();
+
+    assert_eq!(
+        *DESCRIPTORS.lock().unwrap(),
+        vec![
+            (
+                RETRO_MEMDESC_SYSTEM_RAM as u64,
+                wram.as_ptr() as usize,
+                0xC000,
+                0xE000,
+                0x2000,
+                None
+            ),
+            (
+                (RETRO_MEMDESC_SAVE_RAM | RETRO_MEMDESC_BIGENDIAN) as u64,
+                sram.as_ptr() as usize,
+                0xA000,
+                0,
+                0x800,
+                Some("S".to_owned())
+            ),
+        ]
+    );
+    assert_eq!(interfaces.read().unwrap().memory_map_addrspaces.len(), 1);
+
+    assert!(unsafe {
+        ctx.set_memory_maps(&[MemoryDescriptor {
+            addrspace: Some("\0"),
+            ..Default::default()
+        }])
+    }
+    .is_err());
+}
+
+#[test]
+fn set_input_descriptors_appends_terminator() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static DESCRIPTORS: Mutex<Vec<(u32, u32, u32, u32, Option<String>)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS {
+            return false;
+        }
+
+        let mut descriptors = DESCRIPTORS.lock().unwrap();
+        let mut descriptor = data as *const retro_input_descriptor;
+
+        // Read up to and including the terminating entry
+        loop {
+            let entry = &*descriptor;
+            descriptors.push((
+                entry.port,
+                entry.device,
+                entry.index,
+                entry.id,
+                get_string_from_pointer(entry.description),
+            ));
+
+            if entry.description.is_null() {
+                break;
+            }
+
+            descriptor = descriptor.add(1);
+        }
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = SetEnvironmentContext::new(&callback, Arc::clone(&interfaces));
+
+    ctx.set_input_descriptors(&[
+        InputDescriptor {
+            port: 0,
+            device: RETRO_DEVICE_JOYPAD,
+            index: 0,
+            id: RETRO_DEVICE_ID_JOYPAD_B,
+            description: "Jump",
+        },
+        InputDescriptor {
+            port: 1,
+            device: RETRO_DEVICE_ANALOG,
+            index: RETRO_DEVICE_INDEX_ANALOG_LEFT,
+            id: RETRO_DEVICE_ID_ANALOG_X,
+            description: "Move",
+        },
+    ])
+    .unwrap();
+
+    assert_eq!(
+        *DESCRIPTORS.lock().unwrap(),
+        vec![
+            (
+                0,
+                RETRO_DEVICE_JOYPAD,
+                0,
+                RETRO_DEVICE_ID_JOYPAD_B,
+                Some("Jump".to_owned())
+            ),
+            (
+                1,
+                RETRO_DEVICE_ANALOG,
+                RETRO_DEVICE_INDEX_ANALOG_LEFT,
+                RETRO_DEVICE_ID_ANALOG_X,
+                Some("Move".to_owned())
+            ),
+            (0, 0, 0, 0, None),
+        ]
+    );
+    assert_eq!(interfaces.read().unwrap().input_descriptions.len(), 2);
+}
+
+#[test]
+fn joypad_bitmask_matches_fallback() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::RwLock;
+
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+
+    const PRESSED: u16 = 1 << RETRO_DEVICE_ID_JOYPAD_A
+        | 1 << RETRO_DEVICE_ID_JOYPAD_UP
+        | 1 << RETRO_DEVICE_ID_JOYPAD_L2;
+
+    unsafe extern "C" fn input_state(port: u32, device: u32, _index: u32, id: u32) -> i16 {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+
+        if port != 0 || device != RETRO_DEVICE_JOYPAD {
+            return 0;
+        }
+
+        if id == RETRO_DEVICE_ID_JOYPAD_MASK {
+            PRESSED as i16
+        } else {
+            ((PRESSED >> id) & 1) as i16
+        }
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let input_state_callback: retro_input_state_t = Some(input_state);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    for supports_bitmasks in [true, false] {
+        let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+            (false, 0, 0, 0, Vec::new());
+        let ctx = RunContext {
+            environment_callback: &environment_callback,
+            interfaces: Arc::clone(&interfaces),
+
+            audio_sample_batch_callback: &None,
+            audio_sample_callback: &None,
+            input_poll_callback: &None,
+            input_state_callback: &input_state_callback,
+            video_refresh_callback: &None,
+
+            can_dupe: false,
+            had_frame: &mut had_frame,
+            last_width: &mut last_width,
+            last_height: &mut last_height,
+            last_pitch: &mut last_pitch,
+            last_frame: &mut last_frame,
+
+            supports_bitmasks,
+        };
+
+        CALLS.store(0, Ordering::SeqCst);
+        let state = unsafe { ctx.get_joypad_bitmask(0, 0) };
+
+        let expected_calls = if supports_bitmasks { 1 } else { 16 };
+        assert_eq!(CALLS.load(Ordering::SeqCst), expected_calls);
+
+        assert_eq!(state, JoypadState::A | JoypadState::UP | JoypadState::L2);
+        assert!(state.is_pressed(JoypadState::A));
+        assert!(state.is_pressed(JoypadState::UP | JoypadState::L2));
+        assert!(!state.is_pressed(JoypadState::A | JoypadState::B));
+        assert!(!state.is_pressed(JoypadState::START));
+
+        assert_eq!(
+            unsafe { ctx.get_joypad_bitmask(1, 0) },
+            JoypadState::empty()
+        );
+    }
+}
+
+#[test]
+fn poll_all_joypads_reads_every_port() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::RwLock;
+
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn input_state(port: u32, device: u32, _index: u32, id: u32) -> i16 {
+        CALLS.fetch_add(1, Ordering::SeqCst);
+
+        if device != RETRO_DEVICE_JOYPAD {
+            return 0;
+        }
+
+        // Every port presses a different button
+        let pressed = 1u16 << port;
+        if id == RETRO_DEVICE_ID_JOYPAD_MASK {
+            pressed as i16
+        } else {
+            ((pressed >> id) & 1) as i16
+        }
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let input_state_callback: retro_input_state_t = Some(input_state);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    for supports_bitmasks in [true, false] {
+        let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+            (false, 0, 0, 0, Vec::new());
+        let ctx = RunContext {
+            environment_callback: &environment_callback,
+            interfaces: Arc::clone(&interfaces),
+
+            audio_sample_batch_callback: &None,
+            audio_sample_callback: &None,
+            input_poll_callback: &None,
+            input_state_callback: &input_state_callback,
+            video_refresh_callback: &None,
+
+            can_dupe: false,
+            had_frame: &mut had_frame,
+            last_width: &mut last_width,
+            last_height: &mut last_height,
+            last_pitch: &mut last_pitch,
+            last_frame: &mut last_frame,
+
+            supports_bitmasks,
+        };
+
+        CALLS.store(0, Ordering::SeqCst);
+        assert_eq!(
+            ctx.poll_all_joypads(4),
+            [
+                JoypadState::B,
+                JoypadState::Y,
+                JoypadState::SELECT,
+                JoypadState::START
+            ]
+        );
+
+        let expected_calls = if supports_bitmasks && cfg!(feature = "unstable-env-commands") {
+            4
+        } else {
+            4 * 16
+        };
+        assert_eq!(CALLS.load(Ordering::SeqCst), expected_calls);
+
+        assert!(ctx.joypad_states(0).next().is_none());
+        assert_eq!(ctx.joypad_states(2).last(), Some(JoypadState::Y));
+    }
+}
+
+#[test]
+fn analog_helpers_query_the_right_inputs() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn input_state(port: u32, device: u32, index: u32, id: u32) -> i16 {
+        if device != RETRO_DEVICE_ANALOG {
+            return 0;
+        }
+
+        // Encode the request, so every combination returns a different value
+        (port * 1000 + index * 100 + id) as i16
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let input_state_callback: retro_input_state_t = Some(input_state);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &input_state_callback,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert_eq!(ctx.get_analog(0, AnalogStick::Left, AnalogAxis::X), 0);
+    assert_eq!(ctx.get_analog(0, AnalogStick::Left, AnalogAxis::Y), 1);
+    assert_eq!(ctx.get_analog(1, AnalogStick::Right, AnalogAxis::X), 1100);
+    assert_eq!(ctx.get_analog(1, AnalogStick::Right, AnalogAxis::Y), 1101);
+
+    assert_eq!(
+        ctx.get_analog_button(2, RETRO_DEVICE_ID_JOYPAD_R2),
+        (2000 + RETRO_DEVICE_INDEX_ANALOG_BUTTON * 100 + RETRO_DEVICE_ID_JOYPAD_R2) as i16
+    );
+}
+
+#[test]
+fn mouse_and_pointer_helpers_read_their_devices() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn input_state(port: u32, device: u32, index: u32, id: u32) -> i16 {
+        match (port, device, index, id) {
+            (0, RETRO_DEVICE_MOUSE, 0, RETRO_DEVICE_ID_MOUSE_X) => -5,
+            (0, RETRO_DEVICE_MOUSE, 0, RETRO_DEVICE_ID_MOUSE_Y) => 12,
+            (0, RETRO_DEVICE_MOUSE, 0, RETRO_DEVICE_ID_MOUSE_RIGHT) => 1,
+            (0, RETRO_DEVICE_MOUSE, 0, RETRO_DEVICE_ID_MOUSE_WHEELDOWN) => 1,
+
+            (0, RETRO_DEVICE_POINTER, 1, RETRO_DEVICE_ID_POINTER_X) => -0x7FFF,
+            (0, RETRO_DEVICE_POINTER, 1, RETRO_DEVICE_ID_POINTER_Y) => 0x1000,
+            (0, RETRO_DEVICE_POINTER, 1, RETRO_DEVICE_ID_POINTER_PRESSED) => 1,
+            (0, RETRO_DEVICE_POINTER, _, RETRO_DEVICE_ID_POINTER_COUNT) => 2,
+
+            _ => 0,
+        }
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let input_state_callback: retro_input_state_t = Some(input_state);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &input_state_callback,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert_eq!(
+        ctx.get_mouse(0),
+        MouseState {
+            x: -5,
+            y: 12,
+            right: true,
+            wheel_down: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(ctx.get_mouse(1), MouseState::default());
+
+    assert_eq!(
+        ctx.get_pointer(0, 1),
+        PointerState {
+            x: -0x7FFF,
+            y: 0x1000,
+            pressed: true,
+            count: 2,
+        }
+    );
+    assert_eq!(
+        ctx.get_pointer(0, 0),
+        PointerState {
+            count: 2,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn keyboard_helpers_poll_keycodes() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn input_state(port: u32, device: u32, _index: u32, id: u32) -> i16 {
+        let pressed = [
+            retro_key::RETROK_SPACE.0 as u32,
+            retro_key::RETROK_LEFT.0 as u32,
+        ];
+
+        (port == 0 && device == RETRO_DEVICE_KEYBOARD && pressed.contains(&id)) as i16
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let input_state_callback: retro_input_state_t = Some(input_state);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &input_state_callback,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert!(ctx.get_key(0, retro_key::RETROK_SPACE));
+    assert!(!ctx.get_key(0, retro_key::RETROK_RETURN));
+    assert!(!ctx.get_key(1, retro_key::RETROK_SPACE));
+
+    let state = ctx.get_keyboard_state(
+        0,
+        &[
+            retro_key::RETROK_LEFT,
+            retro_key::RETROK_RIGHT,
+            retro_key::RETROK_SPACE,
+        ],
+    );
+
+    assert_eq!(
+        state.pressed(),
+        &[retro_key::RETROK_LEFT, retro_key::RETROK_SPACE]
+    );
+    assert!(state.is_pressed(retro_key::RETROK_SPACE));
+    assert!(!state.is_pressed(retro_key::RETROK_RIGHT));
+    assert!(!state.is_pressed(retro_key::RETROK_RETURN));
+}
+
+#[test]
+fn frame_time_reference_is_stored() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::RwLock;
+
+    static REFERENCE: AtomicI64 = AtomicI64::new(0);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK {
+            return false;
+        }
+
+        let data = &*(data as *const retro_frame_time_callback);
+        REFERENCE.store(data.reference, Ordering::SeqCst);
+        data.callback.is_some()
+    }
+
+    unsafe extern "C" fn unsupported(_cmd: std::os::raw::c_uint, _data: *mut c_void) -> bool {
+        false
+    }
+
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let callback: retro_environment_t = Some(unsupported);
+    let ctx = LoadGameContext::new(&callback, Arc::clone(&interfaces));
+    assert!(ctx.enable_frame_time_callback(16_667).is_err());
+    assert_eq!(GenericContext::from(&ctx).frame_time_reference(), None);
+
+    let callback: retro_environment_t = Some(environment);
+    let ctx = LoadGameContext::new(&callback, Arc::clone(&interfaces));
+    ctx.enable_frame_time_callback(16_667).unwrap();
+    assert_eq!(REFERENCE.load(Ordering::SeqCst), 16_667);
+    assert_eq!(
+        GenericContext::from(&ctx).frame_time_reference(),
+        Some(16_667)
+    );
+}
+
+#[test]
+fn frame_time_callback_can_be_enabled_on_init() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK {
+            return false;
+        }
+
+        (*(data as *const retro_frame_time_callback))
+            .callback
+            .is_some()
+    }
+
+    let callback: retro_environment_t = Some(environment);
+
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = InitContext::new(&callback, Arc::clone(&interfaces));
+    ctx.enable_frame_time_callback(20_000).unwrap();
+    assert_eq!(
+        interfaces.read().unwrap().frame_time_reference,
+        Some(20_000)
+    );
+
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = SetEnvironmentContext::new(&callback, Arc::clone(&interfaces));
+    ctx.enable_frame_time_callback(16_667).unwrap();
+    assert_eq!(
+        interfaces.read().unwrap().frame_time_reference,
+        Some(16_667)
+    );
+}
+
+#[test]
+fn set_geometry_uses_the_cheap_environment_call() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static GEOMETRY: Mutex<Option<(u32, u32, f32)>> = Mutex::new(None);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_GEOMETRY {
+            return false;
+        }
+
+        let geometry = &*(data as *const retro_game_geometry);
+        GEOMETRY.lock().unwrap().replace((
+            geometry.base_width,
+            geometry.base_height,
+            geometry.aspect_ratio,
+        ));
+
+        true
+    }
+
+    let environment_callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert!(ctx.set_geometry(retro_game_geometry {
+        base_width: 512,
+        base_height: 448,
+        max_width: 512,
+        max_height: 478,
+        aspect_ratio: 4.0 / 3.0,
+    }));
+    assert_eq!(*GEOMETRY.lock().unwrap(), Some((512, 448, 4.0 / 3.0)));
+}
+
+#[test]
+fn framebuffer_falls_back_when_declined() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(_cmd: std::os::raw::c_uint, _data: *mut c_void) -> bool {
+        false
+    }
+
+    let environment_callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    let format = PixelFormat::RGB565;
+    assert!(unsafe { ctx.get_current_framebuffer(320, 240, MemoryAccess::WRITE, format) }.is_err());
+
+    let fb =
+        unsafe { ctx.get_current_framebuffer_or_fallback(320, 240, MemoryAccess::WRITE, format) };
+
+    assert_eq!((fb.width, fb.height), (320, 240));
+    assert_eq!(fb.pitch, 320 * 2);
+    assert_eq!(fb.data_len, 240 * 320 * 2);
+    assert!(fb.access_flags.contains(MemoryAccess::WRITE));
+    assert_eq!(fb.borrow_slice_mut().len(), fb.data_len);
+}
+
+#[test]
+fn draw_frame_validates_against_pixel_format() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::RwLock;
+
+    static FRAMES: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        // Behave like an older frontend that only supports 0RGB1555 and RGB565
+        cmd == RETRO_ENVIRONMENT_SET_PIXEL_FORMAT
+            && *(data as *const retro_pixel_format)
+                != retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888
+    }
+
+    unsafe extern "C" fn video_refresh(
+        _data: *const c_void,
+        _width: u32,
+        _height: u32,
+        _pitch: usize,
+    ) {
+        FRAMES.fetch_add(1, Ordering::SeqCst);
+    }
+
+    let environment_callback: retro_environment_t = Some(environment);
+    let video_refresh_callback: retro_video_refresh_t = Some(video_refresh);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let ctx = LoadGameContext::new(&environment_callback, Arc::clone(&interfaces));
+    assert!(ctx.set_pixel_format(PixelFormat::XRGB8888).is_err());
+    assert!(matches!(
+        GenericContext::from(&ctx).pixel_format(),
+        PixelFormat::XRGB1555
+    ));
+
+    ctx.set_pixel_format(PixelFormat::RGB565).unwrap();
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let mut ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &video_refresh_callback,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert!(matches!(ctx.pixel_format(), PixelFormat::RGB565));
+
+    let pixels = vec![0u8; 16 * 8 * 2];
+
+    // Sized for 4 bytes per pixel
+    ctx.draw_frame(&pixels, 16, 8, 16 * 4);
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 0);
+
+    // The pitch is too small for the width
+    ctx.draw_frame(&pixels, 16, 8, 16);
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 0);
+
+    ctx.draw_frame(&pixels, 16, 8, 16 * 2);
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 1);
+
+    // The last row does not need to be padded to the full pitch
+    ctx.draw_frame(&pixels[..16 * 2 * 4 + 8 * 2], 8, 5, 16 * 2);
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn dupe_frame_resubmits_without_frontend_support() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static FRAMES: Mutex<Vec<(Option<Vec<u8>>, u32, u32, usize)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn video_refresh(data: *const c_void, width: u32, height: u32, pitch: usize) {
+        let data = (!data.is_null()).then(|| {
+            std::slice::from_raw_parts(data as *const u8, height as usize * pitch).to_vec()
+        });
+
+        FRAMES.lock().unwrap().push((data, width, height, pitch));
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let video_refresh_callback: retro_video_refresh_t = Some(video_refresh);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    for can_dupe in [true, false] {
+        FRAMES.lock().unwrap().clear();
+
+        let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+            (false, 0, 0, 0, Vec::new());
+        let mut ctx = RunContext {
+            environment_callback: &environment_callback,
+            interfaces: Arc::clone(&interfaces),
+
+            audio_sample_batch_callback: &None,
+            audio_sample_callback: &None,
+            input_poll_callback: &None,
+            input_state_callback: &None,
+            video_refresh_callback: &video_refresh_callback,
+
+            can_dupe,
+            had_frame: &mut had_frame,
+            last_width: &mut last_width,
+            last_height: &mut last_height,
+            last_pitch: &mut last_pitch,
+            last_frame: &mut last_frame,
+
+            supports_bitmasks: false,
+        };
+
+        // Nothing to dupe yet
+        ctx.dupe_frame();
+        assert!(FRAMES.lock().unwrap().is_empty());
+
+        let pixels = (0..2 * 2 * 2).collect::<Vec<u8>>();
+        ctx.draw_frame(&pixels, 2, 2, 4);
+        ctx.dupe_frame();
+
+        let duped = if can_dupe { None } else { Some(pixels.clone()) };
+        assert_eq!(
+            *FRAMES.lock().unwrap(),
+            vec![(Some(pixels), 2, 2, 4), (duped, 2, 2, 4)]
+        );
+    }
+}
+
+#[test]
+fn repeat_frame_respects_can_dupe() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static FRAMES: Mutex<Vec<(bool, u32, u32, usize)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn video_refresh(data: *const c_void, width: u32, height: u32, pitch: usize) {
+        FRAMES
+            .lock()
+            .unwrap()
+            .push((data.is_null(), width, height, pitch));
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let video_refresh_callback: retro_video_refresh_t = Some(video_refresh);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    for can_dupe in [true, false] {
+        FRAMES.lock().unwrap().clear();
+
+        let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+            (false, 0, 0, 0, Vec::new());
+        let mut ctx = RunContext {
+            environment_callback: &environment_callback,
+            interfaces: Arc::clone(&interfaces),
+
+            audio_sample_batch_callback: &None,
+            audio_sample_callback: &None,
+            input_poll_callback: &None,
+            input_state_callback: &None,
+            video_refresh_callback: &video_refresh_callback,
+
+            can_dupe,
+            had_frame: &mut had_frame,
+            last_width: &mut last_width,
+            last_height: &mut last_height,
+            last_pitch: &mut last_pitch,
+            last_frame: &mut last_frame,
+
+            supports_bitmasks: false,
+        };
+        assert_eq!(ctx.can_dupe(), can_dupe);
+
+        ctx.draw_frame(&[0; 3 * 2 * 2], 3, 2, 6);
+        ctx.repeat_frame();
+
+        // Only a NULL frame if the frontend can dupe, the stored copy otherwise
+        assert_eq!(
+            *FRAMES.lock().unwrap(),
+            vec![(false, 3, 2, 6), (can_dupe, 3, 2, 6)]
+        );
+    }
+}
+
+#[test]
+fn audio_helpers_submit_complete_frames() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static BATCHES: Mutex<Vec<Vec<i16>>> = Mutex::new(Vec::new());
+    static SAMPLES: Mutex<Vec<(i16, i16)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn audio_sample_batch(data: *const i16, frames: usize) -> usize {
+        // Consume at most 3 frames at once, like a frontend with a full buffer
+        let frames = frames.min(3);
+        let samples = std::slice::from_raw_parts(data, frames * 2);
+
+        BATCHES.lock().unwrap().push(samples.to_vec());
+        frames
+    }
+
+    unsafe extern "C" fn audio_sample(left: i16, right: i16) {
+        SAMPLES.lock().unwrap().push((left, right));
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let audio_sample_batch_callback: retro_audio_sample_batch_t = Some(audio_sample_batch);
+    let audio_sample_callback: retro_audio_sample_t = Some(audio_sample);
+
+    let ctx = AudioContext {
+        environment_callback: &environment_callback,
+        interfaces: Arc::new(RwLock::new(InterfaceList::default())),
+
+        audio_sample_batch_callback: &audio_sample_batch_callback,
+        audio_sample_callback: &audio_sample_callback,
+    };
+
+    // The trailing sample does not form a complete frame
+    ctx.batch_audio_samples(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+    assert_eq!(
+        *BATCHES.lock().unwrap(),
+        vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 10]]
+    );
+
+    BATCHES.lock().unwrap().clear();
+    ctx.batch_audio_samples(&[1]);
+    assert!(BATCHES.lock().unwrap().is_empty());
+
+    ctx.batch_audio_samples_mono(&[1, 2, 3, 4]);
+    assert_eq!(
+        *BATCHES.lock().unwrap(),
+        vec![vec![1, 1, 2, 2, 3, 3], vec![4, 4]]
+    );
+
+    ctx.queue_audio_sample(-1, 1);
+    ctx.queue_audio_sample_mono(5);
+    assert_eq!(*SAMPLES.lock().unwrap(), vec![(-1, 1), (5, 5)]);
+}
+
+#[test]
+fn set_content_info_override_appends_terminator() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static OVERRIDES: Mutex<Vec<(Option<String>, bool, bool)>> = Mutex::new(Vec::new());
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_CONTENT_INFO_OVERRIDE {
+            return false;
+        }
+
+        let mut overrides = OVERRIDES.lock().unwrap();
+        let mut info = data as *const retro_system_content_info_override;
+
+        // Read up to and including the terminating entry
+        loop {
+            let entry = &*info;
+            overrides.push((
+                get_string_from_pointer(entry.extensions),
+                entry.need_fullpath,
+                entry.persistent_data,
+            ));
+
+            if entry.extensions.is_null() {
+                break;
+            }
+
+            info = info.add(1);
+        }
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = SetEnvironmentContext::new(&callback, Arc::clone(&interfaces));
+
+    ctx.set_content_info_override(&[
+        ContentInfoOverride {
+            extensions: "md|sms|gg",
+            need_fullpath: false,
+            persistent_data: true,
+        },
+        ContentInfoOverride {
+            extensions: "cue",
+            need_fullpath: true,
+            persistent_data: false,
+        },
+    ])
+    .unwrap();
+
+    assert_eq!(
+        *OVERRIDES.lock().unwrap(),
+        vec![
+            (Some("md|sms|gg".to_owned()), false, true),
+            (Some("cue".to_owned()), true, false),
+            (None, false, false),
+        ]
+    );
+    assert_eq!(
+        interfaces
+            .read()
+            .unwrap()
+            .content_info_override_extensions
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn get_game_info_ext_converts_fields() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const DATA: &[u8] = &[1, 2, 3];
+    static PERSISTENT: AtomicBool = AtomicBool::new(true);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_GAME_INFO_EXT {
+            return false;
+        }
+
+        let info = Box::leak(Box::new(retro_game_info_ext {
+            full_path: b"/roms/game.md\0".as_ptr() as *const c_char,
+            archive_path: std::ptr::null(),
+            archive_file: std::ptr::null(),
+            dir: b"/roms\0".as_ptr() as *const c_char,
+            name: b"game\0".as_ptr() as *const c_char,
+            ext: b"md\0".as_ptr() as *const c_char,
+            meta: std::ptr::null(),
+            data: DATA.as_ptr() as *const c_void,
+            size: DATA.len(),
+            file_in_archive: false,
+            persistent_data: PERSISTENT.load(Ordering::SeqCst),
+        }));
+
+        *(data as *mut *const retro_game_info_ext) = info;
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = LoadGameContext::new(&callback, interfaces);
+
+    let info = ctx.get_game_info_ext().unwrap();
+    assert_eq!(info.len(), 1);
+
+    let info = &info[0];
+    assert_eq!(info.full_path(), Some(Path::new("/roms/game.md")));
+    assert_eq!(info.archive_path(), None);
+    assert_eq!(info.dir(), Some(Path::new("/roms")));
+    assert_eq!(info.name(), Some("game"));
+    assert_eq!(info.ext(), Some("md"));
+    assert_eq!(info.meta(), None);
+    assert_eq!(info.data(), Some(DATA));
+    assert!(!info.file_in_archive());
+    assert!(info.persistent_data());
+
+    assert_eq!(unsafe { ctx.persistent_game_data() }, Some(DATA));
+
+    // Data that is only valid while loading has to be copied
+    PERSISTENT.store(false, Ordering::SeqCst);
+    assert_eq!(unsafe { ctx.persistent_game_data() }, None);
+}
+
+#[test]
+fn available_interfaces_reports_acquired_interfaces() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn set_rumble_state(
+        _port: std::os::raw::c_uint,
+        _effect: retro_rumble_effect,
+        _strength: u16,
+    ) -> bool {
+        true
+    }
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE {
+            return false;
+        }
+
+        (*(data as *mut retro_rumble_interface)).set_rumble_state = Some(set_rumble_state);
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let mut ctx = LoadGameContext::new(&callback, Arc::clone(&interfaces));
+
+    assert!(!ctx.has_rumble());
+    assert_eq!(
+        GenericContext::from(&ctx).available_interfaces(),
+        AvailableInterfaces::default()
+    );
+
+    ctx.enable_rumble_interface().unwrap();
+
+    assert!(ctx.has_rumble());
+    assert_eq!(
+        GenericContext::from(&ctx).available_interfaces(),
+        AvailableInterfaces {
+            rumble: true,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn fastforwarding_override_is_forwarded() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static OVERRIDE: Mutex<Option<(f32, bool, bool, bool)>> = Mutex::new(None);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        match cmd {
+            RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE => {
+                let value = &*(data as *const retro_fastforwarding_override);
+                *OVERRIDE.lock().unwrap() = Some((
+                    value.ratio,
+                    value.notification,
+                    value.inhibit_toggle,
+                    value.fastforward,
+                ));
+                true
+            }
+            RETRO_ENVIRONMENT_GET_FASTFORWARDING => {
+                *(data as *mut bool) = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+
+    let ctx = RunContext {
+        environment_callback: &callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert!(ctx.set_fastforwarding_override(2.0, true, false, true));
+    assert_eq!(*OVERRIDE.lock().unwrap(), Some((2.0, true, false, true)));
+
+    assert!(unsafe { ctx.get_fastforwarding() });
+}
+
+#[test]
+fn throttle_state_is_converted() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        RwLock,
+    };
+
+    static MODE: AtomicU32 = AtomicU32::new(RETRO_THROTTLE_REWINDING);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_THROTTLE_STATE {
+            return false;
+        }
+
+        *(data as *mut retro_throttle_state) = retro_throttle_state {
+            mode: MODE.load(Ordering::SeqCst),
+            rate: 30.0,
+        };
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+
+    let ctx = RunContext {
+        environment_callback: &callback,
+        interfaces,
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &None,
+
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    assert_eq!(
+        unsafe { ctx.get_throttle_state() },
+        Some(ThrottleState {
+            mode: ThrottleMode::Rewinding,
+            rate: 30.0,
+        })
+    );
+
+    // Modes added by newer frontends are not guessed
+    MODE.store(42, Ordering::SeqCst);
+    assert_eq!(unsafe { ctx.get_throttle_state() }, None);
+}
+
+#[test]
+fn set_variable_passes_key_and_value() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static VARIABLE: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_VARIABLE {
+            return false;
+        }
+
+        let var = &*(data as *const retro_variable);
+        *VARIABLE.lock().unwrap() =
+            get_string_from_pointer(var.key).zip(get_string_from_pointer(var.value));
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
+
+    assert!(ctx.set_variable("core_region", "PAL").is_ok());
+    assert_eq!(
+        *VARIABLE.lock().unwrap(),
+        Some(("core_region".to_owned(), "PAL".to_owned()))
+    );
+
+    assert!(ctx.set_variable("core\0region", "PAL").is_err());
+}
+
+#[test]
+fn get_variable_reads_the_option_value() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_VARIABLE {
+            return false;
+        }
+
+        let var = &mut *(data as *mut retro_variable);
+        if get_str_from_pointer(var.key) == Some("core_region") {
+            var.value = b"PAL\0".as_ptr() as *const c_char;
+        }
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let mut ctx = OptionsChangedContext::new(&callback, interfaces);
+
+    assert_eq!(ctx.get_variable("core_region").unwrap(), Some("PAL"));
+    assert_eq!(ctx.get_variable("core_unknown").unwrap(), None);
+}
+
+#[test]
+fn set_variable_visible_passes_the_key() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static DISPLAY: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY {
+            return false;
+        }
+
+        let display = &*(data as *const retro_core_option_display);
+        *DISPLAY.lock().unwrap() =
+            get_string_from_pointer(display.key).map(|key| (key, display.visible));
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = OptionsChangedContext::new(&callback, interfaces);
+
+    assert!(ctx.set_variable_visible("core_custom_width", false));
+    assert_eq!(
+        *DISPLAY.lock().unwrap(),
+        Some(("core_custom_width".to_owned(), false))
+    );
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = OptionsChangedContext::new(&callback, interfaces);
+
+    assert!(!ctx.set_variable_visible("core_custom_width", true));
+}
+
+#[test]
+fn set_serialization_quirks_returns_the_accepted_quirks() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS {
+            return false;
+        }
+
+        // Acknowledge variable sizes, but drop everything the "frontend" does not know
+        let quirks = &mut *(data as *mut u64);
+        *quirks = (*quirks & RETRO_SERIALIZATION_QUIRK_CORE_VARIABLE_SIZE as u64)
+            | RETRO_SERIALIZATION_QUIRK_FRONT_VARIABLE_SIZE as u64;
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = LoadGameContext::new(&callback, interfaces);
+
+    let accepted = ctx
+        .set_serialization_quirks(
+            SerializationQuirks::CORE_VARIABLE_SIZE | SerializationQuirks::SINGLE_SESSION,
+        )
+        .unwrap();
+
+    assert_eq!(
+        accepted,
+        SerializationQuirks::CORE_VARIABLE_SIZE | SerializationQuirks::FRONT_VARIABLE_SIZE
+    );
+}
 
-            return mask;
+#[test]
+fn get_input_device_capabilities_decodes_the_mask() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    };
+
+    static MASK: AtomicU64 = AtomicU64::new(0);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_INPUT_DEVICE_CAPABILITIES {
+            return false;
         }
 
-        JoypadState::empty()
+        *(data as *mut u64) = MASK.load(Ordering::SeqCst);
+        true
     }
 
-    /// Queries the frontend for the joypad state with the more efficient, but currently experimental,
-    /// joypad bitmask feature. Only a single call into the frontend gets made.
-    #[proc::unstable(feature = "env-commands")]
-    pub fn get_joypad_bitmask(&self, port: u32, index: u32) -> JoypadState {
-        if let Some(callback) = self.input_state_callback {
-            if self.supports_bitmasks {
-                return JoypadState::from_bits_truncate((callback)(
-                    port,
-                    RETRO_DEVICE_JOYPAD,
-                    index,
-                    RETRO_DEVICE_ID_JOYPAD_MASK,
-                ) as u16);
-            }
+    let callback: retro_environment_t = Some(environment);
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let ctx = RunContext {
+        environment_callback: &callback,
+        interfaces: Arc::new(RwLock::new(InterfaceList::default())),
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &None,
+        can_dupe: false,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
 
-            // Fallback
-            return self.get_joypad_state(port, index);
+    MASK.store(
+        (1 << RETRO_DEVICE_JOYPAD) | (1 << RETRO_DEVICE_ANALOG),
+        Ordering::SeqCst,
+    );
+    assert_eq!(
+        ctx.get_input_device_capabilities().unwrap(),
+        InputDeviceCaps::JOYPAD | InputDeviceCaps::ANALOG
+    );
+
+    MASK.store(
+        (1 << RETRO_DEVICE_MOUSE) | (1 << RETRO_DEVICE_KEYBOARD) | (1 << RETRO_DEVICE_LIGHTGUN),
+        Ordering::SeqCst,
+    );
+    let caps = ctx.get_input_device_capabilities().unwrap();
+    assert!(caps.contains(InputDeviceCaps::LIGHTGUN));
+    assert!(!caps.contains(InputDeviceCaps::POINTER));
+    assert_eq!(
+        caps,
+        InputDeviceCaps::MOUSE | InputDeviceCaps::KEYBOARD | InputDeviceCaps::LIGHTGUN
+    );
+
+    // Bits beyond the known device types get ignored
+    MASK.store((1 << RETRO_DEVICE_POINTER) | (1 << 40), Ordering::SeqCst);
+    assert_eq!(
+        ctx.get_input_device_capabilities().unwrap(),
+        InputDeviceCaps::POINTER
+    );
+}
+
+#[test]
+fn log_cheat_error_shows_a_message() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_MESSAGE {
+            return false;
         }
 
-        JoypadState::empty()
+        let message = &*(data as *const retro_message);
+        *MESSAGE.lock().unwrap() = get_string_from_pointer(message.msg);
+
+        true
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn get_current_framebuffer(
-        &self,
-        width: u32,
-        height: u32,
-        access_flags: MemoryAccess,
-        format: PixelFormat,
-    ) -> Result<Framebuffer, Box<dyn std::error::Error>> {
-        let ctx: GenericContext = self.into();
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
 
-        let fb = ctx.get_current_software_framebuffer(retro_framebuffer {
-            data: std::ptr::null_mut(),
-            width,
-            height,
-            pitch: 0,
-            format: format.into(),
-            access_flags: access_flags.bits(),
-            memory_flags: 0,
-        });
+    assert!(ctx.log_cheat_error(3, "invalid code").is_ok());
+    assert_eq!(
+        MESSAGE.lock().unwrap().as_deref(),
+        Some("Cheat #3 could not be applied: invalid code")
+    );
+}
 
-        if let Some(fb) = fb {
-            if !fb.data.is_null() {
-                // TODO: Can we get rid of the raw pointer and PhantomData in an ergonomic way?
-                // When defining `data` as `&'a mut [u8]` it has the same lifetime as `self`,
-                // which means we borrow `self` for as long as this `FrameBuffer` exists.
-                // Thus we cannot pass the `FrameBuffer` to `Self::draw_frame` for example.
-                return Ok(Framebuffer {
-                    data: fb.data as *mut u8,
-                    data_len: fb.height as usize * fb.pitch,
-                    phantom: ::core::marker::PhantomData,
+#[test]
+fn frontend_info_collects_the_interface_versions() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        let value = match cmd {
+            RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION => 2,
+            RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION => 1,
+            RETRO_ENVIRONMENT_GET_INPUT_MAX_USERS => 4,
+            RETRO_ENVIRONMENT_GET_LANGUAGE => retro_language::RETRO_LANGUAGE_GERMAN as u32,
+            _ => return false,
+        };
 
-                    width: fb.width,
-                    height: fb.height,
-                    pitch: fb.pitch,
-                    format: fb.format.into(),
-                    access_flags: MemoryAccess::from_bits_unchecked(fb.access_flags),
-                    memory_flags: MemoryType::from_bits_unchecked(fb.memory_flags),
-                });
-            }
+        *(data as *mut u32) = value;
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
+
+    let info = ctx.frontend_info();
+
+    assert_eq!(
+        info,
+        FrontendInfo {
+            core_options_version: 2,
+            message_interface_version: 1,
+            disk_control_interface_version: 0,
+            input_max_users: Some(4),
+            language: Some(retro_language::RETRO_LANGUAGE_GERMAN),
+            libretro_path: None,
         }
+    );
+    assert!(!info.is_up_to_date());
+}
 
-        Err("Failed to get current software framebuffer".into())
+#[test]
+fn get_target_refresh_rate_ignores_invalid_rates() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        RwLock,
+    };
+
+    static RATE: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE {
+            return false;
+        }
+
+        *(data as *mut f32) = f32::from_bits(RATE.load(Ordering::SeqCst));
+        true
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn get_current_framebuffer_or_fallback(
-        &self,
-        width: u32,
-        height: u32,
-        access_flags: MemoryAccess,
-        format: PixelFormat,
-    ) -> Framebuffer {
-        match self.get_current_framebuffer(width, height, access_flags, format) {
-            Ok(fb) if fb.access_flags.intersects(access_flags) => fb,
-            _ => {
-                let data = unsafe { &mut FALLBACK_FRAMEBUFFER };
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
 
-                let pitch = width as usize * format.bit_per_pixel();
-                let data_len = width as usize * height as usize * pitch;
+    RATE.store(59.94f32.to_bits(), Ordering::SeqCst);
+    assert_eq!(ctx.get_target_refresh_rate(), Some(59.94));
 
-                if data.len() < data_len {
-                    data.resize(data_len, 0);
-                }
+    RATE.store(0f32.to_bits(), Ordering::SeqCst);
+    assert_eq!(ctx.get_target_refresh_rate(), None);
 
-                Framebuffer {
-                    data: data.as_mut_ptr(),
-                    data_len,
-                    phantom: ::core::marker::PhantomData,
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
 
-                    width,
-                    height,
-                    pitch,
-                    format,
-                    access_flags: MemoryAccess::READ | MemoryAccess::WRITE,
-                    memory_flags: MemoryType::UNCACHED,
-                }
-            }
+    assert_eq!(ctx.get_target_refresh_rate(), None);
+}
+
+#[cfg(feature = "vulkan")]
+#[test]
+fn vulkan_interface_requires_a_vulkan_context() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_HW_RENDER_INTERFACE {
+            return false;
         }
+
+        let interface: &'static mut retro_hw_render_interface_vulkan =
+            Box::leak(Box::new(std::mem::zeroed()));
+        interface.interface_type = retro_hw_render_interface_type::RETRO_HW_RENDER_INTERFACE_VULKAN;
+        interface.interface_version = RETRO_HW_RENDER_INTERFACE_VULKAN_VERSION;
+        interface.queue_index = 3;
+
+        *(data as *mut *const retro_hw_render_interface_vulkan) = interface;
+        true
     }
 
-    /// Draws a new frame if [`RunContext::video_refresh_callback`] has been set
-    pub fn draw_frame(&mut self, data: &[u8], width: u32, height: u32, pitch: usize) {
-        if let Some(callback) = self.video_refresh_callback {
-            *self.had_frame = true;
-            *self.last_width = width;
-            *self.last_height = height;
-            *self.last_pitch = pitch;
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = HwContext::new(&callback, Arc::clone(&interfaces));
 
-            unsafe { (callback)(data.as_ptr() as *const c_void, width, height, pitch) }
+    assert!(unsafe { ctx.vulkan_interface() }.is_none());
+
+    let mut render_callback: retro_hw_render_callback = unsafe { std::mem::zeroed() };
+    render_callback.context_type = retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE;
+    interfaces.write().unwrap().hw_render_callback = Some(render_callback);
+
+    assert!(unsafe { ctx.vulkan_interface() }.is_none());
+
+    render_callback.context_type = retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN;
+    interfaces.write().unwrap().hw_render_callback = Some(render_callback);
+
+    assert_eq!(
+        unsafe { ctx.vulkan_interface() }.map(|i| i.queue_index),
+        Some(3)
+    );
+}
+
+#[cfg(feature = "vulkan")]
+#[test]
+fn set_vulkan_negotiation_interface_requires_a_vulkan_context() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static RECEIVED: Mutex<Option<(u32, bool)>> = Mutex::new(None);
+
+    unsafe extern "C" fn destroy_device() {}
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE {
+            return false;
         }
+
+        let interface = &*(data as *const retro_hw_render_context_negotiation_interface_vulkan);
+        RECEIVED.lock().unwrap().replace((
+            interface.interface_version,
+            interface.destroy_device.is_some(),
+        ));
+
+        true
     }
 
-    /// Duplicates the previous frame
-    pub fn dupe_frame(&self) {
-        if !self.can_dupe {
-            eprintln!("[ERROR] This frontend does not support frame duping!");
-            return;
-        } else if !*self.had_frame {
-            eprintln!("[ERROR] Cannot dupe frame, no previous frame has been drawn!");
-            return;
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let mut ctx = LoadGameContext::new(&callback, Arc::clone(&interfaces));
+
+    let interface = VulkanNegotiationInterface {
+        destroy_device: Some(destroy_device),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        unsafe { ctx.set_vulkan_negotiation_interface(interface) },
+        Err(environment::EnvironmentError::InvalidData)
+    );
+    assert!(RECEIVED.lock().unwrap().is_none());
+
+    let mut render_callback: retro_hw_render_callback = unsafe { std::mem::zeroed() };
+    render_callback.context_type = retro_hw_context_type::RETRO_HW_CONTEXT_VULKAN;
+    interfaces.write().unwrap().hw_render_callback = Some(render_callback);
+
+    assert_eq!(
+        unsafe { ctx.set_vulkan_negotiation_interface(interface) },
+        Ok(())
+    );
+    assert_eq!(
+        *RECEIVED.lock().unwrap(),
+        Some((
+            RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN_VERSION,
+            true
+        ))
+    );
+}
+
+#[test]
+fn enable_keyboard_callback_registers_the_callback() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static RECEIVED: Mutex<Option<usize>> = Mutex::new(None);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_KEYBOARD_CALLBACK {
+            return false;
         }
 
-        if let Some(callback) = self.video_refresh_callback {
-            unsafe {
-                (callback)(
-                    std::ptr::null() as *const c_void,
-                    *self.last_width,
-                    *self.last_height,
-                    *self.last_pitch,
-                )
-            }
+        let data = &*(data as *const retro_keyboard_callback);
+        *RECEIVED.lock().unwrap() = data.callback.map(|callback| callback as usize);
+
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
+
+    assert_eq!(ctx.enable_keyboard_callback(), Ok(()));
+    assert_eq!(
+        *RECEIVED.lock().unwrap(),
+        Some(retro_keyboard_callback_fn as *const () as usize)
+    );
+}
+
+#[test]
+fn enable_audio_callback_registers_both_callbacks() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{Mutex, RwLock};
+
+    static RECEIVED: Mutex<Option<(Option<usize>, Option<usize>)>> = Mutex::new(None);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK {
+            return false;
         }
+
+        let data = &*(data as *const retro_audio_callback);
+        RECEIVED.lock().unwrap().replace((
+            data.callback.map(|callback| callback as usize),
+            data.set_state.map(|set_state| set_state as usize),
+        ));
+
+        true
     }
 
-    pub fn draw_framebuffer(&mut self, framebuffer: retro_framebuffer) {
-        if let Some(callback) = self.video_refresh_callback {
-            *self.had_frame = true;
-            *self.last_width = framebuffer.width;
-            *self.last_height = framebuffer.height;
-            *self.last_pitch = framebuffer.pitch;
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
 
-            unsafe {
-                (callback)(
-                    framebuffer.data,
-                    framebuffer.width,
-                    framebuffer.height,
-                    framebuffer.pitch,
-                )
-            }
+    assert_eq!(ctx.enable_audio_callback(), Ok(()));
+    assert_eq!(
+        *RECEIVED.lock().unwrap(),
+        Some((
+            Some(retro_audio_callback_fn as *const () as usize),
+            Some(retro_audio_set_state_callback_fn as *const () as usize)
+        ))
+    );
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
+
+    assert!(ctx.enable_audio_callback().is_err());
+}
+
+#[test]
+fn last_frame_is_kept_on_request() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn video_refresh(
+        _data: *const c_void,
+        _width: u32,
+        _height: u32,
+        _pitch: usize,
+    ) {
+    }
+
+    let environment_callback: retro_environment_t = None;
+    let video_refresh_callback: retro_video_refresh_t = Some(video_refresh);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+        (false, 0, 0, 0, Vec::new());
+    let mut ctx = RunContext {
+        environment_callback: &environment_callback,
+        interfaces: Arc::clone(&interfaces),
+
+        audio_sample_batch_callback: &None,
+        audio_sample_callback: &None,
+        input_poll_callback: &None,
+        input_state_callback: &None,
+        video_refresh_callback: &video_refresh_callback,
+
+        can_dupe: true,
+        had_frame: &mut had_frame,
+        last_width: &mut last_width,
+        last_height: &mut last_height,
+        last_pitch: &mut last_pitch,
+        last_frame: &mut last_frame,
+
+        supports_bitmasks: false,
+    };
+
+    let pixels = (0..2 * 2 * 2).collect::<Vec<u8>>();
+
+    // The frontend can dupe frames, so no copy gets kept by default
+    ctx.draw_frame(&pixels, 2, 2, 4);
+    assert!(ctx.last_frame().is_none());
+
+    GenericContext::new(&environment_callback, Arc::clone(&interfaces)).set_keep_last_frame(true);
+    ctx.draw_frame(&pixels, 2, 2, 4);
+
+    let frame = ctx.last_frame().unwrap();
+    assert_eq!(frame.data, pixels.as_slice());
+    assert_eq!((frame.width, frame.height, frame.pitch), (2, 2, 4));
+    assert_eq!(
+        frame.format.bit_per_pixel(),
+        ctx.pixel_format().bit_per_pixel()
+    );
+
+    GenericContext::new(&environment_callback, interfaces).set_keep_last_frame(false);
+    ctx.draw_frame(&pixels, 2, 2, 4);
+    assert!(ctx.last_frame().is_none());
+}
+
+#[test]
+fn shutdown_reports_frontend_support() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    };
+
+    static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_SHUTDOWN || !data.is_null() {
+            return false;
         }
+
+        SHUTDOWN.store(true, Ordering::SeqCst);
+        true
     }
 
-    pub fn draw_hardware_frame(&mut self, width: u32, height: u32, pitch: usize) {
-        if let Some(callback) = self.video_refresh_callback {
-            *self.had_frame = true;
-            *self.last_width = width;
-            *self.last_height = height;
-            *self.last_pitch = pitch;
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
 
-            unsafe {
-                (callback)(
-                    RETRO_HW_FRAME_BUFFER_VALID as *const c_void,
-                    width,
-                    height,
-                    pitch,
-                )
-            }
+    assert!(ctx.shutdown());
+    assert!(SHUTDOWN.load(Ordering::SeqCst));
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
+
+    assert!(!ctx.shutdown());
+}
+
+#[test]
+fn refresh_system_info_marks_the_cache_outdated() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, Arc::clone(&interfaces));
+
+    assert!(!interfaces.read().unwrap().system_info_outdated);
+    ctx.refresh_system_info();
+    assert!(interfaces.read().unwrap().system_info_outdated);
+}
+
+#[test]
+fn jit_capable_defaults_to_false() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_JIT_CAPABLE {
+            return false;
         }
+
+        *(data as *mut bool) = true;
+        true
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn camera_start(&self) {
-        let interfaces = self.interfaces.read().unwrap();
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    assert!(InitContext::new(&callback, interfaces).jit_capable());
 
-        if let Some(interface) = interfaces.camera_interface {
-            if let Some(start) = interface.start {
-                unsafe { start() };
-            }
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    assert!(!InitContext::new(&callback, interfaces).jit_capable());
+}
+
+#[test]
+fn load_game_special_exposes_the_games() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    let left = [1u8, 2, 3];
+    let right = [4u8, 5];
+    let info = [&left[..], &right[..]].map(|data| retro_game_info {
+        path: std::ptr::null(),
+        data: data.as_ptr() as *const c_void,
+        size: data.len(),
+        meta: std::ptr::null(),
+    });
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+
+    let ctx = LoadGameSpecialContext::new(&callback, Arc::clone(&interfaces));
+    assert!(ctx.games().is_empty());
+
+    let ctx = ctx.with_games(GameInfo::from_slice(&info));
+    let data: Vec<_> = ctx.games().iter().map(GameInfo::data).collect();
+    assert_eq!(data, [Some(&left[..]), Some(&right[..])]);
+}
+
+#[test]
+fn special_game_info_ext_has_one_entry_per_game() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_GAME_INFO_EXT {
+            return false;
         }
+
+        let ext = [&b"md\0"[..], &b"sms\0"[..]].map(|ext| retro_game_info_ext {
+            full_path: std::ptr::null(),
+            archive_path: std::ptr::null(),
+            archive_file: std::ptr::null(),
+            dir: std::ptr::null(),
+            name: std::ptr::null(),
+            ext: ext.as_ptr() as *const c_char,
+            meta: std::ptr::null(),
+            data: std::ptr::null(),
+            size: 0,
+            file_in_archive: false,
+            persistent_data: false,
+        });
+
+        *(data as *mut *const retro_game_info_ext) = Box::leak(Box::new(ext)).as_ptr();
+        true
     }
 
-    #[proc::unstable(feature = "env-commands")]
-    pub fn camera_stop(&self) {
-        let interfaces = self.interfaces.read().unwrap();
+    let info = [retro_game_info {
+        path: std::ptr::null(),
+        data: std::ptr::null(),
+        size: 0,
+        meta: std::ptr::null(),
+    }; 2];
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx =
+        LoadGameSpecialContext::new(&callback, interfaces).with_games(GameInfo::from_slice(&info));
+
+    let exts: Vec<_> = ctx
+        .get_game_info_ext()
+        .unwrap()
+        .iter()
+        .map(GameInfoExt::ext)
+        .collect();
+    assert_eq!(exts, [Some("md"), Some("sms")]);
+}
 
-        if let Some(interface) = interfaces.camera_interface {
-            if let Some(stop) = interface.stop {
-                unsafe { stop() };
-            }
+#[test]
+fn disk_control_interface_version_defaults_to_zero() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        RwLock,
+    };
+
+    static VERSION: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION {
+            return false;
+        }
+
+        *(data as *mut u32) = VERSION.load(Ordering::SeqCst);
+        true
+    }
+
+    let callback: retro_environment_t = Some(environment);
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    let ctx = GenericContext::new(&callback, interfaces);
+
+    for version in [1, 2] {
+        VERSION.store(version, Ordering::SeqCst);
+        assert_eq!(ctx.disk_control_interface_version(), version);
+    }
+
+    let callback: retro_environment_t = None;
+    let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+    assert_eq!(
+        GenericContext::new(&callback, interfaces).disk_control_interface_version(),
+        0
+    );
+}
+
+#[test]
+fn get_device_power_queries_the_frontend() {
+    use crate::core_wrapper::InterfaceList;
+    use std::sync::RwLock;
+
+    unsafe extern "C" fn environment(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+        if cmd != RETRO_ENVIRONMENT_GET_DEVICE_POWER {
+            return false;
         }
+
+        *(data as *mut retro_device_power) = retro_device_power {
+            state: retro_power_state::RETRO_POWERSTATE_CHARGING,
+            seconds: RETRO_POWERSTATE_NO_ESTIMATE,
+            percent: 80,
+        };
+        true
+    }
+
+    unsafe extern "C" fn unsupported(_cmd: std::os::raw::c_uint, _data: *mut c_void) -> bool {
+        false
+    }
+
+    let cases: [(retro_environment_t, Option<DevicePower>); 2] = [
+        (
+            Some(environment),
+            Some(DevicePower {
+                state: PowerState::Charging,
+                seconds: None,
+                percent: Some(80),
+            }),
+        ),
+        (Some(unsupported), None),
+    ];
+
+    for (environment_callback, expected) in cases {
+        let interfaces = Arc::new(RwLock::new(InterfaceList::default()));
+        let (mut had_frame, mut last_width, mut last_height, mut last_pitch, mut last_frame) =
+            (false, 0, 0, 0, Vec::new());
+        let ctx = RunContext {
+            environment_callback: &environment_callback,
+            interfaces,
+
+            audio_sample_batch_callback: &None,
+            audio_sample_callback: &None,
+            input_poll_callback: &None,
+            input_state_callback: &None,
+            video_refresh_callback: &None,
+
+            can_dupe: false,
+            had_frame: &mut had_frame,
+            last_width: &mut last_width,
+            last_height: &mut last_height,
+            last_pitch: &mut last_pitch,
+            last_frame: &mut last_frame,
+
+            supports_bitmasks: false,
+        };
+
+        assert_eq!(unsafe { ctx.get_device_power() }, expected);
     }
 }
@@ -1,7 +1,129 @@
-//! Provides the [`Core`] and [`CoreOptions`] traits.
+//! Provides the [`Core`], [`CoreOptions`] and [`DiskControl`] traits.
 use crate::*;
+use std::time::Duration;
 
 /// This trait defines the [`set_core_options`](CoreOptions::set_core_options) function.
+///
+/// Usually implemented with `#[derive(CoreOptions)]`, which registers
+/// “options v2” including categories if the frontend supports them and falls back
+/// to “options v1” or the legacy variables API otherwise.
+/// Translations for the frontend language are registered as well,
+/// see the `CoreOptions` derive macro for details.
+///
+/// # Typed accessors
+///
+/// `#[typed_options(Name)]` generates a struct with a typed getter per option
+/// and a `refresh` method that reads the current values from the frontend:
+///
+/// ```
+/// use rust_libretro::{contexts::*, proc::CoreOptions, sys::*};
+///
+/// #[derive(CoreOptions)]
+/// #[typed_options(ExampleOptions)]
+/// #[options({
+///     "example_turbo",
+///     "Turbo",
+///     "Runs the emulated CPU faster.",
+///     {
+///         { "disabled" },
+///         { "enabled" },
+///     },
+///     "disabled"
+/// }, {
+///     "example_scale",
+///     "Scale",
+///     "Internal resolution multiplier.",
+///     {
+///         { "1" },
+///         { "2" },
+///         { "4" },
+///     },
+///     "2"
+/// }, {
+///     "example_region",
+///     "Region",
+///     "The emulated console region.",
+///     {
+///         { "NTSC" },
+///         { "PAL" },
+///     }
+/// }, {
+///     "example_overclock",
+///     "CPU Overclock (%)",
+///     "Runs the emulated CPU faster than the original hardware.",
+///     range(100, 400, 10),
+///     "100"
+/// }, {
+///     "example_gamma",
+///     "Gamma",
+///     "Adjusts the brightness of the output.",
+///     range(0.5, 2.0, 0.25),
+///     "1.0"
+/// })]
+/// struct ExampleCore {
+///     options: ExampleOptions,
+/// }
+///
+/// let options = ExampleOptions::default();
+/// assert!(!options.example_turbo());
+/// assert_eq!(options.example_scale(), 2);
+/// assert_eq!(options.example_region(), "NTSC");
+/// assert_eq!(options.example_overclock(), 100);
+/// assert_eq!(options.example_gamma(), 1.0);
+/// ```
+///
+/// Ranges must be valid as well:
+///
+/// ```compile_fail
+/// use rust_libretro::{contexts::*, proc::CoreOptions, sys::*};
+///
+/// #[derive(CoreOptions)]
+/// #[options({
+///     "example_overclock",
+///     "CPU Overclock (%)",
+///     "Runs the emulated CPU faster than the original hardware.",
+///     range(100, 400, 7),
+///     "100"
+/// })]
+/// struct ExampleCore;
+/// ```
+///
+/// Default values must be one of the values of their option:
+///
+/// ```compile_fail
+/// use rust_libretro::{contexts::*, proc::CoreOptions, sys::*};
+///
+/// #[derive(CoreOptions)]
+/// #[options({
+///     "example_turbo",
+///     "Turbo",
+///     "Runs the emulated CPU faster.",
+///     {
+///         { "disabled" },
+///         { "enabled" },
+///     },
+///     "on"
+/// })]
+/// struct ExampleCore;
+/// ```
+///
+/// ```compile_fail
+/// use rust_libretro::{contexts::*, proc::CoreOptions, sys::*};
+///
+/// #[derive(CoreOptions)]
+/// #[typed_options(ExampleOptions)]
+/// #[options({
+///     "example_scale",
+///     "Scale",
+///     "Internal resolution multiplier.",
+///     {
+///         { "1" },
+///         { "2" },
+///     },
+///     "3"
+/// })]
+/// struct ExampleCore;
+/// ```
 pub trait CoreOptions {
     /// Used to tell the frontend any options / settings your core supports.
     /// This can be done by using either of the following functions:
@@ -19,8 +141,40 @@ pub trait CoreOptions {
 /// See also [`retro_core!()`].
 pub trait Core: CoreOptions {
     /// Returns static info about this core.
+    ///
+    /// This should not depend on any state, as it may be called before [`Core::on_init`]
+    /// and its result is cached for the lifetime of the process, even across [`Core::on_deinit`].
+    /// Cores that change their info at runtime, e.g. the `valid_extensions` depending on
+    /// installed BIOS files, have to call [`GenericContext::refresh_system_info`] afterwards.
     fn get_info(&self) -> SystemInfo;
 
+    /// Returns the most verbose level of messages passed to the frontend’s log.
+    ///
+    /// Gets queried once, when the logger is set up in the first call to `retro_set_environment`.
+    /// The `RUST_LIBRETRO_LOG` and `RUST_LOG` environment variables take precedence,
+    /// both accept [`env_logger`] filter directives such as `info,my_core=trace`.
+    #[cfg(feature = "log")]
+    fn log_level(&self) -> log::LevelFilter {
+        log::LevelFilter::Info
+    }
+
+    /// The most verbose [`tracing`] level that gets forwarded to the frontend.
+    ///
+    /// Like [`Core::log_level`], this can be overridden by the
+    /// `RUST_LIBRETRO_LOG` and `RUST_LOG` environment variables.
+    #[cfg(feature = "tracing")]
+    fn tracing_level(&self) -> tracing::level_filters::LevelFilter {
+        tracing::level_filters::LevelFilter::INFO
+    }
+
+    /// Returns the subsystems supported by this core.
+    ///
+    /// They get registered with the frontend during the first call to `retro_set_environment`,
+    /// which allows loading them through [`Core::on_load_game_special`].
+    fn get_subsystem_info(&self) -> Vec<SubsystemInfo> {
+        Vec::new()
+    }
+
     /// Called when the frontend needs information about the
     /// audio and video timings and the video geometry.
     fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info;
@@ -38,16 +192,24 @@ pub trait Core: CoreOptions {
     }
 
     /// Called when the libretro API gets destucted.
+    ///
+    /// The core gets dropped right afterwards, so resources can also be freed in [`Drop`].
+    /// A frontend may initialize the core again later, which creates a new instance.
     fn on_deinit(&mut self, _ctx: &mut DeinitContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Called when the user selects a different device for input `port`.
+    ///
+    /// The selectable devices are the ones declared with
+    /// [`SetEnvironmentContext::set_controller_info`].
+    /// Cores usually update their input descriptors here,
+    /// see [`GenericContext::set_input_descriptors`].
     fn on_set_controller_port_device(
         &mut self,
         _port: u32,
-        _device: u32,
-        _ctx: &mut GenericContext,
+        _device: DeviceType,
+        _ctx: &mut SetControllerPortDeviceContext,
     ) {
         // Do nothing
     }
@@ -63,7 +225,11 @@ pub trait Core: CoreOptions {
     /// this still counts as a frame, and [`Core::on_run`] should explicitly dupe
     /// a frame if [`environment::can_dupe`] returns [`true`].
     /// In this case, the video callback can take a NULL argument for data.
-    fn on_run(&mut self, _ctx: &mut RunContext, _delta_us: Option<i64>) {
+    ///
+    /// `delta` is the time since the last frame once the frame time callback has been enabled,
+    /// see [`LoadGameContext::enable_frame_time_callback`]. [`RunContext::frame_delta`]
+    /// returns it in microseconds, as reported by the frontend.
+    fn on_run(&mut self, _ctx: &mut RunContext, _delta: Option<Duration>) {
         // Do nothing
     }
 
@@ -78,8 +244,23 @@ pub trait Core: CoreOptions {
         0
     }
 
+    /// Returns the quirks of the implementation’s serialization, which get passed
+    /// to the frontend after a game has been loaded successfully.
+    ///
+    /// Unless [`SerializationQuirks::CORE_VARIABLE_SIZE`] is set,
+    /// the first non-zero value returned by [`Core::get_serialize_size`]
+    /// gets cached until the next game is loaded, as frontends call it very often for rewind.
+    ///
+    /// Don’t call [`LoadGameContext::set_serialization_quirks`] yourself when overriding this.
+    fn serialize_size_quirks(&self) -> SerializationQuirks {
+        SerializationQuirks::empty()
+    }
+
     /// Serializes internal state. If failed, or size is lower than
     /// [`Core::get_serialize_size`], it should return [`false`], [`true'] otherwise.
+    ///
+    /// Runahead may call this every frame, see [`SerializeContext::save_state_context`]
+    /// for skipping work that is only needed for states written to disk.
     fn on_serialize(&mut self, _slice: &mut [u8], _ctx: &mut SerializeContext) -> bool {
         // Tell the frontend that we don’t support serialization
         false
@@ -104,9 +285,12 @@ pub trait Core: CoreOptions {
         Ok(())
     }
 
-    /// Loads a "special" kind of game. Should not be used, except in extreme cases.
+    /// Loads a "special" kind of game, i.e. one of the subsystems returned by
+    /// [`Core::get_subsystem_info`].
     ///
-    /// **TODO:** Better documentation. What’s a “special” game?
+    /// `game_type` is the [`SubsystemInfo::id`] of the requested subsystem and `info`
+    /// points to `num_info` games, one for each of its [`SubsystemInfo::roms`].
+    /// Prefer [`LoadGameSpecialContext::games`] over dereferencing `info` directly.
     fn on_load_game_special(
         &mut self,
         _game_type: std::os::raw::c_uint,
@@ -128,44 +312,93 @@ pub trait Core: CoreOptions {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Enables or disables the cheat at `index`.
+    ///
+    /// The format of `code` is core-specific. It gets converted from the frontend’s C string
+    /// lossily, so invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// Return an error if the code could not be parsed;
+    /// it gets shown to the user with [`GenericContext::log_cheat_error`].
     fn on_cheat_set(
         &mut self,
         _index: std::os::raw::c_uint,
         _enabled: bool,
-        _code: &CStr,
+        _code: &str,
         _ctx: &mut CheatSetContext,
-    ) {
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // Do nothing
+        Ok(())
+    }
+
+    /// Gets the region of the game, which determines its video standard.
+    fn on_get_region(&mut self, _ctx: &mut GetRegionContext) -> Region {
+        Region::Ntsc
     }
 
-    /// Gets the region of the game.
+    /// Returns the memory identified by `id`, or [`None`] if the core does not expose it.
     ///
-    /// Can be any of:
-    /// - [`RETRO_REGION_NTSC`]
-    /// - [`RETRO_REGION_PAL`]
-    fn on_get_region(&mut self, _ctx: &mut GetRegionContext) -> std::os::raw::c_uint {
-        RETRO_REGION_NTSC
+    /// Used by the default implementations of both [`Core::get_memory_data`] and
+    /// [`Core::get_memory_size`], so the pointer and size reported to the frontend always agree.
+    /// The same [memory stability](Core::get_memory_data#memory-stability) rules apply.
+    /// Battery backed save data can be registered with [`LoadGameContext::set_save_ram`] instead.
+    fn memory(&mut self, _id: MemoryId) -> Option<&mut [u8]> {
+        None
     }
 
-    /// **TODO:** Documentation
+    /// Returns a pointer to the memory identified by `id`, one of the `RETRO_MEMORY_*` constants,
+    /// or a null pointer if the core does not expose it.
+    ///
+    /// Defaults to the memory returned by [`Core::memory`]. Only override this and
+    /// [`Core::get_memory_size`] for ids not covered by [`MemoryId`], e.g. subsystem memory.
+    ///
+    /// [`RETRO_MEMORY_SAVE_RAM`] and [`RETRO_MEMORY_RTC`] get saved and restored by the frontend,
+    /// while [`RETRO_MEMORY_SYSTEM_RAM`] gets read by achievements, cheats and memory viewers.
+    ///
+    /// # Memory stability
+    ///
+    /// The frontend caches the returned pointer, so from [`Core::on_cheevos_ready`] until
+    /// [`Core::on_unload_game`] it must keep pointing to the same, valid memory.
+    /// Allocate the memory once, e.g. as a `Box<[u8]>`, instead of using a growing [`Vec`].
+    ///
+    /// If the memory is also described by [`GenericContext::set_memory_maps`],
+    /// [`RETRO_MEMORY_SYSTEM_RAM`] should point to the start of the
+    /// [`MemoryDescriptorFlags::SYSTEM_RAM`] descriptor, so both views agree.
     fn get_memory_data(
         &mut self,
-        _id: std::os::raw::c_uint,
+        id: std::os::raw::c_uint,
         _ctx: &mut GetMemoryDataContext,
     ) -> *mut std::os::raw::c_void {
-        // Tell the frontend that we don’t support direct memory access
-        std::ptr::null_mut()
+        match MemoryId::from_raw(id).and_then(|id| self.memory(id)) {
+            Some(memory) if !memory.is_empty() => memory.as_mut_ptr() as *mut _,
+            // Tell the frontend that we don’t support direct memory access
+            _ => std::ptr::null_mut(),
+        }
     }
 
-    /// **TODO:** Documentation
+    /// Returns the size in bytes of the memory returned by [`Core::get_memory_data`] for the same `id`.
+    ///
+    /// Like the pointer, the size must not change while a game is loaded.
+    /// Must be `0` if [`Core::get_memory_data`] returns a null pointer, otherwise tools like
+    /// the achievement runtime read from `NULL`. The default implementations ensure this.
     fn get_memory_size(
         &mut self,
-        _id: std::os::raw::c_uint,
+        id: std::os::raw::c_uint,
         _ctx: &mut GetMemorySizeContext,
     ) -> usize {
-        // Tell the frontend that we don’t support direct memory access
-        0
+        MemoryId::from_raw(id)
+            .and_then(|id| self.memory(id))
+            .map_or(0, |memory| memory.len())
+    }
+
+    /// Called after [`Core::on_load_game`] or [`Core::on_load_game_special`] succeeded,
+    /// right before the frontend starts inspecting the core’s memory,
+    /// e.g. to initialize achievements or its memory viewer.
+    ///
+    /// From this point until [`Core::on_unload_game`], the memory exposed through
+    /// [`Core::get_memory_data`] and [`GenericContext::set_memory_maps`] must stay valid and in place.
+    /// Achievements also require [`InitContext::set_support_achievements`] to be called in [`Core::on_init`].
+    fn on_cheevos_ready(&mut self, _ctx: &mut GenericContext) {
+        // Do nothing
     }
 
     /// Gets called when the core options have been changed.
@@ -175,140 +408,374 @@ pub trait Core: CoreOptions {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Called for every key press and release once [`GenericContext::enable_keyboard_callback`]
+    /// succeeded, with `character` being the typed UTF-32 character, if any.
+    ///
+    /// Key and character events may arrive separately: `key` is [`Key::Unknown`]
+    /// for character-only events and `character` is `0` for key-only events.
+    /// The raw values passed by the frontend are available through [`Key::raw`] and [`KeyMods::raw`].
+    /// Cores that only need to know which keys are held once per frame can use
+    /// [`RunContext::get_key`] or [`RunContext::get_keyboard_state`] instead.
     fn on_keyboard_event(
         &mut self,
         _down: bool,
-        _keycode: retro_key,
+        _key: Key,
         _character: u32,
-        _key_modifiers: retro_mod,
+        _key_modifiers: KeyMods,
     ) {
         // Do nothing
     }
 
-    /// Called when the frontend needs more audio frames
+    /// Called when the frontend needs more audio frames,
+    /// once [`GenericContext::enable_audio_callback`] succeeded.
+    ///
+    /// This may be called from a different thread than [`Core::on_run`].
     fn on_write_audio(&mut self, _ctx: &mut AudioContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Called when the frontend starts (`enabled` is `true`) or stops
+    /// calling [`Core::on_write_audio`], e.g. while the game is paused.
     fn on_audio_set_state(&mut self, _enabled: bool) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Called once per frame before [`Core::on_run`] with the state of the frontend audio buffer,
+    /// if enabled using [`GenericContext::set_audio_buffer_status_callback`].
+    ///
+    /// - `active`: `true` if the audio buffer is currently in use.
+    /// - `occupancy`: The audio buffer occupancy as a percentage (0 - 100).
+    /// - `underrun_likely`: `true` if the frontend expects an audio buffer underrun
+    ///   during the next frame, which indicates that a core should attempt frame skipping.
     fn on_audio_buffer_status(&mut self, _active: bool, _occupancy: u32, _underrun_likely: bool) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
-    fn on_hw_context_reset(&mut self, _ctx: &mut GenericContext) {
+    /// Called when a hardware rendering context has been (re-)created by the frontend.
+    ///
+    /// All GPU resources have to be (re-)initialized here, the negotiated
+    /// [`retro_hw_render_callback`] is available through [`HwContext::hw_render_callback`].
+    ///
+    /// If the frontend resets the context during another call into the core, e.g. when
+    /// [`RunContext::set_system_av_info`] reinitializes the video driver, this runs once that call returned.
+    fn on_hw_context_reset(&mut self, _ctx: &mut HwContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
-    fn on_hw_context_destroyed(&mut self, _ctx: &mut GenericContext) {
+    /// Called before the hardware rendering context gets destroyed.
+    ///
+    /// GPU resources should be freed here, as the context will be invalid afterwards.
+    fn on_hw_context_destroyed(&mut self, _ctx: &mut HwContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
-    fn on_get_proc_address(&mut self, _symbol_name: &CStr) -> retro_proc_address_t {
+    /// Whether the frontend should be able to look up functions using [`Core::get_proc_address`].
+    ///
+    /// If this returns `true`, the interface gets enabled during the first call
+    /// to `retro_set_environment`, see [`SetEnvironmentContext::enable_proc_address`].
+    fn exports_proc_addresses(&self) -> bool {
+        false
+    }
+
+    /// Returns the function exported under the name `sym`, if any.
+    ///
+    /// Frontends use this to look up custom extension functions of a core,
+    /// which is only possible if [`Core::exports_proc_addresses`] returns `true`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use rust_libretro::sys::*;
+    /// unsafe extern "C" fn my_extension() {
+    ///     // ...
+    /// }
+    ///
+    /// fn get_proc_address(sym: &str) -> retro_proc_address_t {
+    ///     match sym {
+    ///         "my_extension" => Some(my_extension),
+    ///         _ => None,
+    ///     }
+    /// }
+    /// ```
+    fn get_proc_address(&self, _sym: &str) -> retro_proc_address_t {
         None
     }
 
-    /// **TODO:** Documentation
-    fn on_location_lifetime_status_initialized(&mut self, _ctx: &mut GenericContext) {
+    /// Called by the frontend once the location service has been initialized.
+    ///
+    /// The location service can be used through [`RunContext::location`] afterwards.
+    fn on_location_initialized(&mut self, _ctx: &mut GenericContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
-    fn on_location_lifetime_status_deinitialized(&mut self, _ctx: &mut GenericContext) {
+    /// Called by the frontend right before the location service gets deinitialized.
+    fn on_location_deinitialized(&mut self, _ctx: &mut GenericContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Called by the frontend once the camera driver has been initialized.
+    ///
+    /// It is now safe to call [`GenericContext::camera_start`].
     fn on_camera_initialized(&mut self, _ctx: &mut GenericContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Called by the frontend right before the camera driver gets deinitialized.
     fn on_camera_deinitialized(&mut self, _ctx: &mut GenericContext) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
-    fn on_camera_raw_framebuffer(
-        &mut self,
-        _buffer: &[u32],
-        _width: u32,
-        _height: u32,
-        _pitch: usize,
-    ) {
+    /// Called with a new camera frame if the camera interface has been enabled
+    /// with `RETRO_CAMERA_BUFFER_RAW_FRAMEBUFFER` support.
+    ///
+    /// `buffer` contains `height` lines of XRGB8888 pixels, each line `pitch` bytes long.
+    /// Only the first `width` pixels of each line are valid,
+    /// so the last line is cut off after them and `buffer` is empty if `height` is zero.
+    fn on_camera_frame_raw(&mut self, _buffer: &[u32], _width: u32, _height: u32, _pitch: usize) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
-    fn on_camera_gl_texture(
+    /// Called with a new camera frame if the camera interface has been enabled
+    /// with `RETRO_CAMERA_BUFFER_OPENGL_TEXTURE` support.
+    ///
+    /// `affine` is a packed 3x3 column-major matrix used to apply
+    /// an affine transform to the texture coordinates.
+    fn on_camera_frame_gl_texture(
         &mut self,
         _texture_id: u32,
         _texture_target: u32,
-        _affine_matrix: &[f32; 3 * 3],
+        _affine: &[f32; 3 * 3],
     ) {
         // Do nothing
     }
 
-    /// **TODO:** Documentation
+    /// Called when the frontend asks for the first save state for rollback netplay
+    /// after a game has been loaded.
+    ///
+    /// Netplay requires every instance to produce the same results from the same input,
+    /// so this is the place to disable non-deterministic features, e.g. seeding random numbers
+    /// from the wall clock or emulating in asynchronous threads.
+    /// See also [`NetplayContext::is_netplay_active`].
+    fn on_netplay_started(&mut self, _ctx: &mut NetplayContext) {
+        // Do nothing
+    }
+
+    /// Whether the save states of this core can be used for runahead, `true` by default.
+    ///
+    /// Runahead either saves and loads the state of this instance up to several times per frame,
+    /// or transfers states to a second instance, which frontends create by loading a separate
+    /// copy of the core library (see [`retro_core!`](crate::retro_core)).
+    /// If this returns `false`, all save states requested for runahead fail,
+    /// which makes frontends like RetroArch disable it.
+    fn supports_runahead(&self) -> bool {
+        true
+    }
+
+    /// Called when the frontend asks for the first save state for runahead
+    /// after a game has been loaded, unless [`Core::supports_runahead`] returns `false`.
+    ///
+    /// This is the place to prepare for frequent serialization, e.g. by allocating buffers.
+    /// See also [`SerializeContext::save_state_context`].
+    fn on_runahead_started(&mut self, _ctx: &mut GenericContext) {
+        // Do nothing
+    }
+
+    /// Returns the [`DiskControl`] implementation of this core, if any.
+    ///
+    /// Cores supporting content that spans multiple images (e.g. multi-disc games)
+    /// should return [`Some`] here.
+    ///
+    /// Gets queried during the first call to `retro_set_environment`, which registers
+    /// the extended disk control interface if the frontend supports it, or the original one otherwise.
+    fn disk_control(&mut self) -> Option<&mut dyn DiskControl> {
+        None
+    }
+
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::set_eject_state`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::set_eject_state` and return it from `Core::disk_control`"
+    )]
     fn on_set_eject_state(&mut self, _ejected: bool) -> bool {
         false
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::get_eject_state`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::get_eject_state` and return it from `Core::disk_control`"
+    )]
     fn on_get_eject_state(&mut self) -> bool {
         false
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::get_image_index`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::get_image_index` and return it from `Core::disk_control`"
+    )]
     fn on_get_image_index(&mut self) -> u32 {
         0
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::set_image_index`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::set_image_index` and return it from `Core::disk_control`"
+    )]
     fn on_set_image_index(&mut self, _index: u32) -> bool {
         false
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::get_num_images`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::get_num_images` and return it from `Core::disk_control`"
+    )]
     fn on_get_num_images(&mut self) -> u32 {
         0
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::replace_image_index`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::replace_image_index` and return it from `Core::disk_control`"
+    )]
     fn on_replace_image_index(&mut self, _index: u32, _info: *const retro_game_info) -> bool {
         false
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::add_image_index`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::add_image_index` and return it from `Core::disk_control`"
+    )]
     fn on_add_image_index(&mut self) -> bool {
         false
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::set_initial_image`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::set_initial_image` and return it from `Core::disk_control`"
+    )]
     fn on_set_initial_image(&mut self, _index: u32, _path: &CStr) -> bool {
         false
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::get_image_path`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::get_image_path` and return it from `Core::disk_control`"
+    )]
     fn on_get_image_path(&mut self, _index: u32) -> Option<CString> {
         None
     }
 
-    /// **TODO:** Documentation
+    /// Only called if [`Core::disk_control`] returns [`None`].
+    ///
+    /// Implement [`DiskControl::get_image_label`] instead.
+    #[deprecated(
+        note = "implement `DiskControl::get_image_label` and return it from `Core::disk_control`"
+    )]
     fn on_get_image_label(&mut self, _index: u32) -> Option<CString> {
         None
     }
 
-    /// **TODO:** Documentation
-    fn on_core_options_update_display(&mut self) -> bool {
+    /// Called when the frontend is about to display the core options menu,
+    /// or after the user changed an option inside of it.
+    ///
+    /// This is the place to update the visibility of options that depend on the values
+    /// of other options, using [`OptionsChangedContext::set_variable_visible`].
+    /// Return `true` if the visibility of any option changed, so the frontend refreshes its menu.
+    ///
+    /// The callback gets registered automatically during the first call to `retro_set_environment`.
+    fn on_core_options_update_display(&mut self, _ctx: &mut OptionsChangedContext) -> bool {
         false
     }
 }
+
+/// Allows the frontend to swap disk images, e.g. for multi-disc games.
+///
+/// Return an implementation from [`Core::disk_control`] to have it registered with the frontend.
+/// Cores that only provide it later on have to register it themselves using
+/// [`GenericContext::enable_extended_disk_control_interface`] (or
+/// [`GenericContext::enable_disk_control_interface`] for frontends that
+/// only support the original interface).
+///
+/// Image indices are zero based.
+pub trait DiskControl {
+    /// Sets the state of the virtual disk tray.
+    /// If `ejected` is `true`, the disk tray is open.
+    ///
+    /// Returns `true` on success.
+    fn set_eject_state(&mut self, ejected: bool) -> bool;
+
+    /// Returns `true` if the virtual disk tray is currently open.
+    fn get_eject_state(&mut self) -> bool;
+
+    /// Returns the index of the currently inserted disk image.
+    ///
+    /// Returns [`DiskControl::get_num_images`] if no disk is currently inserted.
+    fn get_image_index(&mut self) -> u32;
+
+    /// Sets the disk image to be inserted once the tray gets closed.
+    /// Can only be called while the tray is open.
+    ///
+    /// Setting an index of [`DiskControl::get_num_images`] removes the disk.
+    fn set_image_index(&mut self, index: u32) -> bool;
+
+    /// Returns the total number of disk images available.
+    fn get_num_images(&mut self) -> u32;
+
+    /// Replaces the disk image at `index` with `info`.
+    ///
+    /// If `info` is [`None`], the image gets removed from the internal list,
+    /// shifting all following images down by one.
+    ///
+    /// Can only be called while the tray is open.
+    fn replace_image_index(&mut self, index: u32, info: Option<&retro_game_info>) -> bool;
+
+    /// Adds a new empty disk image slot at the end of the list.
+    /// The frontend follows up with [`DiskControl::replace_image_index`] to fill it.
+    ///
+    /// Can only be called while the tray is open.
+    fn add_image_index(&mut self) -> bool;
+
+    /// Called before [`Core::on_load_game`] to tell the core which disk image
+    /// should be inserted initially.
+    /// `path` is used to verify that the image list did not change since the index was saved.
+    ///
+    /// Only called by frontends supporting the extended disk control interface.
+    fn set_initial_image(&mut self, _index: u32, _path: &CStr) -> bool {
+        false
+    }
+
+    /// Writes the path of the disk image at `index` into `path`.
+    ///
+    /// Use [`util::copy_str_to_buffer`] to fill the buffer.
+    /// Return [`None`] if there is no path for this index.
+    ///
+    /// Only called by frontends supporting the extended disk control interface.
+    fn get_image_path(&mut self, _index: u32, _path: &mut [c_char]) -> Option<CopiedString> {
+        None
+    }
+
+    /// Writes a human readable label of the disk image at `index` into `label`.
+    ///
+    /// Use [`util::copy_str_to_buffer`] to fill the buffer.
+    /// Return [`None`] if there is no label for this index.
+    ///
+    /// Only called by frontends supporting the extended disk control interface.
+    fn get_image_label(&mut self, _index: u32, _label: &mut [c_char]) -> Option<CopiedString> {
+        None
+    }
+}
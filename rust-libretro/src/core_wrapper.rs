@@ -2,7 +2,11 @@
 //!
 //! It stores runtime information provided by the libretro frontend without interfering with your [`Core`] implementation.
 use crate::*;
-use std::sync::{Arc, RwLock};
+use std::{
+    cell::{Cell, UnsafeCell},
+    ops::{Deref, DerefMut},
+    sync::{Arc, RwLock},
+};
 
 pub type Interfaces = Arc<RwLock<InterfaceList>>;
 
@@ -12,6 +16,56 @@ pub struct InterfaceList {
     pub location_interface: Option<retro_location_callback>,
     pub perf_interface: PerfCounters,
     pub rumble_interface: Option<retro_rumble_interface>,
+    pub hw_render_callback: Option<retro_hw_render_callback>,
+
+    /// Descriptions referenced by the input descriptors the frontend was given.
+    pub input_descriptions: Vec<CString>,
+
+    /// The controller info the frontend was given.
+    pub(crate) controller_info: RawControllerInfo,
+
+    /// Extensions referenced by the content info overrides the frontend was given.
+    pub content_info_override_extensions: Vec<CString>,
+
+    /// The reference frame time passed to [`LoadGameContext::enable_frame_time_callback`].
+    pub frame_time_reference: Option<i64>,
+
+    /// The pixel format accepted by the frontend, if one has been set.
+    pub pixel_format: Option<PixelFormat>,
+
+    /// Set by [`RunContext::skip_serialization`] to fail the next call to `retro_serialize`.
+    pub skip_serialization: bool,
+
+    /// The cheats set by the frontend.
+    pub cheats: CheatManager,
+
+    /// Set by [`GenericContext::set_keep_last_frame`] to keep a copy of every frame,
+    /// even if the frontend can dupe frames by itself.
+    pub keep_last_frame: bool,
+
+    /// Set once the frontend asks for a save state for rollback netplay,
+    /// see [`NetplayContext::is_netplay_active`].
+    pub netplay_active: bool,
+
+    /// Set once the frontend asks for a save state for runahead,
+    /// see [`Core::on_runahead_started`].
+    pub runahead_active: bool,
+
+    /// The number of frames run since the game has been loaded or reset,
+    /// see [`RunContext::frame_count`].
+    pub frame_count: u64,
+
+    /// The frame time in microseconds reported for the current call of [`Core::on_run`].
+    pub frame_delta: Option<i64>,
+
+    /// Queried in `retro_init`, see [`GenericContext::capabilities`].
+    pub capabilities: Capabilities,
+
+    /// Set by [`GenericContext::refresh_system_info`].
+    pub system_info_outdated: bool,
+
+    /// Set by [`LoadGameContext::set_save_ram`], answers requests for [`MemoryId::SaveRam`].
+    pub save_ram: Option<SaveRam>,
 
     #[unstable(feature = "env-commands")]
     pub camera_interface: Option<retro_camera_callback>,
@@ -28,11 +82,29 @@ pub struct InterfaceList {
     #[unstable(feature = "env-commands")]
     pub vfs_interface_info: VfsInterfaceInfo,
 
+    /// Address space names referenced by the memory maps the frontend was given.
+    #[unstable(feature = "env-commands")]
+    pub memory_map_addrspaces: Vec<CString>,
+
     #[unstable(feature = "env-commands")]
     pub hw_render_context_negotiation_interface:
         Option<Box<dyn HwRenderContextNegotiationInterface>>,
 }
 
+impl InterfaceList {
+    pub(crate) fn available(&self) -> AvailableInterfaces {
+        AvailableInterfaces {
+            rumble: self.rumble_interface.is_some(),
+            sensor: self.sensor_interface.is_some(),
+            camera: self.camera_interface.is_some(),
+            location: self.location_interface.is_some(),
+            perf: self.perf_interface.interface.is_some(),
+            led: self.led_interface.is_some(),
+            midi: self.midi_interface.is_some(),
+        }
+    }
+}
+
 /// Holds the core instance as well as runtime information from the libretro frontend.
 ///
 /// This struct gets used in the code generated by the [`retro_core!()`]-macro.
@@ -80,13 +152,23 @@ pub(crate) struct CoreWrapper {
     pub(crate) last_width: u32,
     pub(crate) last_height: u32,
     pub(crate) last_pitch: usize,
+    pub(crate) last_frame: Vec<u8>,
 
     pub(crate) supports_bitmasks: bool,
 
     pub(crate) frame_delta: Option<i64>,
 
+    /// The serialization quirks accepted by the frontend.
+    pub(crate) serialization_quirks: SerializationQuirks,
+
+    /// The save state size, unless the [`Core`] uses [`SerializationQuirks::CORE_VARIABLE_SIZE`].
+    pub(crate) serialize_size: Option<usize>,
+
     pub(crate) interfaces: Interfaces,
 
+    /// The subsystems registered with the frontend.
+    pub(crate) subsystem_info: Option<RawSubsystemInfo>,
+
     /// The wrapped [`Core`] implementation.
     pub(crate) core: Box<dyn Core>,
 }
@@ -107,15 +189,20 @@ impl CoreWrapper {
             input_state_callback: None,
 
             interfaces: Arc::new(RwLock::new(InterfaceList::default())),
+            subsystem_info: None,
 
             can_dupe: false,
             had_frame: false,
             last_width: 0,
             last_height: 0,
             last_pitch: 0,
+            last_frame: Vec::new(),
 
             frame_delta: None,
 
+            serialization_quirks: SerializationQuirks::empty(),
+            serialize_size: None,
+
             supports_bitmasks: false,
         }
     }
@@ -145,3 +232,172 @@ impl CoreWrapper {
         self.input_state_callback = arg1;
     }
 }
+
+/// A cell for global state that gets accessed from the `extern "C"` functions called by the frontend.
+///
+/// The libretro API is single-threaded: a frontend never calls into a core from more than
+/// one thread at a time. This invariant is what makes sharing the cell between threads sound,
+/// it must not be accessed from any thread spawned by the core.
+///
+/// Calls may be re-entrant though. While the core is in a call to the frontend, the frontend
+/// may call back into the core on the same thread, e.g. `retro_hw_context_reset_callback` from
+/// within `RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO`, or the core options update display callback
+/// from within `RETRO_ENVIRONMENT_SET_VARIABLE`. Such a nested call must not create a second
+/// mutable reference to the contents, so [`SingleThreadCell::borrow_mut`] refuses it while the
+/// outer call holds the cell. Nested calls that only notify the core can be postponed with
+/// [`SingleThreadCell::defer`] instead, they run once the outer call releases the cell.
+///
+/// The only exception to the single thread is the audio callback, which the frontend may call
+/// from its audio thread, see [`Core::on_write_audio`]. As with the `static mut`
+/// the cell replaced, cores that enable it have to synchronize the state it touches themselves.
+///
+/// `Sync` is implemented without requiring `T: Send`: the cell holds the [`Core`], which
+/// is not required to be [`Send`] so cores can keep `Rc`s or graphics API handles around,
+/// and [`InterfaceList`] holds raw pointers handed out by the frontend.
+/// Apart from the audio callback above, the contents never leave the frontend's thread.
+pub(crate) struct SingleThreadCell<T> {
+    value: UnsafeCell<T>,
+    state: BorrowState,
+}
+
+/// Tracks whether a [`SingleThreadCell`] is borrowed and which calls wait for it.
+struct BorrowState {
+    borrowed: Cell<bool>,
+    deferred: UnsafeCell<Vec<Box<dyn FnOnce()>>>,
+}
+
+impl BorrowState {
+    /// Ends the current borrow and runs the calls deferred in the meantime.
+    fn release(&self) {
+        self.borrowed.set(false);
+
+        // Deferred calls may borrow the cell and defer further calls themselves
+        loop {
+            // SAFETY: The queue is only accessed from the frontend's thread and never kept borrowed.
+            let deferred = std::mem::take(unsafe { &mut *self.deferred.get() });
+
+            if deferred.is_empty() {
+                break;
+            }
+
+            for f in deferred {
+                f();
+            }
+        }
+    }
+}
+
+// SAFETY: See the invariant documented on `SingleThreadCell`.
+unsafe impl<T> Sync for SingleThreadCell<T> {}
+
+impl<T> SingleThreadCell<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+            state: BorrowState {
+                borrowed: Cell::new(false),
+                deferred: UnsafeCell::new(Vec::new()),
+            },
+        }
+    }
+
+    /// Borrows the contained value until the returned guard gets dropped.
+    ///
+    /// Returns [`None`] if the value is already borrowed, i.e. for a nested call.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called from the thread the frontend calls into the core on.
+    pub(crate) unsafe fn borrow_mut(&self) -> Option<SingleThreadRefMut<'_, T>> {
+        if self.state.borrowed.replace(true) {
+            return None;
+        }
+
+        Some(SingleThreadRefMut {
+            value: &mut *self.value.get(),
+            state: &self.state,
+        })
+    }
+
+    /// Returns whether the value is currently borrowed by [`SingleThreadCell::borrow_mut`].
+    pub(crate) fn is_borrowed(&self) -> bool {
+        self.state.borrowed.get()
+    }
+
+    /// Runs `f` once the current borrow of the value ends, or right away if there is none.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called from the thread the frontend calls into the core on.
+    pub(crate) unsafe fn defer(&self, f: impl FnOnce() + 'static) {
+        if !self.is_borrowed() {
+            return f();
+        }
+
+        (*self.state.deferred.get()).push(Box::new(f));
+    }
+
+    /// Returns a mutable reference to the contained value without borrowing it.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called from the thread the frontend calls into the core on,
+    /// and not while the value is borrowed by [`SingleThreadCell::borrow_mut`].
+    /// The returned reference must not be kept around across anything that may
+    /// access the cell again, in particular calls into the frontend or the [`Core`].
+    #[allow(clippy::mut_from_ref)]
+    #[inline(always)]
+    pub(crate) unsafe fn get_mut(&self) -> &mut T {
+        &mut *self.value.get()
+    }
+}
+
+/// The value of a [`SingleThreadCell`] borrowed by [`SingleThreadCell::borrow_mut`].
+///
+/// Dropping it releases the cell and runs the calls deferred in the meantime.
+pub(crate) struct SingleThreadRefMut<'a, T> {
+    value: &'a mut T,
+    state: &'a BorrowState,
+}
+
+impl<'a, T> SingleThreadRefMut<'a, T> {
+    /// Narrows the borrow down to a part of the value, releasing it if `f` returns [`None`].
+    pub(crate) fn filter_map<U>(
+        self,
+        f: impl FnOnce(&mut T) -> Option<&mut U>,
+    ) -> Option<SingleThreadRefMut<'a, U>> {
+        let this = std::mem::ManuallyDrop::new(self);
+        let state = this.state;
+
+        // SAFETY: `this` is never used or dropped again, so the borrow moves to the new guard.
+        let value = unsafe { std::ptr::read(&this.value) };
+
+        match f(value) {
+            Some(value) => Some(SingleThreadRefMut { value, state }),
+            None => {
+                state.release();
+                None
+            }
+        }
+    }
+}
+
+impl<T> Deref for SingleThreadRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for SingleThreadRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<T> Drop for SingleThreadRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.state.release();
+    }
+}
@@ -4,16 +4,54 @@
 
 use super::{types::*, *};
 
+/// Errors returned by the environment helpers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EnvironmentError {
+    /// The frontend does not support the command, or the call failed.
+    ///
+    /// The libretro API does not distinguish between these two cases,
+    /// cores should fall back to a sensible default.
+    Unsupported,
+
+    /// The environment callback has not been set (yet).
+    NullCallback,
+
+    /// The data passed to or returned by the frontend is invalid,
+    /// e.g. a string containing a NUL byte or a NULL pointer where data was expected.
+    InvalidData,
+}
+
+impl std::fmt::Display for EnvironmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported => write!(f, "The environment command is not supported"),
+            Self::NullCallback => write!(f, "The environment callback has not been set"),
+            Self::InvalidData => write!(f, "The environment call got invalid data"),
+        }
+    }
+}
+
+impl std::error::Error for EnvironmentError {}
+
+impl From<std::ffi::NulError> for EnvironmentError {
+    fn from(_: std::ffi::NulError) -> Self {
+        Self::InvalidData
+    }
+}
+
 /// Gets a value from an environment callback.
-///
-/// The first value of the return type is the queried data,
-/// the second value is the return value of the callback itself.
-pub unsafe fn get<T: Default>(callback: retro_environment_t, id: u32) -> Option<(T, bool)> {
+pub unsafe fn get<T: Default>(
+    callback: retro_environment_t,
+    id: u32,
+) -> Result<T, EnvironmentError> {
     get_mut(callback, id, Default::default())
 }
 
-/// Similar to [`get`] but uses uninitialized memory instead of the [`Default`] trait.
-pub unsafe fn get_unchecked<T>(callback: retro_environment_t, id: u32) -> Option<(T, bool)> {
+/// Similar to [`get`] but uses zeroed memory instead of the [`Default`] trait.
+pub unsafe fn get_unchecked<T>(
+    callback: retro_environment_t,
+    id: u32,
+) -> Result<T, EnvironmentError> {
     let data = std::mem::MaybeUninit::zeroed().assume_init();
 
     get_mut(callback, id, data)
@@ -21,60 +59,78 @@ pub unsafe fn get_unchecked<T>(callback: retro_environment_t, id: u32) -> Option
 
 /// Passes a value to the environment callback and returns the modified value.
 ///
-/// The second value is the return value of the callback itself.
-pub unsafe fn get_mut<T>(callback: retro_environment_t, id: u32, mut data: T) -> Option<(T, bool)> {
-    if let Some(callback) = callback {
-        if (callback as *const c_void).is_null() {
-            panic!("Expected environment callback, got NULL pointer instead!");
-        }
-
-        let status = (callback)(id, (&mut data as *mut _) as *mut c_void);
+/// Returns [`EnvironmentError::Unsupported`] if the callback returned `false`.
+pub unsafe fn get_mut<T>(
+    callback: retro_environment_t,
+    id: u32,
+    mut data: T,
+) -> Result<T, EnvironmentError> {
+    set_ptr(callback, id, &mut data as *mut T)?;
 
-        Some((data, status))
-    } else {
-        None
-    }
+    Ok(data)
 }
 
 /// Helper function to query a string pointer and convert it into a [`Path`].
-pub unsafe fn get_path<'a>(callback: retro_environment_t, id: u32) -> Option<&'a Path> {
-    let ptr: *mut c_void = std::ptr::null_mut();
+///
+/// Returns `Ok(None)` if the frontend returned a `NULL` pointer or an empty string.
+pub unsafe fn get_path<'a>(
+    callback: retro_environment_t,
+    id: u32,
+) -> Result<Option<&'a Path>, EnvironmentError> {
+    let ptr: *const c_char = get_mut(callback, id, std::ptr::null())?;
 
-    if let Some((ptr, _)) = get_mut(callback, id, ptr) {
-        return get_path_from_pointer(ptr as *const c_char);
+    Ok(get_path_from_pointer(ptr).filter(|path| !path.as_os_str().is_empty()))
+}
+
+/// Helper function to query a string pointer and convert it into a [`str`].
+///
+/// Returns `Ok(None)` if the frontend returned a `NULL` pointer and
+/// [`EnvironmentError::InvalidData`] if the string is not valid UTF-8.
+pub unsafe fn get_str<'a>(
+    callback: retro_environment_t,
+    id: u32,
+) -> Result<Option<&'a str>, EnvironmentError> {
+    let ptr: *const c_char = get_mut(callback, id, std::ptr::null())?;
+
+    if ptr.is_null() {
+        return Ok(None);
     }
 
-    None
+    get_str_from_pointer(ptr)
+        .map(Some)
+        .ok_or(EnvironmentError::InvalidData)
 }
 
 /// Passes a value to the environment callback.
 ///
-/// Returns [`None`] if the environment callback hasn’t been set
-/// and the return status of the callback otherwise.
+/// Returns [`EnvironmentError::Unsupported`] if the callback returned `false`.
 pub unsafe fn set<T: std::fmt::Debug>(
     callback: retro_environment_t,
     id: u32,
     value: T,
-) -> Option<bool> {
+) -> Result<(), EnvironmentError> {
     set_ptr(callback, id, &value as *const _)
 }
 
 /// Passes a value (by a raw const pointer) to the environment callback.
 ///
-/// Returns [`None`] if the environment callback hasn’t been set
-/// and the return status of the callback otherwise.
-pub unsafe fn set_ptr<T>(callback: retro_environment_t, id: u32, ptr: *const T) -> Option<bool> {
-    if let Some(callback) = callback {
-        if (callback as *const c_void).is_null() {
-            panic!("Expected environment callback, got NULL pointer instead!");
-        }
-
-        let status = (callback)(id, ptr as *mut c_void);
+/// Returns [`EnvironmentError::Unsupported`] if the callback returned `false`.
+pub unsafe fn set_ptr<T>(
+    callback: retro_environment_t,
+    id: u32,
+    ptr: *const T,
+) -> Result<(), EnvironmentError> {
+    let callback = callback.ok_or(EnvironmentError::NullCallback)?;
 
-        return Some(status);
+    if (callback as *const c_void).is_null() {
+        return Err(EnvironmentError::NullCallback);
     }
 
-    None
+    if (callback)(id, ptr as *mut c_void) {
+        Ok(())
+    } else {
+        Err(EnvironmentError::Unsupported)
+    }
 }
 
 /* ========================================================================== *\
@@ -82,15 +138,21 @@ pub unsafe fn set_ptr<T>(callback: retro_environment_t, id: u32, ptr: *const T)
 \* ========================================================================== */
 
 /// Sets screen rotation of graphics.
+///
+/// Vertical games should call this in [`Core::on_load_game`] once their orientation is known.
+/// Returns an error if the frontend cannot rotate the output,
+/// in which case the core has to rotate its frames itself.
 #[proc::context(GenericContext)]
-pub unsafe fn set_rotation(callback: retro_environment_t, rotation: Rotation) -> bool {
+pub unsafe fn set_rotation(
+    callback: retro_environment_t,
+    rotation: Rotation,
+) -> Result<(), EnvironmentError> {
     // const unsigned *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_ROTATION,
         rotation.get_env_value(),
     )
-    .unwrap_or(false)
 }
 
 /// Boolean value whether or not the implementation should use overscan,
@@ -99,21 +161,17 @@ pub unsafe fn set_rotation(callback: retro_environment_t, rotation: Rotation) ->
     note = "This function is considered deprecated in favor of using core options to manage overscan in a more nuanced, core-specific way"
 )]
 #[proc::context(GenericContext)]
-pub unsafe fn get_overscan(callback: retro_environment_t) -> bool {
+pub unsafe fn get_overscan(callback: retro_environment_t) -> Result<bool, EnvironmentError> {
     // bool *
     get(callback, RETRO_ENVIRONMENT_GET_OVERSCAN)
-        .map(|(v, _)| v)
-        .unwrap_or(false)
 }
 
 /// Boolean value whether or not frontend supports frame duping,
 /// passing NULL to video frame callback.
 #[proc::context(GenericContext)]
-pub unsafe fn can_dupe(callback: retro_environment_t) -> bool {
+pub unsafe fn can_dupe(callback: retro_environment_t) -> Result<bool, EnvironmentError> {
     // bool *
     get(callback, RETRO_ENVIRONMENT_GET_CAN_DUPE)
-        .map(|(v, _)| v)
-        .unwrap_or(false)
 }
 
 /// Sets a message to be displayed in implementation-specific manner
@@ -121,17 +179,14 @@ pub unsafe fn can_dupe(callback: retro_environment_t) -> bool {
 /// Should not be used for trivial messages, which should simply be
 /// logged via [`RETRO_ENVIRONMENT_GET_LOG_INTERFACE`] (or as a
 /// fallback, stderr).
-#[proc::context(GenericContext)]
-pub unsafe fn set_message(callback: retro_environment_t, message: &str, frames: u32) -> bool {
-    let msg = match CString::new(message) {
-        Ok(message) => message,
-        Err(err) => {
-            #[cfg(feature = "log")]
-            log::error!("{}", err);
-
-            return false;
-        }
-    };
+///
+/// See also [`GenericContext::set_message`].
+pub unsafe fn set_message(
+    callback: retro_environment_t,
+    message: &str,
+    frames: u32,
+) -> Result<(), EnvironmentError> {
+    let msg = CString::new(message)?;
 
     // const struct retro_message *
     set(
@@ -142,20 +197,20 @@ pub unsafe fn set_message(callback: retro_environment_t, message: &str, frames:
             frames,
         },
     )
-    .unwrap_or(false)
 }
 
 /// Requests the frontend to shutdown.
 /// Should only be used if game has a specific
 /// way to shutdown the game from a menu item or similar.
-#[proc::context(GenericContext)]
-pub unsafe fn shutdown(callback: retro_environment_t) {
+///
+/// See also [`GenericContext::shutdown`].
+pub unsafe fn shutdown(callback: retro_environment_t) -> Result<(), EnvironmentError> {
     // N/A (NULL)
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SHUTDOWN,
         std::ptr::null() as *const c_void,
-    );
+    )
 }
 
 /// Gives a hint to the frontend how demanding this implementation
@@ -171,15 +226,26 @@ pub unsafe fn shutdown(callback: retro_environment_t) {
 /// This function can be called on a per-game basis,
 /// as certain games an implementation can play might be
 /// particularly demanding.
+///
+/// There is no fixed scale, what a level means is up to the frontend.
+/// As a rough guide:
+/// - `0` or `1`: runs on about any device, e.g. cores for 8-bit systems.
+/// - `2` to `4`: needs a mid-range phone or handheld, e.g. cores for 16-bit and 2D 32-bit systems.
+/// - `5` and up: needs a desktop-class CPU, e.g. cores emulating 3D hardware.
+///
+/// Usually called once from [`Core::on_set_environment`] or [`Core::on_load_game`].
+#[proc::context(SetEnvironmentContext)]
 #[proc::context(LoadGameContext)]
-pub unsafe fn set_performance_level(callback: retro_environment_t, level: u8) -> bool {
+pub unsafe fn set_performance_level(
+    callback: retro_environment_t,
+    level: u8,
+) -> Result<(), EnvironmentError> {
     // const unsigned *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL,
         level as u32,
     )
-    .unwrap_or(false)
 }
 
 /// Returns the "system" directory of the frontend.
@@ -193,8 +259,11 @@ pub unsafe fn set_performance_level(callback: retro_environment_t, level: u8) ->
 /// memory cards, etc, for lack of a better place to put it.
 /// This is now discouraged, and if possible, cores should try to
 /// use the new [`get_save_directory()`].
-#[proc::context(GenericContext)]
-pub unsafe fn get_system_directory<'a>(callback: retro_environment_t) -> Option<&'a Path> {
+///
+/// See also [`GenericContext::get_system_directory`].
+pub unsafe fn get_system_directory<'a>(
+    callback: retro_environment_t,
+) -> Result<Option<&'a Path>, EnvironmentError> {
     // const char **
     get_path(callback, RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY)
 }
@@ -202,16 +271,16 @@ pub unsafe fn get_system_directory<'a>(callback: retro_environment_t) -> Option<
 /// Sets the internal pixel format used by the implementation.
 /// The default pixel format is [`retro_pixel_format::RETRO_PIXEL_FORMAT_0RGB1555`].
 /// This pixel format however, is deprecated (see enum [`retro_pixel_format`]).
-/// If the call returns `false`, the frontend does not support this pixel
+/// If the call returns an error, the frontend does not support this pixel
 /// format.
-#[proc::context(LoadGameContext)]
-#[proc::context(GetAvInfoContext)]
+///
+/// See also [`LoadGameContext::set_pixel_format`].
 pub unsafe fn set_pixel_format<F: Into<retro_pixel_format>>(
     callback: retro_environment_t,
     format: F,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const enum retro_pixel_format *
-    set(callback, RETRO_ENVIRONMENT_SET_PIXEL_FORMAT, format.into()).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_PIXEL_FORMAT, format.into())
 }
 
 /// Sets an array of retro_input_descriptors.
@@ -224,14 +293,13 @@ pub unsafe fn set_pixel_format<F: Into<retro_pixel_format>>(
 pub unsafe fn set_input_descriptors(
     callback: retro_environment_t,
     descriptors: &[retro_input_descriptor],
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_input_descriptor *
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
         descriptors.as_ptr(),
     )
-    .unwrap_or(false)
 }
 
 /// Sets a callback function used to notify core about keyboard events.
@@ -239,9 +307,9 @@ pub unsafe fn set_input_descriptors(
 pub unsafe fn set_keyboard_callback(
     callback: retro_environment_t,
     data: retro_keyboard_callback,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_keyboard_callback *
-    set(callback, RETRO_ENVIRONMENT_SET_KEYBOARD_CALLBACK, data).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_KEYBOARD_CALLBACK, data)
 }
 
 /// Sets an interface which frontend can use to eject and insert
@@ -252,9 +320,9 @@ pub unsafe fn set_keyboard_callback(
 pub unsafe fn set_disk_control_interface(
     callback: retro_environment_t,
     data: retro_disk_control_callback,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_disk_control_callback *
-    set(callback, RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE, data).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE, data)
 }
 
 /// Sets an interface to let a libretro core render with
@@ -266,10 +334,16 @@ pub unsafe fn set_disk_control_interface(
 /// max_width/max_height provided in [`Core::on_get_av_info`].
 /// If HW rendering is used, call either
 /// [`RunContext::draw_hardware_frame`] or [`RunContext::dupe_frame`].
-#[proc::context(LoadGameContext)]
-pub unsafe fn set_hw_render(callback: retro_environment_t, data: retro_hw_render_callback) -> bool {
+///
+/// On success the frontend fills in [`retro_hw_render_callback::get_current_framebuffer`]
+/// and [`retro_hw_render_callback::get_proc_address`], the updated struct gets returned.
+/// See [`LoadGameContext::set_hw_render`] for a version that keeps it for later use.
+pub unsafe fn set_hw_render(
+    callback: retro_environment_t,
+    data: retro_hw_render_callback,
+) -> Result<retro_hw_render_callback, EnvironmentError> {
     // struct retro_hw_render_callback *
-    set(callback, RETRO_ENVIRONMENT_SET_HW_RENDER, data).unwrap_or(false)
+    get_mut(callback, RETRO_ENVIRONMENT_SET_HW_RENDER, data)
 }
 
 /// Interface to acquire user-defined information from environment
@@ -278,20 +352,16 @@ pub unsafe fn set_hw_render(callback: retro_environment_t, data: retro_hw_render
 /// The `key` should be set to a key which has already been set by
 /// [`set_variables`] or [`set_core_options`].
 ///
-/// Returns [`None`] if the variable could not be found.
-#[proc::context(GenericContext)]
-#[proc::context(OptionsChangedContext)]
-#[allow(clippy::needless_lifetimes)]
-pub unsafe fn get_variable<'a>(callback: retro_environment_t, key: &'a str) -> Option<&'a str> {
-    let key = match CString::new(key) {
-        Ok(key) => key,
-        Err(err) => {
-            #[cfg(feature = "log")]
-            log::error!("{}", err);
-
-            return None;
-        }
-    };
+/// Returns `Ok(None)` if the variable could not be found.
+///
+/// The returned string is owned by the frontend and only valid until the next environment call.
+///
+/// See also [`GenericContext::get_variable`] and [`OptionsChangedContext::get_variable`].
+pub unsafe fn get_variable<'a>(
+    callback: retro_environment_t,
+    key: &str,
+) -> Result<Option<&'a str>, EnvironmentError> {
+    let key = CString::new(key)?;
 
     let var = retro_variable {
         key: key.as_ptr(),
@@ -299,13 +369,15 @@ pub unsafe fn get_variable<'a>(callback: retro_environment_t, key: &'a str) -> O
     };
 
     // struct retro_variable *
-    if let Some((var, _)) = get_mut(callback, RETRO_ENVIRONMENT_GET_VARIABLE, var) {
-        if !var.value.is_null() {
-            return get_str_from_pointer(var.value as *const c_char);
-        }
+    let var = get_mut(callback, RETRO_ENVIRONMENT_GET_VARIABLE, var)?;
+
+    if var.value.is_null() {
+        return Ok(None);
     }
 
-    None
+    get_str_from_pointer(var.value)
+        .map(Some)
+        .ok_or(EnvironmentError::InvalidData)
 }
 
 /// Allows an implementation to signal the environment
@@ -360,34 +432,40 @@ pub unsafe fn get_variable<'a>(callback: retro_environment_t, key: &'a str) -> O
 /// Only strings are operated on. The possible values will
 /// generally be displayed and stored as-is by the frontend.
 #[proc::context(SetEnvironmentContext)]
-pub unsafe fn set_variables(callback: retro_environment_t, variables: &[retro_variable]) -> bool {
+pub unsafe fn set_variables(
+    callback: retro_environment_t,
+    variables: &[retro_variable],
+) -> Result<(), EnvironmentError> {
     // const struct retro_variable *
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_VARIABLES,
         variables.as_ptr(),
     )
-    .unwrap_or(false)
 }
 
 /// Result is set to [`true`] if some variables are updated by
 /// frontend since last call to [`get_variable`].
 #[proc::context(GenericContext)]
-pub unsafe fn get_variable_update(callback: retro_environment_t) -> bool {
+pub unsafe fn get_variable_update(callback: retro_environment_t) -> Result<bool, EnvironmentError> {
     // bool *
     get(callback, RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE)
-        .map(|(v, _)| v)
-        .unwrap_or(false)
 }
 
 /// Tell the frontend whether this Core can run without particular game data.
 ///
 /// If true, the [`Core`] implementation supports calls to
 /// [`Core::on_load_game`] with [`None`] as argument.
+///
+/// Frontends only offer to start a core without content if this has been
+/// set in [`Core::on_set_environment`].
 #[proc::context(SetEnvironmentContext)]
-pub unsafe fn set_support_no_game(callback: retro_environment_t, value: bool) -> bool {
+pub unsafe fn set_support_no_game(
+    callback: retro_environment_t,
+    value: bool,
+) -> Result<(), EnvironmentError> {
     // const bool *
-    set(callback, RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME, value).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME, value)
 }
 
 /// Retrieves the absolute path from where this libretro
@@ -397,8 +475,11 @@ pub unsafe fn set_support_no_game(callback: retro_environment_t, value: bool) ->
 /// determined.
 /// Mostly useful in cooperation with [`set_support_no_game`] as assets can
 /// be loaded without ugly hacks.
-#[proc::context(GenericContext)]
-pub unsafe fn get_libretro_path<'a>(callback: retro_environment_t) -> Option<&'a Path> {
+///
+/// See also [`GenericContext::get_libretro_path`].
+pub unsafe fn get_libretro_path<'a>(
+    callback: retro_environment_t,
+) -> Result<Option<&'a Path>, EnvironmentError> {
     // const char **
     get_path(callback, RETRO_ENVIRONMENT_GET_LIBRETRO_PATH)
 }
@@ -414,9 +495,9 @@ pub unsafe fn get_libretro_path<'a>(callback: retro_environment_t) -> Option<&'a
 pub unsafe fn set_frame_time_callback(
     callback: retro_environment_t,
     data: retro_frame_time_callback,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_frame_time_callback *
-    set(callback, RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK, data).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK, data)
 }
 
 /// Sets an interface which is used to notify a libretro core about audio
@@ -447,9 +528,9 @@ pub unsafe fn set_frame_time_callback(
 pub unsafe fn set_audio_callback(
     callback: retro_environment_t,
     data: retro_audio_callback,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_audio_callback *
-    set(callback, RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK, data).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK, data)
 }
 
 /// Gets an interface which is used by a libretro core to set
@@ -458,14 +539,14 @@ pub unsafe fn set_audio_callback(
 /// controlled indepedently.
 /// Should be called from either [`Core::on_init`] or [`Core::on_load_game`].
 /// Should not be called from [`Core::on_set_environment`].
-/// Returns false if rumble functionality is unavailable.
+/// Returns an error if rumble functionality is unavailable.
 #[proc::context(InitContext)]
 #[proc::context(LoadGameContext)]
 pub unsafe fn get_rumble_interface(
     callback: retro_environment_t,
-) -> Option<retro_rumble_interface> {
+) -> Result<retro_rumble_interface, EnvironmentError> {
     // struct retro_rumble_interface *
-    get_unchecked(callback, RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE).map(|(v, _)| v)
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE)
 }
 
 /// Gets a bitmask telling which device type are expected to be
@@ -473,15 +554,16 @@ pub unsafe fn get_rumble_interface(
 /// Devices which are not handled or recognized always return
 /// 0 in [`retro_input_state_t`].
 /// Example bitmask: `RetroDevice::JOYPAD | RetroDevice::ANALOG`.
+///
+/// Useful to only enable features like lightgun aiming if the frontend supports them.
 #[proc::context(RunContext)]
-pub unsafe fn get_input_device_capabilities(callback: retro_environment_t) -> RetroDevice {
+pub unsafe fn get_input_device_capabilities(
+    callback: retro_environment_t,
+) -> Result<InputDeviceCaps, EnvironmentError> {
     // I’m not entirely sure why this call returns a 64 bit value when the `RETRO_DEVICE_MASK` allows only eight distinct types.
     // uint64_t *
-    if let Some((caps, _)) = get::<u64>(callback, RETRO_ENVIRONMENT_GET_INPUT_DEVICE_CAPABILITIES) {
-        return RetroDevice::from_bits_truncate(caps as u8);
-    }
-
-    RetroDevice::NONE
+    get::<u64>(callback, RETRO_ENVIRONMENT_GET_INPUT_DEVICE_CAPABILITIES)
+        .map(|caps| RetroDevice::from_bits_truncate(caps as u8))
 }
 
 /// Gets access to the sensor interface.
@@ -494,9 +576,9 @@ pub unsafe fn get_input_device_capabilities(callback: retro_environment_t) -> Re
 #[proc::unstable(feature = "env-commands")]
 pub unsafe fn get_sensor_interface(
     callback: retro_environment_t,
-) -> Option<retro_sensor_interface> {
+) -> Result<retro_sensor_interface, EnvironmentError> {
     // const struct retro_sensor_interface *
-    get_unchecked(callback, RETRO_ENVIRONMENT_GET_SENSOR_INTERFACE).map(|(v, _)| v)
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_SENSOR_INTERFACE)
 }
 
 /// Gets an interface to a video camera driver.
@@ -525,9 +607,9 @@ pub unsafe fn get_sensor_interface(
 pub unsafe fn get_camera_interface(
     callback: retro_environment_t,
     data: retro_camera_callback,
-) -> Option<retro_camera_callback> {
+) -> Result<retro_camera_callback, EnvironmentError> {
     // struct retro_camera_callback *
-    get_mut(callback, RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE, data).map(|(v, _)| v)
+    get_mut(callback, RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE, data)
 }
 
 /// Gets an interface for logging. This is useful for
@@ -540,33 +622,36 @@ pub unsafe fn get_camera_interface(
 #[proc::context(GenericContext)]
 pub unsafe fn get_log_callback(
     callback: retro_environment_t,
-) -> Result<Option<retro_log_callback>, Box<dyn std::error::Error>> {
+) -> Result<retro_log_callback, EnvironmentError> {
     // struct retro_log_callback *
-    match get_unchecked(callback, RETRO_ENVIRONMENT_GET_LOG_INTERFACE) {
-        Some((callback, true)) => Ok(Some(callback)),
-        _ => Err("Failed to query log callback".into()),
-    }
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_LOG_INTERFACE)
 }
 
 /// Gets an interface for performance counters. This is useful
 /// for performance logging in a cross-platform way and for detecting
 /// architecture-specific features, such as SIMD support.
 #[proc::context(GenericContext)]
-pub unsafe fn get_perf_interface(callback: retro_environment_t) -> Option<retro_perf_callback> {
+pub unsafe fn get_perf_interface(
+    callback: retro_environment_t,
+) -> Result<retro_perf_callback, EnvironmentError> {
     // struct retro_perf_callback *
-    get_unchecked(callback, RETRO_ENVIRONMENT_GET_PERF_INTERFACE).map(|(v, _)| v)
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_PERF_INTERFACE)
 }
 
 /// Gets access to the location interface.
 /// The purpose of this interface is to be able to retrieve
 /// location-based information from the host device,
 /// such as current latitude / longitude.
+///
+/// The `initialized` and `deinitialized` callbacks of `data` are provided by the core,
+/// the remaining functions get filled in by the frontend.
 #[proc::context(GenericContext)]
 pub unsafe fn get_location_callback(
     callback: retro_environment_t,
-) -> Option<retro_location_callback> {
+    data: retro_location_callback,
+) -> Result<retro_location_callback, EnvironmentError> {
     // struct retro_location_callback *
-    get_unchecked(callback, RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE).map(|(v, _)| v)
+    get_mut(callback, RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE, data)
 }
 
 /// Returns the "core assets" directory of the frontend.
@@ -576,8 +661,11 @@ pub unsafe fn get_location_callback(
 /// The returned value can be [`None`].
 /// If so, no such directory is defined,
 /// and it's up to the implementation to find a suitable directory.
-#[proc::context(GenericContext)]
-pub unsafe fn get_core_assets_directory<'a>(callback: retro_environment_t) -> Option<&'a Path> {
+///
+/// See also [`GenericContext::get_core_assets_directory`].
+pub unsafe fn get_core_assets_directory<'a>(
+    callback: retro_environment_t,
+) -> Result<Option<&'a Path>, EnvironmentError> {
     // const char **
     get_path(callback, RETRO_ENVIRONMENT_GET_CORE_ASSETS_DIRECTORY)
 }
@@ -594,8 +682,11 @@ pub unsafe fn get_core_assets_directory<'a>(callback: retro_environment_t) -> Op
 /// NOTE: early libretro cores used the system directory for save
 /// files. Cores that need to be backwards-compatible can still check
 /// [`get_system_directory`].
-#[proc::context(GenericContext)]
-pub unsafe fn get_save_directory<'a>(callback: retro_environment_t) -> Option<&'a Path> {
+///
+/// See also [`GenericContext::get_save_directory`].
+pub unsafe fn get_save_directory<'a>(
+    callback: retro_environment_t,
+) -> Result<Option<&'a Path>, EnvironmentError> {
     // const char **
     get_path(callback, RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY)
 }
@@ -629,15 +720,15 @@ pub unsafe fn get_save_directory<'a>(callback: retro_environment_t) -> Option<&'
 /// use [`set_game_geometry`], which is a softer variant
 /// of [`set_system_av_info`].
 ///
-/// If this returns [`false`], the frontend does not acknowledge a
+/// If this returns an error, the frontend does not acknowledge a
 /// changed [`retro_system_av_info`] struct.
 #[proc::context(RunContext)]
 pub unsafe fn set_system_av_info(
     callback: retro_environment_t,
     av_info: retro_system_av_info,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_system_av_info *
-    set(callback, RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO, av_info).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO, av_info)
 }
 
 /// Allows a libretro core to announce support for the
@@ -652,9 +743,9 @@ pub unsafe fn set_system_av_info(
 pub unsafe fn set_proc_address_callback(
     callback: retro_environment_t,
     data: retro_get_proc_address_interface,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_get_proc_address_interface *
-    set(callback, RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK, data).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK, data)
 }
 
 /// This environment call introduces the concept of libretro "subsystems".
@@ -677,14 +768,13 @@ pub unsafe fn set_proc_address_callback(
 pub unsafe fn set_subsystem_info(
     callback: retro_environment_t,
     data: &[retro_subsystem_info],
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_subsystem_info *
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO,
         data.as_ptr(),
     )
-    .unwrap_or(false)
 }
 
 /// This environment call lets a libretro core tell the frontend
@@ -727,14 +817,13 @@ pub unsafe fn set_subsystem_info(
 pub unsafe fn set_controller_info(
     callback: retro_environment_t,
     data: &[retro_controller_info],
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_controller_info *
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_CONTROLLER_INFO,
         data.as_ptr(),
     )
-    .unwrap_or(false)
 }
 
 /// This environment call lets a libretro core tell the frontend
@@ -747,12 +836,15 @@ pub unsafe fn set_controller_info(
 /// retro_get_memory_* as well.
 ///
 /// Can be called from [`Core::on_init`] and [`Core::on_load_game`].
-#[proc::context(InitContext)]
-#[proc::context(LoadGameContext)]
+///
+/// See also [`GenericContext::set_memory_maps`].
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn set_memory_maps(callback: retro_environment_t, data: retro_memory_map) -> bool {
+pub unsafe fn set_memory_maps(
+    callback: retro_environment_t,
+    data: retro_memory_map,
+) -> Result<(), EnvironmentError> {
     // const struct retro_memory_map *
-    set(callback, RETRO_ENVIRONMENT_SET_MEMORY_MAPS, data).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_MEMORY_MAPS, data)
 }
 
 /// Sets a new game_geometry structure.
@@ -772,13 +864,15 @@ pub unsafe fn set_memory_maps(callback: retro_environment_t, data: retro_memory_
 ///
 /// A frontend must guarantee that this environment call completes in
 /// constant time.
+///
+/// See also [`RunContext::set_geometry`].
 #[proc::context(RunContext)]
 pub unsafe fn set_game_geometry(
     callback: retro_environment_t,
     geometry: retro_game_geometry,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_game_geometry *
-    set(callback, RETRO_ENVIRONMENT_SET_GEOMETRY, geometry).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_GEOMETRY, geometry)
 }
 
 /// Returns the specified username of the frontend, if specified by the user.
@@ -788,34 +882,29 @@ pub unsafe fn set_game_geometry(
 /// If this environment callback is used by a core that requires a valid username,
 /// a default username should be specified by the core.
 #[proc::context(GenericContext)]
-pub unsafe fn get_username<'a>(callback: retro_environment_t) -> Option<&'a str> {
-    let ptr: *mut c_void = std::ptr::null_mut();
-
+pub unsafe fn get_username<'a>(
+    callback: retro_environment_t,
+) -> Result<Option<&'a str>, EnvironmentError> {
     // const char **
-    if let Some((ptr, _)) = get_mut(callback, RETRO_ENVIRONMENT_GET_USERNAME, ptr) {
-        if ptr.is_null() {
-            return None;
-        }
-
-        return get_str_from_pointer(ptr as *const c_char);
-    }
-
-    None
+    get_str(callback, RETRO_ENVIRONMENT_GET_USERNAME)
 }
 
 /// Returns the language of the frontend, if specified by the user.
 /// It can be used by the core for localization purposes.
-#[proc::context(GenericContext)]
-pub unsafe fn get_language(callback: retro_environment_t) -> Option<retro_language> {
+///
+/// See also [`GenericContext::get_language`].
+pub unsafe fn get_language(
+    callback: retro_environment_t,
+) -> Result<retro_language, EnvironmentError> {
     // unsigned *
-    if let Some((id, _)) = get::<u32>(callback, RETRO_ENVIRONMENT_GET_LANGUAGE) {
-        if id < retro_language::RETRO_LANGUAGE_LAST as u32 {
-            // This is safe because all values from 0 to RETRO_LANGUAGE_LAST have defined values
-            return Some(std::mem::transmute(id));
-        }
-    }
+    let id = get::<u32>(callback, RETRO_ENVIRONMENT_GET_LANGUAGE)?;
 
-    None
+    if id < retro_language::RETRO_LANGUAGE_LAST as u32 {
+        // This is safe because all values from 0 to RETRO_LANGUAGE_LAST have defined values
+        Ok(std::mem::transmute(id))
+    } else {
+        Err(EnvironmentError::InvalidData)
+    }
 }
 
 /// Returns a preallocated framebuffer which the core can use for rendering
@@ -850,14 +939,13 @@ pub unsafe fn get_language(callback: retro_environment_t) -> Option<retro_langua
 pub unsafe fn get_current_software_framebuffer(
     callback: retro_environment_t,
     data: retro_framebuffer,
-) -> Option<retro_framebuffer> {
+) -> Result<retro_framebuffer, EnvironmentError> {
     // struct retro_framebuffer *
     get_mut(
         callback,
         RETRO_ENVIRONMENT_GET_CURRENT_SOFTWARE_FRAMEBUFFER,
         data,
     )
-    .map(|(v, _)| v)
 }
 
 /// Returns an API specific rendering interface for accessing API specific data.
@@ -871,33 +959,37 @@ pub unsafe fn get_current_software_framebuffer(
 ///
 /// **TODO:** Set a status flag in [`retro_hw_context_reset_callback`] and [`retro_hw_context_destroyed_callback`] to force the mentioned call restrictions.
 #[proc::context(GenericContext)]
+#[proc::context(HwContext)]
 #[proc::unstable(feature = "env-commands")]
 pub unsafe fn get_hw_render_interface(
     callback: retro_environment_t,
-) -> Option<retro_hw_render_interface> {
+) -> Result<retro_hw_render_interface, EnvironmentError> {
     // const struct retro_hw_render_interface **
-    get_mut(
+    let ptr: *const retro_hw_render_interface = get_mut(
         callback,
         RETRO_ENVIRONMENT_GET_HW_RENDER_INTERFACE,
         std::ptr::null(),
-    )
-    .map(|(v, _)| *v)
+    )?;
+
+    ptr.as_ref().copied().ok_or(EnvironmentError::InvalidData)
 }
 
 /// See [`get_hw_render_interface`].
 #[cfg(feature = "vulkan")]
 #[proc::context(GenericContext)]
+#[proc::context(HwContext)]
 #[proc::unstable(feature = "env-commands")]
 pub unsafe fn get_hw_render_interface_vulkan(
     callback: retro_environment_t,
-) -> Option<retro_hw_render_interface_vulkan> {
+) -> Result<retro_hw_render_interface_vulkan, EnvironmentError> {
     // const struct retro_hw_render_interface_vulkan **
-    get_mut(
+    let ptr = get_mut(
         callback,
         RETRO_ENVIRONMENT_GET_HW_RENDER_INTERFACE,
         std::ptr::null::<retro_hw_render_interface_vulkan>(),
-    )
-    .map(|(v, _)| (*v).clone())
+    )?;
+
+    ptr.as_ref().cloned().ok_or(EnvironmentError::InvalidData)
 }
 
 /// If true, the Core implementation supports achievements.
@@ -906,9 +998,12 @@ pub unsafe fn get_hw_render_interface_vulkan(
 /// or via [`Core::get_memory_data`] / [`Core::get_memory_size`].
 #[proc::context(InitContext)]
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn set_support_achievements(callback: retro_environment_t, value: bool) -> bool {
+pub unsafe fn set_support_achievements(
+    callback: retro_environment_t,
+    value: bool,
+) -> Result<(), EnvironmentError> {
     // const bool *
-    set(callback, RETRO_ENVIRONMENT_SET_SUPPORT_ACHIEVEMENTS, value).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_SUPPORT_ACHIEVEMENTS, value)
 }
 
 /// Sets an interface which lets the libretro core negotiate with frontend how a context is created.
@@ -920,53 +1015,54 @@ pub unsafe fn set_support_achievements(callback: retro_environment_t, value: boo
 pub unsafe fn set_hw_render_context_negotiation_interface(
     callback: retro_environment_t,
     interface: &retro_hw_render_context_negotiation_interface,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_hw_render_context_negotiation_interface *
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE,
         interface,
     )
-    .unwrap_or(false)
 }
 
 /// Sets quirk flags associated with serialization.
-/// The frontend will zero any flags it doesn't recognize or support.
+/// The frontend will zero any flags it doesn't recognize or support,
+/// and returns the accepted flags, including [`SerializationQuirks::FRONT_VARIABLE_SIZE`]
+/// if it supports variable-sized states.
 ///
 /// **Should be set in either [`Core::on_init`] or [`Core::on_load_game`], but not both.**
+/// [`Core::serialize_size_quirks`] gets passed to this function automatically after loading a game.
 #[proc::context(InitContext)]
 #[proc::context(LoadGameContext)]
 pub unsafe fn set_serialization_quirks(
     callback: retro_environment_t,
     quirks: SerializationQuirks,
-) -> bool {
+) -> Result<SerializationQuirks, EnvironmentError> {
     // uint64_t *
-    set(
+    let quirks = get_mut(
         callback,
         RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS,
         quirks.bits() as u64,
-    )
-    .unwrap_or(false)
+    )?;
+
+    Ok(SerializationQuirks::from_bits_truncate(quirks as u32))
 }
 
 /// The frontend will try to use a 'shared' hardware context (mostly applicable
 /// to OpenGL) when a hardware context is being set up.
 ///
-/// Returns [`true`] if the frontend supports shared hardware contexts and [`false`]
-/// if the frontend does not support shared hardware contexts.
+/// Returns an error if the frontend does not support shared hardware contexts.
 ///
 /// This will do nothing on its own until `SET_HW_RENDER` environment callbacks are
 /// being used.
 #[proc::context(GenericContext)]
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn set_hw_shared_context(callback: retro_environment_t) -> bool {
+pub unsafe fn set_hw_shared_context(callback: retro_environment_t) -> Result<(), EnvironmentError> {
     // N/A (null) *
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_HW_SHARED_CONTEXT,
         std::ptr::null() as *const c_void,
     )
-    .unwrap_or(false)
 }
 
 /// Gets access to the VFS interface.
@@ -979,17 +1075,19 @@ pub unsafe fn set_hw_shared_context(callback: retro_environment_t) -> bool {
 pub fn get_vfs_interface(
     callback: retro_environment_t,
     data: retro_vfs_interface_info,
-) -> Option<retro_vfs_interface_info> {
+) -> Result<retro_vfs_interface_info, EnvironmentError> {
     // struct retro_vfs_interface_info *
-    get_mut(callback, RETRO_ENVIRONMENT_GET_VFS_INTERFACE, data).map(|(v, _)| v)
+    get_mut(callback, RETRO_ENVIRONMENT_GET_VFS_INTERFACE, data)
 }
 
 /// Gets an interface which is used by a libretro core to set state of LEDs.
 #[proc::context(GenericContext)]
 #[proc::unstable(feature = "env-commands")]
-pub fn get_led_interface(callback: retro_environment_t) -> Option<retro_led_interface> {
+pub fn get_led_interface(
+    callback: retro_environment_t,
+) -> Result<retro_led_interface, EnvironmentError> {
     // struct retro_led_interface *
-    get_unchecked(callback, RETRO_ENVIRONMENT_GET_LED_INTERFACE).map(|(v, _)| v)
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_LED_INTERFACE)
 }
 
 /// Tells the core if the frontend wants audio or video.
@@ -1000,31 +1098,30 @@ pub fn get_led_interface(callback: retro_environment_t) -> Option<retro_led_inte
 /// See [`AudioVideoEnable`] for descriptions of the flags.
 #[proc::context(GenericContext)]
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn get_audio_video_enable(callback: retro_environment_t) -> AudioVideoEnable {
+pub unsafe fn get_audio_video_enable(
+    callback: retro_environment_t,
+) -> Result<AudioVideoEnable, EnvironmentError> {
     // int *
-    if let Some((info, _)) = get(callback, RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE) {
-        return AudioVideoEnable::from_bits_truncate(info);
-    }
-
-    AudioVideoEnable::empty()
+    get(callback, RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE)
+        .map(AudioVideoEnable::from_bits_truncate)
 }
 
 /// Returns a MIDI interface that can be used for raw data I/O.
 #[proc::context(GenericContext)]
 #[proc::unstable(feature = "env-commands")]
-pub fn get_midi_interface(callback: retro_environment_t) -> Option<retro_midi_interface> {
+pub fn get_midi_interface(
+    callback: retro_environment_t,
+) -> Result<retro_midi_interface, EnvironmentError> {
     // struct retro_midi_interface **
-    get_unchecked(callback, RETRO_ENVIRONMENT_GET_MIDI_INTERFACE).map(|(v, _)| v)
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_MIDI_INTERFACE)
 }
 
 /// Boolean value that indicates whether or not the frontend is in fastforwarding mode.
 #[proc::context(GenericContext)]
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn get_fastforwarding(callback: retro_environment_t) -> bool {
+pub unsafe fn get_fastforwarding(callback: retro_environment_t) -> Result<bool, EnvironmentError> {
     // bool *
     get(callback, RETRO_ENVIRONMENT_GET_FASTFORWARDING)
-        .map(|(v, _)| v)
-        .unwrap_or(false)
 }
 
 /// Float value that lets us know what target refresh rate
@@ -1032,11 +1129,14 @@ pub unsafe fn get_fastforwarding(callback: retro_environment_t) -> bool {
 ///
 /// The core can use the returned value to set an ideal
 /// refresh rate/framerate.
-#[proc::context(GenericContext)]
+///
+/// See also [`GenericContext::get_target_refresh_rate`].
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn get_target_refresh_rate(callback: retro_environment_t) -> Option<f32> {
+pub unsafe fn get_target_refresh_rate(
+    callback: retro_environment_t,
+) -> Result<f32, EnvironmentError> {
     // float *
-    get(callback, RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE).map(|(v, _)| v)
+    get(callback, RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE)
 }
 
 /// Boolean value that indicates whether or not the frontend supports
@@ -1049,17 +1149,18 @@ pub unsafe fn get_target_refresh_rate(callback: retro_environment_t) -> Option<f
 /// It will return a bitmask of all the digital buttons.
 #[proc::context(GenericContext)]
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn get_input_bitmasks(callback: retro_environment_t) -> bool {
+pub unsafe fn get_input_bitmasks(callback: retro_environment_t) -> Result<bool, EnvironmentError> {
     // bool *
-    // get(callback, RETRO_ENVIRONMENT_GET_INPUT_BITMASKS).map(|(v, _)| v).unwrap_or(false)
-
-    // RetroArch uses the callback’s return value instead
-    set_ptr(
+    // RetroArch uses the callback’s return value instead of the data
+    match set_ptr(
         callback,
         RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
         std::ptr::null() as *const c_void,
-    )
-    .unwrap_or(false)
+    ) {
+        Ok(()) => Ok(true),
+        Err(EnvironmentError::Unsupported) => Ok(false),
+        Err(err) => Err(err),
+    }
 }
 
 /// The returned value is the API version number of the core options
@@ -1085,23 +1186,27 @@ pub unsafe fn get_input_bitmasks(callback: retro_environment_t) -> bool {
 /// to additionally set optional core option category information
 /// for frontends with core option category support.
 #[proc::context(GenericContext)]
-pub unsafe fn get_core_options_version(callback: retro_environment_t) -> u32 {
+pub unsafe fn get_core_options_version(
+    callback: retro_environment_t,
+) -> Result<u32, EnvironmentError> {
     // unsigned *
     get(callback, RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION)
-        .map(|(v, _)| v)
-        .unwrap_or(0)
 }
 
 /// Checks whether the frontend supports the [`set_core_options`] interface.
 #[proc::context(SetEnvironmentContext)]
-pub unsafe fn supports_set_core_options(callback: retro_environment_t) -> bool {
-    get_core_options_version(callback) >= 1
+pub unsafe fn supports_set_core_options(
+    callback: retro_environment_t,
+) -> Result<bool, EnvironmentError> {
+    get_core_options_version(callback).map(|version| version >= 1)
 }
 
 /// Checks whether the frontend supports the [`set_core_options_v2`] interface.
 #[proc::context(SetEnvironmentContext)]
-pub unsafe fn supports_set_core_options_v2(callback: retro_environment_t) -> bool {
-    get_core_options_version(callback) >= 2
+pub unsafe fn supports_set_core_options_v2(
+    callback: retro_environment_t,
+) -> Result<bool, EnvironmentError> {
+    get_core_options_version(callback).map(|version| version >= 2)
 }
 
 /// Allows an implementation to signal the environment
@@ -1168,14 +1273,13 @@ pub unsafe fn supports_set_core_options_v2(callback: retro_environment_t) -> boo
 pub unsafe fn set_core_options(
     callback: retro_environment_t,
     options: &[retro_core_option_definition],
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_core_option_definition **
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS,
         options.as_ptr(),
     )
-    .unwrap_or(false)
 }
 
 /// Allows an implementation to signal the environment
@@ -1365,14 +1469,13 @@ pub unsafe fn set_core_options(
 pub unsafe fn set_core_options_v2(
     callback: retro_environment_t,
     options: &retro_core_options_v2,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_core_options_v2 *
     set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2,
         options as *const _,
     )
-    .unwrap_or(false)
 }
 
 /// Allows an implementation to signal the environment
@@ -1420,9 +1523,9 @@ pub unsafe fn set_core_options_v2(
 pub unsafe fn set_core_options_intl(
     callback: retro_environment_t,
     options: retro_core_options_intl,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_core_options_intl *
-    set(callback, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL, options).unwrap_or(false)
+    set(callback, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL, options)
 }
 
 /// Allows an implementation to signal the environment
@@ -1486,14 +1589,13 @@ pub unsafe fn set_core_options_intl(
 pub unsafe fn set_core_options_v2_intl(
     callback: retro_environment_t,
     options: retro_core_options_v2_intl,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_core_options_v2_intl *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL,
         options,
     )
-    .unwrap_or(false)
 }
 
 /// Allows an implementation to signal the environment to show
@@ -1511,31 +1613,34 @@ pub unsafe fn set_core_options_v2_intl(
 ///
 /// Note that all core option variables will be set visible by
 /// default when calling [`set_variables`] / [`set_core_options`].
+///
+/// See also [`OptionsChangedContext::set_variable_visible`].
 #[proc::context(GenericContext)]
 pub unsafe fn set_core_options_display(
     callback: retro_environment_t,
     options: retro_core_option_display,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // struct retro_core_option_display *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY,
         options,
     )
-    .unwrap_or(false)
 }
 
 /// Allows an implementation to ask frontend preferred hardware
 /// context to use. Core should use this information to deal
 /// with what specific context to request with SET_HW_RENDER.
 ///
-/// 'data' points to an unsigned variable
+/// Returns [`None`] if the frontend has no preference.
+///
+/// See also [`InitContext::get_preferred_hw_render`].
 #[proc::context(GenericContext)]
-pub unsafe fn get_preferred_hw_render(callback: retro_environment_t) -> u32 {
+pub unsafe fn get_preferred_hw_render(
+    callback: retro_environment_t,
+) -> Result<Option<HwContextType>, EnvironmentError> {
     // unsigned *
-    get(callback, RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER)
-        .map(|(v, _)| v)
-        .unwrap_or(0)
+    get(callback, RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER).map(HwContextType::from_raw)
 }
 
 /// Unsigned value is the API version number of the disk control
@@ -1556,14 +1661,14 @@ pub unsafe fn get_preferred_hw_render(callback: retro_environment_t) -> u32 {
 /// disk images to the frontend and/or enables extra
 /// disk control functionality by the frontend.
 #[proc::context(GenericContext)]
-pub unsafe fn get_disk_control_interface_version(callback: retro_environment_t) -> u32 {
+pub unsafe fn get_disk_control_interface_version(
+    callback: retro_environment_t,
+) -> Result<u32, EnvironmentError> {
     // unsigned *
     get(
         callback,
         RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION,
     )
-    .map(|(v, _)| v)
-    .unwrap_or(0)
 }
 
 /// Sets an interface which frontend can use to eject and insert
@@ -1576,14 +1681,13 @@ pub unsafe fn get_disk_control_interface_version(callback: retro_environment_t)
 pub unsafe fn set_disk_control_ext_interface(
     callback: retro_environment_t,
     data: retro_disk_control_ext_callback,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_disk_control_ext_callback *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE,
         data,
     )
-    .unwrap_or(false)
 }
 
 /// The returned value is the API version number of the message
@@ -1601,11 +1705,11 @@ pub unsafe fn set_disk_control_ext_interface(
 /// This allows the core to specify message logging level, priority and
 /// destination (OSD, logging interface or both).
 #[proc::context(GenericContext)]
-pub unsafe fn get_message_interface_version(callback: retro_environment_t) -> u32 {
+pub unsafe fn get_message_interface_version(
+    callback: retro_environment_t,
+) -> Result<u32, EnvironmentError> {
     // unsigned *
     get(callback, RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION)
-        .map(|(v, _)| v)
-        .unwrap_or(0)
 }
 
 /// Sets a message to be displayed in an implementation-specific
@@ -1614,8 +1718,9 @@ pub unsafe fn get_message_interface_version(callback: retro_environment_t) -> u3
 /// destination (OSD, logging interface or both).
 /// Should not be used for trivial messages, which should simply be
 /// logged via [`RETRO_ENVIRONMENT_GET_LOG_INTERFACE`] (or as a fallback, stderr).
+///
+/// See also [`GenericContext::set_message_ext`].
 #[allow(clippy::too_many_arguments)]
-#[proc::context(GenericContext)]
 pub unsafe fn set_message_ext(
     callback: retro_environment_t,
     message: &str,
@@ -1625,16 +1730,8 @@ pub unsafe fn set_message_ext(
     target: retro_message_target,
     type_: retro_message_type,
     progress: MessageProgress,
-) -> bool {
-    let msg = match CString::new(message) {
-        Ok(message) => message,
-        Err(err) => {
-            #[cfg(feature = "log")]
-            log::error!("{}", err);
-
-            return false;
-        }
-    };
+) -> Result<(), EnvironmentError> {
+    let msg = CString::new(message)?;
 
     // const struct retro_message_ext *
     set(
@@ -1650,44 +1747,51 @@ pub unsafe fn set_message_ext(
             progress: progress.as_i8(),
         },
     )
-    .unwrap_or(false)
 }
 
-/// The first returned value is the number of active input devices
+/// Returns the number of active input devices
 /// provided by the frontend. This may change between
 /// frames, but will remain constant for the duration
 /// of each frame.
 ///
-/// If the second return value is [`true`], a core does not need to
+/// If this succeeds, a core does not need to
 /// poll any input device with an index greater than or equal to
 /// the number of active devices.
 ///
-/// If the second return value is [`false`], the number of active input
+/// If this returns an error, the number of active input
 /// devices is unknown. In this case, all input devices
 /// should be considered active.
 #[proc::context(GenericContext)]
-pub unsafe fn get_input_max_users(callback: retro_environment_t) -> (u32, bool) {
+pub unsafe fn get_input_max_users(callback: retro_environment_t) -> Result<u32, EnvironmentError> {
     // unsigned *
-    get(callback, RETRO_ENVIRONMENT_GET_INPUT_MAX_USERS).unwrap_or((0, false))
+    get(callback, RETRO_ENVIRONMENT_GET_INPUT_MAX_USERS)
 }
 
 /// Lets the core know the occupancy level of the frontend
 /// audio buffer. Can be used by a core to attempt frame
 /// skipping in order to avoid buffer under-runs.
-/// A core may pass `NULL` to disable buffer status reporting
+/// A core may pass [`None`] to disable buffer status reporting
 /// in the frontend.
-#[proc::context(GenericContext)]
+///
+/// See [`GenericContext::set_audio_buffer_status_callback`] for a version
+/// that forwards the status to [`Core::on_audio_buffer_status`].
 pub unsafe fn set_audio_buffer_status_callback(
     callback: retro_environment_t,
-    data: retro_audio_buffer_status_callback,
-) -> bool {
+    data: Option<retro_audio_buffer_status_callback>,
+) -> Result<(), EnvironmentError> {
     // const struct retro_audio_buffer_status_callback *
-    set(
-        callback,
-        RETRO_ENVIRONMENT_SET_AUDIO_BUFFER_STATUS_CALLBACK,
-        data,
-    )
-    .unwrap_or(false)
+    match data {
+        Some(data) => set(
+            callback,
+            RETRO_ENVIRONMENT_SET_AUDIO_BUFFER_STATUS_CALLBACK,
+            data,
+        ),
+        None => set_ptr(
+            callback,
+            RETRO_ENVIRONMENT_SET_AUDIO_BUFFER_STATUS_CALLBACK,
+            std::ptr::null::<retro_audio_buffer_status_callback>(),
+        ),
+    }
 }
 
 /// Sets minimum frontend audio latency in milliseconds.
@@ -1715,27 +1819,35 @@ pub unsafe fn set_audio_buffer_status_callback(
 /// An eventual driver reinitialize will happen so that audio
 /// callbacks happening after this call within the same [`Core::on_run`]
 /// call will target the newly initialized driver.
+#[proc::context(GenericContext)]
 #[proc::context(RunContext)]
-pub unsafe fn set_minimum_audio_latency(callback: retro_environment_t, latency: u32) -> bool {
+pub unsafe fn set_minimum_audio_latency(
+    callback: retro_environment_t,
+    latency: u32,
+) -> Result<(), EnvironmentError> {
     // const unsigned *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_MINIMUM_AUDIO_LATENCY,
         latency,
     )
-    .unwrap_or(false)
 }
 
 /// Checks whether the frontend supports the [`set_fastforwarding_override`] interface.
 #[proc::context(GenericContext)]
-pub unsafe fn supports_fastforwarding_override(callback: retro_environment_t) -> bool {
+pub unsafe fn supports_fastforwarding_override(
+    callback: retro_environment_t,
+) -> Result<bool, EnvironmentError> {
     // const struct retro_fastforwarding_override *
-    set_ptr(
+    match set_ptr(
         callback,
         RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE,
         std::ptr::null() as *const c_void,
-    )
-    .unwrap_or(false)
+    ) {
+        Ok(()) => Ok(true),
+        Err(EnvironmentError::Unsupported) => Ok(false),
+        Err(err) => Err(err),
+    }
 }
 
 /// Used by a libretro core to override the current
@@ -1744,14 +1856,13 @@ pub unsafe fn supports_fastforwarding_override(callback: retro_environment_t) ->
 pub unsafe fn set_fastforwarding_override(
     callback: retro_environment_t,
     value: retro_fastforwarding_override,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_fastforwarding_override *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_FASTFORWARDING_OVERRIDE,
         value,
     )
-    .unwrap_or(false)
 }
 
 ///  Allows an implementation to override 'global' content
@@ -1824,13 +1935,18 @@ pub unsafe fn set_fastforwarding_override(
 ///  NOTE: If an extension is listed multiple times in
 ///  an array of [`retro_system_content_info_override`]
 ///  structs, only the **first** instance will be registered
-#[proc::context(SetEnvironmentContext)]
+///
+/// See also [`SetEnvironmentContext::set_content_info_override`].
 pub unsafe fn set_content_info_override(
     callback: retro_environment_t,
-    value: retro_system_content_info_override,
-) -> bool {
+    overrides: &[retro_system_content_info_override],
+) -> Result<(), EnvironmentError> {
     // const struct retro_system_content_info_override *
-    set(callback, RETRO_ENVIRONMENT_SET_CONTENT_INFO_OVERRIDE, value).unwrap_or(false)
+    set_ptr(
+        callback,
+        RETRO_ENVIRONMENT_SET_CONTENT_INFO_OVERRIDE,
+        overrides.as_ptr(),
+    )
 }
 
 /// Allows an implementation to fetch extended game
@@ -1867,15 +1983,13 @@ pub unsafe fn set_content_info_override(
 ///   the [`retro_game_info_ext`] array is guaranteed to have a
 ///   size equal to the `num_info` argument passed to
 ///   [`Core::on_load_game_special`]
-#[proc::context(LoadGameContext)]
-#[proc::context(LoadGameSpecialContext)]
-pub unsafe fn get_game_info_ext(callback: retro_environment_t) -> Option<retro_game_info_ext> {
+///
+/// See also [`LoadGameContext::get_game_info_ext`] and [`LoadGameSpecialContext::get_game_info_ext`].
+pub unsafe fn get_game_info_ext(
+    callback: retro_environment_t,
+) -> Result<*const retro_game_info_ext, EnvironmentError> {
     // const struct retro_game_info_ext **
-    if let Some((v, true)) = get_unchecked(callback, RETRO_ENVIRONMENT_GET_GAME_INFO_EXT) {
-        Some(v)
-    } else {
-        None
-    }
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_GAME_INFO_EXT)
 }
 
 /// Allows a frontend to signal that a core must update
@@ -1889,14 +2003,13 @@ pub unsafe fn get_game_info_ext(callback: retro_environment_t) -> Option<retro_g
 pub unsafe fn set_core_options_update_display_callback(
     callback: retro_environment_t,
     data: retro_core_options_update_display_callback,
-) -> bool {
+) -> Result<(), EnvironmentError> {
     // const struct retro_core_options_update_display_callback *
     set(
         callback,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK,
         data,
     )
-    .unwrap_or(false)
 }
 
 /// Allows an implementation to notify the frontend
@@ -1920,19 +2033,36 @@ pub unsafe fn set_core_options_update_display_callback(
 /// and the callback will return [`true`]; an
 /// implementation may therefore pass `NULL` in order
 /// to test whether the callback is supported.
+///
+/// Returns an error if the frontend does not support this command,
+/// e.g. to persist a detected setting like the region of a game.
 #[proc::context(GenericContext)]
-pub unsafe fn set_variable(callback: retro_environment_t, value: retro_variable) -> bool {
+pub unsafe fn set_variable(
+    callback: retro_environment_t,
+    key: &str,
+    value: &str,
+) -> Result<(), EnvironmentError> {
+    let key = CString::new(key)?;
+    let value = CString::new(value)?;
+
+    let var = retro_variable {
+        key: key.as_ptr(),
+        value: value.as_ptr(),
+    };
+
     // const struct retro_variable *
-    set(callback, RETRO_ENVIRONMENT_SET_VARIABLE, value).unwrap_or(false)
+    set_ptr(callback, RETRO_ENVIRONMENT_SET_VARIABLE, &var as *const _)
 }
 
 /// Allows an implementation to get details on the actual rate
 /// the frontend is attempting to call [`Core::on_run`].
 #[proc::context(GenericContext)]
 #[proc::unstable(feature = "env-commands")]
-pub unsafe fn get_throttle_state(callback: retro_environment_t) -> Option<retro_throttle_state> {
+pub unsafe fn get_throttle_state(
+    callback: retro_environment_t,
+) -> Result<retro_throttle_state, EnvironmentError> {
     // struct retro_throttle_state *
-    get_unchecked(callback, RETRO_ENVIRONMENT_GET_THROTTLE_STATE).map(|(v, _)| v)
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_THROTTLE_STATE)
 }
 
 /// Tells the core about the context the frontend is asking for savestate.
@@ -1941,14 +2071,34 @@ pub unsafe fn get_throttle_state(callback: retro_environment_t) -> Option<retro_
 #[proc::unstable(feature = "env-commands")]
 pub unsafe fn get_savestate_context(
     callback: retro_environment_t,
-) -> Option<retro_savestate_context> {
+) -> Result<retro_savestate_context, EnvironmentError> {
     // int *
+    let value = get::<retro_savestate_context_REPR_TYPE>(
+        callback,
+        RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT,
+    )?;
 
-    if let Some((value, _)) =
-        get::<retro_savestate_context_REPR_TYPE>(callback, RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT)
-    {
-        return retro_savestate_context::try_from(value).ok();
-    }
+    retro_savestate_context::try_from(value).map_err(|_| EnvironmentError::InvalidData)
+}
 
-    None
+/// Boolean value that indicates whether the frontend has verified that
+/// JIT compilation can be used, which mainly matters on iOS and tvOS.
+/// Frontends on other platforms always report `true`.
+///
+/// See also [`InitContext::jit_capable`].
+pub unsafe fn get_jit_capable(callback: retro_environment_t) -> Result<bool, EnvironmentError> {
+    // bool *
+    get(callback, RETRO_ENVIRONMENT_GET_JIT_CAPABLE)
+}
+
+/// Returns the power state of the device, i.e. whether it runs on battery
+/// and how much of it is left.
+///
+/// See also [`RunContext::get_device_power`].
+#[proc::unstable(feature = "env-commands")]
+pub unsafe fn get_device_power(
+    callback: retro_environment_t,
+) -> Result<retro_device_power, EnvironmentError> {
+    // struct retro_device_power *
+    get_unchecked(callback, RETRO_ENVIRONMENT_GET_DEVICE_POWER)
 }
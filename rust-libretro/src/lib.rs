@@ -13,8 +13,16 @@ mod macros;
 pub mod contexts;
 pub mod core;
 pub mod environment;
+pub mod pacing;
+#[cfg(feature = "serde")]
+pub mod save_state;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tracing")]
+pub mod tracing_layer;
 pub mod types;
 pub mod util;
+pub mod vfs;
 
 pub use const_str;
 pub use macros::*;
@@ -22,6 +30,7 @@ pub use rust_libretro_proc as proc;
 pub use rust_libretro_sys as sys;
 
 use crate::{contexts::*, core::Core, core_wrapper::CoreWrapper, sys::*, types::*, util::*};
+use core_wrapper::{SingleThreadCell, SingleThreadRefMut};
 use std::{
     ffi::*,
     os::raw::c_char,
@@ -30,10 +39,29 @@ use std::{
 };
 
 #[doc(hidden)]
-static mut RETRO_INSTANCE: Option<CoreWrapper> = None;
+static RETRO_INSTANCE: SingleThreadCell<Option<CoreWrapper>> = SingleThreadCell::new(None);
+
+/// The function generated by [`retro_core!`], remembered by [`retro_get_system_info`]
+/// to recreate the instance after [`retro_deinit`] dropped it.
+static CORE_CONSTRUCTOR: SingleThreadCell<Option<unsafe fn()>> = SingleThreadCell::new(None);
+
+/// The [`SystemInfo`]s passed to the frontend, the last one being current.
+///
+/// All of them stay valid for the lifetime of the process, as the frontend may still reference
+/// an outdated one, see [`GenericContext::refresh_system_info`], or keep using the current one
+/// after [`retro_deinit`]. Only the [`Core`] instance gets dropped there.
+static SYS_INFO: SingleThreadCell<Vec<SystemInfo>> = SingleThreadCell::new(Vec::new());
 
 /// This macro must be used to initialize your [`Core`].
 ///
+/// # Instances
+///
+/// The libretro API has no notion of instance handles, so every core library holds exactly one
+/// process-wide instance of its [`Core`]. Frontends that need a second instance, e.g. for
+/// RetroArch’s “Use Second Instance for Run-Ahead” option, must load a separate copy of the
+/// library, which then has an instance of its own.
+/// Runahead within a single instance only relies on save states, see [`Core::supports_runahead`].
+///
 /// # Examples
 /// ```rust
 /// # use rust_libretro::{contexts::*, core::{Core, CoreOptions}, sys::*, types::*, retro_core};
@@ -97,20 +125,88 @@ macro_rules! retro_core {
     }
 }
 
+/// Reports an error without unwinding into the frontend.
+///
+/// Uses the [`log`] crate if the `log` feature is enabled, `stderr` otherwise.
+#[doc(hidden)]
+macro_rules! report_error {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "log")]
+        log::error!($($arg)+);
+
+        #[cfg(not(feature = "log"))]
+        eprintln!($($arg)+);
+    }};
+}
+
+/// Borrows the process-wide instance for the duration of a call from the frontend.
+///
+/// Returns [`None`] if it has not been created yet, or if the frontend called back into the core
+/// while an outer call is still using the instance, see [`SingleThreadCell`].
+unsafe fn instance() -> Option<SingleThreadRefMut<'static, CoreWrapper>> {
+    RETRO_INSTANCE.borrow_mut()?.filter_map(Option::as_mut)
+}
+
+/// Called by the exported functions if the frontend calls them before
+/// [`retro_get_system_info`] created the [`Core`] instance,
+/// or from within another call into the [`Core`], see [`instance`].
+///
+/// Panicking across the FFI boundary is undefined behaviour, so the
+/// functions report the error and return a safe default instead.
+#[cold]
+#[inline(never)]
+fn core_not_initialized(function: &str) {
+    if RETRO_INSTANCE.is_borrowed() {
+        report_error!("{function}: Called from within another call into the core!");
+        return;
+    }
+
+    report_error!("{function}: Core has not been initialized yet!");
+}
+
 #[doc(hidden)]
 macro_rules! forward {
+    // Converts the value returned by the handler into the raw type expected by the frontend
+    ($(#[doc = $doc:tt ], )* $wrapper:ident, $name:ident, $handler:ident -> $handler_type:ty as $return_type:ty, $($context:tt)+) => {
+        #[no_mangle]
+        $(#[doc = $doc])*
+        pub unsafe extern "C" fn $name() -> $return_type {
+            // Check that the instance has been created
+            if let Some($wrapper) = instance().as_deref_mut() {
+                // Forward to the Core implementation
+                let mut ctx = $($context)+;
+                let value: $handler_type = $wrapper.core.$handler(&mut ctx);
+                return value.into();
+            }
+
+            core_not_initialized(stringify!($name));
+            <$handler_type>::default().into()
+        }
+    };
     ($(#[doc = $doc:tt ], )* $wrapper:ident, $name:ident, $handler:ident $(-> $return_type:ty)?, $($context:tt)+) => {
         #[no_mangle]
         $(#[doc = $doc])*
         pub unsafe extern "C" fn $name() $(-> $return_type)? {
             // Check that the instance has been created
-            if let Some($wrapper) = RETRO_INSTANCE.as_mut() {
+            if let Some($wrapper) = instance().as_deref_mut() {
                 // Forward to the Core implementation
                 let mut ctx = $($context)+;
                 return $wrapper.core.$handler(&mut ctx);
             }
 
-            panic!(concat!(stringify!($name), ": Core has not been initialized yet!"));
+            core_not_initialized(stringify!($name));
+            $(<$return_type>::default())?
+        }
+    };
+}
+
+/// Postpones a notification from the frontend until the call into the [`Core`]
+/// it has been sent from returns, see [`SingleThreadCell`].
+#[doc(hidden)]
+macro_rules! defer_nested_call {
+    ($name:ident($($arg:ident),*)) => {
+        if RETRO_INSTANCE.is_borrowed() {
+            return RETRO_INSTANCE.defer(move || unsafe { $name($($arg),*) });
         }
     };
 }
@@ -122,15 +218,17 @@ macro_rules! callback {
         $(#[doc = $doc])*
         pub unsafe extern "C" fn $name(arg1: $arg) {
             // Check that the instance has been created
-            if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-                if arg1.is_some() {
+            if let Some(wrapper) = instance().as_deref_mut() {
+                if let Some(callback) = arg1 {
                     // We were given a callback, make sure that it’s not a NULL pointer
-                    if (arg1.unwrap() as *const c_void).is_null() {
-                        panic!(concat!(
-                            "Expected ",
+                    if (callback as *const c_void).is_null() {
+                        report_error!(concat!(
+                            stringify!($name),
+                            ": Expected ",
                             stringify!($arg),
-                            " got NULL pointer instead!"
+                            ", got NULL pointer instead!"
                         ));
+                        return;
                     }
                 }
 
@@ -138,63 +236,148 @@ macro_rules! callback {
                 return wrapper.$handler(arg1);
             }
 
-            panic!(concat!(
-                stringify!($name),
-                ": Core has not been initialized yet!"
-            ));
+            core_not_initialized(stringify!($name));
         }
     };
 }
 
 #[doc(hidden)]
 pub fn set_core<C: 'static + Core>(core: C) {
-    unsafe {
-        if RETRO_INSTANCE.is_some() {
-            let core = &RETRO_INSTANCE.as_ref().unwrap().core;
-            let info = core.get_info();
-            let name = info.library_name.into_string().unwrap();
-            let version = info.library_version.into_string().unwrap();
-
-            panic!("Attempted to set a core after the system was already initialized.\nAlready registered core: {} {}", name, version)
+    let Some(mut instance) = (unsafe { RETRO_INSTANCE.borrow_mut() }) else {
+        report_error!("Attempted to set a core from within a call into the core.");
+        return;
+    };
+
+    if let Some(wrapper) = instance.as_ref() {
+        let info = wrapper.core.get_info();
+        let name = info.library_name.to_string_lossy();
+        let version = info.library_version.to_string_lossy();
+
+        report_error!("Attempted to set a core after the system was already initialized.\nAlready registered core: {} {}", name, version);
+        return;
+    }
+
+    instance.replace(CoreWrapper::new(core));
+}
+
+/// A [`Core`] instance that lives outside of the process-wide instance.
+///
+/// The libretro API has no notion of instance handles: the exported `retro_*` functions
+/// always operate on the single instance registered by [`retro_core!`].
+/// A `CoreInstance` owns a separate [`Core`] along with all of its runtime state
+/// and can be swapped in for the duration of a few calls, which allows driving
+/// several cores from within the same process, one at a time.
+///
+/// This is what [`MockFrontend`](crate::testing::MockFrontend) uses,
+/// so tests of multiple cores don’t interfere with each other.
+///
+/// Note that [`retro_get_system_info`] caches the [`SystemInfo`] of the first core it sees
+/// until [`retro_deinit`] gets called.
+pub struct CoreInstance {
+    wrapper: Option<CoreWrapper>,
+}
+
+impl CoreInstance {
+    /// Wraps `core` without registering it as the process-wide instance.
+    pub fn new<C: 'static + Core>(core: C) -> Self {
+        Self {
+            wrapper: Some(CoreWrapper::new(core)),
         }
+    }
 
-        RETRO_INSTANCE.replace(CoreWrapper::new(core));
+    /// Installs this instance as the process-wide instance while `f` runs,
+    /// so calls of the exported `retro_*` functions within `f` operate on it.
+    /// The previous instance gets restored afterwards, even if `f` panics.
+    ///
+    /// # Safety
+    ///
+    /// Must not be called while the frontend is calling into the process-wide instance,
+    /// e.g. from within a [`Core`] method, nor concurrently from multiple threads.
+    pub unsafe fn activate<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        struct Restore<'a>(&'a mut Option<CoreWrapper>);
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                std::mem::swap(unsafe { RETRO_INSTANCE.get_mut() }, self.0);
+            }
+        }
+
+        std::mem::swap(RETRO_INSTANCE.get_mut(), &mut self.wrapper);
+        let _restore = Restore(&mut self.wrapper);
+
+        f()
     }
 }
 
+/// The logger installed by the first call to [`retro_set_environment`].
+#[cfg(feature = "log")]
+static LOGGER: std::sync::OnceLock<logger::RetroLogger> = std::sync::OnceLock::new();
+
+/// The log callback used by the tracing subscriber installed by the first call to [`retro_set_environment`].
+#[cfg(feature = "tracing")]
+static TRACING_CALLBACK: std::sync::OnceLock<Arc<std::sync::RwLock<retro_log_callback>>> =
+    std::sync::OnceLock::new();
+
+/// Installs the global logger, or hands the log callback of `env_callback` to it
+/// if a previous instance of the core installed it already, see [`retro_deinit`].
 #[cfg(feature = "log")]
 #[doc(hidden)]
-fn init_log(env_callback: retro_environment_t) {
-    let retro_logger = unsafe { environment::get_log_callback(env_callback) };
+fn init_log(env_callback: retro_environment_t, default_level: log::LevelFilter) {
+    let log_callback = unsafe { environment::get_log_callback(env_callback) }
+        .unwrap_or(retro_log_callback { log: None });
 
-    let retro_logger = if let Ok(Some(log_callback)) = retro_logger {
-        logger::RetroLogger::new(log_callback)
-    } else {
-        logger::RetroLogger::new(retro_log_callback { log: None })
-    };
+    if let Some(logger) = LOGGER.get() {
+        return logger.set_callback(log_callback);
+    }
+
+    let retro_logger = LOGGER.get_or_init(|| logger::RetroLogger::new(log_callback, default_level));
+
+    // Lets the `log` macros skip disabled messages before formatting them
+    let max_level = retro_logger.max_level();
+
+    match log::set_logger(retro_logger) {
+        Ok(()) => log::set_max_level(max_level),
+        Err(err) => eprintln!("Could not set logger: {err}"),
+    }
+}
+
+/// Installs the global tracing subscriber, or hands the log callback of `env_callback` to it
+/// if a previous instance of the core installed it already, see [`retro_deinit`].
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+fn init_tracing(
+    env_callback: retro_environment_t,
+    default_level: tracing::level_filters::LevelFilter,
+) {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let log_callback = unsafe { environment::get_log_callback(env_callback) }
+        .unwrap_or(retro_log_callback { log: None });
+
+    if let Some(callback) = TRACING_CALLBACK.get() {
+        *callback
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = log_callback;
+        return;
+    }
+
+    let layer = tracing_layer::RetroLayer::new(log_callback);
+    TRACING_CALLBACK.get_or_init(|| layer.callback_handle());
 
-    log::set_max_level(log::LevelFilter::Trace);
-    log::set_boxed_logger(Box::new(retro_logger)).expect("could not set logger");
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_layer::RetroLayer::filter(default_level))
+        .with(layer);
+
+    // Does not install a `log` bridge, so this works alongside the `log` feature
+    if let Err(err) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Could not set tracing subscriber: {err}");
+    }
 }
 
 /*****************************************************************************\
 |                              CORE API FUNCTIONS                             |
 \*****************************************************************************/
 
-forward!(
-    #[doc = "Notifies the [`Core`] when all cheats should be unapplied."],
-    wrapper,
-    retro_cheat_reset,
-    on_cheat_reset,
-    GenericContext::new(&wrapper.environment_callback, Arc::clone(&wrapper.interfaces))
-);
-forward!(
-    #[doc = "Notifies the [`Core`] when it is being closed and its resources should be freed."],
-    wrapper,
-    retro_deinit,
-    on_deinit,
-    GenericContext::new(&wrapper.environment_callback, Arc::clone(&wrapper.interfaces))
-);
 forward!(
     #[doc = "Called when the frontend needs region information from the [`Core`]."],
     #[doc = ""],
@@ -202,23 +385,7 @@ forward!(
     #[doc = "RetroArch doesn’t use this interface anymore, because [`retro_get_system_av_info`] provides similar information."],
     wrapper,
     retro_get_region,
-    on_get_region -> std::os::raw::c_uint,
-    GenericContext::new(&wrapper.environment_callback, Arc::clone(&wrapper.interfaces))
-);
-forward!(
-    #[doc = "Notifies the [`Core`] when the current game should be reset."],
-    wrapper,
-    retro_reset,
-    on_reset,
-    GenericContext::new(&wrapper.environment_callback, Arc::clone(&wrapper.interfaces))
-);
-forward!(
-    #[doc = "Called when the frontend needs to know how large a buffer to allocate for save states."],
-    #[doc = ""],
-    #[doc = "See also [`rust_libretro_sys::retro_serialize_size`]."],
-    wrapper,
-    retro_serialize_size,
-    get_serialize_size -> usize,
+    on_get_region -> Region as std::os::raw::c_uint,
     GenericContext::new(&wrapper.environment_callback, Arc::clone(&wrapper.interfaces))
 );
 forward!(
@@ -287,8 +454,9 @@ pub unsafe extern "C" fn retro_init() {
     #[cfg(feature = "log")]
     log::trace!("retro_init()");
 
-    if let Some(mut wrapper) = RETRO_INSTANCE.as_mut() {
-        wrapper.can_dupe = environment::can_dupe(wrapper.environment_callback);
+    if let Some(mut wrapper) = instance().as_deref_mut() {
+        wrapper.can_dupe = environment::can_dupe(wrapper.environment_callback).unwrap_or(false);
+        wrapper.interfaces.write().unwrap().capabilities = query_capabilities(wrapper);
 
         let mut ctx = InitContext::new(
             &wrapper.environment_callback,
@@ -298,7 +466,46 @@ pub unsafe extern "C" fn retro_init() {
         return wrapper.core.on_init(&mut ctx);
     }
 
-    panic!("retro_init: Core has not been initialized yet!");
+    core_not_initialized("retro_init");
+}
+
+/// Notifies the [`Core`] when it is being closed and its resources should be freed.
+///
+/// The [`Core`] gets dropped afterwards. If the frontend initializes the core again
+/// (e.g. when switching back to it), a new instance is created from the [`retro_core!`] definition.
+///
+/// See also [`rust_libretro_sys::retro_deinit`].
+#[no_mangle]
+pub unsafe extern "C" fn retro_deinit() {
+    #[cfg(feature = "log")]
+    log::trace!("retro_deinit()");
+
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let mut ctx = DeinitContext::new(
+            &wrapper.environment_callback,
+            Arc::clone(&wrapper.interfaces),
+        );
+
+        wrapper.core.on_deinit(&mut ctx);
+    } else {
+        return core_not_initialized("retro_deinit");
+    }
+
+    // The instance has been released above
+    RETRO_INSTANCE.get_mut().take();
+}
+
+/// Creates the process-wide instance if it does not exist (anymore),
+/// see [`retro_deinit`].
+unsafe fn create_instance() {
+    // A borrowed instance exists
+    if RETRO_INSTANCE.is_borrowed() || RETRO_INSTANCE.get_mut().is_some() {
+        return;
+    }
+
+    if let Some(constructor) = *CORE_CONSTRUCTOR.get_mut() {
+        constructor();
+    }
 }
 
 /// Provides _statically known_ system info to the frontend.
@@ -311,33 +518,38 @@ pub unsafe extern "C" fn retro_get_system_info(info: *mut retro_system_info) {
 
     // Make sure that the pointer we got is plausible
     if info.is_null() {
-        panic!("Expected retro_system_info, got NULL pointer instead!");
+        report_error!(
+            "retro_get_system_info: Expected retro_system_info, got NULL pointer instead!"
+        );
+        return;
     }
 
     // We didn’t get a NULL pointer, so this should be safe
     let info = &mut *info;
 
+    extern "Rust" {
+        fn __retro_init_core();
+    }
+
+    CORE_CONSTRUCTOR.get_mut().get_or_insert(__retro_init_core);
+    create_instance();
+
     // retro_get_system_info requires statically allocated data
-    // This is unsafe because we mutate a static value.
-    //
-    // TODO: Should this be put behind an Arc<Mutex> or Arc<RwLock>?
-    static mut SYS_INFO: Option<*const SystemInfo> = None;
-
-    let sys_info = {
-        if SYS_INFO.is_none() {
-            extern "Rust" {
-                fn __retro_init_core();
-            }
-            __retro_init_core();
+    let sys_infos = SYS_INFO.get_mut();
 
-            if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-                SYS_INFO = Some(Box::into_raw(Box::new(wrapper.core.get_info())));
-            } else {
-                panic!("No core instance found!");
-            }
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let outdated =
+            std::mem::take(&mut wrapper.interfaces.write().unwrap().system_info_outdated);
+
+        if outdated || sys_infos.is_empty() {
+            sys_infos.push(wrapper.core.get_info());
         }
+    }
 
-        &*SYS_INFO.unwrap()
+    let Some(sys_info) = sys_infos.last() else {
+        // There is no sensible data we could return to the frontend
+        report_error!("retro_get_system_info: No core instance found!");
+        std::process::abort();
     };
 
     info.library_name = sys_info.library_name.as_ptr();
@@ -357,10 +569,13 @@ pub unsafe extern "C" fn retro_get_system_av_info(info: *mut retro_system_av_inf
     #[cfg(feature = "log")]
     log::trace!("retro_get_system_av_info(info = {info:#?})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    if let Some(wrapper) = instance().as_deref_mut() {
         // Make sure that the pointer we got is plausible
         if info.is_null() {
-            panic!("Expected retro_system_av_info, got NULL pointer instead!");
+            report_error!(
+                "retro_get_system_av_info: Expected retro_system_av_info, got NULL pointer instead!"
+            );
+            return;
         }
 
         // We didn’t get a NULL pointer, so this should be safe
@@ -379,7 +594,7 @@ pub unsafe extern "C" fn retro_get_system_av_info(info: *mut retro_system_av_inf
         return;
     }
 
-    panic!("retro_get_system_av_info: Core has not been initialized yet!");
+    core_not_initialized("retro_get_system_av_info");
 }
 
 /// Provides the environment callback to the [`Core`].
@@ -392,7 +607,9 @@ pub unsafe extern "C" fn retro_set_environment(environment: retro_environment_t)
     #[cfg(feature = "log")]
     log::trace!("retro_set_environment(environment = {environment:#?})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    create_instance();
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut initial = false;
 
         if let Some(callback) = environment {
@@ -401,11 +618,15 @@ pub unsafe extern "C" fn retro_set_environment(environment: retro_environment_t)
                 wrapper.environment_set = true;
 
                 #[cfg(feature = "log")]
-                init_log(Some(callback));
+                init_log(Some(callback), wrapper.core.log_level());
+
+                #[cfg(feature = "tracing")]
+                init_tracing(Some(callback), wrapper.core.tracing_level());
 
                 #[cfg(feature = "unstable-env-commands")]
                 {
-                    wrapper.supports_bitmasks = environment::get_input_bitmasks(Some(callback));
+                    wrapper.supports_bitmasks =
+                        environment::get_input_bitmasks(Some(callback)).unwrap_or(false);
                 }
             }
 
@@ -426,10 +647,48 @@ pub unsafe extern "C" fn retro_set_environment(environment: retro_environment_t)
             log::warn!("Failed to set core options");
         }
 
+        if initial && ctx.enable_options_update_display_callback().is_err() {
+            #[cfg(feature = "log")]
+            log::debug!("The frontend does not support the core options update display callback");
+        }
+
+        if initial && wrapper.core.exports_proc_addresses() && ctx.enable_proc_address().is_err() {
+            #[cfg(feature = "log")]
+            log::warn!("The frontend does not support looking up functions of the core");
+        }
+
+        if initial {
+            let subsystems = wrapper.core.get_subsystem_info();
+
+            if !subsystems.is_empty() {
+                let subsystems = RawSubsystemInfo::new(subsystems);
+
+                if ctx.set_subsystem_info(subsystems.as_slice()).is_err() {
+                    #[cfg(feature = "log")]
+                    log::warn!("Failed to set subsystem info");
+                }
+
+                wrapper.subsystem_info.replace(subsystems);
+            }
+        }
+
+        // Prefer the extended interface, which adds `set_initial_image`,
+        // `get_image_path` and `get_image_label`
+        if initial && wrapper.core.disk_control().is_some() {
+            let ctx: GenericContext = (&ctx).into();
+
+            if ctx.enable_extended_disk_control_interface().is_err()
+                && ctx.enable_disk_control_interface().is_err()
+            {
+                #[cfg(feature = "log")]
+                log::warn!("Failed to set the disk control interface");
+            }
+        }
+
         return wrapper.core.on_set_environment(initial, &mut ctx);
     }
 
-    panic!("retro_set_environment: Core has not been initialized yet!");
+    core_not_initialized("retro_set_environment");
 }
 
 /// Sets the device type to be used for player `port`.
@@ -443,18 +702,20 @@ pub unsafe extern "C" fn retro_set_controller_port_device(
     #[cfg(feature = "log")]
     log::trace!("retro_set_controller_port_device(port = {port}, device = {device})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        let mut ctx = GenericContext::new(
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let mut ctx = SetControllerPortDeviceContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
         );
 
-        return wrapper
-            .core
-            .on_set_controller_port_device(port, device, &mut ctx);
+        return wrapper.core.on_set_controller_port_device(
+            port,
+            DeviceType::from_raw(device),
+            &mut ctx,
+        );
     }
 
-    panic!("retro_set_controller_port_device: Core has not been initialized yet!");
+    core_not_initialized("retro_set_controller_port_device");
 }
 
 /// Runs the game for one frame.
@@ -465,8 +726,8 @@ pub unsafe extern "C" fn retro_run() {
     #[cfg(feature = "log")]
     log::trace!("retro_run()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        if environment::get_variable_update(wrapper.environment_callback) {
+    if let Some(wrapper) = instance().as_deref_mut() {
+        if environment::get_variable_update(wrapper.environment_callback).unwrap_or(false) {
             let mut ctx = OptionsChangedContext::new(
                 &wrapper.environment_callback,
                 Arc::clone(&wrapper.interfaces),
@@ -479,6 +740,9 @@ pub unsafe extern "C" fn retro_run() {
             (callback)();
         }
 
+        let frame_delta = wrapper.frame_delta.take();
+        wrapper.interfaces.write().unwrap().frame_delta = frame_delta;
+
         let mut ctx = RunContext {
             environment_callback: &wrapper.environment_callback,
             interfaces: Arc::clone(&wrapper.interfaces),
@@ -494,14 +758,81 @@ pub unsafe extern "C" fn retro_run() {
             last_width: &mut wrapper.last_width,
             last_height: &mut wrapper.last_height,
             last_pitch: &mut wrapper.last_pitch,
+            last_frame: &mut wrapper.last_frame,
 
             supports_bitmasks: wrapper.supports_bitmasks,
         };
 
-        return wrapper.core.on_run(&mut ctx, wrapper.frame_delta.take());
+        let frame_time = ctx.frame_time();
+        wrapper.core.on_run(&mut ctx, frame_time);
+        wrapper.interfaces.write().unwrap().frame_count += 1;
+
+        return;
     }
 
-    panic!("retro_run: Core has not been initialized yet!");
+    core_not_initialized("retro_run");
+}
+
+/// Notifies the [`Core`] when the current game should be reset.
+///
+/// Also resets [`RunContext::frame_count`].
+#[no_mangle]
+pub unsafe extern "C" fn retro_reset() {
+    #[cfg(feature = "log")]
+    log::trace!("retro_reset()");
+
+    if let Some(wrapper) = instance().as_deref_mut() {
+        wrapper.interfaces.write().unwrap().frame_count = 0;
+
+        let mut ctx = ResetContext::new(
+            &wrapper.environment_callback,
+            Arc::clone(&wrapper.interfaces),
+        );
+
+        return wrapper.core.on_reset(&mut ctx);
+    }
+
+    core_not_initialized("retro_reset");
+}
+
+/// Called when the frontend needs to know how large a buffer to allocate for save states.
+///
+/// The size gets cached unless [`Core::serialize_size_quirks`] contains
+/// [`SerializationQuirks::CORE_VARIABLE_SIZE`].
+///
+/// See also [`rust_libretro_sys::retro_serialize_size`].
+#[no_mangle]
+pub unsafe extern "C" fn retro_serialize_size() -> usize {
+    #[cfg(feature = "log")]
+    log::trace!("retro_serialize_size()");
+
+    if let Some(wrapper) = instance().as_deref_mut() {
+        if let Some(size) = wrapper.serialize_size {
+            return size;
+        }
+
+        let mut ctx = GenericContext::new(
+            &wrapper.environment_callback,
+            Arc::clone(&wrapper.interfaces),
+        );
+
+        let size = wrapper.core.get_serialize_size(&mut ctx);
+
+        // The frontend might not support variable sizes, but the core still needs them
+        if size != 0
+            && !wrapper
+                .core
+                .serialize_size_quirks()
+                .contains(SerializationQuirks::CORE_VARIABLE_SIZE)
+        {
+            wrapper.serialize_size = Some(size);
+        }
+
+        return size;
+    }
+
+    core_not_initialized("retro_serialize_size");
+    0
 }
 
 /// Called by the frontend when the [`Core`]s state should be serialized (“save state”).
@@ -520,11 +851,26 @@ pub unsafe extern "C" fn retro_serialize(data: *mut std::os::raw::c_void, size:
         return false;
     }
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        let mut ctx = GenericContext::new(
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let save_state_context = get_save_state_context(wrapper);
+        detect_netplay(wrapper, save_state_context);
+
+        if !check_runahead(wrapper, save_state_context) {
+            return false;
+        }
+
+        if std::mem::take(&mut wrapper.interfaces.write().unwrap().skip_serialization) {
+            #[cfg(feature = "log")]
+            log::debug!("retro_serialize: skipped by the core");
+
+            return false;
+        }
+
+        let mut ctx = SerializeContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
-        );
+        )
+        .with_save_state_context(save_state_context);
 
         // Convert the given buffer into a proper slice
         let slice = std::slice::from_raw_parts_mut(data as *mut u8, size);
@@ -532,7 +878,8 @@ pub unsafe extern "C" fn retro_serialize(data: *mut std::os::raw::c_void, size:
         return wrapper.core.on_serialize(slice, &mut ctx);
     }
 
-    panic!("retro_serialize: Core has not been initialized yet!");
+    core_not_initialized("retro_serialize");
+    false
 }
 
 /// Called by the frontend when a “save state” should be loaded.
@@ -551,11 +898,19 @@ pub unsafe extern "C" fn retro_unserialize(data: *const std::os::raw::c_void, si
         return false;
     }
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        let mut ctx = GenericContext::new(
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let save_state_context = get_save_state_context(wrapper);
+        detect_netplay(wrapper, save_state_context);
+
+        if !check_runahead(wrapper, save_state_context) {
+            return false;
+        }
+
+        let mut ctx = UnserializeContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
-        );
+        )
+        .with_save_state_context(save_state_context);
 
         // Convert the given buffer into a proper slice
         let slice = std::slice::from_raw_parts_mut(data as *mut u8, size);
@@ -563,13 +918,90 @@ pub unsafe extern "C" fn retro_unserialize(data: *const std::os::raw::c_void, si
         return wrapper.core.on_unserialize(slice, &mut ctx);
     }
 
-    panic!("retro_unserialize: Core has not been initialized yet!");
+    core_not_initialized("retro_unserialize");
+    false
+}
+
+/// Queries the frontend features cached for [`GenericContext::capabilities`].
+unsafe fn query_capabilities(wrapper: &CoreWrapper) -> Capabilities {
+    let callback = wrapper.environment_callback;
+
+    Capabilities {
+        can_dupe: wrapper.can_dupe,
+        supports_bitmasks: wrapper.supports_bitmasks,
+        supports_core_options_v2: environment::get_core_options_version(callback).unwrap_or(0) >= 2,
+        supports_message_ext: environment::get_message_interface_version(callback).unwrap_or(0)
+            >= 1,
+    }
+}
+
+/// Asks the frontend why it saves or loads a state, see [`SerializeContext::save_state_context`].
+///
+/// The query is unstable, so without the `unstable-env-commands` feature
+/// every save state is treated as [`SaveStateContext::Normal`].
+unsafe fn get_save_state_context(wrapper: &CoreWrapper) -> SaveStateContext {
+    #[cfg(feature = "unstable-env-commands")]
+    {
+        environment::get_savestate_context(wrapper.environment_callback)
+            .map_or(SaveStateContext::Normal, SaveStateContext::from)
+    }
+
+    #[cfg(not(feature = "unstable-env-commands"))]
+    {
+        let _ = wrapper;
+        SaveStateContext::Normal
+    }
+}
+
+/// Calls [`Core::on_netplay_started`] the first time the frontend
+/// asks for a save state in the context of rollback netplay.
+unsafe fn detect_netplay(wrapper: &mut CoreWrapper, context: SaveStateContext) {
+    if context != SaveStateContext::RollbackNetplay {
+        return;
+    }
+
+    if std::mem::replace(
+        &mut wrapper.interfaces.write().unwrap().netplay_active,
+        true,
+    ) {
+        return;
+    }
+
+    #[cfg(feature = "log")]
+    log::debug!("Rollback netplay started");
+
+    let mut ctx = NetplayContext::new(
+        &wrapper.environment_callback,
+        Arc::clone(&wrapper.interfaces),
+    );
+
+    wrapper.core.on_netplay_started(&mut ctx);
+}
+
+/// Notifies the [`Core`] when all cheats should be unapplied.
+#[no_mangle]
+pub unsafe extern "C" fn retro_cheat_reset() {
+    #[cfg(feature = "log")]
+    log::trace!("retro_cheat_reset()");
+
+    if let Some(wrapper) = instance().as_deref_mut() {
+        wrapper.interfaces.write().unwrap().cheats.reset();
+
+        let mut ctx = GenericContext::new(
+            &wrapper.environment_callback,
+            Arc::clone(&wrapper.interfaces),
+        );
+
+        return wrapper.core.on_cheat_reset(&mut ctx);
+    }
+
+    core_not_initialized("retro_cheat_reset");
 }
 
 /// Called by the frontend whenever a cheat should be applied.
 ///
 /// The format is core-specific but this function lacks a return value,
-/// so a [`Core`] can’t tell the frontend if it failed to parse a code.
+/// so errors returned by [`Core::on_cheat_set`] get shown to the user instead.
 #[no_mangle]
 pub unsafe extern "C" fn retro_cheat_set(
     index: std::os::raw::c_uint,
@@ -586,7 +1018,7 @@ pub unsafe extern "C" fn retro_cheat_set(
         return;
     }
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = GenericContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
@@ -595,15 +1027,25 @@ pub unsafe extern "C" fn retro_cheat_set(
         // Wrap the pointer into a `CStr`.
         // This assumes the pointer is valid and ends on a null byte.
         //
-        // For now we’ll let the core handle conversion to Rust `str` or `String`,
-        // as the lack of documentation doesn’t make it clear if the returned string
-        // is encoded as valid UTF-8.
-        let code = CStr::from_ptr(code);
+        // libretro.h doesn’t specify the encoding of cheat codes,
+        // so anything that isn’t valid UTF-8 gets replaced.
+        let code = CStr::from_ptr(code).to_string_lossy();
+
+        wrapper
+            .interfaces
+            .write()
+            .unwrap()
+            .cheats
+            .set(index, enabled, &code);
+
+        if let Err(err) = wrapper.core.on_cheat_set(index, enabled, &code, &mut ctx) {
+            let _ = ctx.log_cheat_error(index, &err.to_string());
+        }
 
-        return wrapper.core.on_cheat_set(index, enabled, code, &mut ctx);
+        return;
     }
 
-    panic!("retro_cheat_set: Core has not been initialized yet!");
+    core_not_initialized("retro_cheat_set");
 }
 
 /// Called by the frontend when a game should be loaded.
@@ -614,7 +1056,7 @@ pub unsafe extern "C" fn retro_load_game(game: *const retro_game_info) -> bool {
     #[cfg(feature = "log")]
     log::trace!("retro_load_game(game_type = {game:#?})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = OptionsChangedContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
@@ -622,10 +1064,16 @@ pub unsafe extern "C" fn retro_load_game(game: *const retro_game_info) -> bool {
 
         wrapper.core.on_options_changed(&mut ctx);
 
+        // The save RAM of the previous game must not be answered for this one
+        wrapper.interfaces.write().unwrap().save_ram = None;
+
+        let game_data = game.as_ref().and_then(|game| GameInfo::new(game).data());
+
         let mut ctx = LoadGameContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
-        );
+        )
+        .with_game_data(game_data);
 
         let status = if game.is_null() {
             wrapper.core.on_load_game(None, &mut ctx)
@@ -633,6 +1081,17 @@ pub unsafe extern "C" fn retro_load_game(game: *const retro_game_info) -> bool {
             wrapper.core.on_load_game(Some(*game), &mut ctx)
         };
 
+        if status.is_ok() {
+            prepare_loaded_game(wrapper);
+
+            let mut ctx = GenericContext::new(
+                &wrapper.environment_callback,
+                Arc::clone(&wrapper.interfaces),
+            );
+
+            wrapper.core.on_cheevos_ready(&mut ctx);
+        }
+
         cfg_if::cfg_if! {
             if #[cfg(feature = "log")] {
                 match status {
@@ -649,7 +1108,77 @@ pub unsafe extern "C" fn retro_load_game(game: *const retro_game_info) -> bool {
         }
     }
 
-    panic!("retro_load_game: Core has not been initialized yet!");
+    core_not_initialized("retro_load_game");
+    false
+}
+
+/// Refuses save states for runahead if the [`Core`] does not support it,
+/// and calls [`Core::on_runahead_started`] the first time the frontend asks for one.
+///
+/// Returns whether the save state may be used.
+unsafe fn check_runahead(wrapper: &mut CoreWrapper, context: SaveStateContext) -> bool {
+    if !matches!(
+        context,
+        SaveStateContext::Runahead | SaveStateContext::RunaheadSameBinary
+    ) {
+        return true;
+    }
+
+    if !wrapper.core.supports_runahead() {
+        #[cfg(feature = "log")]
+        log::debug!("Refusing a save state for runahead, the core does not support it");
+
+        return false;
+    }
+
+    if std::mem::replace(
+        &mut wrapper.interfaces.write().unwrap().runahead_active,
+        true,
+    ) {
+        return true;
+    }
+
+    #[cfg(feature = "log")]
+    log::debug!("Runahead started");
+
+    let mut ctx = GenericContext::new(
+        &wrapper.environment_callback,
+        Arc::clone(&wrapper.interfaces),
+    );
+
+    wrapper.core.on_runahead_started(&mut ctx);
+
+    true
+}
+
+/// Passes [`Core::serialize_size_quirks`] to the frontend and forgets
+/// the save state size, netplay and runahead state and frame count of the previous game.
+unsafe fn prepare_loaded_game(wrapper: &mut CoreWrapper) {
+    wrapper.serialize_size = None;
+    wrapper.serialization_quirks = SerializationQuirks::empty();
+
+    {
+        let mut interfaces = wrapper.interfaces.write().unwrap();
+        interfaces.netplay_active = false;
+        interfaces.runahead_active = false;
+        interfaces.frame_count = 0;
+    }
+
+    let quirks = wrapper.core.serialize_size_quirks();
+    if quirks.is_empty() {
+        return;
+    }
+
+    match environment::set_serialization_quirks(wrapper.environment_callback, quirks) {
+        Ok(accepted) => wrapper.serialization_quirks = accepted,
+        Err(_err) => {
+            // Without the frontend’s support we still must not cache variable sizes
+            wrapper.serialization_quirks = quirks;
+
+            #[cfg(feature = "log")]
+            log::warn!("Failed to set serialization quirks: {_err}");
+        }
+    }
 }
 
 /// See [`rust_libretro_sys::retro_load_game_special`].
@@ -671,7 +1200,7 @@ pub unsafe extern "C" fn retro_load_game_special(
         return false;
     }
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = OptionsChangedContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
@@ -679,15 +1208,31 @@ pub unsafe extern "C" fn retro_load_game_special(
 
         wrapper.core.on_options_changed(&mut ctx);
 
+        // The save RAM of the previous game must not be answered for this one
+        wrapper.interfaces.write().unwrap().save_ram = None;
+
+        let games = GameInfo::from_slice(std::slice::from_raw_parts(info, num_info));
         let mut ctx = LoadGameSpecialContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
-        );
+        )
+        .with_games(games);
 
         let status = wrapper
             .core
             .on_load_game_special(game_type, info, num_info, &mut ctx);
 
+        if status.is_ok() {
+            prepare_loaded_game(wrapper);
+
+            let mut ctx = GenericContext::new(
+                &wrapper.environment_callback,
+                Arc::clone(&wrapper.interfaces),
+            );
+
+            wrapper.core.on_cheevos_ready(&mut ctx);
+        }
+
         cfg_if::cfg_if! {
             if #[cfg(feature = "log")] {
                 match status {
@@ -704,7 +1249,8 @@ pub unsafe extern "C" fn retro_load_game_special(
         }
     }
 
-    panic!("retro_load_game_special: Core has not been initialized yet!");
+    core_not_initialized("retro_load_game_special");
+    false
 }
 
 /// Returns a mutable pointer to queried memory type.
@@ -718,16 +1264,30 @@ pub unsafe extern "C" fn retro_get_memory_data(
     #[cfg(feature = "log")]
     log::trace!("retro_get_memory_data(id = {id})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = GenericContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
         );
 
+        if let Some(save_ram) = registered_save_ram(wrapper, id) {
+            return save_ram.as_mut_ptr();
+        }
+
         return wrapper.core.get_memory_data(id, &mut ctx);
     }
 
-    panic!("retro_get_memory_data: Core has not been initialized yet!");
+    core_not_initialized("retro_get_memory_data");
+    std::ptr::null_mut()
+}
+
+/// Returns the [`SaveRam`] registered with [`LoadGameContext::set_save_ram`] if `id` asks for it.
+fn registered_save_ram(wrapper: &CoreWrapper, id: std::os::raw::c_uint) -> Option<SaveRam> {
+    if id != RETRO_MEMORY_SAVE_RAM {
+        return None;
+    }
+
+    wrapper.interfaces.read().unwrap().save_ram.clone()
 }
 
 /// Returns the size (in bytes) of the queried memory type.
@@ -739,16 +1299,21 @@ pub unsafe extern "C" fn retro_get_memory_size(id: std::os::raw::c_uint) -> usiz
     #[cfg(feature = "log")]
     log::trace!("retro_get_memory_size(id = {id})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = GenericContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
         );
 
+        if let Some(save_ram) = registered_save_ram(wrapper, id) {
+            return save_ram.len();
+        }
+
         return wrapper.core.get_memory_size(id, &mut ctx);
     }
 
-    panic!("retro_get_memory_size: Core has not been initialized yet!");
+    core_not_initialized("retro_get_memory_size");
+    0
 }
 
 /*****************************************************************************\
@@ -772,7 +1337,14 @@ pub unsafe extern "C" fn retro_keyboard_callback_fn(
     #[cfg(feature = "log")]
     log::trace!("retro_keyboard_callback_fn(down = {down}, keycode = {keycode}, character = {character}, key_modifiers = {key_modifiers})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_keyboard_callback_fn(
+        down,
+        keycode,
+        character,
+        key_modifiers
+    ));
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         // Not sure why bindgen uses `c_int32` as value type
         // for the newtype enum on Windows but `c_uint32` on Unix.
         cfg_if::cfg_if! {
@@ -783,23 +1355,25 @@ pub unsafe extern "C" fn retro_keyboard_callback_fn(
 
         return wrapper.core.on_keyboard_event(
             down,
-            retro_key(keycode),
+            Key::from(retro_key(keycode)),
             character,
-            retro_mod(key_modifiers.into()),
+            KeyMods::from_bits_truncate(key_modifiers),
         );
     }
 
-    panic!("retro_keyboard_callback_fn: Core has not been initialized yet!");
+    core_not_initialized("retro_keyboard_callback_fn");
 }
 
-/// **TODO:** Documentation.
+/// Notifies the [`Core`] when the hardware rendering context has been (re-)created.
 #[no_mangle]
 pub unsafe extern "C" fn retro_hw_context_reset_callback() {
     #[cfg(feature = "log")]
     log::trace!("retro_hw_context_reset_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        let mut ctx = GenericContext::new(
+    defer_nested_call!(retro_hw_context_reset_callback());
+
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let mut ctx = HwContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
         );
@@ -807,17 +1381,19 @@ pub unsafe extern "C" fn retro_hw_context_reset_callback() {
         return wrapper.core.on_hw_context_reset(&mut ctx);
     }
 
-    panic!("retro_hw_context_reset_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_hw_context_reset_callback");
 }
 
-/// **TODO:** Documentation.
+/// Notifies the [`Core`] when the hardware rendering context is about to be destroyed.
 #[no_mangle]
 pub unsafe extern "C" fn retro_hw_context_destroyed_callback() {
     #[cfg(feature = "log")]
     log::trace!("retro_hw_context_destroyed_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        let mut ctx = GenericContext::new(
+    defer_nested_call!(retro_hw_context_destroyed_callback());
+
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let mut ctx = HwContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
         );
@@ -825,75 +1401,100 @@ pub unsafe extern "C" fn retro_hw_context_destroyed_callback() {
         return wrapper.core.on_hw_context_destroyed(&mut ctx);
     }
 
-    panic!("retro_hw_context_destroyed_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_hw_context_destroyed_callback");
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::set_eject_state`](core::DiskControl::set_eject_state).
 #[no_mangle]
 pub unsafe extern "C" fn retro_set_eject_state_callback(ejected: bool) -> bool {
     #[cfg(feature = "log")]
     log::trace!("retro_set_eject_state_callback(ejected = {ejected})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_set_eject_state(ejected);
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.set_eject_state(ejected),
+            #[allow(deprecated)]
+            None => wrapper.core.on_set_eject_state(ejected),
+        };
     }
 
-    panic!("retro_set_eject_state_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_set_eject_state_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::get_eject_state`](core::DiskControl::get_eject_state).
 #[no_mangle]
 pub unsafe extern "C" fn retro_get_eject_state_callback() -> bool {
     #[cfg(feature = "log")]
     log::trace!("retro_get_eject_state_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_get_eject_state();
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.get_eject_state(),
+            #[allow(deprecated)]
+            None => wrapper.core.on_get_eject_state(),
+        };
     }
 
-    panic!("retro_get_eject_state_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_get_eject_state_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::get_image_index`](core::DiskControl::get_image_index).
 #[no_mangle]
 pub unsafe extern "C" fn retro_get_image_index_callback() -> ::std::os::raw::c_uint {
     #[cfg(feature = "log")]
     log::trace!("retro_get_image_index_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_get_image_index();
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.get_image_index(),
+            #[allow(deprecated)]
+            None => wrapper.core.on_get_image_index(),
+        };
     }
 
-    panic!("retro_get_image_index_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_get_image_index_callback");
+    0
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::set_image_index`](core::DiskControl::set_image_index).
 #[no_mangle]
 pub unsafe extern "C" fn retro_set_image_index_callback(index: ::std::os::raw::c_uint) -> bool {
     #[cfg(feature = "log")]
-    log::trace!("retro_set_image_index_callback()");
+    log::trace!("retro_set_image_index_callback(index = {index})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_set_image_index(index);
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.set_image_index(index),
+            #[allow(deprecated)]
+            None => wrapper.core.on_set_image_index(index),
+        };
     }
 
-    panic!("retro_set_image_index_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_set_image_index_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::get_num_images`](core::DiskControl::get_num_images).
 #[no_mangle]
 pub unsafe extern "C" fn retro_get_num_images_callback() -> ::std::os::raw::c_uint {
     #[cfg(feature = "log")]
     log::trace!("retro_get_num_images_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_get_num_images();
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.get_num_images(),
+            #[allow(deprecated)]
+            None => wrapper.core.on_get_num_images(),
+        };
     }
 
-    panic!("retro_get_num_images_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_get_num_images_callback");
+    0
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::replace_image_index`](core::DiskControl::replace_image_index).
 #[no_mangle]
 pub unsafe extern "C" fn retro_replace_image_index_callback(
     index: ::std::os::raw::c_uint,
@@ -902,27 +1503,37 @@ pub unsafe extern "C" fn retro_replace_image_index_callback(
     #[cfg(feature = "log")]
     log::trace!("retro_replace_image_index_callback(index = {index}, info = {info:#?})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_replace_image_index(index, info);
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.replace_image_index(index, info.as_ref()),
+            #[allow(deprecated)]
+            None => wrapper.core.on_replace_image_index(index, info),
+        };
     }
 
-    panic!("retro_replace_image_index_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_replace_image_index_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::add_image_index`](core::DiskControl::add_image_index).
 #[no_mangle]
 pub unsafe extern "C" fn retro_add_image_index_callback() -> bool {
     #[cfg(feature = "log")]
     log::trace!("retro_add_image_index_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_add_image_index();
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.add_image_index(),
+            #[allow(deprecated)]
+            None => wrapper.core.on_add_image_index(),
+        };
     }
 
-    panic!("retro_add_image_index_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_add_image_index_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::set_initial_image`](core::DiskControl::set_initial_image).
 #[no_mangle]
 pub unsafe extern "C" fn retro_set_initial_image_callback(
     index: ::std::os::raw::c_uint,
@@ -931,16 +1542,25 @@ pub unsafe extern "C" fn retro_set_initial_image_callback(
     #[cfg(feature = "log")]
     log::trace!("retro_set_initial_image_callback(index = {index}, path = {path:#?})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper
-            .core
-            .on_set_initial_image(index, CStr::from_ptr(path));
+    if path.is_null() {
+        return false;
     }
 
-    panic!("retro_set_initial_image_callback: Core has not been initialized yet!");
+    if let Some(wrapper) = instance().as_deref_mut() {
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.set_initial_image(index, CStr::from_ptr(path)),
+            #[allow(deprecated)]
+            None => wrapper
+                .core
+                .on_set_initial_image(index, CStr::from_ptr(path)),
+        };
+    }
+
+    core_not_initialized("retro_set_initial_image_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::get_image_path`](core::DiskControl::get_image_path).
 #[no_mangle]
 pub unsafe extern "C" fn retro_get_image_path_callback(
     index: ::std::os::raw::c_uint,
@@ -950,24 +1570,29 @@ pub unsafe extern "C" fn retro_get_image_path_callback(
     #[cfg(feature = "log")]
     log::trace!("retro_get_image_path_callback(index = {index}, path = {path:#?}, len = {len})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        match wrapper.core.on_get_image_path(index) {
-            Some(image_path) => {
-                let image_path = image_path.as_bytes();
-                let buf = std::slice::from_raw_parts_mut(path as *mut u8, len);
-                let len = image_path.len().min(buf.len());
+    if path.is_null() || len == 0 {
+        return false;
+    }
 
-                buf[..len].copy_from_slice(&image_path[..len]);
-                return true;
-            }
-            None => return false,
-        }
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let buf = std::slice::from_raw_parts_mut(path, len);
+
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.get_image_path(index, buf).is_some(),
+            #[allow(deprecated)]
+            None => wrapper
+                .core
+                .on_get_image_path(index)
+                .and_then(|path| copy_str_to_buffer(&path.to_string_lossy(), buf))
+                .is_some(),
+        };
     }
 
-    panic!("retro_get_image_path_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_get_image_path_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`DiskControl::get_image_label`](core::DiskControl::get_image_label).
 #[no_mangle]
 pub unsafe extern "C" fn retro_get_image_label_callback(
     index: ::std::os::raw::c_uint,
@@ -977,35 +1602,46 @@ pub unsafe extern "C" fn retro_get_image_label_callback(
     #[cfg(feature = "log")]
     log::trace!("retro_get_image_label_callback(index = {index}, label = {label:#?}, len = {len})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        match wrapper.core.on_get_image_label(index) {
-            Some(image_label) => {
-                let image_label = image_label.as_bytes();
-                let buf = std::slice::from_raw_parts_mut(label as *mut u8, len);
-                let len = image_label.len().min(buf.len());
+    if label.is_null() || len == 0 {
+        return false;
+    }
 
-                buf[..len].copy_from_slice(&image_label[..len]);
-                return true;
-            }
-            None => return false,
-        }
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let buf = std::slice::from_raw_parts_mut(label, len);
+
+        return match wrapper.core.disk_control() {
+            Some(disk_control) => disk_control.get_image_label(index, buf).is_some(),
+            #[allow(deprecated)]
+            None => wrapper
+                .core
+                .on_get_image_label(index)
+                .and_then(|label| copy_str_to_buffer(&label.to_string_lossy(), buf))
+                .is_some(),
+        };
     }
 
-    panic!("retro_get_image_label_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_get_image_label_callback");
+    false
 }
 
-/// **TODO:** Documentation
+/// Receives the time in microseconds that passed since the previous call to [`retro_run`],
+/// once [`LoadGameContext::enable_frame_time_callback`] succeeded.
+///
+/// The frontend calls this right before [`retro_run`], which hands the value to
+/// [`Core::on_run`] and makes it available through [`RunContext::frame_delta`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_frame_time_callback_fn(usec: retro_usec_t) {
     #[cfg(feature = "log")]
     log::trace!("retro_frame_time_callback_fn(usec = {usec})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_frame_time_callback_fn(usec));
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         wrapper.frame_delta = Some(usec);
         return;
     }
 
-    panic!("retro_frame_time_callback_fn: Core has not been initialized yet!");
+    core_not_initialized("retro_frame_time_callback_fn");
 }
 
 /// Notifies the [`Core`] when audio data should be written.
@@ -1015,7 +1651,9 @@ pub unsafe extern "C" fn retro_audio_callback_fn() {
     // #[cfg(feature = "log")]
     // log::trace!("retro_audio_callback_fn()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    // May be called from the frontend's audio thread, which must not touch the borrow state,
+    // see the exception documented on `SingleThreadCell`
+    if let Some(wrapper) = RETRO_INSTANCE.get_mut().as_mut() {
         let mut ctx = AudioContext {
             environment_callback: &wrapper.environment_callback,
             interfaces: Arc::clone(&wrapper.interfaces),
@@ -1027,7 +1665,7 @@ pub unsafe extern "C" fn retro_audio_callback_fn() {
         return wrapper.core.on_write_audio(&mut ctx);
     }
 
-    panic!("retro_audio_callback_fn: Core has not been initialized yet!");
+    core_not_initialized("retro_audio_callback_fn");
 }
 
 /// Notifies the [`Core`] about the state of the frontend’s audio system.
@@ -1046,14 +1684,16 @@ pub unsafe extern "C" fn retro_audio_set_state_callback_fn(enabled: bool) {
     #[cfg(feature = "log")]
     log::trace!("retro_audio_set_state_callback_fn(enabled = {enabled})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_audio_set_state_callback_fn(enabled));
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         return wrapper.core.on_audio_set_state(enabled);
     }
 
-    panic!("retro_audio_set_state_callback_fn: Core has not been initialized yet!");
+    core_not_initialized("retro_audio_set_state_callback_fn");
 }
 
-/// **TODO:** Documentation
+/// Forwards a raw camera frame to [`Core::on_camera_frame_raw`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_camera_frame_raw_framebuffer_callback(
     buffer: *const u32,
@@ -1061,22 +1701,30 @@ pub unsafe extern "C" fn retro_camera_frame_raw_framebuffer_callback(
     height: ::std::os::raw::c_uint,
     pitch: usize,
 ) {
-    let buffer_size = height as usize * pitch;
-    let buffer = std::slice::from_raw_parts(buffer, buffer_size);
-
     #[cfg(feature = "log")]
-    log::trace!("retro_camera_frame_raw_framebuffer_callback(buffer = &[u32; {}], width = {width}, height = {height}, pitch = {pitch})", buffer.len());
+    log::trace!("retro_camera_frame_raw_framebuffer_callback(buffer = {buffer:#?}, width = {width}, height = {height}, pitch = {pitch})");
+
+    if buffer.is_null() {
+        return;
+    }
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    // `pitch` is given in bytes and the last line may end right after its `width` pixels
+    let buffer_size = match (height as usize).checked_sub(1) {
+        Some(last_line) => last_line * (pitch / std::mem::size_of::<u32>()) + width as usize,
+        None => 0,
+    };
+    let buffer = std::slice::from_raw_parts(buffer, buffer_size);
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         return wrapper
             .core
-            .on_camera_raw_framebuffer(buffer, width, height, pitch);
+            .on_camera_frame_raw(buffer, width, height, pitch);
     }
 
-    panic!("retro_camera_frame_raw_framebuffer_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_camera_frame_raw_framebuffer_callback");
 }
 
-/// **TODO:** Documentation
+/// Forwards an OpenGL camera texture to [`Core::on_camera_frame_gl_texture`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_camera_frame_opengl_texture_callback(
     texture_id: ::std::os::raw::c_uint,
@@ -1084,29 +1732,33 @@ pub unsafe extern "C" fn retro_camera_frame_opengl_texture_callback(
     affine: *const f32,
 ) {
     #[cfg(feature = "log")]
-    log::trace!("retro_camera_frame_opengl_texture_callback(texture_id = {texture_id}, texture_target = {texture_target}, affine = {:#?})", std::slice::from_raw_parts(affine, 3 * 3));
+    log::trace!("retro_camera_frame_opengl_texture_callback(texture_id = {texture_id}, texture_target = {texture_target}, affine = {affine:#?})");
+
+    if affine.is_null() {
+        return;
+    }
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    if let Some(wrapper) = instance().as_deref_mut() {
         // Packed 3x3 column-major matrix
-        let matrix = std::slice::from_raw_parts(affine, 3 * 3);
-        // Convert to fixed size array; we know it contains 9 elements
-        let matrix: &[f32; 3 * 3] = matrix.try_into().unwrap();
+        let matrix = &*(affine as *const [f32; 3 * 3]);
 
         return wrapper
             .core
-            .on_camera_gl_texture(texture_id, texture_target, matrix);
+            .on_camera_frame_gl_texture(texture_id, texture_target, matrix);
     }
 
-    panic!("retro_camera_frame_opengl_texture_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_camera_frame_opengl_texture_callback");
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`Core::on_camera_initialized`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_camera_initialized_callback() {
     #[cfg(feature = "log")]
     log::trace!("retro_camera_initialized_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_camera_initialized_callback());
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = GenericContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
@@ -1115,16 +1767,18 @@ pub unsafe extern "C" fn retro_camera_initialized_callback() {
         return wrapper.core.on_camera_initialized(&mut ctx);
     }
 
-    panic!("retro_camera_initialized_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_camera_initialized_callback");
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`Core::on_camera_deinitialized`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_camera_deinitialized_callback() {
     #[cfg(feature = "log")]
     log::trace!("retro_camera_deinitialized_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_camera_deinitialized_callback());
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = GenericContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
@@ -1133,54 +1787,50 @@ pub unsafe extern "C" fn retro_camera_deinitialized_callback() {
         return wrapper.core.on_camera_deinitialized(&mut ctx);
     }
 
-    panic!("retro_camera_deinitialized_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_camera_deinitialized_callback");
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`Core::on_location_initialized`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_location_lifetime_status_initialized_callback() {
     #[cfg(feature = "log")]
     log::trace!("retro_location_lifetime_status_initialized_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_location_lifetime_status_initialized_callback());
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = GenericContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
         );
 
-        return wrapper
-            .core
-            .on_location_lifetime_status_initialized(&mut ctx);
+        return wrapper.core.on_location_initialized(&mut ctx);
     }
 
-    panic!(
-        "retro_location_lifetime_status_initialized_callback: Core has not been initialized yet!"
-    );
+    core_not_initialized("retro_location_lifetime_status_initialized_callback");
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`Core::on_location_deinitialized`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_location_lifetime_status_deinitialized_callback() {
     #[cfg(feature = "log")]
     log::trace!("retro_location_lifetime_status_deinitialized_callback()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_location_lifetime_status_deinitialized_callback());
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         let mut ctx = GenericContext::new(
             &wrapper.environment_callback,
             Arc::clone(&wrapper.interfaces),
         );
 
-        return wrapper
-            .core
-            .on_location_lifetime_status_deinitialized(&mut ctx);
+        return wrapper.core.on_location_deinitialized(&mut ctx);
     }
 
-    panic!(
-        "retro_location_lifetime_status_deinitialized_callback: Core has not been initialized yet!"
-    );
+    core_not_initialized("retro_location_lifetime_status_deinitialized_callback");
 }
 
-/// **TODO:** Documentation
+/// Looks up `sym` using [`Core::get_proc_address`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_get_proc_address_callback(
     sym: *const ::std::os::raw::c_char,
@@ -1188,14 +1838,20 @@ pub unsafe extern "C" fn retro_get_proc_address_callback(
     #[cfg(feature = "log")]
     log::trace!("retro_get_proc_address_callback({sym:#?})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_get_proc_address(CStr::from_ptr(sym));
+    let sym = match get_str_from_pointer(sym) {
+        Some(sym) => sym,
+        None => return None,
+    };
+
+    if let Some(wrapper) = instance().as_deref() {
+        return wrapper.core.get_proc_address(sym);
     }
 
-    panic!("retro_get_proc_address_callback: Core has not been initialized yet!");
+    core_not_initialized("retro_get_proc_address_callback");
+    None
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`Core::on_audio_buffer_status`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_audio_buffer_status_callback_fn(
     active: bool,
@@ -1205,24 +1861,45 @@ pub unsafe extern "C" fn retro_audio_buffer_status_callback_fn(
     #[cfg(feature = "log")]
     log::trace!("retro_audio_buffer_status_callback_fn(active = {active}, occupancy = {occupancy}, underrun_likely = {underrun_likely})");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
+    defer_nested_call!(retro_audio_buffer_status_callback_fn(
+        active,
+        occupancy,
+        underrun_likely
+    ));
+
+    if let Some(wrapper) = instance().as_deref_mut() {
         return wrapper
             .core
             .on_audio_buffer_status(active, occupancy, underrun_likely);
     }
 
-    panic!("retro_audio_buffer_status_callback_fn: Core has not been initialized yet!");
+    core_not_initialized("retro_audio_buffer_status_callback_fn");
 }
 
-/// **TODO:** Documentation
+/// Forwards to [`Core::on_core_options_update_display`].
 #[no_mangle]
 pub unsafe extern "C" fn retro_core_options_update_display_callback_fn() -> bool {
     #[cfg(feature = "log")]
     log::trace!("retro_core_options_update_display_callback_fn()");
 
-    if let Some(wrapper) = RETRO_INSTANCE.as_mut() {
-        return wrapper.core.on_core_options_update_display();
+    // The result only matters for calls from the options menu, so a nested call
+    // caused by the core changing options itself can run afterwards
+    if RETRO_INSTANCE.is_borrowed() {
+        RETRO_INSTANCE.defer(|| {
+            unsafe { retro_core_options_update_display_callback_fn() };
+        });
+        return false;
+    }
+
+    if let Some(wrapper) = instance().as_deref_mut() {
+        let mut ctx = OptionsChangedContext::new(
+            &wrapper.environment_callback,
+            Arc::clone(&wrapper.interfaces),
+        );
+
+        return wrapper.core.on_core_options_update_display(&mut ctx);
     }
 
-    panic!("retro_core_options_update_display_callback_fn: Core has not been initialized yet!");
+    core_not_initialized("retro_core_options_update_display_callback_fn");
+    false
 }
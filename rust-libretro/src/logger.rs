@@ -2,37 +2,94 @@
 use super::*;
 use env_logger::filter::{Builder as FilterBuilder, Filter};
 use log::{Level, Metadata, Record};
-use std::io::Write;
+use std::{
+    io::Write,
+    sync::{Mutex, PoisonError, RwLock},
+};
 
 pub struct RetroLogger {
-    callback: retro_log_callback,
+    callback: RwLock<retro_log_callback>,
     filter: Filter,
+
+    /// Receives the messages if the frontend provides no log callback.
+    fallback: Mutex<Box<dyn Write + Send>>,
 }
 
 impl RetroLogger {
-    pub fn new(callback: retro_log_callback) -> Self {
+    /// Creates a logger that uses the filter directives of the `RUST_LIBRETRO_LOG`
+    /// or `RUST_LOG` environment variables (in that order), or `default_level` if neither is set.
+    pub fn new(callback: retro_log_callback, default_level: log::LevelFilter) -> Self {
+        Self::with_fallback(callback, default_level, std::io::stderr())
+    }
+
+    /// Like [`RetroLogger::new`], but writes the messages to `fallback` instead of `stderr`
+    /// if the frontend provides no log callback.
+    pub fn with_fallback(
+        callback: retro_log_callback,
+        default_level: log::LevelFilter,
+        fallback: impl Write + Send + 'static,
+    ) -> Self {
         let mut builder = FilterBuilder::new();
         let mut set_default_level = true;
 
-        if let Ok(s) = std::env::var("RUST_LOG") {
-            builder.parse(&s);
+        let directives = ["RUST_LIBRETRO_LOG", "RUST_LOG"]
+            .into_iter()
+            .filter_map(|variable| std::env::var(variable).ok())
+            .find(|directives| !directives.trim().is_empty());
 
-            // We assume that the user passed a valid filter when the
-            // environment variable was not empty.
-            if !s.trim().is_empty() {
-                set_default_level = false;
-            }
+        // We assume that the user passed a valid filter when the
+        // environment variable was not empty.
+        if let Some(directives) = directives {
+            builder.parse(&directives);
+            set_default_level = false;
         }
 
         // By default the env_logger filter defaults to the error level
         // if no directives have been set.
         if set_default_level {
-            builder.filter(None, log::LevelFilter::Trace);
+            builder.filter(None, default_level);
         }
 
         let filter = builder.build();
 
-        Self { callback, filter }
+        Self {
+            callback: RwLock::new(callback),
+            filter,
+            fallback: Mutex::new(Box::new(fallback)),
+        }
+    }
+
+    /// Replaces the log callback, e.g. once a new environment has been set.
+    ///
+    /// The global logger can only be installed once per process,
+    /// so later instances of the core keep using it with their own callback.
+    pub fn set_callback(&self, callback: retro_log_callback) {
+        *self
+            .callback
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = callback;
+    }
+
+    /// The most verbose level any message can pass the filter with.
+    pub fn max_level(&self) -> log::LevelFilter {
+        self.filter.filter()
+    }
+
+    /// Writes a message with a level prefix, used if the frontend provides no log callback.
+    fn write_fallback(
+        writer: &mut impl Write,
+        target: &str,
+        record: &Record,
+    ) -> std::io::Result<()> {
+        let level = match record.level() {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+            Level::Trace => "TRACE",
+        };
+
+        writeln!(writer, "[libretro {level}] [{target}] {}", record.args())
     }
 
     fn get_retro_log_level(level: Level) -> retro_log_level {
@@ -62,10 +119,12 @@ impl log::Log for RetroLogger {
             record.module_path().unwrap_or_default()
         };
 
-        if let Some(cb) = self.callback.log {
+        let callback = *self.callback.read().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(cb) = callback.log {
             let mut args: Vec<u8> = Vec::new();
 
-            if writeln!(args, "{}\0", record.args()).is_ok() {
+            if write!(args, "{}\0", record.args()).is_ok() {
                 let level = Self::get_retro_log_level(record.level());
                 let target = CString::new(target).unwrap();
 
@@ -82,24 +141,9 @@ impl log::Log for RetroLogger {
                 }
             }
         } else {
-            let level = match record.level() {
-                Level::Debug => "DEBUG",
-                Level::Info => "INFO",
-                Level::Warn => "WARN",
-                Level::Error => "ERROR",
-                Level::Trace => "TRACE",
-            };
-
-            let stderr = std::io::stderr();
-            let mut stderr_lock = stderr.lock();
-
-            let _ = writeln!(
-                stderr_lock,
-                "[libretro {}] [{}] {}",
-                level,
-                target,
-                record.args()
-            );
+            // Without a frontend callback the messages would be lost otherwise
+            let mut fallback = self.fallback.lock().unwrap_or_else(PoisonError::into_inner);
+            let _ = Self::write_fallback(&mut *fallback, target, record);
         }
     }
 
@@ -107,3 +151,29 @@ impl log::Log for RetroLogger {
         // Do nothing
     }
 }
+
+#[test]
+fn logger_falls_back_to_a_writer_without_callback() {
+    use crate::util::SharedBuffer;
+    use log::Log;
+
+    let output = SharedBuffer::default();
+    let logger = RetroLogger::with_fallback(
+        retro_log_callback { log: None },
+        log::LevelFilter::Info,
+        output.clone(),
+    );
+
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Loaded {} bytes", 42))
+            .level(Level::Warn)
+            .target("my_core")
+            .build(),
+    );
+
+    assert_eq!(
+        output.contents(),
+        "[libretro WARN] [my_core] Loaded 42 bytes\n"
+    );
+}
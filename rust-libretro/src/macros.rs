@@ -46,3 +46,69 @@ macro_rules! env_version {
         $crate::util::Version::new(major, minor, patch)
     }};
 }
+
+/// Implements [`Core::get_serialize_size`](crate::core::Core::get_serialize_size),
+/// [`Core::on_serialize`](crate::core::Core::on_serialize) and
+/// [`Core::on_unserialize`](crate::core::Core::on_unserialize)
+/// on top of [`SaveState`](crate::save_state::SaveState).
+///
+/// Invoke it inside of an `impl Core` block.
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! save_state_glue {
+    () => {
+        fn get_serialize_size(
+            &mut self,
+            _ctx: &mut $crate::contexts::GetSerializeSizeContext,
+        ) -> usize {
+            $crate::save_state::SaveState::save_state_size(self)
+        }
+
+        fn on_serialize(
+            &mut self,
+            slice: &mut [u8],
+            _ctx: &mut $crate::contexts::SerializeContext,
+        ) -> bool {
+            $crate::save_state::serialize_into(self, slice)
+        }
+
+        fn on_unserialize(
+            &mut self,
+            slice: &mut [u8],
+            _ctx: &mut $crate::contexts::UnserializeContext,
+        ) -> bool {
+            $crate::save_state::deserialize_from(self, slice)
+        }
+    };
+}
+
+/// Like [`save_state_glue!`](crate::save_state_glue), but uses [`SaveStateVersioned`](crate::save_state::SaveStateVersioned),
+/// which stores a header with the format version in front of the state.
+#[cfg(feature = "serde")]
+#[macro_export]
+macro_rules! versioned_save_state_glue {
+    () => {
+        fn get_serialize_size(
+            &mut self,
+            _ctx: &mut $crate::contexts::GetSerializeSizeContext,
+        ) -> usize {
+            $crate::save_state::SaveStateVersioned::versioned_save_state_size(self)
+        }
+
+        fn on_serialize(
+            &mut self,
+            slice: &mut [u8],
+            _ctx: &mut $crate::contexts::SerializeContext,
+        ) -> bool {
+            $crate::save_state::serialize_versioned_into(self, slice)
+        }
+
+        fn on_unserialize(
+            &mut self,
+            slice: &mut [u8],
+            _ctx: &mut $crate::contexts::UnserializeContext,
+        ) -> bool {
+            $crate::save_state::deserialize_versioned_from(self, slice)
+        }
+    };
+}
@@ -0,0 +1,142 @@
+//! Helpers for frame pacing and dynamic audio rate control.
+//!
+//! Forward [`Core::on_audio_buffer_status`] to [`FramePacer::set_audio_buffer_status`]
+//! after enabling it with [`GenericContext::set_audio_buffer_status_callback`],
+//! then use [`FramePacer::resampler_ratio`] in [`Core::on_run`] to stretch or shrink
+//! the generated audio, so that the frontend’s audio buffer neither runs dry nor overflows.
+//!
+//! [`RunContext::notify_audio_latency_target`] raises the frontend’s audio latency,
+//! which gives the pacer more room to work with.
+use super::*;
+
+/// The audio buffer state reported to [`Core::on_audio_buffer_status`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct AudioBufferStatus {
+    /// `true` if the audio buffer is currently in use.
+    pub active: bool,
+
+    /// The audio buffer occupancy as a percentage (0 - 100).
+    pub occupancy: u32,
+
+    /// `true` if the frontend expects an audio buffer underrun during the next frame.
+    pub underrun_likely: bool,
+}
+
+/// Combines the audio buffer status and the throttle state of the frontend
+/// into a recommended resampler ratio.
+#[derive(Debug, Clone)]
+pub struct FramePacer {
+    max_deviation: f32,
+    buffer_status: Option<AudioBufferStatus>,
+    pub(crate) throttle_state: Option<ThrottleState>,
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_DEVIATION)
+    }
+}
+
+impl FramePacer {
+    /// A deviation of half a percent, which is not audible as a change in pitch.
+    pub const DEFAULT_MAX_DEVIATION: f32 = 0.005;
+
+    /// Creates a pacer whose ratio never deviates from `1.0` by more than `max_deviation`.
+    pub fn new(max_deviation: f32) -> Self {
+        Self {
+            max_deviation: max_deviation.abs(),
+            buffer_status: None,
+            throttle_state: None,
+        }
+    }
+
+    /// Stores the values passed to [`Core::on_audio_buffer_status`].
+    pub fn set_audio_buffer_status(&mut self, active: bool, occupancy: u32, underrun_likely: bool) {
+        self.buffer_status = Some(AudioBufferStatus {
+            active,
+            occupancy: occupancy.min(100),
+            underrun_likely,
+        });
+    }
+
+    /// Returns the last audio buffer status, if any was reported.
+    pub fn audio_buffer_status(&self) -> Option<AudioBufferStatus> {
+        self.buffer_status
+    }
+
+    /// Stores the throttle state returned by [`RunContext::get_throttle_state`].
+    ///
+    /// [`RunContext::resampler_ratio`] does this automatically.
+    #[cfg(feature = "unstable-env-commands")]
+    pub fn set_throttle_state(&mut self, state: Option<ThrottleState>) {
+        self.throttle_state = state;
+    }
+
+    /// Returns `true` if the frontend expects an audio buffer underrun,
+    /// in which case skipping the rendering of the next frame helps to catch up.
+    pub fn should_skip_frame(&self) -> bool {
+        self.buffer_status
+            .is_some_and(|status| status.active && status.underrun_likely)
+    }
+
+    /// Returns the factor the number of generated audio frames should be multiplied with.
+    ///
+    /// Values above `1.0` mean that the audio buffer is less than half full and more
+    /// audio is needed, values below `1.0` mean that it is more than half full.
+    /// Returns exactly `1.0` if no audio buffer status is known, or while the frontend
+    /// is not running in real time, e.g. while fast-forwarding or rewinding.
+    pub fn resampler_ratio(&self) -> f32 {
+        let real_time = match self.throttle_state {
+            Some(state) => matches!(state.mode, ThrottleMode::None | ThrottleMode::Vsync),
+            None => true,
+        };
+
+        match self.buffer_status {
+            Some(status) if status.active && real_time => {
+                let fill = status.occupancy as f32 / 100.0;
+
+                1.0 + self.max_deviation * (1.0 - 2.0 * fill)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+#[test]
+fn resampler_ratio_follows_buffer_occupancy() {
+    let mut pacer = FramePacer::new(0.01);
+    assert_eq!(pacer.resampler_ratio(), 1.0);
+
+    pacer.set_audio_buffer_status(true, 50, false);
+    assert_eq!(pacer.resampler_ratio(), 1.0);
+
+    pacer.set_audio_buffer_status(true, 0, true);
+    assert!((pacer.resampler_ratio() - 1.01).abs() < 1e-6);
+    assert!(pacer.should_skip_frame());
+
+    pacer.set_audio_buffer_status(true, 100, false);
+    assert!((pacer.resampler_ratio() - 0.99).abs() < 1e-6);
+    assert!(!pacer.should_skip_frame());
+
+    pacer.set_audio_buffer_status(false, 0, false);
+    assert_eq!(pacer.resampler_ratio(), 1.0);
+}
+
+#[cfg(feature = "unstable-env-commands")]
+#[test]
+fn resampler_ratio_ignores_non_real_time_modes() {
+    let mut pacer = FramePacer::new(0.01);
+    pacer.set_audio_buffer_status(true, 0, false);
+
+    pacer.set_throttle_state(Some(ThrottleState {
+        mode: ThrottleMode::FastForward,
+        rate: 0.0,
+    }));
+    assert_eq!(pacer.resampler_ratio(), 1.0);
+
+    pacer.set_throttle_state(Some(ThrottleState {
+        mode: ThrottleMode::Vsync,
+        rate: 50.0,
+    }));
+    assert!((pacer.resampler_ratio() - 1.01).abs() < 1e-6);
+}
@@ -0,0 +1,438 @@
+//! Save states built on [`serde`] instead of raw byte buffers.
+//!
+//! Implement [`SaveState`] for your core, usually with the help of [`to_bytes`] and [`from_bytes`],
+//! and invoke [`save_state_glue!`](crate::save_state_glue) inside of your `impl Core` block
+//! to get [`Core::get_serialize_size`], [`Core::on_serialize`] and [`Core::on_unserialize`]
+//! implementations that use it.
+//!
+//! Implement [`SaveStateVersioned`] as well and use
+//! [`versioned_save_state_glue!`](crate::versioned_save_state_glue) instead
+//! to be able to load states written by older versions of your core.
+//!
+//! Cores that need a byte-exact layout can keep implementing those methods by hand.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct State {
+//!     frame: u64,
+//!     registers: [u8; 16],
+//! }
+//!
+//! impl SaveState for MyCore {
+//!     fn serialize_state(&self) -> Result<Vec<u8>, SaveError> {
+//!         to_bytes(&self.state)
+//!     }
+//!
+//!     fn deserialize_state(&mut self, bytes: &[u8]) -> Result<(), SaveError> {
+//!         self.state = from_bytes(bytes)?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! impl Core for MyCore {
+//!     rust_libretro::save_state_glue!();
+//!
+//!     // …
+//! }
+//! ```
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Errors that can occur while saving or loading a [`SaveState`].
+#[derive(Debug)]
+pub enum SaveError {
+    /// The state could not be encoded or decoded.
+    Bincode(bincode::Error),
+
+    /// The buffer provided by the frontend is too small to hold the state.
+    BufferTooSmall { needed: usize, available: usize },
+
+    /// The buffer does not start with the header written by [`SaveStateVersioned`].
+    InvalidHeader,
+
+    /// The state was written by a version of the core that cannot be loaded.
+    UnsupportedVersion { version: u32, current: u32 },
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bincode(err) => write!(f, "Failed to encode or decode the state: {err}"),
+            Self::BufferTooSmall { needed, available } => write!(
+                f,
+                "The state needs {needed} bytes, but the buffer only holds {available} bytes"
+            ),
+            Self::InvalidHeader => write!(f, "The buffer does not contain a save state"),
+            Self::UnsupportedVersion { version, current } => write!(
+                f,
+                "The state has version {version}, which cannot be loaded by version {current}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Bincode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<bincode::Error> for SaveError {
+    fn from(err: bincode::Error) -> Self {
+        Self::Bincode(err)
+    }
+}
+
+/// Encodes `value` with [`bincode`].
+pub fn to_bytes<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, SaveError> {
+    Ok(bincode::serialize(value)?)
+}
+
+/// Decodes a value that was encoded by [`to_bytes`].
+///
+/// Trailing bytes are ignored, as the frontend’s buffer may be larger than the encoded state.
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, SaveError> {
+    Ok(bincode::deserialize(bytes)?)
+}
+
+/// A core state that can be turned into bytes and back.
+pub trait SaveState {
+    /// Encodes the current state.
+    fn serialize_state(&self) -> Result<Vec<u8>, SaveError>;
+
+    /// Restores a state that was encoded by [`SaveState::serialize_state`].
+    fn deserialize_state(&mut self, bytes: &[u8]) -> Result<(), SaveError>;
+
+    /// Returns the number of bytes the current state encodes to, or `0` on error.
+    ///
+    /// The frontend allocates its buffer once, so states whose size varies
+    /// should reserve enough space for the largest one, e.g. by using fixed-size arrays.
+    fn save_state_size(&self) -> usize {
+        self.serialize_state().map_or(0, |bytes| bytes.len())
+    }
+
+    /// Writes the encoded state to the start of `slice` and zeroes the remaining bytes.
+    fn write_save_state(&self, slice: &mut [u8]) -> Result<(), SaveError> {
+        let bytes = self.serialize_state()?;
+
+        if bytes.len() > slice.len() {
+            return Err(SaveError::BufferTooSmall {
+                needed: bytes.len(),
+                available: slice.len(),
+            });
+        }
+
+        let (state, padding) = slice.split_at_mut(bytes.len());
+        state.copy_from_slice(&bytes);
+        padding.fill(0);
+
+        Ok(())
+    }
+
+    /// Restores the state from a buffer filled by [`SaveState::write_save_state`].
+    fn read_save_state(&mut self, slice: &[u8]) -> Result<(), SaveError> {
+        self.deserialize_state(slice)
+    }
+}
+
+/// Identifies states written by [`SaveStateVersioned`].
+pub const SAVE_STATE_MAGIC: [u8; 4] = *b"RLSS";
+
+/// The size of the header that [`SaveStateVersioned`] puts in front of the state:
+/// [`SAVE_STATE_MAGIC`], the version and the length of the state, both as little endian `u32`s.
+pub const SAVE_STATE_HEADER_SIZE: usize = 12;
+
+/// A [`SaveState`] whose format carries a version, so states of older core versions can still be loaded.
+///
+/// Use [`versioned_save_state_glue!`](crate::versioned_save_state_glue) to hook it up to [`Core`].
+pub trait SaveStateVersioned: SaveState {
+    /// The version of the format produced by [`SaveState::serialize_state`].
+    ///
+    /// Increase it whenever the format changes.
+    const VERSION: u32;
+
+    /// Converts a state written with `old_version` into the format of [`SaveStateVersioned::VERSION`].
+    ///
+    /// Only gets called for versions lower than the current one.
+    /// The default implementation rejects all older states.
+    fn migrate(old_version: u32, _bytes: &[u8]) -> Result<Vec<u8>, SaveError> {
+        Err(SaveError::UnsupportedVersion {
+            version: old_version,
+            current: Self::VERSION,
+        })
+    }
+
+    /// Like [`SaveState::save_state_size`], including the header.
+    fn versioned_save_state_size(&self) -> usize {
+        match self.save_state_size() {
+            0 => 0,
+            size => SAVE_STATE_HEADER_SIZE + size,
+        }
+    }
+
+    /// Like [`SaveState::write_save_state`], but prepends the header.
+    fn write_versioned_save_state(&self, slice: &mut [u8]) -> Result<(), SaveError> {
+        let bytes = self.serialize_state()?;
+        let needed = SAVE_STATE_HEADER_SIZE + bytes.len();
+
+        let length = u32::try_from(bytes.len()).map_err(|_| SaveError::BufferTooSmall {
+            needed,
+            available: slice.len(),
+        })?;
+
+        if needed > slice.len() {
+            return Err(SaveError::BufferTooSmall {
+                needed,
+                available: slice.len(),
+            });
+        }
+
+        let (header, rest) = slice.split_at_mut(SAVE_STATE_HEADER_SIZE);
+        header[0..4].copy_from_slice(&SAVE_STATE_MAGIC);
+        header[4..8].copy_from_slice(&Self::VERSION.to_le_bytes());
+        header[8..12].copy_from_slice(&length.to_le_bytes());
+
+        let (state, padding) = rest.split_at_mut(bytes.len());
+        state.copy_from_slice(&bytes);
+        padding.fill(0);
+
+        Ok(())
+    }
+
+    /// Validates the header written by [`SaveStateVersioned::write_versioned_save_state`]
+    /// and restores the state, calling [`SaveStateVersioned::migrate`] for older versions.
+    fn read_versioned_save_state(&mut self, slice: &[u8]) -> Result<(), SaveError> {
+        if slice.len() < SAVE_STATE_HEADER_SIZE || slice[0..4] != SAVE_STATE_MAGIC {
+            return Err(SaveError::InvalidHeader);
+        }
+
+        let version = u32::from_le_bytes([slice[4], slice[5], slice[6], slice[7]]);
+        let length = u32::from_le_bytes([slice[8], slice[9], slice[10], slice[11]]) as usize;
+
+        let bytes = slice[SAVE_STATE_HEADER_SIZE..]
+            .get(..length)
+            .ok_or(SaveError::InvalidHeader)?;
+
+        match version.cmp(&Self::VERSION) {
+            std::cmp::Ordering::Equal => self.deserialize_state(bytes),
+            std::cmp::Ordering::Less => self.deserialize_state(&Self::migrate(version, bytes)?),
+            std::cmp::Ordering::Greater => Err(SaveError::UnsupportedVersion {
+                version,
+                current: Self::VERSION,
+            }),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn serialize_into<T: SaveState + ?Sized>(state: &T, slice: &mut [u8]) -> bool {
+    match state.write_save_state(slice) {
+        Ok(()) => true,
+        Err(_err) => {
+            #[cfg(feature = "log")]
+            log::error!("Failed to save the state: {_err}");
+
+            false
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn deserialize_from<T: SaveState + ?Sized>(state: &mut T, slice: &[u8]) -> bool {
+    match state.read_save_state(slice) {
+        Ok(()) => true,
+        Err(_err) => {
+            #[cfg(feature = "log")]
+            log::error!("Failed to load the state: {_err}");
+
+            false
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn serialize_versioned_into<T: SaveStateVersioned + ?Sized>(
+    state: &T,
+    slice: &mut [u8],
+) -> bool {
+    match state.write_versioned_save_state(slice) {
+        Ok(()) => true,
+        Err(_err) => {
+            #[cfg(feature = "log")]
+            log::error!("Failed to save the state: {_err}");
+
+            false
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn deserialize_versioned_from<T: SaveStateVersioned + ?Sized>(
+    state: &mut T,
+    slice: &[u8],
+) -> bool {
+    match state.read_versioned_save_state(slice) {
+        Ok(()) => true,
+        Err(_err) => {
+            #[cfg(feature = "log")]
+            log::error!("Failed to load the state: {_err}");
+
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct TestState {
+    frame: u64,
+    registers: [u8; 4],
+}
+
+#[cfg(test)]
+impl SaveState for TestState {
+    fn serialize_state(&self) -> Result<Vec<u8>, SaveError> {
+        to_bytes(self)
+    }
+
+    fn deserialize_state(&mut self, bytes: &[u8]) -> Result<(), SaveError> {
+        *self = from_bytes(bytes)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn save_state_round_trips_through_a_larger_buffer() {
+    let state = TestState {
+        frame: 42,
+        registers: [1, 2, 3, 4],
+    };
+
+    let size = state.save_state_size();
+    assert_eq!(size, 12);
+
+    let mut buffer = vec![0xFF; size + 4];
+    assert!(serialize_into(&state, &mut buffer));
+    assert_eq!(&buffer[size..], &[0; 4]);
+
+    let mut restored = TestState::default();
+    assert!(deserialize_from(&mut restored, &buffer));
+    assert_eq!(restored.frame, 42);
+    assert_eq!(restored.registers, [1, 2, 3, 4]);
+}
+
+#[test]
+fn save_state_rejects_small_buffers() {
+    let state = TestState::default();
+    let mut buffer = vec![0; 4];
+
+    assert!(matches!(
+        state.write_save_state(&mut buffer),
+        Err(SaveError::BufferTooSmall {
+            needed: 12,
+            available: 4
+        })
+    ));
+    assert!(!deserialize_from(&mut TestState::default(), &buffer));
+}
+
+#[cfg(test)]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TestStateV1 {
+    frame: u32,
+}
+
+#[cfg(test)]
+impl SaveState for TestStateV1 {
+    fn serialize_state(&self) -> Result<Vec<u8>, SaveError> {
+        to_bytes(self)
+    }
+
+    fn deserialize_state(&mut self, bytes: &[u8]) -> Result<(), SaveError> {
+        *self = from_bytes(bytes)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl SaveStateVersioned for TestStateV1 {
+    const VERSION: u32 = 1;
+}
+
+#[cfg(test)]
+impl SaveStateVersioned for TestState {
+    const VERSION: u32 = 2;
+
+    fn migrate(old_version: u32, bytes: &[u8]) -> Result<Vec<u8>, SaveError> {
+        match old_version {
+            1 => {
+                let old: TestStateV1 = from_bytes(bytes)?;
+
+                to_bytes(&TestState {
+                    frame: old.frame as u64,
+                    registers: [0xAA; 4],
+                })
+            }
+            _ => Err(SaveError::UnsupportedVersion {
+                version: old_version,
+                current: Self::VERSION,
+            }),
+        }
+    }
+}
+
+#[test]
+fn versioned_save_state_round_trips() {
+    let state = TestState {
+        frame: 7,
+        registers: [4, 3, 2, 1],
+    };
+
+    let mut buffer = vec![0; state.versioned_save_state_size()];
+    assert_eq!(buffer.len(), SAVE_STATE_HEADER_SIZE + 12);
+    assert!(serialize_versioned_into(&state, &mut buffer));
+    assert_eq!(&buffer[0..8], b"RLSS\x02\0\0\0");
+
+    let mut restored = TestState::default();
+    assert!(deserialize_versioned_from(&mut restored, &buffer));
+    assert_eq!(restored.frame, 7);
+    assert_eq!(restored.registers, [4, 3, 2, 1]);
+
+    // Unversioned states are rejected instead of being misinterpreted
+    assert!(matches!(
+        restored.read_versioned_save_state(&buffer[SAVE_STATE_HEADER_SIZE..]),
+        Err(SaveError::InvalidHeader)
+    ));
+}
+
+#[test]
+fn versioned_save_state_migrates_older_versions() {
+    let old = TestStateV1 { frame: 99 };
+
+    let mut buffer = vec![0; old.versioned_save_state_size() + 8];
+    assert!(serialize_versioned_into(&old, &mut buffer));
+
+    let mut restored = TestState::default();
+    assert!(deserialize_versioned_from(&mut restored, &buffer));
+    assert_eq!(restored.frame, 99);
+    assert_eq!(restored.registers, [0xAA; 4]);
+
+    // States of newer versions cannot be loaded
+    let mut newer = TestStateV1 { frame: 0 };
+    assert!(matches!(
+        newer.read_versioned_save_state(&buffer_of(&restored)),
+        Err(SaveError::UnsupportedVersion {
+            version: 2,
+            current: 1
+        })
+    ));
+}
+
+#[cfg(test)]
+fn buffer_of<T: SaveStateVersioned>(state: &T) -> Vec<u8> {
+    let mut buffer = vec![0; state.versioned_save_state_size()];
+    state.write_versioned_save_state(&mut buffer).unwrap();
+    buffer
+}
@@ -0,0 +1,1469 @@
+//! A simulated frontend for testing [`Core`] implementations without loading them into RetroArch.
+//!
+//! [`MockFrontend`] drives a core through the usual lifecycle by calling the exported
+//! `retro_*` functions, answers the common environment queries itself and records
+//! everything the core sends back: frames, audio samples and environment calls.
+//!
+//! ```ignore
+//! #[test]
+//! fn draws_a_frame() {
+//!     let mut frontend = MockFrontend::new(MyCore::default());
+//!     frontend.set_environment();
+//!     frontend.init();
+//!     assert!(frontend.load_game(include_bytes!("test.rom")));
+//!
+//!     frontend.set_input(0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_A, 1);
+//!     frontend.run();
+//!
+//!     assert_eq!(frontend.last_frame().unwrap().width, 320);
+//! }
+//! ```
+//!
+//! Every [`MockFrontend`] owns its own [`CoreInstance`] and frontend state, which get installed
+//! only while it calls into the core. So multiple frontends can exist at the same time,
+//! e.g. in parallel tests, while calls into their cores are serialized.
+use super::*;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+/// Handles an environment command before the [`MockFrontend`] does.
+///
+/// Returning [`None`] lets the [`MockFrontend`] handle the command.
+pub type EnvironmentHandler = Box<dyn FnMut(u32, *mut c_void) -> Option<bool> + Send>;
+
+/// A frame passed to the video refresh callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The raw pixel data; the last row is not padded to `pitch`.
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub pitch: usize,
+}
+
+#[derive(Default)]
+struct FrontendState {
+    handler: Option<EnvironmentHandler>,
+    environment_calls: Vec<u32>,
+
+    can_dupe: bool,
+    pixel_format: Option<retro_pixel_format>,
+    system_directory: Option<CString>,
+    save_directory: Option<CString>,
+
+    variables: HashMap<String, CString>,
+    variables_updated: bool,
+
+    input: HashMap<(u32, u32, u32, u32), i16>,
+    poll_count: usize,
+
+    frames: Vec<Option<Frame>>,
+    last_frame: Option<Frame>,
+    audio: Vec<i16>,
+
+    proc_address_interface: Option<retro_get_proc_address_interface>,
+}
+
+/// Serializes the calls of all [`MockFrontend`]s into their cores.
+static FRONTEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// The state of the [`MockFrontend`] that is currently calling into its core.
+static STATE: Mutex<Option<FrontendState>> = Mutex::new(None);
+
+fn with_state<R>(f: impl FnOnce(&mut FrontendState) -> R) -> R {
+    let mut state = STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    f(state.get_or_insert_with(FrontendState::default))
+}
+
+unsafe extern "C" fn environment_callback(cmd: std::os::raw::c_uint, data: *mut c_void) -> bool {
+    with_state(|state| {
+        state.environment_calls.push(cmd);
+
+        if let Some(handler) = state.handler.as_mut() {
+            if let Some(handled) = handler(cmd, data) {
+                return handled;
+            }
+        }
+
+        state.handle_environment(cmd, data)
+    })
+}
+
+impl FrontendState {
+    unsafe fn handle_environment(&mut self, cmd: u32, data: *mut c_void) -> bool {
+        match cmd {
+            RETRO_ENVIRONMENT_GET_CAN_DUPE => {
+                *(data as *mut bool) = self.can_dupe;
+            }
+            RETRO_ENVIRONMENT_GET_INPUT_BITMASKS => {
+                if !data.is_null() {
+                    *(data as *mut bool) = true;
+                }
+            }
+            RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => {
+                self.pixel_format = Some(*(data as *const retro_pixel_format));
+            }
+            RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY => {
+                let Some(dir) = self.system_directory.as_ref() else {
+                    return false;
+                };
+
+                *(data as *mut *const c_char) = dir.as_ptr();
+            }
+            RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY => {
+                let Some(dir) = self.save_directory.as_ref() else {
+                    return false;
+                };
+
+                *(data as *mut *const c_char) = dir.as_ptr();
+            }
+            RETRO_ENVIRONMENT_SET_VARIABLES => {
+                let mut variable = data as *const retro_variable;
+
+                while !(*variable).key.is_null() {
+                    let key = get_string_from_pointer((*variable).key);
+                    let value = get_str_from_pointer((*variable).value);
+
+                    // The legacy format is “Description; default|other|…”
+                    let default = value
+                        .and_then(|value| value.split_once("; "))
+                        .and_then(|(_, values)| values.split('|').next());
+
+                    if let (Some(key), Some(default)) = (key, default) {
+                        if let Ok(default) = CString::new(default) {
+                            self.variables.entry(key).or_insert(default);
+                        }
+                    }
+
+                    variable = variable.add(1);
+                }
+            }
+            RETRO_ENVIRONMENT_GET_VARIABLE => {
+                let variable = &mut *(data as *mut retro_variable);
+
+                let value = get_str_from_pointer(variable.key)
+                    .and_then(|key| self.variables.get(key))
+                    .map(|value| value.as_ptr());
+
+                variable.value = value.unwrap_or(std::ptr::null());
+
+                return value.is_some();
+            }
+            RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE => {
+                *(data as *mut bool) = std::mem::take(&mut self.variables_updated);
+            }
+            RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK => {
+                self.proc_address_interface =
+                    Some(*(data as *const retro_get_proc_address_interface));
+            }
+            RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME
+            | RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS
+            | RETRO_ENVIRONMENT_SET_CONTROLLER_INFO
+            | RETRO_ENVIRONMENT_SET_GEOMETRY
+            | RETRO_ENVIRONMENT_SET_SYSTEM_AV_INFO
+            | RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY => {}
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+unsafe extern "C" fn video_refresh_callback(
+    data: *const c_void,
+    width: std::os::raw::c_uint,
+    height: std::os::raw::c_uint,
+    pitch: usize,
+) {
+    with_state(|state| {
+        // Duped frames and frames rendered by the hardware don’t have any data
+        if data.is_null() || data as isize == -1 {
+            state.frames.push(None);
+            return;
+        }
+
+        let bytes_per_pixel = match state.pixel_format {
+            Some(retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888) => 4,
+            _ => 2,
+        };
+
+        let length = match height as usize {
+            0 => 0,
+            height => (height - 1) * pitch + width as usize * bytes_per_pixel,
+        };
+
+        let data = std::slice::from_raw_parts(data as *const u8, length);
+        let frame = Frame {
+            data: data.to_vec(),
+            width,
+            height,
+            pitch,
+        };
+
+        state.frames.push(Some(frame.clone()));
+        state.last_frame = Some(frame);
+    })
+}
+
+unsafe extern "C" fn audio_sample_callback(left: i16, right: i16) {
+    with_state(|state| state.audio.extend_from_slice(&[left, right]))
+}
+
+unsafe extern "C" fn audio_sample_batch_callback(data: *const i16, frames: usize) -> usize {
+    if data.is_null() {
+        return 0;
+    }
+
+    let samples = std::slice::from_raw_parts(data, frames * 2);
+    with_state(|state| state.audio.extend_from_slice(samples));
+
+    frames
+}
+
+unsafe extern "C" fn input_poll_callback() {
+    with_state(|state| state.poll_count += 1)
+}
+
+unsafe extern "C" fn input_state_callback(
+    port: std::os::raw::c_uint,
+    device: std::os::raw::c_uint,
+    index: std::os::raw::c_uint,
+    id: std::os::raw::c_uint,
+) -> i16 {
+    with_state(|state| {
+        if device == RETRO_DEVICE_JOYPAD && id == RETRO_DEVICE_ID_JOYPAD_MASK {
+            return (0..16)
+                .filter(|&button| {
+                    state
+                        .input
+                        .get(&(port, device, index, button))
+                        .is_some_and(|&value| value != 0)
+                })
+                .fold(0u16, |mask, button| mask | (1 << button)) as i16;
+        }
+
+        state
+            .input
+            .get(&(port, device, index, id))
+            .copied()
+            .unwrap_or(0)
+    })
+}
+
+/// Drives a [`Core`] like a libretro frontend would, see the [module documentation](self).
+pub struct MockFrontend {
+    instance: CoreInstance,
+    state: Option<FrontendState>,
+
+    initialized: bool,
+    game_loaded: bool,
+    av_info: Option<retro_system_av_info>,
+}
+
+impl MockFrontend {
+    /// Creates the frontend along with its own [`CoreInstance`] of `core`.
+    pub fn new<C: 'static + Core>(core: C) -> Self {
+        Self {
+            instance: CoreInstance::new(core),
+            state: Some(FrontendState {
+                can_dupe: true,
+                ..Default::default()
+            }),
+
+            initialized: false,
+            game_loaded: false,
+            av_info: None,
+        }
+    }
+
+    fn state(&mut self) -> &mut FrontendState {
+        self.state.get_or_insert_with(FrontendState::default)
+    }
+
+    /// Installs the core and the frontend state while `f` calls into the core.
+    fn call<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        let _guard = FRONTEND_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+        std::mem::swap(
+            &mut *STATE.lock().unwrap_or_else(PoisonError::into_inner),
+            &mut self.state,
+        );
+
+        let result = unsafe { self.instance.activate(f) };
+
+        std::mem::swap(
+            &mut *STATE.lock().unwrap_or_else(PoisonError::into_inner),
+            &mut self.state,
+        );
+
+        result
+    }
+
+    /// Installs a handler that sees every environment command before the frontend does.
+    ///
+    /// The handler must not call back into the [`MockFrontend`].
+    pub fn on_environment(
+        &mut self,
+        handler: impl FnMut(u32, *mut c_void) -> Option<bool> + Send + 'static,
+    ) {
+        self.state().handler = Some(Box::new(handler));
+    }
+
+    /// Sets the value returned for `RETRO_ENVIRONMENT_GET_CAN_DUPE`, `true` by default.
+    pub fn set_can_dupe(&mut self, can_dupe: bool) {
+        self.state().can_dupe = can_dupe;
+    }
+
+    /// Sets the directory returned for `RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY`.
+    pub fn set_system_directory(&mut self, dir: &str) {
+        self.state().system_directory =
+            Some(CString::new(dir).expect("directory contains a NUL byte"));
+    }
+
+    /// Sets the directory returned for `RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY`.
+    pub fn set_save_directory(&mut self, dir: &str) {
+        self.state().save_directory =
+            Some(CString::new(dir).expect("directory contains a NUL byte"));
+    }
+
+    /// Changes a core option, as if the user changed it in the menu.
+    pub fn set_variable(&mut self, key: &str, value: &str) {
+        let value = CString::new(value).expect("value contains a NUL byte");
+
+        let state = self.state();
+        state.variables.insert(key.to_owned(), value);
+        state.variables_updated = true;
+    }
+
+    /// Returns the current value of a core option.
+    pub fn variable(&mut self, key: &str) -> Option<String> {
+        self.state()
+            .variables
+            .get(key)
+            .map(|value| value.to_string_lossy().into_owned())
+    }
+
+    /// Sets the value returned by the input state callback.
+    pub fn set_input(&mut self, port: u32, device: u32, index: u32, id: u32, value: i16) {
+        self.state().input.insert((port, device, index, id), value);
+    }
+
+    /// Releases all inputs.
+    pub fn clear_input(&mut self) {
+        self.state().input.clear();
+    }
+
+    /// Provides the environment and the audio, video and input callbacks to the core.
+    pub fn set_environment(&mut self) {
+        self.call(|| unsafe {
+            retro_set_environment(Some(environment_callback));
+            retro_set_video_refresh(Some(video_refresh_callback));
+            retro_set_audio_sample(Some(audio_sample_callback));
+            retro_set_audio_sample_batch(Some(audio_sample_batch_callback));
+            retro_set_input_poll(Some(input_poll_callback));
+            retro_set_input_state(Some(input_state_callback));
+        })
+    }
+
+    /// Calls `retro_init`.
+    pub fn init(&mut self) {
+        self.call(|| unsafe { retro_init() });
+        self.initialized = true;
+    }
+
+    /// Returns the [`SystemInfo`] of the core.
+    pub fn system_info(&mut self) -> Option<SystemInfo> {
+        self.call(|| {
+            unsafe { instance() }
+                .as_deref()
+                .map(|wrapper| wrapper.core.get_info())
+        })
+    }
+
+    fn load(&mut self, info: Option<&retro_game_info>) -> bool {
+        let info = info.map_or(std::ptr::null(), |info| info as *const _);
+
+        let av_info = self.call(|| unsafe {
+            if !retro_load_game(info) {
+                return None;
+            }
+
+            let mut av_info = std::mem::zeroed::<retro_system_av_info>();
+            retro_get_system_av_info(&mut av_info);
+
+            Some(av_info)
+        });
+
+        self.game_loaded |= av_info.is_some();
+        self.av_info = av_info.or(self.av_info);
+
+        av_info.is_some()
+    }
+
+    /// Loads a game from memory, like a frontend does unless [`SystemInfo::need_fullpath`] is set.
+    pub fn load_game(&mut self, data: &[u8]) -> bool {
+        self.load(Some(&retro_game_info {
+            path: std::ptr::null(),
+            data: data.as_ptr() as *const c_void,
+            size: data.len(),
+            meta: std::ptr::null(),
+        }))
+    }
+
+    /// Loads a game by its path only, like a frontend does if [`SystemInfo::need_fullpath`] is set.
+    pub fn load_game_path(&mut self, path: &str) -> bool {
+        let path = CString::new(path).expect("path contains a NUL byte");
+
+        self.load(Some(&retro_game_info {
+            path: path.as_ptr(),
+            data: std::ptr::null(),
+            size: 0,
+            meta: std::ptr::null(),
+        }))
+    }
+
+    /// Starts the core without any content.
+    pub fn load_no_game(&mut self) -> bool {
+        self.load(None)
+    }
+
+    /// Returns the audio/video information queried after the game has been loaded.
+    pub fn av_info(&self) -> Option<retro_system_av_info> {
+        self.av_info
+    }
+
+    /// Calls `retro_set_controller_port_device`.
+    pub fn set_controller_port_device(&mut self, port: u32, device: u32) {
+        self.call(|| unsafe { retro_set_controller_port_device(port, device) });
+    }
+
+    /// Runs a single frame.
+    ///
+    /// Inputs and core options changed before take effect in this frame.
+    pub fn run(&mut self) {
+        self.call(|| unsafe { retro_run() });
+    }
+
+    /// Runs `count` frames.
+    pub fn run_frames(&mut self, count: usize) {
+        for _ in 0..count {
+            self.run();
+        }
+    }
+
+    /// Calls `retro_reset`.
+    pub fn reset(&mut self) {
+        self.call(|| unsafe { retro_reset() });
+    }
+
+    /// Creates a save state, returns [`None`] if the core does not support them or failed.
+    pub fn serialize(&mut self) -> Option<Vec<u8>> {
+        self.call(|| unsafe {
+            let size = retro_serialize_size();
+            if size == 0 {
+                return None;
+            }
+
+            let mut data = vec![0; size];
+            retro_serialize(data.as_mut_ptr() as *mut c_void, size).then_some(data)
+        })
+    }
+
+    /// Loads a save state created by [`MockFrontend::serialize`].
+    pub fn unserialize(&mut self, data: &[u8]) -> bool {
+        self.call(|| unsafe { retro_unserialize(data.as_ptr() as *const c_void, data.len()) })
+    }
+
+    /// Copies the memory identified by `id`, like a frontend does when saving the SRAM.
+    ///
+    /// Returns [`None`] if the core does not expose the memory.
+    pub fn memory(&mut self, id: MemoryId) -> Option<Vec<u8>> {
+        self.call(|| unsafe {
+            let data = retro_get_memory_data(id.into());
+            let size = retro_get_memory_size(id.into());
+            if data.is_null() || size == 0 {
+                return None;
+            }
+
+            Some(std::slice::from_raw_parts(data as *const u8, size).to_vec())
+        })
+    }
+
+    /// Calls `retro_unload_game`.
+    pub fn unload_game(&mut self) {
+        if std::mem::take(&mut self.game_loaded) {
+            self.call(|| unsafe { retro_unload_game() });
+        }
+    }
+
+    /// Unloads the game if necessary and calls `retro_deinit`.
+    pub fn deinit(&mut self) {
+        self.unload_game();
+
+        if std::mem::take(&mut self.initialized) {
+            self.call(|| unsafe { retro_deinit() });
+        }
+    }
+
+    /// Returns and forgets all frames passed to the video refresh callback,
+    /// [`None`] entries are duped frames.
+    pub fn take_frames(&mut self) -> Vec<Option<Frame>> {
+        std::mem::take(&mut self.state().frames)
+    }
+
+    /// Returns the last frame that was not a dupe.
+    pub fn last_frame(&mut self) -> Option<Frame> {
+        self.state().last_frame.clone()
+    }
+
+    /// Returns and forgets all interleaved stereo samples the core has output.
+    pub fn take_audio(&mut self) -> Vec<i16> {
+        std::mem::take(&mut self.state().audio)
+    }
+
+    /// Returns the pixel format set by the core.
+    pub fn pixel_format(&mut self) -> Option<retro_pixel_format> {
+        self.state().pixel_format
+    }
+
+    /// Returns how often the input poll callback has been called.
+    pub fn poll_count(&mut self) -> usize {
+        self.state().poll_count
+    }
+
+    /// Returns every environment command the core has sent so far.
+    pub fn environment_calls(&mut self) -> Vec<u32> {
+        self.state().environment_calls.clone()
+    }
+
+    /// Looks up `sym` through the `retro_get_proc_address_interface` the core has
+    /// registered using `RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK`.
+    ///
+    /// Returns [`None`] if the core has not registered the interface or does not export `sym`.
+    pub fn get_proc_address(&mut self, sym: &str) -> retro_proc_address_t {
+        let get_proc_address = self.state().proc_address_interface?.get_proc_address?;
+        let sym = CString::new(sym).expect("symbol contains a NUL byte");
+
+        self.call(|| unsafe { get_proc_address(sym.as_ptr()) })
+    }
+}
+
+impl Drop for MockFrontend {
+    fn drop(&mut self) {
+        self.deinit();
+    }
+}
+
+/// The audio/video info of the test cores below, which draw 1x1 frames.
+#[cfg(test)]
+fn test_av_info() -> retro_system_av_info {
+    retro_system_av_info {
+        geometry: retro_game_geometry {
+            base_width: 1,
+            base_height: 1,
+            max_width: 1,
+            max_height: 1,
+            aspect_ratio: 0.0,
+        },
+        timing: retro_system_timing {
+            fps: 60.0,
+            sample_rate: 48000.0,
+        },
+    }
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct CounterCore {
+    counter: u8,
+}
+
+#[cfg(test)]
+impl crate::core::CoreOptions for CounterCore {}
+
+#[cfg(test)]
+impl Core for CounterCore {
+    fn get_info(&self) -> SystemInfo {
+        SystemInfo {
+            library_name: CString::new("CounterCore").unwrap(),
+            library_version: CString::new("1.0.0").unwrap(),
+            valid_extensions: CString::new("bin").unwrap(),
+            need_fullpath: false,
+            block_extract: false,
+        }
+    }
+
+    fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+        test_av_info()
+    }
+
+    fn on_load_game(
+        &mut self,
+        info: Option<retro_game_info>,
+        ctx: &mut LoadGameContext,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let info = info.ok_or("No game")?;
+        self.counter = GameInfo::new(&info)
+            .data()
+            .and_then(|data| data.first().copied())
+            .unwrap_or(0);
+
+        ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+        Ok(())
+    }
+
+    fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<std::time::Duration>) {
+        if ctx.get_input_state(0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_A) != 0 {
+            self.counter = self.counter.wrapping_add(1);
+        }
+
+        ctx.draw_frame(&[self.counter, 0, 0, 0], 1, 1, 4);
+        ctx.audio().batch_audio_samples(&[1, -1]);
+    }
+
+    fn get_serialize_size(&mut self, _ctx: &mut GetSerializeSizeContext) -> usize {
+        1
+    }
+
+    fn on_serialize(&mut self, slice: &mut [u8], _ctx: &mut SerializeContext) -> bool {
+        slice[0] = self.counter;
+        true
+    }
+
+    fn on_unserialize(&mut self, slice: &mut [u8], _ctx: &mut UnserializeContext) -> bool {
+        self.counter = slice[0];
+        true
+    }
+}
+
+#[test]
+fn mock_frontend_drives_a_core() {
+    let mut frontend = MockFrontend::new(CounterCore::default());
+    frontend.set_environment();
+    frontend.init();
+
+    assert!(!frontend.load_no_game());
+    assert!(frontend.load_game(&[5]));
+    assert_eq!(
+        frontend.pixel_format(),
+        Some(retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888)
+    );
+
+    frontend.run();
+    frontend.set_input(0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_A, 1);
+    frontend.run_frames(2);
+
+    assert_eq!(frontend.poll_count(), 3);
+    assert_eq!(frontend.last_frame().unwrap().data, vec![7, 0, 0, 0]);
+    assert_eq!(frontend.take_frames().len(), 3);
+    assert_eq!(frontend.take_audio(), vec![1, -1, 1, -1, 1, -1]);
+
+    let state = frontend.serialize().unwrap();
+    assert_eq!(state, vec![7]);
+
+    frontend.run();
+    assert_eq!(frontend.last_frame().unwrap().data[0], 8);
+
+    assert!(frontend.unserialize(&state));
+    frontend.clear_input();
+    frontend.run();
+    assert_eq!(frontend.last_frame().unwrap().data[0], 7);
+
+    assert!(frontend
+        .environment_calls()
+        .contains(&RETRO_ENVIRONMENT_SET_PIXEL_FORMAT));
+}
+
+#[test]
+fn mock_frontends_keep_their_cores_apart() {
+    let mut first = MockFrontend::new(CounterCore::default());
+    let mut second = MockFrontend::new(CounterCore::default());
+
+    for frontend in [&mut first, &mut second] {
+        frontend.set_environment();
+        frontend.init();
+    }
+
+    assert!(first.load_game(&[10]));
+    assert!(second.load_game(&[20]));
+
+    first.set_input(0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_A, 1);
+    first.run();
+    second.run();
+
+    assert_eq!(first.last_frame().unwrap().data[0], 11);
+    assert_eq!(second.last_frame().unwrap().data[0], 20);
+    assert_eq!(first.poll_count(), 1);
+}
+
+#[cfg(feature = "unstable-env-commands")]
+#[test]
+fn rollback_netplay_gets_detected_once_per_game() {
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    static NETPLAY: AtomicBool = AtomicBool::new(false);
+    static STARTED: AtomicU32 = AtomicU32::new(0);
+
+    struct NetplayCore;
+
+    impl crate::core::CoreOptions for NetplayCore {}
+
+    impl Core for NetplayCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("NetplayCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn get_serialize_size(&mut self, _ctx: &mut GetSerializeSizeContext) -> usize {
+            1
+        }
+
+        fn on_serialize(&mut self, _slice: &mut [u8], _ctx: &mut SerializeContext) -> bool {
+            true
+        }
+
+        fn on_netplay_started(&mut self, ctx: &mut NetplayContext) {
+            assert!(ctx.is_netplay_active());
+            STARTED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut frontend = MockFrontend::new(NetplayCore);
+    frontend.on_environment(|cmd, data| {
+        if cmd != RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT || !NETPLAY.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        unsafe {
+            *(data as *mut retro_savestate_context_REPR_TYPE) =
+                retro_savestate_context::RETRO_SAVESTATE_CONTEXT_ROLLBACK_NETPLAY
+                    as retro_savestate_context_REPR_TYPE;
+        }
+
+        Some(true)
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    assert!(frontend.serialize().is_some());
+    assert_eq!(STARTED.load(Ordering::SeqCst), 0);
+
+    NETPLAY.store(true, Ordering::SeqCst);
+    assert!(frontend.serialize().is_some());
+    assert!(frontend.serialize().is_some());
+    assert_eq!(STARTED.load(Ordering::SeqCst), 1);
+
+    frontend.unload_game();
+    assert!(frontend.load_game(&[0]));
+    assert!(frontend.serialize().is_some());
+    assert_eq!(STARTED.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn frame_count_restarts_on_reset_and_load() {
+    struct FrameCountCore;
+
+    impl crate::core::CoreOptions for FrameCountCore {}
+
+    impl Core for FrameCountCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("FrameCountCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn on_load_game(
+            &mut self,
+            _info: Option<retro_game_info>,
+            ctx: &mut LoadGameContext,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            ctx.set_pixel_format(PixelFormat::XRGB8888)?;
+            Ok(())
+        }
+
+        fn on_run(&mut self, ctx: &mut RunContext, delta: Option<std::time::Duration>) {
+            assert_eq!(ctx.frame_time(), delta);
+
+            let count = ctx.frame_count() as u8;
+            ctx.draw_frame(&[count, 0, 0, 0], 1, 1, 4);
+        }
+    }
+
+    let mut frontend = MockFrontend::new(FrameCountCore);
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    frontend.run_frames(3);
+    assert_eq!(frontend.last_frame().unwrap().data[0], 2);
+
+    frontend.reset();
+    frontend.run();
+    assert_eq!(frontend.last_frame().unwrap().data[0], 0);
+
+    frontend.run();
+    frontend.unload_game();
+    assert!(frontend.load_game(&[0]));
+    frontend.run();
+    assert_eq!(frontend.last_frame().unwrap().data[0], 0);
+}
+
+#[test]
+fn load_game_data_can_be_taken_once() {
+    #[derive(Default)]
+    struct DataCore {
+        rom: Option<Vec<u8>>,
+    }
+
+    impl crate::core::CoreOptions for DataCore {}
+
+    impl Core for DataCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("DataCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn on_load_game(
+            &mut self,
+            _info: Option<retro_game_info>,
+            ctx: &mut LoadGameContext,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.rom = ctx.take_game_data();
+            assert_eq!(ctx.take_game_data(), None);
+
+            Ok(())
+        }
+
+        fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<std::time::Duration>) {
+            let first = self.rom.as_ref().map_or(0, |rom| rom[0]);
+            ctx.draw_frame(&[first, 0], 1, 1, 2);
+        }
+    }
+
+    let mut frontend = MockFrontend::new(DataCore::default());
+    frontend.set_environment();
+    frontend.init();
+
+    // The core keeps its own copy after the frontend dropped the buffer
+    let data = vec![42, 1, 2, 3];
+    assert!(frontend.load_game(&data));
+    drop(data);
+
+    frontend.run();
+    assert_eq!(frontend.last_frame().unwrap().data[0], 42);
+
+    frontend.unload_game();
+    assert!(frontend.load_game_path("/roms/game.bin"));
+    frontend.run();
+    assert_eq!(frontend.last_frame().unwrap().data[0], 0);
+}
+
+#[test]
+fn capabilities_are_queried_on_init() {
+    static CAPABILITIES: Mutex<Option<Capabilities>> = Mutex::new(None);
+
+    struct CapabilitiesCore;
+
+    impl crate::core::CoreOptions for CapabilitiesCore {}
+
+    impl Core for CapabilitiesCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("CapabilitiesCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn on_init(&mut self, ctx: &mut InitContext) {
+            *CAPABILITIES.lock().unwrap() = Some(ctx.capabilities());
+        }
+
+        fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<std::time::Duration>) {
+            assert_eq!(Some(ctx.capabilities()), *CAPABILITIES.lock().unwrap());
+        }
+    }
+
+    let mut frontend = MockFrontend::new(CapabilitiesCore);
+    frontend.set_can_dupe(false);
+    frontend.on_environment(|cmd, data| {
+        if cmd != RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION {
+            return None;
+        }
+
+        unsafe { *(data as *mut u32) = 2 };
+
+        Some(true)
+    });
+
+    frontend.set_environment();
+    frontend.init();
+
+    assert_eq!(
+        *CAPABILITIES.lock().unwrap(),
+        Some(Capabilities {
+            can_dupe: false,
+            supports_bitmasks: cfg!(feature = "unstable-env-commands"),
+            supports_core_options_v2: true,
+            supports_message_ext: false,
+        })
+    );
+
+    assert!(frontend.load_game(&[0]));
+    frontend.run();
+}
+
+#[test]
+fn deinit_drops_the_core_and_allows_reloading() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct ReloadCore;
+
+    impl Drop for ReloadCore {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl crate::core::CoreOptions for ReloadCore {}
+
+    impl Core for ReloadCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("ReloadCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+    }
+
+    // Stands in for the function generated by `retro_core!`
+    unsafe fn create_core() {
+        CREATED.fetch_add(1, Ordering::SeqCst);
+        crate::set_core(ReloadCore);
+    }
+
+    let mut frontend = MockFrontend::new(ReloadCore);
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    frontend.deinit();
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+
+    // Loading the core again recreates it
+    let previous =
+        frontend.call(|| unsafe { crate::CORE_CONSTRUCTOR.get_mut().replace(create_core) });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+    frontend.run();
+    assert_eq!(CREATED.load(Ordering::SeqCst), 1);
+
+    frontend.deinit();
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+
+    frontend.call(|| unsafe { *crate::CORE_CONSTRUCTOR.get_mut() = previous });
+}
+
+#[cfg(feature = "unstable-env-commands")]
+#[test]
+fn serialize_context_reports_the_save_state_context() {
+    static CONTEXTS: Mutex<Vec<SaveStateContext>> = Mutex::new(Vec::new());
+
+    struct ContextCore;
+
+    impl crate::core::CoreOptions for ContextCore {}
+
+    impl Core for ContextCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("ContextCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn get_serialize_size(&mut self, _ctx: &mut GetSerializeSizeContext) -> usize {
+            1
+        }
+
+        fn on_serialize(&mut self, _slice: &mut [u8], ctx: &mut SerializeContext) -> bool {
+            CONTEXTS.lock().unwrap().push(ctx.save_state_context());
+            true
+        }
+
+        fn on_unserialize(&mut self, _slice: &mut [u8], ctx: &mut UnserializeContext) -> bool {
+            CONTEXTS.lock().unwrap().push(ctx.save_state_context());
+            true
+        }
+    }
+
+    let mut frontend = MockFrontend::new(ContextCore);
+    frontend.on_environment(|cmd, data| {
+        if cmd != RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT {
+            return None;
+        }
+
+        unsafe {
+            *(data as *mut retro_savestate_context_REPR_TYPE) =
+                retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_INSTANCE
+                    as retro_savestate_context_REPR_TYPE;
+        }
+
+        Some(true)
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    let state = frontend.serialize().unwrap();
+    assert!(frontend.unserialize(&state));
+
+    assert_eq!(
+        CONTEXTS.lock().unwrap().as_slice(),
+        [SaveStateContext::Runahead, SaveStateContext::Runahead]
+    );
+}
+
+#[test]
+fn variable_save_state_sizes_are_not_cached() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SIZE: AtomicUsize = AtomicUsize::new(1);
+
+    struct GrowingCore;
+
+    impl crate::core::CoreOptions for GrowingCore {}
+
+    impl Core for GrowingCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("GrowingCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn serialize_size_quirks(&self) -> SerializationQuirks {
+            SerializationQuirks::CORE_VARIABLE_SIZE
+        }
+
+        fn get_serialize_size(&mut self, _ctx: &mut GetSerializeSizeContext) -> usize {
+            SIZE.fetch_add(1, Ordering::SeqCst)
+        }
+
+        fn on_serialize(&mut self, _slice: &mut [u8], _ctx: &mut SerializeContext) -> bool {
+            true
+        }
+    }
+
+    let mut frontend = MockFrontend::new(GrowingCore);
+
+    // A frontend without support for variable sizes clears the flag
+    frontend.on_environment(|cmd, data| {
+        if cmd != RETRO_ENVIRONMENT_SET_SERIALIZATION_QUIRKS {
+            return None;
+        }
+
+        unsafe { *(data as *mut u64) &= !(RETRO_SERIALIZATION_QUIRK_CORE_VARIABLE_SIZE as u64) };
+        Some(true)
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    assert_eq!(frontend.serialize().unwrap().len(), 1);
+    assert_eq!(frontend.serialize().unwrap().len(), 2);
+}
+
+#[cfg(feature = "unstable-env-commands")]
+#[test]
+fn runahead_states_respect_core_support() {
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    static SUPPORTED: AtomicBool = AtomicBool::new(false);
+    static STARTED: AtomicU32 = AtomicU32::new(0);
+
+    struct RunaheadCore;
+
+    impl crate::core::CoreOptions for RunaheadCore {}
+
+    impl Core for RunaheadCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("RunaheadCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn get_serialize_size(&mut self, _ctx: &mut GetSerializeSizeContext) -> usize {
+            1
+        }
+
+        fn on_serialize(&mut self, _slice: &mut [u8], _ctx: &mut SerializeContext) -> bool {
+            true
+        }
+
+        fn on_unserialize(&mut self, _slice: &mut [u8], _ctx: &mut UnserializeContext) -> bool {
+            true
+        }
+
+        fn supports_runahead(&self) -> bool {
+            SUPPORTED.load(Ordering::SeqCst)
+        }
+
+        fn on_runahead_started(&mut self, _ctx: &mut GenericContext) {
+            STARTED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut frontend = MockFrontend::new(RunaheadCore);
+    frontend.on_environment(|cmd, data| {
+        if cmd != RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT {
+            return None;
+        }
+
+        unsafe {
+            *(data as *mut retro_savestate_context_REPR_TYPE) =
+                retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_BINARY
+                    as retro_savestate_context_REPR_TYPE;
+        }
+
+        Some(true)
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    assert!(frontend.serialize().is_none());
+    assert!(!frontend.unserialize(&[0]));
+    assert_eq!(STARTED.load(Ordering::SeqCst), 0);
+
+    SUPPORTED.store(true, Ordering::SeqCst);
+    assert!(frontend.serialize().is_some());
+    assert!(frontend.unserialize(&[0]));
+    assert_eq!(STARTED.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn memory_data_and_size_agree() {
+    struct MemoryCore {
+        sram: Box<[u8]>,
+    }
+
+    impl crate::core::CoreOptions for MemoryCore {}
+
+    impl Core for MemoryCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("MemoryCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn memory(&mut self, id: MemoryId) -> Option<&mut [u8]> {
+            match id {
+                MemoryId::SaveRam => Some(&mut self.sram[..]),
+                _ => None,
+            }
+        }
+    }
+
+    let mut frontend = MockFrontend::new(MemoryCore {
+        sram: vec![0xAA; 4].into_boxed_slice(),
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    assert_eq!(frontend.memory(MemoryId::SaveRam), Some(vec![0xAA; 4]));
+    assert_eq!(frontend.memory(MemoryId::SystemRam), None);
+    assert_eq!(
+        frontend.call(|| unsafe { retro_get_memory_size(RETRO_MEMORY_SYSTEM_RAM) }),
+        0
+    );
+}
+
+#[cfg(feature = "unstable-env-commands")]
+#[test]
+fn wall_clock_time_is_withheld_during_netplay() {
+    static TIMES: Mutex<Vec<bool>> = Mutex::new(Vec::new());
+
+    struct ClockCore;
+
+    impl crate::core::CoreOptions for ClockCore {}
+
+    impl Core for ClockCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("ClockCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn get_serialize_size(&mut self, _ctx: &mut GetSerializeSizeContext) -> usize {
+            1
+        }
+
+        fn on_serialize(&mut self, _slice: &mut [u8], _ctx: &mut SerializeContext) -> bool {
+            true
+        }
+
+        fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<std::time::Duration>) {
+            TIMES.lock().unwrap().push(ctx.wall_clock_time().is_some());
+        }
+    }
+
+    let mut frontend = MockFrontend::new(ClockCore);
+    frontend.on_environment(|cmd, data| {
+        if cmd != RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT {
+            return None;
+        }
+
+        unsafe {
+            *(data as *mut retro_savestate_context_REPR_TYPE) =
+                retro_savestate_context::RETRO_SAVESTATE_CONTEXT_ROLLBACK_NETPLAY
+                    as retro_savestate_context_REPR_TYPE;
+        }
+
+        Some(true)
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    frontend.run();
+    assert!(frontend.serialize().is_some());
+    frontend.run();
+
+    assert_eq!(TIMES.lock().unwrap().as_slice(), [true, false]);
+}
+
+#[test]
+fn proc_addresses_can_be_looked_up_by_name() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static VALUE: AtomicU32 = AtomicU32::new(0);
+
+    unsafe extern "C" fn set_value(value: u32) {
+        VALUE.store(value, Ordering::SeqCst);
+    }
+
+    struct ProcCore;
+
+    impl crate::core::CoreOptions for ProcCore {}
+
+    impl Core for ProcCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("ProcCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn exports_proc_addresses(&self) -> bool {
+            true
+        }
+
+        fn get_proc_address(&self, sym: &str) -> retro_proc_address_t {
+            match sym {
+                "proc_core_set_value" => Some(unsafe {
+                    std::mem::transmute::<unsafe extern "C" fn(u32), unsafe extern "C" fn()>(
+                        set_value,
+                    )
+                }),
+                _ => None,
+            }
+        }
+    }
+
+    let mut frontend = MockFrontend::new(ProcCore);
+    assert!(frontend.get_proc_address("proc_core_set_value").is_none());
+
+    frontend.set_environment();
+    frontend.init();
+
+    assert!(frontend
+        .environment_calls()
+        .contains(&RETRO_ENVIRONMENT_SET_PROC_ADDRESS_CALLBACK));
+    assert!(frontend.get_proc_address("unknown").is_none());
+
+    let set_value = frontend.get_proc_address("proc_core_set_value").unwrap();
+    unsafe {
+        let set_value: unsafe extern "C" fn(u32) = std::mem::transmute(set_value);
+        set_value(42);
+    }
+
+    assert_eq!(VALUE.load(Ordering::SeqCst), 42);
+}
+
+#[test]
+fn registered_save_ram_answers_memory_requests() {
+    struct SaveRamCore {
+        save_ram: SaveRam,
+    }
+
+    impl crate::core::CoreOptions for SaveRamCore {}
+
+    impl Core for SaveRamCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("SaveRamCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn on_load_game(
+            &mut self,
+            info: Option<retro_game_info>,
+            ctx: &mut LoadGameContext,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            // Only the first game uses save data
+            if info.map_or(0, |info| info.size) == 1 {
+                ctx.set_save_ram(&self.save_ram);
+            }
+
+            Ok(())
+        }
+
+        fn on_run(&mut self, _ctx: &mut RunContext, _delta: Option<std::time::Duration>) {
+            self.save_ram.write()[0] += 1;
+        }
+    }
+
+    let mut frontend = MockFrontend::new(SaveRamCore {
+        save_ram: SaveRam::new(8),
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    frontend.run_frames(3);
+
+    let mut expected = vec![0; 8];
+    expected[0] = 3;
+    assert_eq!(frontend.memory(MemoryId::SaveRam), Some(expected));
+
+    frontend.unload_game();
+    assert!(frontend.load_game(&[0, 0]));
+    assert_eq!(frontend.memory(MemoryId::SaveRam), None);
+}
+
+#[test]
+fn nested_notifications_run_after_the_outer_call() {
+    static EVENTS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+    struct NestedCore;
+
+    impl crate::core::CoreOptions for NestedCore {}
+
+    impl Core for NestedCore {
+        fn get_info(&self) -> SystemInfo {
+            SystemInfo::builder("NestedCore")
+                .version("1.0.0")
+                .valid_extensions(&["bin"])
+                .build()
+        }
+
+        fn on_get_av_info(&mut self, _ctx: &mut GetAvInfoContext) -> retro_system_av_info {
+            test_av_info()
+        }
+
+        fn on_run(&mut self, ctx: &mut RunContext, _delta: Option<std::time::Duration>) {
+            EVENTS.lock().unwrap().push("run");
+            assert!(ctx.set_geometry(test_av_info().geometry));
+            EVENTS.lock().unwrap().push("geometry set");
+        }
+
+        fn on_hw_context_reset(&mut self, _ctx: &mut HwContext) {
+            EVENTS.lock().unwrap().push("context reset");
+        }
+    }
+
+    let mut frontend = MockFrontend::new(NestedCore);
+
+    // Resets the context from within the environment call, like a frontend reinitializing its video driver
+    frontend.on_environment(|cmd, _| {
+        if cmd != RETRO_ENVIRONMENT_SET_GEOMETRY {
+            return None;
+        }
+
+        unsafe { retro_hw_context_reset_callback() };
+        Some(true)
+    });
+
+    frontend.set_environment();
+    frontend.init();
+    assert!(frontend.load_game(&[0]));
+
+    frontend.run();
+
+    assert_eq!(
+        EVENTS.lock().unwrap().as_slice(),
+        ["run", "geometry set", "context reset"]
+    );
+}
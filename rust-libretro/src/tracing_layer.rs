@@ -0,0 +1,250 @@
+//! [`tracing_subscriber::Layer`] implementation using the libretro logging interface.
+//!
+//! Events are flattened into a single line, prefixed by the spans they were recorded in:
+//! `[target] outer{id=1}:inner: message key=value`.
+use super::*;
+use std::{
+    fmt::{Debug, Write},
+    io,
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, EnvFilter, Layer};
+
+/// Forwards [`tracing`] events to the frontend.
+///
+/// Falls back to `stderr` if the frontend provides no log callback.
+pub struct RetroLayer {
+    /// Shared with [`RetroLayer::callback_handle`], so it can be replaced after
+    /// the layer became part of the global subscriber.
+    callback: Arc<RwLock<retro_log_callback>>,
+
+    /// Receives the events if the frontend provides no log callback.
+    fallback: Mutex<Box<dyn io::Write + Send>>,
+}
+
+impl RetroLayer {
+    pub fn new(callback: retro_log_callback) -> Self {
+        Self::with_fallback(callback, io::stderr())
+    }
+
+    /// Like [`RetroLayer::new`], but writes the events to `fallback` instead of `stderr`
+    /// if the frontend provides no log callback.
+    pub fn with_fallback(
+        callback: retro_log_callback,
+        fallback: impl io::Write + Send + 'static,
+    ) -> Self {
+        Self {
+            callback: Arc::new(RwLock::new(callback)),
+            fallback: Mutex::new(Box::new(fallback)),
+        }
+    }
+
+    /// Returns a handle to replace the log callback, e.g. once a new environment has been set.
+    ///
+    /// The global subscriber can only be installed once per process,
+    /// so later instances of the core keep using it with their own callback.
+    pub(crate) fn callback_handle(&self) -> Arc<RwLock<retro_log_callback>> {
+        Arc::clone(&self.callback)
+    }
+
+    /// Creates a filter that uses the directives of the `RUST_LIBRETRO_LOG`
+    /// or `RUST_LOG` environment variables (in that order), or `default_level` if neither is set.
+    pub fn filter(default_level: tracing::level_filters::LevelFilter) -> EnvFilter {
+        ["RUST_LIBRETRO_LOG", "RUST_LOG"]
+            .into_iter()
+            .filter_map(|variable| std::env::var(variable).ok())
+            .find(|directives| !directives.trim().is_empty())
+            .and_then(|directives| EnvFilter::try_new(directives).ok())
+            .unwrap_or_else(|| EnvFilter::default().add_directive(default_level.into()))
+    }
+
+    fn get_retro_log_level(level: &Level) -> retro_log_level {
+        match *level {
+            Level::ERROR => retro_log_level::RETRO_LOG_ERROR,
+            Level::WARN => retro_log_level::RETRO_LOG_WARN,
+            Level::INFO => retro_log_level::RETRO_LOG_INFO,
+            Level::DEBUG => retro_log_level::RETRO_LOG_DEBUG,
+            Level::TRACE => retro_log_level::RETRO_LOG_DEBUG,
+        }
+    }
+
+    /// Builds the message of an event, prefixed by its spans from the outermost inwards.
+    fn format_event<S>(event: &Event, ctx: &Context<S>) -> String
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let mut message = String::new();
+
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                message.push_str(span.name());
+
+                let extensions = span.extensions();
+                if let Some(fields) = extensions.get::<SpanFields>() {
+                    if !fields.0.is_empty() {
+                        let _ = write!(message, "{{{}}}", fields.0);
+                    }
+                }
+
+                message.push_str(": ");
+            }
+        }
+
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+
+        message.push_str(&visitor.message);
+
+        if !visitor.fields.is_empty() {
+            if !visitor.message.is_empty() {
+                message.push(' ');
+            }
+
+            message.push_str(&visitor.fields);
+        }
+
+        message
+    }
+}
+
+impl<S> Layer<S> for RetroLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut fields = SpanFields::default();
+        attrs.record(&mut fields);
+
+        span.extensions_mut().insert(fields);
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut extensions = span.extensions_mut();
+
+        if let Some(fields) = extensions.get_mut::<SpanFields>() {
+            values.record(fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let message = Self::format_event(event, &ctx);
+
+        let callback = *self.callback.read().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(cb) = callback.log {
+            let level = Self::get_retro_log_level(metadata.level());
+            let target = CString::new(metadata.target()).unwrap_or_default();
+            let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+
+            unsafe {
+                // The callback works like `printf`
+                (cb)(
+                    level,
+                    "[%s] %s\n\0".as_ptr() as *const c_char,
+                    target.as_ptr() as *const c_char,
+                    message.as_ptr() as *const c_char,
+                )
+            }
+        } else {
+            // Without a frontend callback the messages would be lost otherwise
+            let line = format!(
+                "[libretro {}] [{}] {message}\n",
+                metadata.level(),
+                metadata.target()
+            );
+
+            let mut fallback = self.fallback.lock().unwrap_or_else(PoisonError::into_inner);
+            let _ = fallback.write_all(line.as_bytes());
+        }
+    }
+}
+
+/// The formatted fields of a span, stored in its extensions.
+#[derive(Default)]
+struct SpanFields(String);
+
+impl Visit for SpanFields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+
+        let _ = write!(self.0, "{}={value:?}", field.name());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &value as &dyn Debug)
+    }
+}
+
+/// Separates the `message` field of an event from its other fields.
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for EventVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+            return;
+        }
+
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+
+        let _ = write!(self.fields, "{}={value:?}", field.name());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.record_debug(field, &value as &dyn Debug)
+        }
+    }
+}
+
+#[test]
+fn layer_flattens_spans_into_the_message() {
+    use crate::util::SharedBuffer;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let output = SharedBuffer::default();
+    let subscriber = tracing_subscriber::registry().with(RetroLayer::with_fallback(
+        retro_log_callback { log: None },
+        output.clone(),
+    ));
+
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("load_game", size = 42);
+        let _enter = span.enter();
+
+        tracing::warn!(mapper = "NROM", "Unknown header");
+    });
+
+    assert_eq!(
+        output.contents(),
+        "[libretro WARN] [rust_libretro::tracing_layer] load_game{size=42}: Unknown header mapper=\"NROM\"\n"
+    );
+
+    assert_eq!(
+        RetroLayer::get_retro_log_level(&Level::TRACE),
+        retro_log_level::RETRO_LOG_DEBUG
+    );
+}
@@ -1,6 +1,6 @@
 //! Rust versions of libretro data structures.
 use super::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Static information about the [`Core`] implementation.
 #[derive(Debug, Default)]
@@ -45,6 +45,778 @@ pub struct SystemInfo {
     pub block_extract: bool,
 }
 
+impl SystemInfo {
+    /// Creates a [`SystemInfoBuilder`] for the core called `name`.
+    pub fn builder(name: &str) -> SystemInfoBuilder {
+        SystemInfoBuilder::default().name(name)
+    }
+}
+
+/// Builds a [`SystemInfo`] from Rust strings.
+///
+/// ```
+/// # use rust_libretro::types::SystemInfo;
+/// let info = SystemInfo::builder("Example")
+///     .version(env!("CARGO_PKG_VERSION"))
+///     .valid_extensions(&["nes", "fds"])
+///     .build();
+///
+/// assert_eq!(info.valid_extensions.to_str(), Ok("nes|fds"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SystemInfoBuilder {
+    name: String,
+    version: String,
+    valid_extensions: String,
+    need_fullpath: bool,
+    block_extract: bool,
+}
+
+impl SystemInfoBuilder {
+    /// See [`SystemInfo::library_name`].
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_owned();
+        self
+    }
+
+    /// See [`SystemInfo::library_version`].
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = version.to_owned();
+        self
+    }
+
+    /// Extensions without leading dot, see [`SystemInfo::valid_extensions`].
+    pub fn valid_extensions(mut self, extensions: &[&str]) -> Self {
+        self.valid_extensions = extensions.join("|");
+        self
+    }
+
+    /// See [`SystemInfo::need_fullpath`].
+    pub fn need_fullpath(mut self, need_fullpath: bool) -> Self {
+        self.need_fullpath = need_fullpath;
+        self
+    }
+
+    /// See [`SystemInfo::block_extract`].
+    pub fn block_extract(mut self, block_extract: bool) -> Self {
+        self.block_extract = block_extract;
+        self
+    }
+
+    /// Returns the [`SystemInfo`], or the name of the first field containing a NUL byte.
+    pub fn try_build(self) -> Result<SystemInfo, &'static str> {
+        let to_c_string =
+            |field: &'static str, value: String| CString::new(value).map_err(|_| field);
+
+        Ok(SystemInfo {
+            library_name: to_c_string("name", self.name)?,
+            library_version: to_c_string("version", self.version)?,
+            valid_extensions: to_c_string("valid_extensions", self.valid_extensions)?,
+            need_fullpath: self.need_fullpath,
+            block_extract: self.block_extract,
+        })
+    }
+
+    /// Returns the [`SystemInfo`].
+    ///
+    /// # Panics
+    ///
+    /// If a string contains a NUL byte. See [`SystemInfoBuilder::try_build`] for a
+    /// non-panicking version.
+    pub fn build(self) -> SystemInfo {
+        match self.try_build() {
+            Ok(info) => info,
+            Err(field) => panic!("SystemInfoBuilder: `{field}` must not contain NUL bytes"),
+        }
+    }
+}
+
+#[test]
+fn system_info_builder_joins_extensions() {
+    let info = SystemInfo::builder("Core")
+        .version("1.0.0")
+        .valid_extensions(&["bin", "rom"])
+        .need_fullpath(true)
+        .build();
+
+    assert_eq!(info.library_name.to_str(), Ok("Core"));
+    assert_eq!(info.library_version.to_str(), Ok("1.0.0"));
+    assert_eq!(info.valid_extensions.to_str(), Ok("bin|rom"));
+    assert!(info.need_fullpath);
+    assert!(!info.block_extract);
+
+    assert_eq!(
+        SystemInfo::builder("Core")
+            .valid_extensions(&["bin\0"])
+            .try_build()
+            .err(),
+        Some("valid_extensions")
+    );
+}
+
+#[test]
+#[should_panic(expected = "`name` must not contain NUL bytes")]
+fn system_info_builder_panics_on_nul_bytes() {
+    SystemInfo::builder("Co\0re").build();
+}
+
+/// Builds the [`retro_system_av_info`] returned by [`Core::on_get_av_info`].
+///
+/// ```
+/// # use rust_libretro::types::SystemAvInfoBuilder;
+/// let av_info = SystemAvInfoBuilder::new(256, 224)
+///     .max_size(512, 448)
+///     .aspect_ratio(4.0 / 3.0)
+///     .fps(60.0988)
+///     .sample_rate(32040.0)
+///     .build();
+///
+/// assert_eq!(av_info.geometry.max_width, 512);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SystemAvInfoBuilder {
+    base_width: u32,
+    base_height: u32,
+    max_size: Option<(u32, u32)>,
+    aspect_ratio: f32,
+    fps: f64,
+    sample_rate: f64,
+}
+
+impl SystemAvInfoBuilder {
+    /// Creates a builder for frames of `base_width` × `base_height` pixels at 60 FPS without audio.
+    ///
+    /// The maximum size equals the base size unless [`SystemAvInfoBuilder::max_size`] is used.
+    pub fn new(base_width: u32, base_height: u32) -> Self {
+        Self {
+            base_width,
+            base_height,
+            max_size: None,
+            aspect_ratio: 0.0,
+            fps: 60.0,
+            sample_rate: 0.0,
+        }
+    }
+
+    /// The nominal video size, see [`retro_game_geometry::base_width`].
+    pub fn base_size(mut self, width: u32, height: u32) -> Self {
+        self.base_width = width;
+        self.base_height = height;
+        self
+    }
+
+    /// The largest frame the core will ever draw, see [`retro_game_geometry::max_width`].
+    pub fn max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    /// The display aspect ratio, which may differ from `base_width / base_height`
+    /// for non-square pixels.
+    pub fn aspect_ratio(mut self, aspect_ratio: f32) -> Self {
+        self.aspect_ratio = aspect_ratio;
+        self
+    }
+
+    /// Makes the frontend derive the aspect ratio from the base size,
+    /// see [`util::effective_aspect_ratio`].
+    pub fn aspect_ratio_auto(self) -> Self {
+        self.aspect_ratio(0.0)
+    }
+
+    /// See [`retro_system_timing::fps`].
+    pub fn fps(mut self, fps: f64) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// The rate of the samples passed to [`AudioContext::batch_audio_samples`] and
+    /// similar functions, see [`retro_system_timing::sample_rate`].
+    pub fn sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Returns the [`retro_system_av_info`], or an error if the maximum size is smaller
+    /// than the base size.
+    pub fn try_build(self) -> Result<retro_system_av_info, &'static str> {
+        let (max_width, max_height) = self.max_size.unwrap_or((self.base_width, self.base_height));
+
+        if max_width < self.base_width || max_height < self.base_height {
+            return Err("the maximum size must not be smaller than the base size");
+        }
+
+        Ok(retro_system_av_info {
+            geometry: retro_game_geometry {
+                base_width: self.base_width,
+                base_height: self.base_height,
+                max_width,
+                max_height,
+                aspect_ratio: self.aspect_ratio,
+            },
+            timing: retro_system_timing {
+                fps: self.fps,
+                sample_rate: self.sample_rate,
+            },
+        })
+    }
+
+    /// Returns the [`retro_system_av_info`].
+    ///
+    /// # Panics
+    ///
+    /// If the maximum size is smaller than the base size.
+    /// See [`SystemAvInfoBuilder::try_build`] for a non-panicking version.
+    pub fn build(self) -> retro_system_av_info {
+        match self.try_build() {
+            Ok(av_info) => av_info,
+            Err(err) => panic!("SystemAvInfoBuilder: {err}"),
+        }
+    }
+}
+
+#[test]
+fn system_av_info_builder_validates_the_size() {
+    let av_info = SystemAvInfoBuilder::new(320, 240)
+        .sample_rate(48000.0)
+        .build();
+
+    assert_eq!(av_info.geometry.max_width, 320);
+    assert_eq!(av_info.geometry.max_height, 240);
+    assert_eq!(av_info.geometry.aspect_ratio, 0.0);
+    assert_eq!(av_info.timing.fps, 60.0);
+    assert_eq!(av_info.timing.sample_rate, 48000.0);
+
+    let av_info = SystemAvInfoBuilder::new(256, 224)
+        .aspect_ratio(4.0 / 3.0)
+        .aspect_ratio_auto()
+        .base_size(512, 448)
+        .build();
+
+    assert_eq!(av_info.geometry.base_width, 512);
+    assert_eq!(av_info.geometry.max_height, 448);
+    assert_eq!(av_info.geometry.aspect_ratio, 0.0);
+
+    assert!(SystemAvInfoBuilder::new(320, 240)
+        .max_size(320, 200)
+        .try_build()
+        .is_err());
+}
+
+/// Describes a subsystem, i.e. a special kind of game that consists of multiple ROMs,
+/// like the Super Game Boy or Sufami Turbo.
+///
+/// Subsystems are returned by [`Core::get_subsystem_info`] and get loaded through
+/// [`Core::on_load_game_special`], which receives the subsystem `id` as `game_type`.
+#[derive(Debug, Default, Clone)]
+pub struct SubsystemInfo {
+    /// Human-readable string of the subsystem type, e.g. "Super GameBoy".
+    pub desc: CString,
+
+    /// A computer friendly short string identifier for the subsystem type.
+    /// This name must be [a-z].
+    /// E.g. if `desc` is "Super GameBoy", this can be "sgb".
+    /// This identifier can be used for command-line interfaces, etc.
+    pub ident: CString,
+
+    /// Identifier passed to [`Core::on_load_game_special`] as `game_type`.
+    pub id: u32,
+
+    /// The ROMs of this subsystem, in the order they get passed to
+    /// [`Core::on_load_game_special`].
+    pub roms: Vec<SubsystemRomInfo>,
+}
+
+/// Describes one ROM of a [`SubsystemInfo`].
+#[derive(Debug, Default, Clone)]
+pub struct SubsystemRomInfo {
+    /// Describes what the content is (SGB BIOS, GB ROM, etc).
+    pub desc: CString,
+
+    /// Same definition as [`SystemInfo::valid_extensions`].
+    pub valid_extensions: CString,
+
+    /// Same definition as [`SystemInfo::need_fullpath`].
+    pub need_fullpath: bool,
+
+    /// Same definition as [`SystemInfo::block_extract`].
+    pub block_extract: bool,
+
+    /// This is set if the content is required to load a game.
+    /// If this is set to [`false`], a zeroed-out [`retro_game_info`] can be passed.
+    pub required: bool,
+
+    /// Content can have multiple associated persistent memory types
+    /// (retro_get_memory()).
+    pub memory: Vec<SubsystemMemoryInfo>,
+}
+
+/// Describes a persistent memory type of a [`SubsystemRomInfo`].
+#[derive(Debug, Default, Clone)]
+pub struct SubsystemMemoryInfo {
+    /// The extension associated with a memory type, e.g. "psram".
+    pub extension: CString,
+
+    /// The memory type for retro_get_memory().
+    /// This should be at least 0x100 to avoid conflict with standardized
+    /// libretro memory types.
+    pub type_: u32,
+}
+
+/// Keeps the C representation of a list of [`SubsystemInfo`]s alive,
+/// as the frontend may keep referencing it.
+#[derive(Debug)]
+pub(crate) struct RawSubsystemInfo {
+    _subsystems: Vec<SubsystemInfo>,
+    _memory: Vec<Vec<retro_subsystem_memory_info>>,
+    _roms: Vec<Vec<retro_subsystem_rom_info>>,
+    info: Vec<retro_subsystem_info>,
+}
+
+impl RawSubsystemInfo {
+    pub(crate) fn new(subsystems: Vec<SubsystemInfo>) -> Self {
+        let memory = subsystems
+            .iter()
+            .flat_map(|subsystem| &subsystem.roms)
+            .map(|rom| {
+                rom.memory
+                    .iter()
+                    .map(|memory| retro_subsystem_memory_info {
+                        extension: memory.extension.as_ptr(),
+                        type_: memory.type_,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut rom_memory = memory.iter();
+        let roms = subsystems
+            .iter()
+            .map(|subsystem| {
+                subsystem
+                    .roms
+                    .iter()
+                    .zip(rom_memory.by_ref())
+                    .map(|(rom, memory)| retro_subsystem_rom_info {
+                        desc: rom.desc.as_ptr(),
+                        valid_extensions: rom.valid_extensions.as_ptr(),
+                        need_fullpath: rom.need_fullpath,
+                        block_extract: rom.block_extract,
+                        required: rom.required,
+                        memory: memory.as_ptr(),
+                        num_memory: memory.len() as u32,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut info = subsystems
+            .iter()
+            .zip(&roms)
+            .map(|(subsystem, roms)| retro_subsystem_info {
+                desc: subsystem.desc.as_ptr(),
+                ident: subsystem.ident.as_ptr(),
+                roms: roms.as_ptr(),
+                num_roms: roms.len() as u32,
+                id: subsystem.id,
+            })
+            .collect::<Vec<_>>();
+
+        // The list is terminated by a zeroed out entry
+        info.push(retro_subsystem_info {
+            desc: std::ptr::null(),
+            ident: std::ptr::null(),
+            roms: std::ptr::null(),
+            num_roms: 0,
+            id: 0,
+        });
+
+        Self {
+            _subsystems: subsystems,
+            _memory: memory,
+            _roms: roms,
+            info,
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[retro_subsystem_info] {
+        &self.info
+    }
+}
+
+#[test]
+fn raw_subsystem_info_is_terminated() {
+    let memory = SubsystemMemoryInfo {
+        extension: CString::new("psram").unwrap(),
+        type_: 0x100,
+    };
+
+    let rom = |desc: &str| SubsystemRomInfo {
+        desc: CString::new(desc).unwrap(),
+        valid_extensions: CString::new("gb|gbc").unwrap(),
+        required: true,
+        ..Default::default()
+    };
+
+    let raw = RawSubsystemInfo::new(vec![SubsystemInfo {
+        desc: CString::new("Link Cable").unwrap(),
+        ident: CString::new("link").unwrap(),
+        id: 1,
+        roms: vec![
+            rom("Player 1"),
+            SubsystemRomInfo {
+                memory: vec![memory],
+                ..rom("Player 2")
+            },
+        ],
+    }]);
+
+    let info = raw.as_slice();
+    assert_eq!(info.len(), 2);
+    assert!(info[1].desc.is_null() && info[1].ident.is_null() && info[1].roms.is_null());
+
+    assert_eq!(get_str_from_pointer(info[0].ident), Some("link"));
+    assert_eq!(info[0].id, 1);
+    assert_eq!(info[0].num_roms, 2);
+
+    let roms = unsafe { std::slice::from_raw_parts(info[0].roms, info[0].num_roms as usize) };
+    assert_eq!(get_str_from_pointer(roms[0].desc), Some("Player 1"));
+    assert_eq!(roms[0].num_memory, 0);
+    assert_eq!(get_str_from_pointer(roms[1].desc), Some("Player 2"));
+    assert_eq!(roms[1].num_memory, 1);
+
+    let memory = unsafe { &*roms[1].memory };
+    assert_eq!(get_str_from_pointer(memory.extension), Some("psram"));
+    assert_eq!(memory.type_, 0x100);
+}
+
+/// Describes what an input does, e.g. that the B button makes the character jump.
+///
+/// See [`SetEnvironmentContext::set_input_descriptors`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InputDescriptor<'a> {
+    pub port: u32,
+    pub device: u32,
+    pub index: u32,
+    pub id: u32,
+
+    /// Human readable description of the input, e.g. "Jump".
+    pub description: &'a str,
+}
+
+/// Makes a device (subclass) selectable for an input port,
+/// see [`SetEnvironmentContext::set_controller_info`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ControllerDescription<'a> {
+    /// The port this device can be selected for.
+    pub port: u32,
+
+    /// Device type passed to [`Core::on_set_controller_port_device`].
+    /// Subclasses of the generic device types can be created with
+    /// [`RETRO_DEVICE_SUBCLASS`](crate::sys::RETRO_DEVICE_SUBCLASS).
+    pub id: u32,
+
+    /// Human readable name of the device, e.g. "Standard Gamepad".
+    pub desc: &'a str,
+}
+
+/// A safe view of the [`retro_game_info`] passed to [`Core::on_load_game`].
+///
+/// ```ignore
+/// fn on_load_game(&mut self, info: Option<retro_game_info>, ctx: &mut LoadGameContext) -> … {
+///     let info = info.as_ref().map(GameInfo::new);
+///     let rom = info.and_then(GameInfo::data).ok_or("No ROM data")?;
+///     …
+/// }
+/// ```
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone)]
+pub struct GameInfo(retro_game_info);
+
+impl GameInfo {
+    /// Wraps a [`retro_game_info`] provided by the frontend.
+    pub fn new(info: &retro_game_info) -> &Self {
+        // SAFETY: `GameInfo` is a transparent wrapper around `retro_game_info`
+        unsafe { &*(info as *const retro_game_info as *const Self) }
+    }
+
+    /// Wraps the [`retro_game_info`]s passed to [`Core::on_load_game_special`].
+    pub fn from_slice(infos: &[retro_game_info]) -> &[Self] {
+        // SAFETY: `GameInfo` is a transparent wrapper around `retro_game_info`
+        unsafe { std::slice::from_raw_parts(infos.as_ptr() as *const Self, infos.len()) }
+    }
+
+    /// The path of the content file.
+    ///
+    /// Always set if [`SystemInfo::need_fullpath`] is `true`, otherwise it may be [`None`],
+    /// e.g. if the content was loaded from an archive.
+    pub fn path(&self) -> Option<&Path> {
+        get_path_from_pointer(self.0.path)
+    }
+
+    /// The contents of the loaded file.
+    ///
+    /// This is [`None`] if [`SystemInfo::need_fullpath`] is `true`,
+    /// in which case the core has to load the file from [`GameInfo::path`] itself.
+    /// The data is only valid until [`Core::on_load_game`] returns and must be copied to be kept.
+    pub fn data(&self) -> Option<&[u8]> {
+        if self.0.data.is_null() {
+            return None;
+        }
+
+        Some(unsafe { std::slice::from_raw_parts(self.0.data as *const u8, self.0.size) })
+    }
+
+    /// Implementation specific meta data.
+    pub fn meta(&self) -> Option<&str> {
+        get_str_from_pointer(self.0.meta)
+    }
+}
+
+/// Extended information about a loaded content file,
+/// see [`LoadGameContext::get_game_info_ext`].
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone)]
+pub struct GameInfoExt(retro_game_info_ext);
+
+#[test]
+fn game_info_handles_null_pointers() {
+    const DATA: &[u8] = &[1, 2, 3];
+
+    let info = retro_game_info {
+        path: b"/roms/game.md\0".as_ptr() as *const c_char,
+        data: DATA.as_ptr() as *const c_void,
+        size: DATA.len(),
+        meta: std::ptr::null(),
+    };
+
+    let info = GameInfo::new(&info);
+    assert_eq!(info.path(), Some(Path::new("/roms/game.md")));
+    assert_eq!(info.data(), Some(DATA));
+    assert_eq!(info.meta(), None);
+
+    // Cores that need the full path don’t get any data
+    let infos = [retro_game_info {
+        path: b"/roms/game.md\0".as_ptr() as *const c_char,
+        data: std::ptr::null(),
+        size: 0,
+        meta: b"meta\0".as_ptr() as *const c_char,
+    }];
+
+    let infos = GameInfo::from_slice(&infos);
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].data(), None);
+    assert_eq!(infos[0].meta(), Some("meta"));
+}
+
+impl GameInfoExt {
+    /// The path of the content file, if it is not inside an archive.
+    pub fn full_path(&self) -> Option<&Path> {
+        get_path_from_pointer(self.0.full_path)
+    }
+
+    /// The path of the archive containing the content file.
+    pub fn archive_path(&self) -> Option<&Path> {
+        get_path_from_pointer(self.0.archive_path)
+    }
+
+    /// The path of the content file inside its archive.
+    pub fn archive_file(&self) -> Option<&str> {
+        get_str_from_pointer(self.0.archive_file)
+    }
+
+    /// The directory of the content file, or of its archive.
+    pub fn dir(&self) -> Option<&Path> {
+        get_path_from_pointer(self.0.dir)
+    }
+
+    /// The canonical name of the content file without extension, e.g. “Super Mario Bros. (USA)”.
+    pub fn name(&self) -> Option<&str> {
+        get_str_from_pointer(self.0.name)
+    }
+
+    /// The lowercase extension of the content file, e.g. “nes”.
+    pub fn ext(&self) -> Option<&str> {
+        get_str_from_pointer(self.0.ext)
+    }
+
+    /// Implementation specific meta data.
+    pub fn meta(&self) -> Option<&str> {
+        get_str_from_pointer(self.0.meta)
+    }
+
+    /// The contents of the loaded file.
+    ///
+    /// This is [`None`] if the core (or a [`ContentInfoOverride`] for this extension)
+    /// requested the full path instead.
+    pub fn data(&self) -> Option<&[u8]> {
+        if self.0.data.is_null() {
+            return None;
+        }
+
+        Some(unsafe { std::slice::from_raw_parts(self.0.data as *const u8, self.0.size) })
+    }
+
+    /// Whether the content file is inside a compressed archive.
+    pub fn file_in_archive(&self) -> bool {
+        self.0.file_in_archive
+    }
+
+    /// If `true`, [`GameInfoExt::data`] stays valid until [`Core::on_deinit`] returns.
+    /// Otherwise it is only valid until [`Core::on_load_game`] returns and must be copied to be kept.
+    ///
+    /// See [`ContentInfoOverride::persistent_data`] and [`LoadGameContext::persistent_game_data`].
+    pub fn persistent_data(&self) -> bool {
+        self.0.persistent_data
+    }
+}
+
+/// Overrides how the frontend handles content with specific file extensions,
+/// see [`SetEnvironmentContext::set_content_info_override`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ContentInfoOverride<'a> {
+    /// The file extensions this override applies to, delimited by a pipe character, e.g. "md|sms|gg".
+    ///
+    /// Only extensions listed in [`SystemInfo::valid_extensions`] or
+    /// [`SubsystemRomInfo::valid_extensions`] are permitted.
+    pub extensions: &'a str,
+
+    /// Overrides [`SystemInfo::need_fullpath`] for these extensions.
+    pub need_fullpath: bool,
+
+    /// If `true`, the game data passed to [`Core::on_load_game`] stays valid until
+    /// [`Core::on_deinit`] returns, instead of only until [`Core::on_load_game`] returns.
+    /// Has no effect if `need_fullpath` is `true`.
+    pub persistent_data: bool,
+}
+
+/// Keeps the C representation of a list of [`ControllerDescription`]s alive,
+/// as the frontend may keep referencing it.
+#[derive(Debug, Default)]
+pub(crate) struct RawControllerInfo {
+    _descs: Vec<CString>,
+    _types: Vec<Vec<retro_controller_description>>,
+    info: Vec<retro_controller_info>,
+}
+
+impl RawControllerInfo {
+    pub(crate) fn new(controllers: &[ControllerDescription]) -> Result<Self, std::ffi::NulError> {
+        let descs = controllers
+            .iter()
+            .map(|controller| CString::new(controller.desc))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Ports are identified by their index, so every port up to the highest one needs an entry
+        let num_ports = controllers
+            .iter()
+            .map(|controller| controller.port as usize + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut types = vec![Vec::new(); num_ports];
+        for (controller, desc) in controllers.iter().zip(&descs) {
+            types[controller.port as usize].push(retro_controller_description {
+                desc: desc.as_ptr(),
+                id: controller.id,
+            });
+        }
+
+        let mut info = types
+            .iter_mut()
+            .map(|types| {
+                let num_types = types.len() as u32;
+
+                // A null `types` pointer ends the list, so empty ports point to a dummy entry
+                if types.is_empty() {
+                    types.push(retro_controller_description {
+                        desc: std::ptr::null(),
+                        id: RETRO_DEVICE_NONE,
+                    });
+                }
+
+                retro_controller_info {
+                    types: types.as_ptr(),
+                    num_types,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // The list is terminated by a zeroed out entry
+        info.push(retro_controller_info {
+            types: std::ptr::null(),
+            num_types: 0,
+        });
+
+        Ok(Self {
+            _descs: descs,
+            _types: types,
+            info,
+        })
+    }
+
+    pub(crate) fn as_slice(&self) -> &[retro_controller_info] {
+        &self.info
+    }
+}
+
+#[test]
+fn raw_controller_info_groups_ports() {
+    let raw = RawControllerInfo::new(&[
+        ControllerDescription {
+            port: 0,
+            id: RETRO_DEVICE_JOYPAD,
+            desc: "Standard Gamepad",
+        },
+        ControllerDescription {
+            port: 2,
+            id: RETRO_DEVICE_MOUSE,
+            desc: "Mouse",
+        },
+        ControllerDescription {
+            port: 0,
+            id: RETRO_DEVICE_MOUSE,
+            desc: "Mouse",
+        },
+    ])
+    .unwrap();
+
+    let info = raw.as_slice();
+    assert_eq!(info.len(), 4);
+    assert_eq!(info[1].num_types, 0);
+    assert!(!info[1].types.is_null());
+    assert_eq!(info[3].num_types, 0);
+    assert!(info[3].types.is_null());
+
+    // Frontends stop at the first entry without types, so the empty port must not end the list
+    assert_eq!(
+        info.iter().take_while(|info| !info.types.is_null()).count(),
+        3
+    );
+
+    let port = |index: usize| unsafe {
+        std::slice::from_raw_parts(info[index].types, info[index].num_types as usize)
+            .iter()
+            .map(|desc| (desc.id, get_str_from_pointer(desc.desc).unwrap()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        port(0),
+        vec![
+            (RETRO_DEVICE_JOYPAD, "Standard Gamepad"),
+            (RETRO_DEVICE_MOUSE, "Mouse")
+        ]
+    );
+    assert_eq!(port(2), vec![(RETRO_DEVICE_MOUSE, "Mouse")]);
+
+    assert!(RawControllerInfo::new(&[ControllerDescription {
+        port: 0,
+        id: RETRO_DEVICE_JOYPAD,
+        desc: "\0",
+    }])
+    .is_err());
+}
+
 bitflags::bitflags! {
     /// Bitflags indicating the type of input device
     pub struct RetroDevice: u8 {
@@ -124,6 +896,10 @@ bitflags::bitflags! {
         const POINTER = (1 << RETRO_DEVICE_POINTER);
     }
 }
+/// The device types the frontend handles, as returned by
+/// [`RunContext::get_input_device_capabilities`](crate::contexts::RunContext::get_input_device_capabilities).
+pub type InputDeviceCaps = RetroDevice;
+
 #[test]
 fn retro_device_struct_size() {
     assert_eq!(
@@ -132,6 +908,129 @@ fn retro_device_struct_size() {
     );
 }
 
+/// A device selected for an input port, see [`Core::on_set_controller_port_device`].
+///
+/// `subclass` is the `id` passed to [`RETRO_DEVICE_SUBCLASS`](crate::sys::RETRO_DEVICE_SUBCLASS),
+/// or [`None`] for the generic device type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DeviceType {
+    /// Input disabled, [`RETRO_DEVICE_NONE`].
+    None,
+
+    /// [`RETRO_DEVICE_JOYPAD`]
+    Joypad { subclass: Option<u32> },
+
+    /// [`RETRO_DEVICE_MOUSE`]
+    Mouse { subclass: Option<u32> },
+
+    /// [`RETRO_DEVICE_KEYBOARD`]
+    Keyboard { subclass: Option<u32> },
+
+    /// [`RETRO_DEVICE_LIGHTGUN`]
+    Lightgun { subclass: Option<u32> },
+
+    /// [`RETRO_DEVICE_ANALOG`]
+    Analog { subclass: Option<u32> },
+
+    /// [`RETRO_DEVICE_POINTER`]
+    Pointer { subclass: Option<u32> },
+
+    /// A base type unknown to this version of libretro, holding the raw device id.
+    Unknown(u32),
+}
+
+impl DeviceType {
+    /// Splits a raw device id into its base type and subclass.
+    pub fn from_raw(device: u32) -> Self {
+        let subclass = (device >> RETRO_DEVICE_TYPE_SHIFT).checked_sub(1);
+
+        match device & RETRO_DEVICE_MASK {
+            RETRO_DEVICE_NONE => Self::None,
+            RETRO_DEVICE_JOYPAD => Self::Joypad { subclass },
+            RETRO_DEVICE_MOUSE => Self::Mouse { subclass },
+            RETRO_DEVICE_KEYBOARD => Self::Keyboard { subclass },
+            RETRO_DEVICE_LIGHTGUN => Self::Lightgun { subclass },
+            RETRO_DEVICE_ANALOG => Self::Analog { subclass },
+            RETRO_DEVICE_POINTER => Self::Pointer { subclass },
+            _ => Self::Unknown(device),
+        }
+    }
+
+    /// Returns the raw device id, including the subclass bits.
+    pub fn to_raw(&self) -> u32 {
+        let (base, subclass) = match *self {
+            Self::None => return RETRO_DEVICE_NONE,
+            Self::Joypad { subclass } => (RETRO_DEVICE_JOYPAD, subclass),
+            Self::Mouse { subclass } => (RETRO_DEVICE_MOUSE, subclass),
+            Self::Keyboard { subclass } => (RETRO_DEVICE_KEYBOARD, subclass),
+            Self::Lightgun { subclass } => (RETRO_DEVICE_LIGHTGUN, subclass),
+            Self::Analog { subclass } => (RETRO_DEVICE_ANALOG, subclass),
+            Self::Pointer { subclass } => (RETRO_DEVICE_POINTER, subclass),
+            Self::Unknown(device) => return device,
+        };
+
+        match subclass {
+            Some(id) => RETRO_DEVICE_SUBCLASS!(base, id),
+            None => base,
+        }
+    }
+
+    /// Returns the base type without subclass bits, e.g. [`RETRO_DEVICE_JOYPAD`].
+    pub fn base(&self) -> u32 {
+        self.to_raw() & RETRO_DEVICE_MASK
+    }
+
+    /// Returns the subclass `id`, if any.
+    pub fn subclass(&self) -> Option<u32> {
+        match *self {
+            Self::Joypad { subclass }
+            | Self::Mouse { subclass }
+            | Self::Keyboard { subclass }
+            | Self::Lightgun { subclass }
+            | Self::Analog { subclass }
+            | Self::Pointer { subclass } => subclass,
+            Self::None | Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl From<u32> for DeviceType {
+    fn from(device: u32) -> Self {
+        Self::from_raw(device)
+    }
+}
+
+impl From<DeviceType> for u32 {
+    fn from(device: DeviceType) -> Self {
+        device.to_raw()
+    }
+}
+
+#[test]
+fn device_type_splits_the_subclass() {
+    assert_eq!(DeviceType::from_raw(RETRO_DEVICE_NONE), DeviceType::None);
+    assert_eq!(
+        DeviceType::from_raw(RETRO_DEVICE_JOYPAD),
+        DeviceType::Joypad { subclass: None }
+    );
+
+    let multitap = RETRO_DEVICE_SUBCLASS!(RETRO_DEVICE_JOYPAD, 1);
+    let device = DeviceType::from_raw(multitap);
+    assert_eq!(device, DeviceType::Joypad { subclass: Some(1) });
+    assert_eq!(device.base(), RETRO_DEVICE_JOYPAD);
+    assert_eq!(device.subclass(), Some(1));
+    assert_eq!(u32::from(device), multitap);
+
+    let gun = RETRO_DEVICE_SUBCLASS!(RETRO_DEVICE_LIGHTGUN, 0);
+    assert_eq!(
+        DeviceType::from(gun),
+        DeviceType::Lightgun { subclass: Some(0) }
+    );
+
+    assert_eq!(DeviceType::from_raw(0x42), DeviceType::Unknown(0x42));
+    assert_eq!(DeviceType::Unknown(0x42).to_raw(), 0x42);
+}
+
 bitflags::bitflags! {
     /// Signifies quirks of the [`Core`]’s serialization feature (if any).
     pub struct SerializationQuirks: u32 {
@@ -195,6 +1094,7 @@ bitflags::bitflags! {
 }
 
 /// Used in [`environment::set_message_ext`] to signal some ongoing progress.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum MessageProgress {
     /// The message is unmetered or the progress cannot be determined.
     Indeterminate,
@@ -224,65 +1124,264 @@ impl MessageProgress {
     }
 }
 
-/// Screen rotation in degrees
-pub enum Rotation {
-    None,
+/// An on-screen message for [`GenericContext::set_message_ext`].
+///
+/// ```
+/// # use rust_libretro::{sys::*, types::*};
+/// let message = MessageExt::new("Downloading BIOS…")
+///     .duration(5000)
+///     .message_type(retro_message_type::RETRO_MESSAGE_TYPE_PROGRESS)
+///     .progress(MessageProgress::Percentage(42));
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct MessageExt<'a> {
+    /// The message text.
+    pub msg: &'a str,
 
-    Clockwise90,
-    Clockwise180,
-    Clockwise270,
+    /// Duration in milliseconds.
+    pub duration: u32,
 
-    CounterClockwise90,
-    CounterClockwise180,
-    CounterClockwise270,
-}
+    /// Messages with a higher priority take precedence over lower priority ones.
+    pub priority: u32,
 
-impl Rotation {
-    pub fn get_env_value(&self) -> u32 {
-        match self {
-            Rotation::None => 0,
+    /// The severity of the message, used when the message is logged.
+    pub level: retro_log_level,
 
-            Rotation::Clockwise90 => 3,
-            Rotation::Clockwise180 => 2,
-            Rotation::Clockwise270 => 1,
+    /// Whether the message is shown on screen, logged or both.
+    pub target: retro_message_target,
 
-            Rotation::CounterClockwise90 => 1,
-            Rotation::CounterClockwise180 => 2,
-            Rotation::CounterClockwise270 => 3,
-        }
-    }
-}
+    /// How the message should be presented.
+    pub type_: retro_message_type,
 
-#[derive(Debug, Copy, Clone)]
-pub enum PixelFormat {
-    XRGB1555 = retro_pixel_format::RETRO_PIXEL_FORMAT_0RGB1555 as isize,
-    XRGB8888 = retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888 as isize,
-    RGB565 = retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565 as isize,
-    UNKNOWN = retro_pixel_format::RETRO_PIXEL_FORMAT_UNKNOWN as isize,
+    /// The progress of an ongoing operation, e.g. for loading bars.
+    pub progress: MessageProgress,
 }
 
-impl PixelFormat {
-    #[inline]
-    pub fn bit_per_pixel(self) -> usize {
-        match self {
-            Self::XRGB1555 => 2,
-            Self::XRGB8888 => 4,
-            Self::RGB565 => 2,
-            Self::UNKNOWN => 0,
+impl<'a> MessageExt<'a> {
+    /// Creates a notification that is displayed for three seconds
+    /// and logged with [`retro_log_level::RETRO_LOG_INFO`].
+    pub fn new(msg: &'a str) -> Self {
+        Self {
+            msg,
+            duration: 3000,
+            priority: 0,
+            level: retro_log_level::RETRO_LOG_INFO,
+            target: retro_message_target::RETRO_MESSAGE_TARGET_ALL,
+            type_: retro_message_type::RETRO_MESSAGE_TYPE_NOTIFICATION,
+            progress: MessageProgress::Indeterminate,
         }
     }
-}
 
-impl From<retro_pixel_format> for PixelFormat {
-    fn from(other: retro_pixel_format) -> Self {
-        match other {
-            retro_pixel_format::RETRO_PIXEL_FORMAT_0RGB1555 => Self::XRGB1555,
-            retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888 => Self::XRGB8888,
-            retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565 => Self::RGB565,
-            _ => Self::UNKNOWN,
-        }
+    /// Sets the duration in milliseconds.
+    pub fn duration(mut self, duration: u32) -> Self {
+        self.duration = duration;
+        self
     }
-}
+
+    pub fn priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn level(mut self, level: retro_log_level) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn target(mut self, target: retro_message_target) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn message_type(mut self, type_: retro_message_type) -> Self {
+        self.type_ = type_;
+        self
+    }
+
+    pub fn progress(mut self, progress: MessageProgress) -> Self {
+        self.progress = progress;
+        self
+    }
+}
+
+/// A cheat code set by the frontend, see [`CheatManager`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cheat {
+    pub index: u32,
+    pub enabled: bool,
+
+    /// The core-specific cheat code.
+    pub code: String,
+}
+
+/// Tracks the cheats set by the frontend.
+///
+/// The wrapper keeps one of these up to date with every call of `retro_cheat_set`
+/// and `retro_cheat_reset`, so cores can use [`GenericContext::cheats`] to get
+/// the enabled cheats, e.g. after [`Core::on_reset`], instead of tracking them themselves.
+#[derive(Debug, Default, Clone)]
+pub struct CheatManager {
+    cheats: BTreeMap<u32, Cheat>,
+}
+
+impl CheatManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the cheat at `index` or replaces it.
+    pub fn set(&mut self, index: u32, enabled: bool, code: &str) {
+        self.cheats.insert(
+            index,
+            Cheat {
+                index,
+                enabled,
+                code: code.to_owned(),
+            },
+        );
+    }
+
+    /// Removes all cheats.
+    pub fn reset(&mut self) {
+        self.cheats.clear();
+    }
+
+    /// Returns the cheat at `index`, whether it is enabled or not.
+    pub fn get(&self, index: u32) -> Option<&Cheat> {
+        self.cheats.get(&index)
+    }
+
+    /// Returns all cheats ordered by their index, whether they are enabled or not.
+    pub fn iter(&self) -> impl Iterator<Item = &Cheat> {
+        self.cheats.values()
+    }
+
+    /// Returns the enabled cheats ordered by their index.
+    pub fn enabled(&self) -> impl Iterator<Item = &Cheat> {
+        self.iter().filter(|cheat| cheat.enabled)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cheats.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cheats.is_empty()
+    }
+}
+
+#[test]
+fn cheat_manager_tracks_cheats() {
+    let mut cheats = CheatManager::new();
+    assert!(cheats.is_empty());
+
+    cheats.set(2, true, "C0DE-0002");
+    cheats.set(0, true, "C0DE-0000");
+    cheats.set(1, false, "C0DE-0001");
+
+    let enabled = cheats
+        .enabled()
+        .map(|cheat| cheat.index)
+        .collect::<Vec<_>>();
+    assert_eq!(enabled, vec![0, 2]);
+
+    // Setting an index again replaces the cheat
+    cheats.set(2, false, "C0DE-2222");
+    assert_eq!(cheats.len(), 3);
+    assert_eq!(cheats.get(2).unwrap().code, "C0DE-2222");
+    assert_eq!(cheats.enabled().count(), 1);
+
+    cheats.reset();
+    assert!(cheats.is_empty());
+    assert_eq!(cheats.enabled().count(), 0);
+}
+
+/// Screen rotation in degrees, see [`GenericContext::set_rotation`].
+///
+/// libretro expresses rotations counter-clockwise in steps of 90 degrees,
+/// so each clockwise rotation maps to its counter-clockwise equivalent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rotation {
+    None,
+
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+
+    CounterClockwise90,
+    CounterClockwise180,
+    CounterClockwise270,
+}
+
+impl Rotation {
+    /// Returns the value passed to `RETRO_ENVIRONMENT_SET_ROTATION`,
+    /// i.e. the number of counter-clockwise 90 degree steps.
+    pub fn get_env_value(&self) -> u32 {
+        match self {
+            Rotation::None => 0,
+
+            Rotation::Clockwise90 => 3,
+            Rotation::Clockwise180 => 2,
+            Rotation::Clockwise270 => 1,
+
+            Rotation::CounterClockwise90 => 1,
+            Rotation::CounterClockwise180 => 2,
+            Rotation::CounterClockwise270 => 3,
+        }
+    }
+}
+
+#[test]
+fn rotation_uses_counter_clockwise_steps() {
+    assert_eq!(Rotation::None.get_env_value(), 0);
+    assert_eq!(Rotation::CounterClockwise90.get_env_value(), 1);
+    assert_eq!(Rotation::CounterClockwise180.get_env_value(), 2);
+    assert_eq!(Rotation::CounterClockwise270.get_env_value(), 3);
+
+    assert_eq!(
+        Rotation::Clockwise90.get_env_value(),
+        Rotation::CounterClockwise270.get_env_value()
+    );
+    assert_eq!(
+        Rotation::Clockwise180.get_env_value(),
+        Rotation::CounterClockwise180.get_env_value()
+    );
+    assert_eq!(
+        Rotation::Clockwise270.get_env_value(),
+        Rotation::CounterClockwise90.get_env_value()
+    );
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum PixelFormat {
+    XRGB1555 = retro_pixel_format::RETRO_PIXEL_FORMAT_0RGB1555 as isize,
+    XRGB8888 = retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888 as isize,
+    RGB565 = retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565 as isize,
+    UNKNOWN = retro_pixel_format::RETRO_PIXEL_FORMAT_UNKNOWN as isize,
+}
+
+impl PixelFormat {
+    #[inline]
+    pub fn bit_per_pixel(self) -> usize {
+        match self {
+            Self::XRGB1555 => 2,
+            Self::XRGB8888 => 4,
+            Self::RGB565 => 2,
+            Self::UNKNOWN => 0,
+        }
+    }
+}
+
+impl From<retro_pixel_format> for PixelFormat {
+    fn from(other: retro_pixel_format) -> Self {
+        match other {
+            retro_pixel_format::RETRO_PIXEL_FORMAT_0RGB1555 => Self::XRGB1555,
+            retro_pixel_format::RETRO_PIXEL_FORMAT_XRGB8888 => Self::XRGB8888,
+            retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565 => Self::RGB565,
+            _ => Self::UNKNOWN,
+        }
+    }
+}
 
 impl From<PixelFormat> for retro_pixel_format {
     fn from(other: PixelFormat) -> Self {
@@ -295,18 +1394,859 @@ impl From<PixelFormat> for retro_pixel_format {
     }
 }
 
-#[derive(Debug)]
+/// A hardware rendering API, see [`InitContext::get_preferred_hw_render`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HwContextType {
+    /// OpenGL 2.x, or a compatibility profile of a later version.
+    OpenGl,
+    OpenGlEs2,
+    /// A core profile of OpenGL 3.x or later.
+    OpenGlCore,
+    OpenGlEs3,
+    /// OpenGL ES 3.1 or later.
+    OpenGlEsVersion,
+    Vulkan,
+    /// Direct3D 10 or later, the exact version gets negotiated via [`retro_hw_render_callback`].
+    Direct3D,
+
+    /// A context type that got added to libretro after this crate was released.
+    Unknown(u32),
+}
+
+impl HwContextType {
+    /// Converts the raw value of a [`retro_hw_context_type`],
+    /// [`RETRO_HW_CONTEXT_NONE`](retro_hw_context_type::RETRO_HW_CONTEXT_NONE) yields [`None`].
+    pub fn from_raw(value: u32) -> Option<Self> {
+        use retro_hw_context_type::*;
+
+        let context_type = match value {
+            value if value == RETRO_HW_CONTEXT_NONE as u32 => return None,
+            value if value == RETRO_HW_CONTEXT_OPENGL as u32 => Self::OpenGl,
+            value if value == RETRO_HW_CONTEXT_OPENGLES2 as u32 => Self::OpenGlEs2,
+            value if value == RETRO_HW_CONTEXT_OPENGL_CORE as u32 => Self::OpenGlCore,
+            value if value == RETRO_HW_CONTEXT_OPENGLES3 as u32 => Self::OpenGlEs3,
+            value if value == RETRO_HW_CONTEXT_OPENGLES_VERSION as u32 => Self::OpenGlEsVersion,
+            value if value == RETRO_HW_CONTEXT_VULKAN as u32 => Self::Vulkan,
+            value if value == RETRO_HW_CONTEXT_DIRECT3D as u32 => Self::Direct3D,
+            value => Self::Unknown(value),
+        };
+
+        Some(context_type)
+    }
+
+    /// Returns the matching [`retro_hw_context_type`] to request with
+    /// [`LoadGameContext::set_hw_render`], or [`None`] for [`HwContextType::Unknown`].
+    pub fn to_context_type(self) -> Option<retro_hw_context_type> {
+        use retro_hw_context_type::*;
+
+        Some(match self {
+            Self::OpenGl => RETRO_HW_CONTEXT_OPENGL,
+            Self::OpenGlEs2 => RETRO_HW_CONTEXT_OPENGLES2,
+            Self::OpenGlCore => RETRO_HW_CONTEXT_OPENGL_CORE,
+            Self::OpenGlEs3 => RETRO_HW_CONTEXT_OPENGLES3,
+            Self::OpenGlEsVersion => RETRO_HW_CONTEXT_OPENGLES_VERSION,
+            Self::Vulkan => RETRO_HW_CONTEXT_VULKAN,
+            Self::Direct3D => RETRO_HW_CONTEXT_DIRECT3D,
+            Self::Unknown(_) => return None,
+        })
+    }
+}
+
+#[test]
+fn hw_context_type_handles_unknown_values() {
+    use retro_hw_context_type::*;
+
+    assert_eq!(HwContextType::from_raw(RETRO_HW_CONTEXT_NONE as u32), None);
+    assert_eq!(
+        HwContextType::from_raw(RETRO_HW_CONTEXT_VULKAN as u32),
+        Some(HwContextType::Vulkan)
+    );
+    assert_eq!(
+        HwContextType::from_raw(RETRO_HW_CONTEXT_OPENGL_CORE as u32)
+            .and_then(HwContextType::to_context_type),
+        Some(RETRO_HW_CONTEXT_OPENGL_CORE)
+    );
+
+    assert_eq!(
+        HwContextType::from_raw(100),
+        Some(HwContextType::Unknown(100))
+    );
+    assert_eq!(HwContextType::Unknown(100).to_context_type(), None);
+}
+
+/// A frame that has been submitted to the frontend, see [`RunContext::last_frame`].
+#[derive(Debug, Copy, Clone)]
+pub struct FrameView<'a> {
+    /// `height` rows of `width` pixels, each starting `pitch` bytes after the previous one.
+    pub data: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+    pub pitch: usize,
+    pub format: PixelFormat,
+}
+
+/// The region of a game, see [`Core::on_get_region`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Region {
+    /// 60 Hz, [`RETRO_REGION_NTSC`].
+    #[default]
+    Ntsc,
+
+    /// 50 Hz, [`RETRO_REGION_PAL`].
+    Pal,
+}
+
+impl From<Region> for std::os::raw::c_uint {
+    fn from(region: Region) -> Self {
+        match region {
+            Region::Ntsc => RETRO_REGION_NTSC,
+            Region::Pal => RETRO_REGION_PAL,
+        }
+    }
+}
+
+#[test]
+fn region_maps_to_the_libretro_constants() {
+    assert_eq!(
+        std::os::raw::c_uint::from(Region::default()),
+        RETRO_REGION_NTSC
+    );
+    assert_eq!(std::os::raw::c_uint::from(Region::Pal), RETRO_REGION_PAL);
+}
+
+/// The memory areas a frontend can ask for, see [`Core::memory`].
+///
+/// Not to be confused with [`MemoryType`], which describes hardware rendering buffers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MemoryId {
+    /// Battery backed save data, [`RETRO_MEMORY_SAVE_RAM`].
+    /// Saved by the frontend when the game gets unloaded.
+    SaveRam,
+
+    /// The state of a real time clock, [`RETRO_MEMORY_RTC`].
+    /// Saved by the frontend like [`MemoryId::SaveRam`].
+    Rtc,
+
+    /// The main memory of the emulated system, [`RETRO_MEMORY_SYSTEM_RAM`].
+    /// Used by achievements, cheats and memory viewers.
+    SystemRam,
+
+    /// Video memory, [`RETRO_MEMORY_VIDEO_RAM`].
+    VideoRam,
+}
+
+impl MemoryId {
+    /// Maps one of the `RETRO_MEMORY_*` constants to its [`MemoryId`].
+    ///
+    /// Returns [`None`] for any other id, like the memory of subsystem content.
+    pub fn from_raw(id: std::os::raw::c_uint) -> Option<Self> {
+        match id {
+            RETRO_MEMORY_SAVE_RAM => Some(Self::SaveRam),
+            RETRO_MEMORY_RTC => Some(Self::Rtc),
+            RETRO_MEMORY_SYSTEM_RAM => Some(Self::SystemRam),
+            RETRO_MEMORY_VIDEO_RAM => Some(Self::VideoRam),
+            _ => None,
+        }
+    }
+}
+
+impl From<MemoryId> for std::os::raw::c_uint {
+    fn from(id: MemoryId) -> Self {
+        match id {
+            MemoryId::SaveRam => RETRO_MEMORY_SAVE_RAM,
+            MemoryId::Rtc => RETRO_MEMORY_RTC,
+            MemoryId::SystemRam => RETRO_MEMORY_SYSTEM_RAM,
+            MemoryId::VideoRam => RETRO_MEMORY_VIDEO_RAM,
+        }
+    }
+}
+
+#[test]
+fn memory_id_maps_the_libretro_constants() {
+    for id in [
+        MemoryId::SaveRam,
+        MemoryId::Rtc,
+        MemoryId::SystemRam,
+        MemoryId::VideoRam,
+    ] {
+        assert_eq!(MemoryId::from_raw(id.into()), Some(id));
+    }
+
+    assert_eq!(
+        std::os::raw::c_uint::from(MemoryId::SystemRam),
+        RETRO_MEMORY_SYSTEM_RAM
+    );
+    assert_eq!(MemoryId::from_raw(0x100 | RETRO_MEMORY_SAVE_RAM), None);
+}
+
+/// Battery backed save data of a fixed size, see [`LoadGameContext::set_save_ram`].
+///
+/// Once registered, the frontend reads and writes it as [`MemoryId::SaveRam`]:
+/// it copies the previously saved data into it right after the game has been loaded
+/// and writes it to disk when the game gets unloaded, so the core only has to use it.
+///
+/// Cloning creates another handle to the same memory. The buffer never moves or changes
+/// its size, so the pointer handed to the frontend stays valid as long as a handle exists.
+#[derive(Debug, Clone)]
+pub struct SaveRam(Arc<std::sync::RwLock<Box<[u8]>>>);
+
+impl SaveRam {
+    /// Allocates `size` bytes of zeroed save data.
+    pub fn new(size: usize) -> Self {
+        Self(Arc::new(std::sync::RwLock::new(
+            vec![0; size].into_boxed_slice(),
+        )))
+    }
+
+    /// The size of the save data in bytes.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns `true` if the save data has a size of zero.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Locks the save data for reading.
+    pub fn read(&self) -> std::sync::RwLockReadGuard<'_, Box<[u8]>> {
+        self.0.read().unwrap()
+    }
+
+    /// Locks the save data for writing.
+    pub fn write(&self) -> std::sync::RwLockWriteGuard<'_, Box<[u8]>> {
+        self.0.write().unwrap()
+    }
+
+    /// Returns the pointer handed to the frontend, which stays valid after the lock is released.
+    pub(crate) fn as_mut_ptr(&self) -> *mut std::os::raw::c_void {
+        self.write().as_mut_ptr() as *mut _
+    }
+}
+
+#[test]
+fn save_ram_handles_share_their_memory() {
+    let save_ram = SaveRam::new(4);
+    let handle = save_ram.clone();
+
+    assert_eq!(save_ram.len(), 4);
+    assert!(!save_ram.is_empty());
+
+    handle.write()[1] = 0xAB;
+    assert_eq!(**save_ram.read(), [0, 0xAB, 0, 0]);
+    assert_eq!(save_ram.as_mut_ptr(), handle.as_mut_ptr());
+}
+
+/// Whether the device is running on battery, see [`RunContext::get_device_power`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum PowerState {
+    /// The frontend cannot tell, also used for states unknown to this version of libretro.
+    #[default]
+    Unknown,
+
+    /// Running on battery.
+    Discharging,
+
+    /// Plugged in and charging the battery.
+    Charging,
+
+    /// Plugged in with a fully charged battery.
+    Charged,
+
+    /// Plugged in, the device has no battery.
+    PluggedNoBattery,
+}
+
+impl From<retro_power_state> for PowerState {
+    fn from(state: retro_power_state) -> Self {
+        match state {
+            retro_power_state::RETRO_POWERSTATE_DISCHARGING => Self::Discharging,
+            retro_power_state::RETRO_POWERSTATE_CHARGING => Self::Charging,
+            retro_power_state::RETRO_POWERSTATE_CHARGED => Self::Charged,
+            retro_power_state::RETRO_POWERSTATE_PLUGGED_IN => Self::PluggedNoBattery,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The power state of the device, see [`RunContext::get_device_power`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct DevicePower {
+    pub state: PowerState,
+
+    /// The estimated battery life left in seconds, if the frontend can tell.
+    pub seconds: Option<u32>,
+
+    /// The remaining battery charge in percent, if the frontend can tell.
+    pub percent: Option<u8>,
+}
+
+impl From<retro_device_power> for DevicePower {
+    fn from(power: retro_device_power) -> Self {
+        // Any negative value means that there is no estimate
+        Self {
+            state: power.state.into(),
+            seconds: power.seconds.try_into().ok(),
+            percent: power.percent.try_into().ok(),
+        }
+    }
+}
+
+#[test]
+fn device_power_decodes_the_frontend_values() {
+    assert_eq!(
+        DevicePower::from(retro_device_power {
+            state: retro_power_state::RETRO_POWERSTATE_DISCHARGING,
+            seconds: 3600,
+            percent: 42,
+        }),
+        DevicePower {
+            state: PowerState::Discharging,
+            seconds: Some(3600),
+            percent: Some(42),
+        }
+    );
+
+    assert_eq!(
+        DevicePower::from(retro_device_power {
+            state: retro_power_state::RETRO_POWERSTATE_PLUGGED_IN,
+            seconds: RETRO_POWERSTATE_NO_ESTIMATE,
+            percent: RETRO_POWERSTATE_NO_ESTIMATE as i8,
+        }),
+        DevicePower {
+            state: PowerState::PluggedNoBattery,
+            seconds: None,
+            percent: None,
+        }
+    );
+
+    assert_eq!(
+        PowerState::from(retro_power_state::RETRO_POWERSTATE_CHARGING),
+        PowerState::Charging
+    );
+    assert_eq!(
+        PowerState::from(retro_power_state::RETRO_POWERSTATE_CHARGED),
+        PowerState::Charged
+    );
+    assert_eq!(
+        PowerState::from(retro_power_state::RETRO_POWERSTATE_UNKNOWN),
+        PowerState::Unknown
+    );
+    assert_eq!(
+        PowerState::from(retro_power_state(1000)),
+        PowerState::Unknown
+    );
+}
+
+/// Why the frontend asks for a save state, see [`SerializeContext::save_state_context`].
+///
+/// libretro does not tell rewind states apart, they use [`SaveStateContext::Normal`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum SaveStateContext {
+    /// A regular save state that may be written to disk.
+    /// Also used if the frontend does not report the context.
+    #[default]
+    Normal,
+
+    /// Runahead within the same instance, which is the only one to ever load the state.
+    /// The state may contain pointers to code or data.
+    Runahead,
+
+    /// Runahead using a second instance of the same binary.
+    /// The state never leaves the process, so anything that slows down saving or loading
+    /// can be skipped, but it must not contain pointers.
+    RunaheadSameBinary,
+
+    /// Rollback netplay, the state gets sent over the network and should be as small as possible.
+    RollbackNetplay,
+
+    /// A context unknown to this version of libretro, treat it like [`SaveStateContext::Normal`].
+    Unknown,
+}
+
+impl From<retro_savestate_context> for SaveStateContext {
+    fn from(context: retro_savestate_context) -> Self {
+        match context {
+            retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL => Self::Normal,
+            retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_INSTANCE => {
+                Self::Runahead
+            }
+            retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_BINARY => {
+                Self::RunaheadSameBinary
+            }
+            retro_savestate_context::RETRO_SAVESTATE_CONTEXT_ROLLBACK_NETPLAY => {
+                Self::RollbackNetplay
+            }
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[test]
+fn save_state_context_maps_the_libretro_contexts() {
+    assert_eq!(
+        SaveStateContext::from(
+            retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_INSTANCE
+        ),
+        SaveStateContext::Runahead
+    );
+    assert_eq!(
+        SaveStateContext::from(retro_savestate_context::RETRO_SAVESTATE_CONTEXT_UNKNOWN),
+        SaveStateContext::Unknown
+    );
+}
+
+/// Describes how a string got copied into a buffer provided by the frontend.
+///
+/// See [`util::copy_str_to_buffer`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CopiedString {
+    /// The whole string fit into the buffer, contains the number of bytes copied
+    /// without the terminating NUL byte.
+    Complete(usize),
+
+    /// The string had to be truncated, contains the number of bytes copied
+    /// without the terminating NUL byte.
+    Truncated(usize),
+}
+
+impl CopiedString {
+    /// Returns the number of bytes copied, excluding the terminating NUL byte.
+    pub fn len(&self) -> usize {
+        match *self {
+            CopiedString::Complete(len) | CopiedString::Truncated(len) => len,
+        }
+    }
+
+    /// Returns `true` if nothing but the terminating NUL byte has been copied.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the string had to be truncated.
+    pub fn is_truncated(&self) -> bool {
+        matches!(self, CopiedString::Truncated(_))
+    }
+}
+
+/// The analog sticks of the RetroPad, see [`RunContext::get_analog`].
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnalogStick {
+    Left = RETRO_DEVICE_INDEX_ANALOG_LEFT,
+    Right = RETRO_DEVICE_INDEX_ANALOG_RIGHT,
+}
+
+/// The axes of an [`AnalogStick`].
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AnalogAxis {
+    /// Negative values point to the left, positive values to the right.
+    X = RETRO_DEVICE_ID_ANALOG_X,
+
+    /// Negative values point up, positive values down.
+    Y = RETRO_DEVICE_ID_ANALOG_Y,
+}
+
+/// The state of a mouse, see [`RunContext::get_mouse`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct MouseState {
+    /// Horizontal movement since the last poll.
+    pub x: i16,
+
+    /// Vertical movement since the last poll.
+    pub y: i16,
+
+    pub left: bool,
+    pub right: bool,
+    pub middle: bool,
+    pub wheel_up: bool,
+    pub wheel_down: bool,
+}
+
+/// Describes the frontend the core is running in, see [`GenericContext::frontend_info`].
+///
+/// libretro has no command to query the name or version of the frontend,
+/// but the versions of the interfaces it implements tell frontends
+/// (and releases of the same frontend) apart well enough to work around their quirks.
+/// A version of `0` means the frontend does not implement the corresponding command.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FrontendInfo {
+    /// See [`environment::get_core_options_version`].
+    pub core_options_version: u32,
+
+    /// See [`environment::get_message_interface_version`].
+    pub message_interface_version: u32,
+
+    /// See [`environment::get_disk_control_interface_version`].
+    pub disk_control_interface_version: u32,
+
+    /// The number of users the frontend handles input for, if it reports it.
+    pub input_max_users: Option<u32>,
+
+    /// The language configured in the frontend, if it reports it.
+    pub language: Option<retro_language>,
+
+    /// The path of the loaded core library, if the frontend reports it.
+    pub libretro_path: Option<PathBuf>,
+}
+
+impl FrontendInfo {
+    /// Whether the frontend implements all of the interfaces known to this crate
+    /// in their latest version, as current releases of RetroArch do.
+    pub fn is_up_to_date(&self) -> bool {
+        self.core_options_version >= 2
+            && self.message_interface_version >= 1
+            && self.disk_control_interface_version >= 1
+    }
+}
+
+/// Frontend features that do not change while the core is running,
+/// see [`GenericContext::capabilities`].
+///
+/// Queried once in `retro_init`, so checking them in [`Core::on_run`] is cheap.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// See [`RunContext::can_dupe`].
+    pub can_dupe: bool,
+
+    /// Whether all joypad buttons of a port can be queried at once,
+    /// see [`environment::get_input_bitmasks`].
+    pub supports_bitmasks: bool,
+
+    /// Whether [`SetEnvironmentContext::set_core_options_v2`] is supported.
+    pub supports_core_options_v2: bool,
+
+    /// Whether [`GenericContext::set_message_ext`] is supported.
+    pub supports_message_ext: bool,
+}
+
+/// Reports which optional interfaces the frontend provided,
+/// see [`GenericContext::available_interfaces`].
+///
+/// Each interface has to be requested with the corresponding `enable_*_interface`
+/// function of [`LoadGameContext`] first.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct AvailableInterfaces {
+    pub rumble: bool,
+    pub sensor: bool,
+    pub camera: bool,
+    pub location: bool,
+    pub perf: bool,
+    pub led: bool,
+    pub midi: bool,
+}
+
+/// The state of a pointer, e.g. a finger on a touchscreen, see [`RunContext::get_pointer`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct PointerState {
+    /// Horizontal position in the range `[-0x7FFF, 0x7FFF]`,
+    /// where `-0x7FFF` is the left edge of the screen.
+    pub x: i16,
+
+    /// Vertical position in the range `[-0x7FFF, 0x7FFF]`,
+    /// where `-0x7FFF` is the top edge of the screen.
+    pub y: i16,
+
+    pub pressed: bool,
+
+    /// The number of pointers currently pressed, for multi-touch input.
+    pub count: i16,
+}
+
+/// A snapshot of the keys queried with [`RunContext::get_keyboard_state`].
+#[derive(Debug, Default, Clone)]
+pub struct KeyboardState {
+    pub(crate) pressed: Vec<retro_key>,
+}
+
+impl KeyboardState {
+    /// Returns `true` if `key` was pressed when the snapshot was taken.
+    ///
+    /// Keys that were not queried are reported as released.
+    pub fn is_pressed(&self, key: retro_key) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    /// Returns the queried keys that were pressed.
+    pub fn pressed(&self) -> &[retro_key] {
+        &self.pressed
+    }
+}
+
+macro_rules! keys {
+    ($($variant:ident => $raw:ident,)*) => {
+        /// A key of the keyboard, see [`Core::on_keyboard_event`].
+        ///
+        /// Letters are reported in lowercase, use the `character` of the event
+        /// or [`KeyMods::SHIFT`] to tell upper and lower case apart.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+        pub enum Key {
+            /// [`retro_key::RETROK_UNKNOWN`], e.g. for character-only events.
+            Unknown,
+
+            $(
+                #[doc = concat!("[`retro_key::", stringify!($raw), "`].")]
+                $variant,
+            )*
+
+            /// A key code unknown to this version of libretro.
+            Other(retro_key),
+        }
+
+        impl From<retro_key> for Key {
+            fn from(key: retro_key) -> Self {
+                match key {
+                    retro_key::RETROK_UNKNOWN => Self::Unknown,
+                    $(retro_key::$raw => Self::$variant,)*
+                    key => Self::Other(key),
+                }
+            }
+        }
+
+        impl From<Key> for retro_key {
+            fn from(key: Key) -> Self {
+                match key {
+                    Key::Unknown => retro_key::RETROK_UNKNOWN,
+                    $(Key::$variant => retro_key::$raw,)*
+                    Key::Other(key) => key,
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    Backspace => RETROK_BACKSPACE,
+    Tab => RETROK_TAB,
+    Clear => RETROK_CLEAR,
+    Return => RETROK_RETURN,
+    Pause => RETROK_PAUSE,
+    Escape => RETROK_ESCAPE,
+    Space => RETROK_SPACE,
+    Exclaim => RETROK_EXCLAIM,
+    QuoteDbl => RETROK_QUOTEDBL,
+    Hash => RETROK_HASH,
+    Dollar => RETROK_DOLLAR,
+    Ampersand => RETROK_AMPERSAND,
+    Quote => RETROK_QUOTE,
+    LeftParen => RETROK_LEFTPAREN,
+    RightParen => RETROK_RIGHTPAREN,
+    Asterisk => RETROK_ASTERISK,
+    Plus => RETROK_PLUS,
+    Comma => RETROK_COMMA,
+    Minus => RETROK_MINUS,
+    Period => RETROK_PERIOD,
+    Slash => RETROK_SLASH,
+    Num0 => RETROK_0,
+    Num1 => RETROK_1,
+    Num2 => RETROK_2,
+    Num3 => RETROK_3,
+    Num4 => RETROK_4,
+    Num5 => RETROK_5,
+    Num6 => RETROK_6,
+    Num7 => RETROK_7,
+    Num8 => RETROK_8,
+    Num9 => RETROK_9,
+    Colon => RETROK_COLON,
+    Semicolon => RETROK_SEMICOLON,
+    Less => RETROK_LESS,
+    Equals => RETROK_EQUALS,
+    Greater => RETROK_GREATER,
+    Question => RETROK_QUESTION,
+    At => RETROK_AT,
+    LeftBracket => RETROK_LEFTBRACKET,
+    Backslash => RETROK_BACKSLASH,
+    RightBracket => RETROK_RIGHTBRACKET,
+    Caret => RETROK_CARET,
+    Underscore => RETROK_UNDERSCORE,
+    Backquote => RETROK_BACKQUOTE,
+    A => RETROK_a,
+    B => RETROK_b,
+    C => RETROK_c,
+    D => RETROK_d,
+    E => RETROK_e,
+    F => RETROK_f,
+    G => RETROK_g,
+    H => RETROK_h,
+    I => RETROK_i,
+    J => RETROK_j,
+    K => RETROK_k,
+    L => RETROK_l,
+    M => RETROK_m,
+    N => RETROK_n,
+    O => RETROK_o,
+    P => RETROK_p,
+    Q => RETROK_q,
+    R => RETROK_r,
+    S => RETROK_s,
+    T => RETROK_t,
+    U => RETROK_u,
+    V => RETROK_v,
+    W => RETROK_w,
+    X => RETROK_x,
+    Y => RETROK_y,
+    Z => RETROK_z,
+    LeftBrace => RETROK_LEFTBRACE,
+    Bar => RETROK_BAR,
+    RightBrace => RETROK_RIGHTBRACE,
+    Tilde => RETROK_TILDE,
+    Delete => RETROK_DELETE,
+    Kp0 => RETROK_KP0,
+    Kp1 => RETROK_KP1,
+    Kp2 => RETROK_KP2,
+    Kp3 => RETROK_KP3,
+    Kp4 => RETROK_KP4,
+    Kp5 => RETROK_KP5,
+    Kp6 => RETROK_KP6,
+    Kp7 => RETROK_KP7,
+    Kp8 => RETROK_KP8,
+    Kp9 => RETROK_KP9,
+    KpPeriod => RETROK_KP_PERIOD,
+    KpDivide => RETROK_KP_DIVIDE,
+    KpMultiply => RETROK_KP_MULTIPLY,
+    KpMinus => RETROK_KP_MINUS,
+    KpPlus => RETROK_KP_PLUS,
+    KpEnter => RETROK_KP_ENTER,
+    KpEquals => RETROK_KP_EQUALS,
+    Up => RETROK_UP,
+    Down => RETROK_DOWN,
+    Right => RETROK_RIGHT,
+    Left => RETROK_LEFT,
+    Insert => RETROK_INSERT,
+    Home => RETROK_HOME,
+    End => RETROK_END,
+    PageUp => RETROK_PAGEUP,
+    PageDown => RETROK_PAGEDOWN,
+    F1 => RETROK_F1,
+    F2 => RETROK_F2,
+    F3 => RETROK_F3,
+    F4 => RETROK_F4,
+    F5 => RETROK_F5,
+    F6 => RETROK_F6,
+    F7 => RETROK_F7,
+    F8 => RETROK_F8,
+    F9 => RETROK_F9,
+    F10 => RETROK_F10,
+    F11 => RETROK_F11,
+    F12 => RETROK_F12,
+    F13 => RETROK_F13,
+    F14 => RETROK_F14,
+    F15 => RETROK_F15,
+    NumLock => RETROK_NUMLOCK,
+    CapsLock => RETROK_CAPSLOCK,
+    ScrollLock => RETROK_SCROLLOCK,
+    RShift => RETROK_RSHIFT,
+    LShift => RETROK_LSHIFT,
+    RCtrl => RETROK_RCTRL,
+    LCtrl => RETROK_LCTRL,
+    RAlt => RETROK_RALT,
+    LAlt => RETROK_LALT,
+    RMeta => RETROK_RMETA,
+    LMeta => RETROK_LMETA,
+    LSuper => RETROK_LSUPER,
+    RSuper => RETROK_RSUPER,
+    Mode => RETROK_MODE,
+    Compose => RETROK_COMPOSE,
+    Help => RETROK_HELP,
+    Print => RETROK_PRINT,
+    SysReq => RETROK_SYSREQ,
+    Break => RETROK_BREAK,
+    Menu => RETROK_MENU,
+    Power => RETROK_POWER,
+    Euro => RETROK_EURO,
+    Undo => RETROK_UNDO,
+    Oem102 => RETROK_OEM_102,
+}
+
+impl Key {
+    /// Returns the raw key code passed by the frontend.
+    pub fn raw(&self) -> retro_key {
+        (*self).into()
+    }
+}
+
+bitflags::bitflags! {
+    /// The modifier keys held during a keyboard event, see [`Core::on_keyboard_event`].
+    ///
+    /// The lock modifiers are set while the lock is active, not while the key is held.
+    pub struct KeyMods: u16 {
+        const SHIFT = retro_mod::RETROKMOD_SHIFT.0 as u16;
+        const CTRL = retro_mod::RETROKMOD_CTRL.0 as u16;
+        const ALT = retro_mod::RETROKMOD_ALT.0 as u16;
+        const META = retro_mod::RETROKMOD_META.0 as u16;
+
+        const NUMLOCK = retro_mod::RETROKMOD_NUMLOCK.0 as u16;
+        const CAPSLOCK = retro_mod::RETROKMOD_CAPSLOCK.0 as u16;
+        const SCROLLOCK = retro_mod::RETROKMOD_SCROLLOCK.0 as u16;
+    }
+}
+
+impl KeyMods {
+    /// Returns the modifiers as the raw value used by libretro.
+    pub fn raw(&self) -> retro_mod {
+        retro_mod(self.bits().into())
+    }
+}
+
+#[test]
+fn keys_map_the_libretro_key_codes() {
+    assert_eq!(Key::from(retro_key::RETROK_UNKNOWN), Key::Unknown);
+    assert_eq!(Key::from(retro_key::RETROK_a), Key::A);
+    assert_eq!(Key::from(retro_key::RETROK_0), Key::Num0);
+    assert_eq!(Key::from(retro_key::RETROK_KP_ENTER), Key::KpEnter);
+    assert_eq!(Key::from(retro_key::RETROK_OEM_102), Key::Oem102);
+
+    let other = retro_key(1000);
+    assert_eq!(Key::from(other), Key::Other(other));
+    assert_eq!(Key::Other(other).raw(), other);
+
+    for key in [Key::Space, Key::Z, Key::F15, Key::ScrollLock] {
+        assert_eq!(Key::from(key.raw()), key);
+    }
+}
+
+#[test]
+fn key_mods_map_the_libretro_modifiers() {
+    let mods = KeyMods::from_bits_truncate(
+        (retro_mod::RETROKMOD_SHIFT.0 | retro_mod::RETROKMOD_CAPSLOCK.0) as u16,
+    );
+
+    assert_eq!(mods, KeyMods::SHIFT | KeyMods::CAPSLOCK);
+    assert_eq!(
+        mods.raw(),
+        retro_mod(retro_mod::RETROKMOD_SHIFT.0 | retro_mod::RETROKMOD_CAPSLOCK.0)
+    );
+}
+
+/// A performance counter registered with [`PerfContext::perf_register`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct PerfCounter {
+    pub(crate) name: &'static str,
+}
+
+impl PerfCounter {
+    /// The name this counter has been registered with.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RegisteredPerfCounter {
     #[allow(unused)]
     /// Borrowed by the `retro_perf_counter`.
     pub(crate) ident: CString,
-    pub(crate) counter: retro_perf_counter,
+
+    /// The frontend keeps a pointer to registered counters,
+    /// so they must not move when the map grows.
+    pub(crate) counter: Box<retro_perf_counter>,
 }
 
 #[derive(Debug, Default)]
 pub struct PerfCounters {
     pub interface: Option<retro_perf_callback>,
-    pub counters: HashMap<&'static str, PerfCounter>,
+    pub(crate) counters: HashMap<&'static str, RegisteredPerfCounter>,
 }
 
 #[derive(Debug, Default)]
@@ -317,6 +2257,36 @@ pub struct Position {
     pub vert_accuracy: f64,
 }
 
+/// The functions a Vulkan [`Core`] provides to the frontend to take part in creating
+/// the Vulkan instance and device, see [`LoadGameContext::set_vulkan_negotiation_interface`].
+///
+/// Functions left at [`None`] make the frontend use its defaults.
+#[cfg(feature = "vulkan")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct VulkanNegotiationInterface {
+    /// Returns the `VkApplicationInfo` the frontend should create the instance with.
+    pub get_application_info: retro_vulkan_get_application_info_t,
+
+    /// Creates the device, e.g. to pick a specific physical device or enable extensions.
+    pub create_device: retro_vulkan_create_device_t,
+
+    /// Destroys resources the core created in `create_device`.
+    pub destroy_device: retro_vulkan_destroy_device_t,
+}
+
+#[cfg(feature = "vulkan")]
+impl From<VulkanNegotiationInterface> for retro_hw_render_context_negotiation_interface_vulkan {
+    fn from(other: VulkanNegotiationInterface) -> Self {
+        Self {
+            interface_type: retro_hw_render_context_negotiation_interface_type::RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN,
+            interface_version: RETRO_HW_RENDER_CONTEXT_NEGOTIATION_INTERFACE_VULKAN_VERSION,
+            get_application_info: other.get_application_info,
+            create_device: other.create_device,
+            destroy_device: other.destroy_device,
+        }
+    }
+}
+
 /// Data structures used by experimental libretro environment function calls
 #[proc::unstable(feature = "env-commands")]
 pub mod unstable {
@@ -366,6 +2336,59 @@ pub mod unstable {
         }
     }
 
+    /// The decoded [`AudioVideoEnable`] flags, see [`RunContext::get_av_enable`].
+    ///
+    /// [`RunContext::get_av_enable`]: crate::contexts::RunContext::get_av_enable
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct AvEnable {
+        /// See [`AudioVideoEnable::ENABLE_VIDEO`].
+        pub video: bool,
+
+        /// See [`AudioVideoEnable::ENABLE_AUDIO`].
+        pub audio: bool,
+
+        /// See [`AudioVideoEnable::USE_FAST_SAVESTATES`].
+        pub fast_savestates: bool,
+
+        /// See [`AudioVideoEnable::HARD_DISABLE_AUDIO`].
+        pub hard_disable_audio: bool,
+    }
+
+    impl Default for AvEnable {
+        /// Audio and video are wanted, as if the frontend did not support the query.
+        fn default() -> Self {
+            (AudioVideoEnable::ENABLE_VIDEO | AudioVideoEnable::ENABLE_AUDIO).into()
+        }
+    }
+
+    impl From<AudioVideoEnable> for AvEnable {
+        fn from(flags: AudioVideoEnable) -> Self {
+            Self {
+                video: flags.contains(AudioVideoEnable::ENABLE_VIDEO),
+                audio: flags.contains(AudioVideoEnable::ENABLE_AUDIO),
+                fast_savestates: flags.contains(AudioVideoEnable::USE_FAST_SAVESTATES),
+                hard_disable_audio: flags.contains(AudioVideoEnable::HARD_DISABLE_AUDIO),
+            }
+        }
+    }
+
+    #[test]
+    fn av_enable_decodes_the_flags() {
+        assert_eq!(
+            AvEnable::from(AudioVideoEnable::ENABLE_AUDIO | AudioVideoEnable::USE_FAST_SAVESTATES),
+            AvEnable {
+                video: false,
+                audio: true,
+                fast_savestates: true,
+                hard_disable_audio: false,
+            }
+        );
+
+        let default = AvEnable::default();
+        assert!(default.video && default.audio);
+        assert!(!default.fast_savestates && !default.hard_disable_audio);
+    }
+
     bitflags::bitflags! {
         /// Joypad button mask
         pub struct JoypadState: u16 {
@@ -391,6 +2414,97 @@ pub mod unstable {
         }
     }
 
+    impl JoypadState {
+        /// Returns `true` if all of the given `buttons` are pressed.
+        pub fn is_pressed(&self, buttons: JoypadState) -> bool {
+            self.contains(buttons)
+        }
+    }
+
+    /// Sensor values that can be read with [`SensorContext::get_sensor_input`].
+    ///
+    /// [`SensorContext::get_sensor_input`]: crate::contexts::SensorContext::get_sensor_input
+    #[repr(u32)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum SensorInput {
+        AccelerometerX = RETRO_SENSOR_ACCELEROMETER_X,
+        AccelerometerY = RETRO_SENSOR_ACCELEROMETER_Y,
+        AccelerometerZ = RETRO_SENSOR_ACCELEROMETER_Z,
+        GyroscopeX = RETRO_SENSOR_GYROSCOPE_X,
+        GyroscopeY = RETRO_SENSOR_GYROSCOPE_Y,
+        GyroscopeZ = RETRO_SENSOR_GYROSCOPE_Z,
+        Illuminance = RETRO_SENSOR_ILLUMINANCE,
+    }
+
+    /// The reason for the rate the frontend calls [`Core::on_run`] at, see [`ThrottleState`].
+    ///
+    /// [`Core::on_run`]: crate::core::Core::on_run
+    #[repr(u32)]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum ThrottleMode {
+        /// Normal operation, the rate equals the core’s FPS.
+        None = RETRO_THROTTLE_NONE,
+
+        /// Paused or stepping single frames, the rate is 0.
+        FrameStepping = RETRO_THROTTLE_FRAME_STEPPING,
+
+        /// Fast-forwarding, the rate is 0 if the speed is not limited.
+        FastForward = RETRO_THROTTLE_FAST_FORWARD,
+
+        /// Slow motion, the rate is lower than the core’s FPS.
+        SlowMotion = RETRO_THROTTLE_SLOW_MOTION,
+
+        /// Rewinding, the rate is 0 if the frontend is not aiming for a specific rate.
+        Rewinding = RETRO_THROTTLE_REWINDING,
+
+        /// Vsync with a refresh rate lower than the core’s FPS, which is the rate.
+        Vsync = RETRO_THROTTLE_VSYNC,
+
+        /// No throttling at all, the rate is 0.
+        Unblocked = RETRO_THROTTLE_UNBLOCKED,
+    }
+
+    impl TryFrom<u32> for ThrottleMode {
+        type Error = u32;
+
+        fn try_from(value: u32) -> Result<Self, Self::Error> {
+            Ok(match value {
+                RETRO_THROTTLE_NONE => Self::None,
+                RETRO_THROTTLE_FRAME_STEPPING => Self::FrameStepping,
+                RETRO_THROTTLE_FAST_FORWARD => Self::FastForward,
+                RETRO_THROTTLE_SLOW_MOTION => Self::SlowMotion,
+                RETRO_THROTTLE_REWINDING => Self::Rewinding,
+                RETRO_THROTTLE_VSYNC => Self::Vsync,
+                RETRO_THROTTLE_UNBLOCKED => Self::Unblocked,
+                _ => return Err(value),
+            })
+        }
+    }
+
+    /// The rate the frontend is attempting to call [`Core::on_run`] at,
+    /// see [`RunContext::get_throttle_state`].
+    ///
+    /// [`Core::on_run`]: crate::core::Core::on_run
+    /// [`RunContext::get_throttle_state`]: crate::contexts::RunContext::get_throttle_state
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    pub struct ThrottleState {
+        pub mode: ThrottleMode,
+
+        /// Calls per second, or 0 if there is no known fixed rate.
+        pub rate: f32,
+    }
+
+    impl TryFrom<retro_throttle_state> for ThrottleState {
+        type Error = u32;
+
+        fn try_from(state: retro_throttle_state) -> Result<Self, Self::Error> {
+            Ok(Self {
+                mode: state.mode.try_into()?,
+                rate: state.rate,
+            })
+        }
+    }
+
     #[derive(Debug, Default)]
     pub struct VfsInterfaceInfo {
         pub(crate) supported_version: u32,
@@ -442,6 +2556,115 @@ pub mod unstable {
         }
     }
 
+    bitflags::bitflags! {
+        /// Describes the contents and access pattern of a [`MemoryDescriptor`].
+        pub struct MemoryDescriptorFlags: u64 {
+            /// The frontend will never change this memory area once [`Core::on_load_game`] has returned.
+            ///
+            /// [`Core::on_load_game`]: crate::Core::on_load_game
+            const CONST = RETRO_MEMDESC_CONST as u64;
+            /// The memory area contains big endian data. Default is little endian.
+            const BIGENDIAN = RETRO_MEMDESC_BIGENDIAN as u64;
+            /// The memory area is the main RAM of the emulated system.
+            const SYSTEM_RAM = RETRO_MEMDESC_SYSTEM_RAM as u64;
+            /// The memory area is battery backed save RAM.
+            const SAVE_RAM = RETRO_MEMDESC_SAVE_RAM as u64;
+            /// The memory area is video RAM.
+            const VIDEO_RAM = RETRO_MEMDESC_VIDEO_RAM as u64;
+            /// All memory accesses in this area are aligned to their own size, or 2, whichever is smaller.
+            const ALIGN_2 = RETRO_MEMDESC_ALIGN_2 as u64;
+            const ALIGN_4 = RETRO_MEMDESC_ALIGN_4 as u64;
+            const ALIGN_8 = RETRO_MEMDESC_ALIGN_8 as u64;
+            /// All memory in this area is accessed at least 2 bytes at a time.
+            const MINSIZE_2 = RETRO_MEMDESC_MINSIZE_2 as u64;
+            const MINSIZE_4 = RETRO_MEMDESC_MINSIZE_4 as u64;
+            const MINSIZE_8 = RETRO_MEMDESC_MINSIZE_8 as u64;
+        }
+    }
+
+    /// Describes one region of the emulated address space,
+    /// see [`GenericContext::set_memory_maps`](crate::contexts::GenericContext::set_memory_maps).
+    ///
+    /// Mirrors `retro_memory_descriptor`; refer to `libretro.h` for the exact
+    /// semantics of `start`, `select`, `disconnect` and `len`.
+    #[derive(Debug, Clone, Copy)]
+    pub struct MemoryDescriptor<'a> {
+        pub flags: MemoryDescriptorFlags,
+
+        /// Pointer to the start of the relevant ROM or RAM chip.
+        ///
+        /// The frontend keeps accessing this memory after the map has been set,
+        /// so it must stay valid until the game gets unloaded.
+        /// May be null for unusable areas like hardware registers or open bus,
+        /// in which case no flags should be set.
+        pub ptr: *mut std::os::raw::c_void,
+
+        /// Offset of the first mapped byte relative to `ptr`.
+        pub offset: usize,
+
+        /// The location in the emulated address space where the mapping starts.
+        pub start: usize,
+
+        /// Which bits must be the same as in `start` for this mapping to apply.
+        pub select: usize,
+
+        /// Address bits that are not connected to the memory chip’s address pins.
+        pub disconnect: usize,
+
+        /// The size of the memory area.
+        pub len: usize,
+
+        /// Name of the address space, for systems with more than one.
+        pub addrspace: Option<&'a str>,
+    }
+
+    impl MemoryDescriptor<'_> {
+        /// Describes the main RAM of the emulated system, mapped at `start` in its address space.
+        ///
+        /// Returned by [`Core::get_memory_data`](crate::core::Core::get_memory_data)
+        /// for [`RETRO_MEMORY_SYSTEM_RAM`] as well, `memory` must stay in place until the game gets unloaded.
+        pub fn system_ram(memory: &mut [u8], start: usize) -> Self {
+            Self {
+                flags: MemoryDescriptorFlags::SYSTEM_RAM,
+                ptr: memory.as_mut_ptr() as *mut std::os::raw::c_void,
+                start,
+                len: memory.len(),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Default for MemoryDescriptor<'_> {
+        fn default() -> Self {
+            Self {
+                flags: MemoryDescriptorFlags::empty(),
+                ptr: std::ptr::null_mut(),
+                offset: 0,
+                start: 0,
+                select: 0,
+                disconnect: 0,
+                len: 0,
+                addrspace: None,
+            }
+        }
+    }
+
+    #[test]
+    fn system_ram_descriptor_covers_the_memory() {
+        let mut ram = vec![0u8; 0x800];
+        let descriptor = MemoryDescriptor::system_ram(&mut ram, 0x1000);
+
+        assert_eq!(descriptor.flags, MemoryDescriptorFlags::SYSTEM_RAM);
+        assert_eq!(
+            descriptor.ptr,
+            ram.as_mut_ptr() as *mut std::os::raw::c_void
+        );
+        assert_eq!(descriptor.start, 0x1000);
+        assert_eq!(descriptor.len, 0x800);
+        assert_eq!(descriptor.offset, 0);
+        assert_eq!(descriptor.select, 0);
+    }
+
     // TODO: Can we get rid of the raw pointer and PhantomData in an ergonomic way?
     pub struct Framebuffer<'a> {
         pub data: *mut u8,
@@ -48,6 +48,93 @@ pub fn get_path_buf_from_pointer(ptr: *mut c_char) -> Option<PathBuf> {
     get_str_from_pointer(ptr).map(PathBuf::from)
 }
 
+/// Copies `src` into a C string buffer provided by the frontend.
+///
+/// The result is always NUL-terminated. If `src` does not fit into `buf`
+/// it gets truncated at the last character boundary that does, so the buffer
+/// never contains a partial UTF-8 sequence.
+///
+/// Returns [`None`] if `buf` is empty, as not even the terminating NUL byte fits.
+pub fn copy_str_to_buffer(src: &str, buf: &mut [c_char]) -> Option<CopiedString> {
+    let capacity = buf.len().checked_sub(1)?;
+
+    let (len, truncated) = if src.len() <= capacity {
+        (src.len(), false)
+    } else {
+        let mut len = capacity;
+        while !src.is_char_boundary(len) {
+            len -= 1;
+        }
+        (len, true)
+    };
+
+    for (dst, byte) in buf.iter_mut().zip(&src.as_bytes()[..len]) {
+        *dst = *byte as c_char;
+    }
+    buf[len] = 0;
+
+    if truncated {
+        Some(CopiedString::Truncated(len))
+    } else {
+        Some(CopiedString::Complete(len))
+    }
+}
+
+#[test]
+fn copy_str_to_buffer_truncates() {
+    let mut buf = [0x7f as c_char; 8];
+    assert_eq!(
+        copy_str_to_buffer("disk1", &mut buf),
+        Some(CopiedString::Complete(5))
+    );
+    assert_eq!(buf[5], 0);
+
+    // Only "abä" fits, cutting "ö" in half would leave invalid UTF-8 behind
+    let mut buf = [0x7f as c_char; 6];
+    assert_eq!(
+        copy_str_to_buffer("abäöü", &mut buf),
+        Some(CopiedString::Truncated(4))
+    );
+    assert_eq!(
+        &buf[..5],
+        &[
+            b'a' as c_char,
+            b'b' as c_char,
+            0xc3u8 as c_char,
+            0xa4u8 as c_char,
+            0
+        ]
+    );
+
+    assert_eq!(copy_str_to_buffer("disk1", &mut []), None);
+}
+
+/// Returns the aspect ratio the frontend displays `geometry` with.
+///
+/// An `aspect_ratio` of zero or less means `base_width / base_height`.
+pub fn effective_aspect_ratio(geometry: &retro_game_geometry) -> f32 {
+    if geometry.aspect_ratio > 0.0 {
+        geometry.aspect_ratio
+    } else {
+        geometry.base_width as f32 / geometry.base_height as f32
+    }
+}
+
+#[test]
+fn effective_aspect_ratio_falls_back_to_the_base_size() {
+    let mut geometry = retro_game_geometry {
+        base_width: 320,
+        base_height: 200,
+        max_width: 320,
+        max_height: 200,
+        aspect_ratio: 0.0,
+    };
+    assert_eq!(effective_aspect_ratio(&geometry), 1.6);
+
+    geometry.aspect_ratio = 4.0 / 3.0;
+    assert_eq!(effective_aspect_ratio(&geometry), 4.0 / 3.0);
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Version {
     major: u16,
@@ -96,3 +183,302 @@ impl Display for Version {
         write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
     }
 }
+
+/// Expands a 5 bit color channel to 8 bits, mapping `0x1f` to `0xff`.
+const fn expand5(value: u32) -> u32 {
+    (value << 3) | (value >> 2)
+}
+
+/// Expands a 6 bit color channel to 8 bits, mapping `0x3f` to `0xff`.
+const fn expand6(value: u32) -> u32 {
+    (value << 2) | (value >> 4)
+}
+
+/// Converts [`PixelFormat::RGB565`] pixels to [`PixelFormat::XRGB8888`].
+///
+/// Pixels are native-endian integers, which is the layout the frontend expects
+/// for [`RunContext::draw_frame`]; reinterpret byte buffers accordingly.
+/// Converts as many pixels as fit into both `src` and `dst`.
+/// The unused `X` bits of the output are set to zero.
+pub fn rgb565_to_xrgb8888(src: &[u16], dst: &mut [u32]) {
+    for (dst, &pixel) in dst.iter_mut().zip(src) {
+        let pixel = pixel as u32;
+
+        let r = expand5((pixel >> 11) & 0x1f);
+        let g = expand6((pixel >> 5) & 0x3f);
+        let b = expand5(pixel & 0x1f);
+
+        *dst = (r << 16) | (g << 8) | b;
+    }
+}
+
+/// Converts [`PixelFormat::XRGB8888`] pixels to [`PixelFormat::RGB565`],
+/// dropping the lowest bits of each channel.
+///
+/// See [`rgb565_to_xrgb8888`] for the endianness and length rules.
+pub fn xrgb8888_to_rgb565(src: &[u32], dst: &mut [u16]) {
+    for (dst, &pixel) in dst.iter_mut().zip(src) {
+        let r = (pixel >> 19) & 0x1f;
+        let g = (pixel >> 10) & 0x3f;
+        let b = (pixel >> 3) & 0x1f;
+
+        *dst = ((r << 11) | (g << 5) | b) as u16;
+    }
+}
+
+/// Converts [`PixelFormat::XRGB1555`] pixels to [`PixelFormat::XRGB8888`].
+///
+/// See [`rgb565_to_xrgb8888`] for the endianness and length rules.
+pub fn xrgb1555_to_xrgb8888(src: &[u16], dst: &mut [u32]) {
+    for (dst, &pixel) in dst.iter_mut().zip(src) {
+        let pixel = pixel as u32;
+
+        let r = expand5((pixel >> 10) & 0x1f);
+        let g = expand5((pixel >> 5) & 0x1f);
+        let b = expand5(pixel & 0x1f);
+
+        *dst = (r << 16) | (g << 8) | b;
+    }
+}
+
+/// Converts [`PixelFormat::XRGB8888`] pixels to [`PixelFormat::XRGB1555`],
+/// dropping the lowest bits of each channel.
+///
+/// See [`rgb565_to_xrgb8888`] for the endianness and length rules.
+pub fn xrgb8888_to_xrgb1555(src: &[u32], dst: &mut [u16]) {
+    for (dst, &pixel) in dst.iter_mut().zip(src) {
+        let r = (pixel >> 19) & 0x1f;
+        let g = (pixel >> 11) & 0x1f;
+        let b = (pixel >> 3) & 0x1f;
+
+        *dst = ((r << 10) | (g << 5) | b) as u16;
+    }
+}
+
+#[test]
+fn pixel_conversions_expand_and_truncate_channels() {
+    // White, black, pure red, green and blue, and a mid grey
+    let rgb565 = [0xffff, 0x0000, 0xf800, 0x07e0, 0x001f, 0x8410];
+    let mut xrgb8888 = [0; 6];
+
+    rgb565_to_xrgb8888(&rgb565, &mut xrgb8888);
+    assert_eq!(
+        xrgb8888,
+        [0xffffff, 0x000000, 0xff0000, 0x00ff00, 0x0000ff, 0x848284]
+    );
+
+    let mut roundtrip = [0; 6];
+    xrgb8888_to_rgb565(&xrgb8888, &mut roundtrip);
+    assert_eq!(roundtrip, rgb565);
+
+    let xrgb1555 = [0x7fff, 0x0000, 0x7c00, 0x03e0, 0x001f, 0x4210];
+    xrgb1555_to_xrgb8888(&xrgb1555, &mut xrgb8888);
+    assert_eq!(
+        xrgb8888,
+        [0xffffff, 0x000000, 0xff0000, 0x00ff00, 0x0000ff, 0x848484]
+    );
+
+    xrgb8888_to_xrgb1555(&xrgb8888, &mut roundtrip);
+    assert_eq!(roundtrip, xrgb1555);
+
+    // The `X` bits are ignored
+    xrgb8888_to_rgb565(&[0xff00_0000], &mut roundtrip);
+    assert_eq!(roundtrip[0], 0);
+
+    // Only as many pixels as fit into both slices are converted
+    let mut dst = [0; 2];
+    rgb565_to_xrgb8888(&[0xffff], &mut dst);
+    assert_eq!(dst, [0xffffff, 0]);
+}
+
+/// How [`Resampler`] interpolates between input samples.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Cheap, but attenuates high frequencies and adds some aliasing.
+    #[default]
+    Linear,
+
+    /// Catmull-Rom spline through the four surrounding samples.
+    Cubic,
+}
+
+/// Converts interleaved stereo samples from the core’s native sample rate
+/// to the one reported in [`Core::on_get_av_info`].
+///
+/// The fractional position between input frames is kept across calls,
+/// so audio can be processed in chunks of any size (e.g. once per frame)
+/// without clicks at the boundaries. The output lags the input by one frame.
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    /// Input frames per output frame, as fixed point number with [`Resampler::FRACTION_BITS`].
+    step: i64,
+    /// Position of the next output frame in the input, in the same format as `step`.
+    position: i64,
+    history: [[i16; 2]; Self::HISTORY],
+    interpolation: Interpolation,
+}
+
+impl Resampler {
+    /// The number of input frames kept from the previous call.
+    const HISTORY: usize = 3;
+
+    /// Fixed point keeps the position exact, no matter how the input is split up.
+    const FRACTION_BITS: u32 = 32;
+
+    /// Creates a linear resampler from `in_rate` to `out_rate`, both in Hz.
+    ///
+    /// # Panics
+    ///
+    /// If a rate is not a positive number.
+    pub fn new(in_rate: f64, out_rate: f64) -> Self {
+        assert!(
+            in_rate > 0.0 && out_rate > 0.0,
+            "Sample rates must be positive"
+        );
+
+        Self {
+            step: (in_rate / out_rate * (1u64 << Self::FRACTION_BITS) as f64) as i64,
+            position: -1 << Self::FRACTION_BITS,
+            history: [[0; 2]; Self::HISTORY],
+            interpolation: Interpolation::default(),
+        }
+    }
+
+    /// Uses `interpolation` instead of [`Interpolation::Linear`].
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Forgets the samples of previous calls, e.g. after [`Core::on_reset`].
+    pub fn reset(&mut self) {
+        self.position = -1 << Self::FRACTION_BITS;
+        self.history = [[0; 2]; Self::HISTORY];
+    }
+
+    /// Resamples interleaved stereo `input` into a new buffer.
+    ///
+    /// See [`Resampler::process_into`].
+    pub fn process(&mut self, input: &[i16]) -> Vec<i16> {
+        let mut output = Vec::new();
+        self.process_into(input, &mut output);
+        output
+    }
+
+    /// Resamples interleaved stereo `input` and appends the result to `output`.
+    ///
+    /// Reusing `output` between calls avoids allocating once it has grown large enough.
+    /// A trailing sample without a partner for the other channel is ignored.
+    pub fn process_into(&mut self, input: &[i16], output: &mut Vec<i16>) {
+        let frames = (input.len() / 2) as isize;
+        let history = self.history;
+
+        // Negative indices refer to the last frames of the previous call
+        let frame = |index: isize| -> [i16; 2] {
+            if index < 0 {
+                history[(index + Self::HISTORY as isize) as usize]
+            } else {
+                let index = index as usize * 2;
+                [input[index], input[index + 1]]
+            }
+        };
+
+        output.reserve(
+            (((frames as i64) << Self::FRACTION_BITS) / self.step.max(1)) as usize * 2 + 2,
+        );
+
+        let mut position = self.position;
+
+        // Cubic interpolation needs one frame before and two frames after `position`
+        while ((position >> Self::FRACTION_BITS) as isize) + 2 < frames {
+            let index = (position >> Self::FRACTION_BITS) as isize;
+            let fraction = position & ((1 << Self::FRACTION_BITS) - 1);
+            let t = (fraction as f64 / (1u64 << Self::FRACTION_BITS) as f64) as f32;
+
+            for channel in 0..2 {
+                let sample = |offset: isize| frame(index + offset)[channel] as f32;
+
+                let value = match self.interpolation {
+                    Interpolation::Linear => sample(0) + (sample(1) - sample(0)) * t,
+                    Interpolation::Cubic => {
+                        let (p0, p1, p2, p3) = (sample(-1), sample(0), sample(1), sample(2));
+
+                        p1 + 0.5
+                            * t
+                            * (p2 - p0
+                                + t * (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3
+                                    + t * (3.0 * (p1 - p2) + p3 - p0)))
+                    }
+                };
+
+                output.push(value.round().clamp(i16::MIN as f32, i16::MAX as f32) as i16);
+            }
+
+            position += self.step;
+        }
+
+        self.position = position - ((frames as i64) << Self::FRACTION_BITS);
+
+        for (offset, frame_history) in self.history.iter_mut().enumerate() {
+            *frame_history = frame(frames - Self::HISTORY as isize + offset as isize);
+        }
+    }
+}
+
+#[test]
+fn resampler_keeps_its_phase_across_calls() {
+    let input: Vec<i16> = (0..200).flat_map(|i| [i * 10, -i * 10]).collect();
+
+    // Same rate, the output is the input delayed by one frame
+    let mut resampler = Resampler::new(48000.0, 48000.0);
+    let output = resampler.process(&input[..20]);
+    assert_eq!(&output[..6], &[0, 0, 0, 0, 10, -10]);
+
+    // Processing in uneven chunks matches processing everything at once
+    for interpolation in [Interpolation::Linear, Interpolation::Cubic] {
+        let mut whole = Resampler::new(44100.0, 48000.0).with_interpolation(interpolation);
+        let expected = whole.process(&input);
+
+        let mut chunked = Resampler::new(44100.0, 48000.0).with_interpolation(interpolation);
+        let mut output = Vec::new();
+        for chunk in input.chunks(14) {
+            chunked.process_into(chunk, &mut output);
+        }
+
+        assert_eq!(output, expected);
+    }
+
+    // Downsampling a ramp by half keeps every other frame, interpolating linearly
+    let mut resampler = Resampler::new(2.0, 1.0);
+    let output = resampler.process(&input[..40]);
+    assert_eq!(&output[2..8], &[10, -10, 30, -30, 50, -50]);
+
+    // A constant signal stays constant
+    let mut resampler = Resampler::new(32000.0, 48000.0).with_interpolation(Interpolation::Cubic);
+    let output = resampler.process(&[1000; 100]);
+    assert!(output[8..].iter().all(|&sample| sample == 1000));
+}
+
+/// A writer for tests that keeps what has been written to any of its clones.
+#[cfg(all(test, any(feature = "log", feature = "tracing")))]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(all(test, any(feature = "log", feature = "tracing")))]
+impl SharedBuffer {
+    /// Returns everything written so far.
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[cfg(all(test, any(feature = "log", feature = "tracing")))]
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
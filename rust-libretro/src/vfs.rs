@@ -0,0 +1,478 @@
+//! Safe wrappers around the frontend’s virtual filesystem.
+//!
+//! Use [`SetEnvironmentContext::enable_vfs_interface`] to request the interface
+//! and [`GenericContext::vfs`] to obtain a [`Vfs`] afterwards.
+use super::*;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+fn unsupported(function: &str, required_version: u32, version: u32) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{function} requires VFS interface version {required_version}, but the frontend only supports version {version}"
+        ),
+    )
+}
+
+fn missing(function: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("The frontend does not provide `{function}`"),
+    )
+}
+
+fn failed(message: &str) -> io::Error {
+    io::Error::other(message)
+}
+
+fn c_string(value: &str) -> io::Result<CString> {
+    CString::new(value).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+/// The virtual filesystem provided by the frontend.
+#[derive(Debug, Copy, Clone)]
+pub struct Vfs {
+    interface: retro_vfs_interface,
+    version: u32,
+}
+
+impl Vfs {
+    pub(crate) fn new(interface: retro_vfs_interface, version: u32) -> Self {
+        Self { interface, version }
+    }
+
+    /// The interface version supported by the frontend.
+    ///
+    /// - Version 1 provides basic file access.
+    /// - Version 2 adds [`VfsFile::truncate`].
+    /// - Version 3 adds [`Vfs::stat`], [`Vfs::mkdir`] and [`Vfs::read_dir`].
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn require(&self, function: &str, version: u32) -> io::Result<()> {
+        if self.version < version {
+            return Err(unsupported(function, version, self.version));
+        }
+
+        Ok(())
+    }
+
+    /// Opens the file at `path` with the given access `flags`.
+    pub fn open(&self, path: &str, flags: VfsFileOpenFlags) -> io::Result<VfsFile> {
+        self.open_with_hints(path, flags, VfsFileOpenHints::NONE)
+    }
+
+    /// Like [`Vfs::open`], but additionally passes access hints to the frontend.
+    pub fn open_with_hints(
+        &self,
+        path: &str,
+        flags: VfsFileOpenFlags,
+        hints: VfsFileOpenHints,
+    ) -> io::Result<VfsFile> {
+        let open = self.interface.open.ok_or_else(|| missing("open"))?;
+        let path = c_string(path)?;
+
+        let handle = unsafe { open(path.as_ptr(), flags.bits(), hints.bits()) };
+        if handle.is_null() {
+            return Err(failed("Failed to open file"));
+        }
+
+        Ok(VfsFile { vfs: *self, handle })
+    }
+
+    /// Removes the file at `path`.
+    pub fn remove(&self, path: &str) -> io::Result<()> {
+        let remove = self.interface.remove.ok_or_else(|| missing("remove"))?;
+        let path = c_string(path)?;
+
+        if unsafe { remove(path.as_ptr()) } != 0 {
+            return Err(failed("Failed to remove file"));
+        }
+
+        Ok(())
+    }
+
+    /// Renames the file at `old_path` to `new_path`.
+    pub fn rename(&self, old_path: &str, new_path: &str) -> io::Result<()> {
+        let rename = self.interface.rename.ok_or_else(|| missing("rename"))?;
+        let old_path = c_string(old_path)?;
+        let new_path = c_string(new_path)?;
+
+        if unsafe { rename(old_path.as_ptr(), new_path.as_ptr()) } != 0 {
+            return Err(failed("Failed to rename file"));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the [`VfsStat`] flags and the size of the file at `path`.
+    ///
+    /// Requires interface version 3.
+    pub fn stat(&self, path: &str) -> io::Result<(VfsStat, u64)> {
+        self.require("stat", 3)?;
+
+        let stat = self.interface.stat.ok_or_else(|| missing("stat"))?;
+        let path = c_string(path)?;
+
+        let mut size = 0i32;
+        let flags = unsafe { VfsStat::from_bits_unchecked(stat(path.as_ptr(), &mut size)) };
+
+        if !flags.contains(VfsStat::STAT_IS_VALID) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "The path does not exist",
+            ));
+        } else if size < 0 {
+            return Err(failed(&format!("Invalid file size: {size}")));
+        }
+
+        Ok((flags, size as u64))
+    }
+
+    /// Creates the directory `dir`.
+    ///
+    /// Requires interface version 3.
+    pub fn mkdir(&self, dir: &str) -> io::Result<()> {
+        self.require("mkdir", 3)?;
+
+        let mkdir = self.interface.mkdir.ok_or_else(|| missing("mkdir"))?;
+        let dir = c_string(dir)?;
+
+        match unsafe { mkdir(dir.as_ptr()) } {
+            0 => Ok(()),
+            -2 => Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "The directory exists already",
+            )),
+            _ => Err(failed("Failed to create directory")),
+        }
+    }
+
+    /// Returns an iterator over the entries of the directory `dir`.
+    ///
+    /// Requires interface version 3.
+    pub fn read_dir(&self, dir: &str, include_hidden: bool) -> io::Result<VfsDir> {
+        self.require("read_dir", 3)?;
+
+        let opendir = self.interface.opendir.ok_or_else(|| missing("opendir"))?;
+        let dir = c_string(dir)?;
+
+        let handle = unsafe { opendir(dir.as_ptr(), include_hidden) };
+        if handle.is_null() {
+            return Err(failed("Failed to open directory"));
+        }
+
+        Ok(VfsDir {
+            vfs: *self,
+            handle,
+            done: false,
+        })
+    }
+}
+
+/// A file opened through the frontend’s [`Vfs`].
+///
+/// The file gets closed when dropped.
+#[derive(Debug)]
+pub struct VfsFile {
+    vfs: Vfs,
+    handle: *mut retro_vfs_file_handle,
+}
+
+impl VfsFile {
+    /// Returns the path that was used to open this file.
+    pub fn path(&self) -> Option<&str> {
+        let get_path = self.vfs.interface.get_path?;
+
+        get_str_from_pointer(unsafe { get_path(self.handle) })
+    }
+
+    /// Returns the size of this file in bytes.
+    pub fn size(&self) -> io::Result<u64> {
+        let size = self.vfs.interface.size.ok_or_else(|| missing("size"))?;
+
+        let size = unsafe { size(self.handle) };
+        if size < 0 {
+            return Err(failed("Failed to get file size"));
+        }
+
+        Ok(size as u64)
+    }
+
+    /// Truncates or extends this file to `length` bytes.
+    ///
+    /// Requires interface version 2.
+    pub fn truncate(&mut self, length: u64) -> io::Result<()> {
+        self.vfs.require("truncate", 2)?;
+
+        let truncate = self
+            .vfs
+            .interface
+            .truncate
+            .ok_or_else(|| missing("truncate"))?;
+
+        let length = i64::try_from(length)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+        if unsafe { truncate(self.handle, length) } != 0 {
+            return Err(failed("Failed to truncate file"));
+        }
+
+        Ok(())
+    }
+
+    /// Closes this file and removes it.
+    pub fn remove(self) -> io::Result<()> {
+        let path = self
+            .path()
+            .map(str::to_owned)
+            .ok_or_else(|| missing("get_path"))?;
+        let vfs = self.close()?;
+
+        vfs.remove(&path)
+    }
+
+    /// Closes this file and renames it to `new_path`.
+    pub fn rename(self, new_path: &str) -> io::Result<()> {
+        let path = self
+            .path()
+            .map(str::to_owned)
+            .ok_or_else(|| missing("get_path"))?;
+        let vfs = self.close()?;
+
+        vfs.rename(&path, new_path)
+    }
+
+    /// Closes this file, reporting any errors that [`Drop`] would ignore.
+    pub fn close(self) -> io::Result<Vfs> {
+        let this = std::mem::ManuallyDrop::new(self);
+        let close = this.vfs.interface.close.ok_or_else(|| missing("close"))?;
+
+        if unsafe { close(this.handle) } != 0 {
+            return Err(failed("Failed to close file"));
+        }
+
+        Ok(this.vfs)
+    }
+}
+
+impl Read for VfsFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.vfs.interface.read.ok_or_else(|| missing("read"))?;
+
+        let length = unsafe {
+            read(
+                self.handle,
+                buf.as_mut_ptr() as *mut c_void,
+                buf.len() as u64,
+            )
+        };
+        if length < 0 {
+            return Err(failed("Failed to read from file"));
+        }
+
+        Ok(length as usize)
+    }
+}
+
+impl Write for VfsFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let write = self.vfs.interface.write.ok_or_else(|| missing("write"))?;
+
+        let length = unsafe { write(self.handle, buf.as_ptr() as *const c_void, buf.len() as u64) };
+        if length < 0 {
+            return Err(failed("Failed to write to file"));
+        }
+
+        Ok(length as usize)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let flush = self.vfs.interface.flush.ok_or_else(|| missing("flush"))?;
+
+        if unsafe { flush(self.handle) } != 0 {
+            return Err(failed("Failed to flush file"));
+        }
+
+        Ok(())
+    }
+}
+
+impl Seek for VfsFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let seek = self.vfs.interface.seek.ok_or_else(|| missing("seek"))?;
+
+        let (offset, position) = match pos {
+            SeekFrom::Start(offset) => (
+                i64::try_from(offset)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?,
+                VfsSeekPosition::Start,
+            ),
+            SeekFrom::Current(offset) => (offset, VfsSeekPosition::Current),
+            SeekFrom::End(offset) => (offset, VfsSeekPosition::End),
+        };
+
+        let position = unsafe { seek(self.handle, offset, position as i32) };
+        if position < 0 {
+            return Err(failed("Failed to seek into file"));
+        }
+
+        Ok(position as u64)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        let tell = self.vfs.interface.tell.ok_or_else(|| missing("tell"))?;
+
+        let position = unsafe { tell(self.handle) };
+        if position < 0 {
+            return Err(failed("Failed to get cursor position"));
+        }
+
+        Ok(position as u64)
+    }
+}
+
+impl Drop for VfsFile {
+    fn drop(&mut self) {
+        if let Some(close) = self.vfs.interface.close {
+            unsafe { close(self.handle) };
+        }
+    }
+}
+
+/// An entry returned by [`VfsDir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VfsDirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// An iterator over the entries of a directory, see [`Vfs::read_dir`].
+///
+/// The directory gets closed when dropped.
+#[derive(Debug)]
+pub struct VfsDir {
+    vfs: Vfs,
+    handle: *mut retro_vfs_dir_handle,
+    done: bool,
+}
+
+impl Iterator for VfsDir {
+    type Item = io::Result<VfsDirEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let interface = &self.vfs.interface;
+
+        let (readdir, dirent_get_name, dirent_is_dir) = match (
+            interface.readdir,
+            interface.dirent_get_name,
+            interface.dirent_is_dir,
+        ) {
+            (Some(readdir), Some(dirent_get_name), Some(dirent_is_dir)) => {
+                (readdir, dirent_get_name, dirent_is_dir)
+            }
+            _ => {
+                // Report the missing functions only once, so loops over the entries end
+                self.done = true;
+                return Some(Err(missing("readdir")));
+            }
+        };
+
+        if !unsafe { readdir(self.handle) } {
+            self.done = true;
+            return None;
+        }
+
+        let name = match get_string_from_pointer(unsafe { dirent_get_name(self.handle) }) {
+            Some(name) => name,
+            None => return Some(Err(failed("Failed to get entry name"))),
+        };
+
+        Some(Ok(VfsDirEntry {
+            name,
+            is_dir: unsafe { dirent_is_dir(self.handle) },
+        }))
+    }
+}
+
+impl Drop for VfsDir {
+    fn drop(&mut self) {
+        if let Some(closedir) = self.vfs.interface.closedir {
+            unsafe { closedir(self.handle) };
+        }
+    }
+}
+
+#[cfg(test)]
+unsafe extern "C" fn test_opendir(
+    _dir: *const c_char,
+    _include_hidden: bool,
+) -> *mut retro_vfs_dir_handle {
+    std::ptr::NonNull::dangling().as_ptr()
+}
+
+#[test]
+fn read_dir_reports_missing_functions_once() {
+    let interface = retro_vfs_interface {
+        opendir: Some(test_opendir),
+        ..unsafe { std::mem::zeroed() }
+    };
+
+    let mut dir = Vfs::new(interface, 3).read_dir("dir", false).unwrap();
+
+    let err = dir.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    assert!(dir.next().is_none());
+    assert!(dir.next().is_none());
+}
+
+#[test]
+fn read_dir_ends_after_the_last_entry() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static REMAINING: AtomicU32 = AtomicU32::new(2);
+
+    unsafe extern "C" fn readdir(_dir: *mut retro_vfs_dir_handle) -> bool {
+        REMAINING
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+    }
+
+    unsafe extern "C" fn dirent_get_name(_dir: *mut retro_vfs_dir_handle) -> *const c_char {
+        b"entry\0".as_ptr() as *const c_char
+    }
+
+    unsafe extern "C" fn dirent_is_dir(_dir: *mut retro_vfs_dir_handle) -> bool {
+        false
+    }
+
+    let interface = retro_vfs_interface {
+        opendir: Some(test_opendir),
+        readdir: Some(readdir),
+        dirent_get_name: Some(dirent_get_name),
+        dirent_is_dir: Some(dirent_is_dir),
+        ..unsafe { std::mem::zeroed() }
+    };
+
+    let entries: Vec<_> = Vfs::new(interface, 3)
+        .read_dir("dir", false)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(
+        entries,
+        vec![
+            VfsDirEntry {
+                name: "entry".to_owned(),
+                is_dir: false,
+            };
+            2
+        ]
+    );
+}